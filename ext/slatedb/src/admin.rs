@@ -1,13 +1,57 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use futures::TryStreamExt;
+
+use magnus::block::Proc;
 use magnus::prelude::*;
-use magnus::{function, method, Error, RHash, Ruby};
+use magnus::{function, method, Error, RHash, Ruby, Value};
 use slatedb::admin::AdminBuilder;
-use slatedb::config::{CheckpointOptions, GarbageCollectorOptions};
+use slatedb::config::{CheckpointOptions, DbReaderOptions, GarbageCollectorOptions};
+use slatedb::manifest::VersionedManifest;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{
+    Error as ObjectStoreError, ObjectStore, ObjectStoreExt, PutMode, UpdateVersion,
+};
+use slatedb::{DbReader, Error as SlateError};
+use slatedb_common::clock::SystemClock;
 
-use crate::errors::invalid_argument_error;
+use crate::backup;
+use crate::checkpoint_lease::CheckpointLease;
+use crate::clock::test_clock;
+use crate::encryption;
+use crate::errors::{invalid_argument_error, map_error};
+use crate::expired;
+use crate::fault;
+use crate::overlay::OverlayStore;
+use crate::progress::{self, Progress};
+use crate::retry::{self, RetryPolicy};
 use crate::runtime::{block_on, block_on_result};
-use crate::utils::{get_optional, resolve_object_store};
+use crate::sample_stats;
+use crate::utils::{
+    get_optional, get_positive, resolve_object_store, to_slate_error, StoreOptions,
+};
+use crate::writer::read_writer_info;
+
+/// How long the checkpoint pinning an in-progress backup is kept alive.
+///
+/// The checkpoint is deleted as soon as the backup finishes; the lifetime only
+/// matters if the process dies mid-copy.
+const BACKUP_CHECKPOINT_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// Keys read by `verify_backup` between progress reports.
+const VERIFY_PROGRESS_KEYS: usize = 10_000;
+
+/// Name of the object, under the database path, holding database labels.
+///
+/// It sits next to the `manifest/`, `wal/` and `compacted/` directories and
+/// is never read or collected by SlateDB itself.
+const METADATA_FILE: &str = "metadata.json";
+
+/// How many times `set_metadata` re-reads and re-applies its changes after
+/// losing a race with another admin.
+const METADATA_UPDATE_ATTEMPTS: usize = 10;
 
 /// Ruby wrapper for SlateDB Admin.
 ///
@@ -15,7 +59,13 @@ use crate::utils::{get_optional, resolve_object_store};
 /// Provides administrative functions for managing manifests, checkpoints, and GC.
 #[magnus::wrap(class = "SlateDb::Admin", free_immediately, size)]
 pub struct Admin {
-    inner: slatedb::admin::Admin,
+    inner: Arc<slatedb::admin::Admin>,
+    path: Path,
+    object_store: Arc<dyn ObjectStore>,
+    /// Store settings and credentials, reused for backup destinations.
+    store_options: StoreOptions,
+    /// The clock the admin was built with, if not the system clock.
+    clock: Option<Arc<dyn SystemClock>>,
 }
 
 impl Admin {
@@ -24,15 +74,24 @@ impl Admin {
     /// # Arguments
     /// * `path` - The path identifier for the database
     /// * `url` - Optional object store URL
-    pub fn new(path: String, url: Option<String>) -> Result<Self, Error> {
+    /// * `kwargs` - Additional options (store_options)
+    pub fn new(path: String, url: Option<String>, kwargs: RHash) -> Result<Self, Error> {
+        let store_options = StoreOptions::from_kwargs(&kwargs)?;
         let object_store: Arc<dyn slatedb::object_store::ObjectStore> = if let Some(ref url) = url {
-            block_on_result(async { resolve_object_store(url) })?
+            block_on_result(async { resolve_object_store(url, &store_options) })?
         } else {
             Arc::new(slatedb::object_store::memory::InMemory::new())
         };
+        let object_store = fault::wrap(object_store);
 
-        let admin = AdminBuilder::new(path, object_store).build();
-        Ok(Self { inner: admin })
+        let clock = test_clock();
+        Ok(Self {
+            inner: Arc::new(build_admin(&path, object_store.clone(), clock.clone())),
+            path: Path::from(path),
+            object_store,
+            store_options,
+            clock,
+        })
     }
 
     /// Read the latest or a specific manifest as a JSON string.
@@ -43,7 +102,7 @@ impl Admin {
     /// # Returns
     /// JSON string of the manifest, or None if no manifests exist.
     pub fn read_manifest(&self, id: Option<u64>) -> Result<Option<String>, Error> {
-        let manifest = block_on(async { self.inner.read_manifest(id).await }).map_err(|e| {
+        let manifest = block_on(async { self.inner.read_manifest(id).await })?.map_err(|e| {
             let ruby = Ruby::get().expect("Ruby runtime not available");
             Error::new(ruby.exception_runtime_error(), format!("{}", e))
         })?;
@@ -73,10 +132,11 @@ impl Admin {
             (None, None) => 0..u64::MAX,
         };
 
-        let manifests = block_on(async { self.inner.list_manifests(range).await }).map_err(|e| {
-            let ruby = Ruby::get().expect("Ruby runtime not available");
-            Error::new(ruby.exception_runtime_error(), format!("{}", e))
-        })?;
+        let manifests =
+            block_on(async { self.inner.list_manifests(range).await })?.map_err(|e| {
+                let ruby = Ruby::get().expect("Ruby runtime not available");
+                Error::new(ruby.exception_runtime_error(), format!("{}", e))
+            })?;
 
         serde_json::to_string(&manifests).map_err(|e| {
             let ruby = Ruby::get().expect("Ruby runtime not available");
@@ -124,6 +184,60 @@ impl Admin {
         Ok(hash)
     }
 
+    /// Create a named detached checkpoint, then delete the checkpoints that
+    /// had the name before it was created.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options: name (required), lifetime (ms), replace
+    ///   (default true; when false, raise if the name is taken)
+    ///
+    /// # Returns
+    /// Hash with id (UUID string), manifest_id (int) and replaced (the ids
+    /// of the deleted checkpoints)
+    pub fn create_checkpoint_replacing(&self, kwargs: RHash) -> Result<RHash, Error> {
+        let name = get_optional::<String>(&kwargs, "name")?
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| invalid_argument_error("name is required"))?;
+        let lifetime = get_optional::<u64>(&kwargs, "lifetime")?.map(Duration::from_millis);
+        let replace = get_optional::<bool>(&kwargs, "replace")?.unwrap_or(true);
+
+        let (result, replaced) = block_on_result(async {
+            let existing = self.inner.list_checkpoints(Some(&name)).await?;
+            if !replace && !existing.is_empty() {
+                return Err(SlateError::invalid(format!(
+                    "a checkpoint named {} already exists",
+                    name
+                )));
+            }
+
+            let options = CheckpointOptions {
+                lifetime,
+                source: None,
+                name: Some(name.clone()),
+            };
+            let result = self.inner.create_detached_checkpoint(&options).await?;
+            // Only checkpoints listed before this one was created are
+            // deleted, so two concurrent calls can each leave a checkpoint
+            // behind but never delete each other's.
+            for checkpoint in &existing {
+                self.inner.delete_checkpoint(checkpoint.id).await?;
+            }
+            Ok((result, existing))
+        })?;
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("id"), result.id.to_string())?;
+        hash.aset(ruby.to_symbol("manifest_id"), result.manifest_id)?;
+        let ids = ruby.ary_new_capa(replaced.len());
+        for checkpoint in replaced {
+            ids.push(checkpoint.id.to_string())?;
+        }
+        hash.aset(ruby.to_symbol("replaced"), ids)?;
+
+        Ok(hash)
+    }
+
     /// List known checkpoints for the database.
     ///
     /// # Arguments
@@ -132,11 +246,11 @@ impl Admin {
     /// # Returns
     /// Array of checkpoint hashes
     pub fn list_checkpoints(&self, name: Option<String>) -> Result<magnus::RArray, Error> {
-        let checkpoints = block_on(async { self.inner.list_checkpoints(name.as_deref()).await })
+        let checkpoints = block_on(async { self.inner.list_checkpoints(name.as_deref()).await })?
             .map_err(|e| {
-                let ruby = Ruby::get().expect("Ruby runtime not available");
-                Error::new(ruby.exception_runtime_error(), format!("{}", e))
-            })?;
+            let ruby = Ruby::get().expect("Ruby runtime not available");
+            Error::new(ruby.exception_runtime_error(), format!("{}", e))
+        })?;
 
         let ruby = Ruby::get().expect("Ruby runtime not available");
         let result = ruby.ary_new_capa(checkpoints.len());
@@ -177,6 +291,44 @@ impl Admin {
         Ok(())
     }
 
+    /// Describe the database's current writer.
+    ///
+    /// # Returns
+    /// Hash with epoch, manifest_id, last_manifest_write (seconds since the
+    /// epoch) and client_id (nil if the writer didn't record one), or nil if
+    /// the database has no manifest
+    pub fn active_writer(&self) -> Result<Option<RHash>, Error> {
+        let info = block_on_result(read_writer_info(&self.object_store, self.path.as_ref()))?;
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        info.map(|info| info.to_hash(&ruby)).transpose()
+    }
+
+    /// Keep a checkpoint alive from a background task until released.
+    ///
+    /// # Arguments
+    /// * `id` - Checkpoint UUID string
+    /// * `kwargs` - Lease options:
+    ///   - `refresh_every_ms`: Interval between refreshes (required)
+    ///   - `lifetime_ms`: Lifetime set on each refresh (default: three intervals)
+    pub fn hold_checkpoint(&self, id: String, kwargs: RHash) -> Result<CheckpointLease, Error> {
+        let checkpoint_uuid = uuid::Uuid::parse_str(&id)
+            .map_err(|e| invalid_argument_error(&format!("invalid checkpoint UUID: {}", e)))?;
+        let interval = get_positive(&kwargs, "refresh_every_ms")?
+            .ok_or_else(|| invalid_argument_error("refresh_every_ms is required"))?;
+        let interval = Duration::from_millis(interval as u64);
+        let lifetime = match get_positive(&kwargs, "lifetime_ms")? {
+            Some(ms) => Duration::from_millis(ms as u64),
+            None => interval * 3,
+        };
+        if lifetime <= interval {
+            return Err(invalid_argument_error(
+                "lifetime_ms must be greater than refresh_every_ms",
+            ));
+        }
+
+        CheckpointLease::hold(self.inner.clone(), checkpoint_uuid, interval, lifetime)
+    }
+
     /// Delete a checkpoint.
     ///
     /// # Arguments
@@ -200,7 +352,14 @@ impl Admin {
     ///
     /// If `min_age` is provided, it will be used for all directories unless a specific override is provided.
     /// If no options are provided, defaults are used (manifest: 1 day, wal: 1 minute, compacted: 1 minute).
-    pub fn run_gc(&self, kwargs: RHash) -> Result<(), Error> {
+    ///
+    /// `progress` is called after each directory is collected.
+    ///
+    /// # Returns
+    /// Hash with deleted_objects, deleted_bytes, duration (in seconds) and
+    /// directories (the objects and bytes deleted from each directory), or
+    /// nil with `report: false`
+    pub fn run_gc(&self, kwargs: RHash, progress: Option<Proc>) -> Result<Option<RHash>, Error> {
         use slatedb::config::GarbageCollectorDirectoryOptions;
 
         // Extract options from kwargs
@@ -208,6 +367,7 @@ impl Admin {
         let manifest_min_age = get_optional::<u64>(&kwargs, "manifest_min_age")?;
         let wal_min_age = get_optional::<u64>(&kwargs, "wal_min_age")?;
         let compacted_min_age = get_optional::<u64>(&kwargs, "compacted_min_age")?;
+        let report = get_optional::<bool>(&kwargs, "report")?.unwrap_or(true);
 
         // Build GC options
         let gc_opts = if min_age.is_none()
@@ -256,12 +416,877 @@ impl Admin {
             }
         };
 
-        block_on(async { self.inner.run_gc_once(gc_opts).await }).map_err(|e| {
-            let ruby = Ruby::get().expect("Ruby runtime not available");
-            Error::new(ruby.exception_runtime_error(), format!("{}", e))
+        // With a progress block, each directory is collected in its own pass
+        // so the block can be called between them.
+        let progress = Progress::new(progress);
+        let passes = if progress.is_enabled() {
+            gc_passes(gc_opts)
+        } else {
+            vec![("all", gc_opts)]
+        };
+
+        // SlateDB doesn't report what GC deleted, so compare the objects
+        // under the database path before and after.
+        let started = Instant::now();
+        let before = if report {
+            Some(block_on_result(inventory(&self.object_store, &self.path))?)
+        } else {
+            None
+        };
+
+        let total = passes.len() as u64;
+        for (completed, (phase, opts)) in (1..).zip(passes) {
+            block_on(async { self.inner.run_gc_once(opts).await })?.map_err(|e| {
+                let ruby = Ruby::get().expect("Ruby runtime not available");
+                Error::new(ruby.exception_runtime_error(), format!("{}", e))
+            })?;
+            progress.report_phase(
+                Some(phase),
+                &[("completed", completed), ("total", total)],
+                Some(progress::percent(completed, total)),
+            )?;
+        }
+
+        let Some(before) = before else {
+            return Ok(None);
+        };
+        let after = block_on_result(inventory(&self.object_store, &self.path))?;
+        gc_report(&self.path, &before, &after, started.elapsed()).map(Some)
+    }
+
+    /// Back up the SSTs added since a previous backup to another object store.
+    ///
+    /// # Arguments
+    /// * `since_manifest_id` - Manifest id returned by the previous backup, or
+    ///   None for a full backup
+    /// * `dest_url` - Object store URL to copy into
+    /// * `progress` - Optional block called after each SST is copied
+    ///
+    /// # Returns
+    /// Hash with manifest_id (the new high-water mark), copied_ssts,
+    /// copied_wal_ssts and copied_bytes
+    pub fn incremental_backup(
+        &self,
+        since_manifest_id: Option<u64>,
+        dest_url: String,
+        progress: Option<Proc>,
+    ) -> Result<RHash, Error> {
+        let dest = block_on_result(async { resolve_object_store(&dest_url, &self.store_options) })?;
+        let report = incremental_backup(
+            &self.inner,
+            &self.path,
+            &self.object_store,
+            &dest,
+            self.clock.clone(),
+            since_manifest_id,
+            &Progress::new(progress),
+        )?;
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("manifest_id"), report.manifest_id)?;
+        hash.aset(ruby.to_symbol("copied_ssts"), report.copied_ssts)?;
+        hash.aset(ruby.to_symbol("copied_wal_ssts"), report.copied_wal_ssts)?;
+        hash.aset(ruby.to_symbol("copied_bytes"), report.copied_bytes)?;
+
+        Ok(hash)
+    }
+
+    /// Verify that a backup of this database can be restored.
+    ///
+    /// # Arguments
+    /// * `src_url` - Object store URL holding the backup
+    /// * `checkpoint_id` - Optional checkpoint UUID string to verify. If None,
+    ///   verifies the latest manifest.
+    /// * `progress` - Optional block called as SSTs and entries are checked
+    ///
+    /// # Returns
+    /// Hash with ok, manifest_id, checked_ssts, missing_ssts, keys, bytes,
+    /// fingerprint and error
+    pub fn verify_backup(
+        &self,
+        src_url: String,
+        checkpoint_id: Option<String>,
+        progress: Option<Proc>,
+    ) -> Result<RHash, Error> {
+        let checkpoint_uuid = checkpoint_id
+            .map(|id| uuid::Uuid::parse_str(&id))
+            .transpose()
+            .map_err(|e| invalid_argument_error(&format!("invalid checkpoint UUID: {}", e)))?;
+
+        let backup =
+            block_on_result(async { resolve_object_store(&src_url, &self.store_options) })?;
+        let report = verify_backup(
+            &self.path,
+            backup,
+            checkpoint_uuid,
+            &Progress::new(progress),
+        )?;
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(
+            ruby.to_symbol("ok"),
+            report.missing_ssts.is_empty() && report.error.is_none(),
+        )?;
+        hash.aset(ruby.to_symbol("manifest_id"), report.manifest_id)?;
+        hash.aset(ruby.to_symbol("checked_ssts"), report.checked_ssts)?;
+        hash.aset(
+            ruby.to_symbol("missing_ssts"),
+            ruby.ary_from_vec(report.missing_ssts),
+        )?;
+        hash.aset(ruby.to_symbol("keys"), report.keys)?;
+        hash.aset(ruby.to_symbol("bytes"), report.bytes)?;
+        hash.aset(ruby.to_symbol("fingerprint"), report.fingerprint)?;
+        hash.aset(ruby.to_symbol("error"), report.error)?;
+
+        Ok(hash)
+    }
+
+    /// Export the database's entries as JSON Lines or CSV.
+    ///
+    /// # Arguments
+    /// * `dest` - File path or IO to write to
+    /// * `kwargs` - Options (format, batch_size, range_start, range_end,
+    ///   range_exclusive)
+    /// * `progress` - Optional block called after each chunk
+    ///
+    /// # Returns
+    /// Hash with entries and bytes
+    pub fn export(
+        &self,
+        dest: Value,
+        kwargs: RHash,
+        progress: Option<Proc>,
+    ) -> Result<RHash, Error> {
+        backup::export(&self.path, &self.object_store, dest, kwargs, progress)
+    }
+
+    /// Import entries exported by `export`.
+    ///
+    /// # Arguments
+    /// * `source` - File path or IO to read from
+    /// * `kwargs` - Options (format, batch_size)
+    /// * `progress` - Optional block called after each batch
+    ///
+    /// # Returns
+    /// Hash with entries, skipped and bytes
+    pub fn import(
+        &self,
+        source: Value,
+        kwargs: RHash,
+        progress: Option<Proc>,
+    ) -> Result<RHash, Error> {
+        backup::import(&self.path, &self.object_store, source, kwargs, progress)
+    }
+
+    /// Re-encrypt the database under a new key with a full compaction.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (encryption, merge_operator, timeout_ms)
+    /// * `progress` - Optional block called while the compaction runs
+    ///
+    /// # Returns
+    /// Hash with id, status, l0_ssts, sorted_runs, destination, bytes and
+    /// key_id, or nil if there is nothing to compact
+    pub fn rewrap_keys(
+        &self,
+        kwargs: RHash,
+        progress: Option<Proc>,
+    ) -> Result<Option<RHash>, Error> {
+        encryption::rewrap_keys(
+            &self.inner,
+            &self.path,
+            &self.object_store,
+            kwargs,
+            progress,
+        )
+    }
+
+    /// Sample the sizes of the database's keys and values.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (sample_rate, prefix_delimiter, top)
+    ///
+    /// # Returns
+    /// Hash with scanned_entries, sampled_entries, sample_rate, duration,
+    /// keys, values and prefixes
+    pub fn sample_stats(&self, kwargs: RHash) -> Result<RHash, Error> {
+        sample_stats::sample_stats(&self.path, &self.object_store, kwargs)
+    }
+
+    /// Count the entries and bytes under each of the given prefixes.
+    ///
+    /// # Arguments
+    /// * `prefixes` - Key prefixes to count
+    /// * `kwargs` - Options (sample_rate)
+    ///
+    /// # Returns
+    /// Hash of prefix to entries, key_bytes, value_bytes and bytes
+    pub fn prefix_stats(&self, prefixes: Vec<String>, kwargs: RHash) -> Result<RHash, Error> {
+        sample_stats::prefix_stats(&self.path, &self.object_store, prefixes, kwargs)
+    }
+
+    /// Count the rows in the compacted SSTs whose TTL has passed.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (now)
+    ///
+    /// # Returns
+    /// Hash with now, compaction_clock, duration, ssts, entries, bytes,
+    /// expired_entries, expired_bytes, reclaimable_entries,
+    /// reclaimable_bytes, l0 and sorted_runs
+    pub fn expired_stats(&self, kwargs: RHash) -> Result<RHash, Error> {
+        expired::expired_stats(
+            &self.inner,
+            &self.path,
+            &self.object_store,
+            self.clock.as_ref(),
+            kwargs,
+        )
+    }
+
+    /// Submit a compaction of the whole tree, dropping expired rows.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (wait, timeout_ms)
+    ///
+    /// # Returns
+    /// Hash with id, status, l0_ssts, sorted_runs and destination, or nil if
+    /// there is nothing to compact
+    pub fn purge_expired(&self, kwargs: RHash) -> Result<Option<RHash>, Error> {
+        expired::purge_expired(&self.inner, kwargs)
+    }
+
+    /// Read the labels stamped on the database.
+    ///
+    /// # Returns
+    /// Hash of label names to values, empty if none have been set
+    pub fn metadata(&self) -> Result<BTreeMap<String, String>, Error> {
+        block_on_result(read_metadata(&self.object_store, &self.path))
+    }
+
+    /// Set or remove labels on the database.
+    ///
+    /// # Arguments
+    /// * `labels` - Label names to values; a nil value removes the label
+    ///
+    /// # Returns
+    /// Hash of all labels after the update
+    pub fn set_metadata(
+        &self,
+        labels: BTreeMap<String, Option<String>>,
+    ) -> Result<BTreeMap<String, String>, Error> {
+        if labels.keys().any(|name| name.is_empty()) {
+            return Err(invalid_argument_error("metadata key cannot be empty"));
+        }
+
+        block_on_result(async {
+            for _ in 0..METADATA_UPDATE_ATTEMPTS {
+                let (mut metadata, version) =
+                    read_metadata_versioned(&self.object_store, &self.path).await?;
+                for (name, value) in &labels {
+                    match value {
+                        Some(value) => metadata.insert(name.clone(), value.clone()),
+                        None => metadata.remove(name),
+                    };
+                }
+                if write_metadata(&self.object_store, &self.path, &metadata, version).await? {
+                    return Ok(metadata);
+                }
+            }
+            Err(SlateError::transaction(format!(
+                "database metadata changed concurrently {} times in a row",
+                METADATA_UPDATE_ATTEMPTS
+            )))
+        })
+    }
+}
+
+/// Build a slatedb admin for the database at `path` in `object_store`.
+fn build_admin(
+    path: &str,
+    object_store: Arc<dyn ObjectStore>,
+    clock: Option<Arc<dyn SystemClock>>,
+) -> slatedb::admin::Admin {
+    let mut builder = AdminBuilder::new(path, object_store);
+    if let Some(clock) = clock {
+        builder = builder.with_system_clock(clock);
+    }
+    builder.build()
+}
+
+/// Read the database labels, treating a missing metadata object as empty.
+async fn read_metadata(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &Path,
+) -> Result<BTreeMap<String, String>, SlateError> {
+    Ok(read_metadata_versioned(object_store, path).await?.0)
+}
+
+/// Read the database labels along with the version of the metadata object
+/// they came from, or `None` if it does not exist yet.
+async fn read_metadata_versioned(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &Path,
+) -> Result<(BTreeMap<String, String>, Option<UpdateVersion>), SlateError> {
+    let location = path.clone().join(METADATA_FILE);
+    let result = match object_store.get(&location).await {
+        Ok(result) => result,
+        Err(ObjectStoreError::NotFound { .. }) => return Ok((BTreeMap::new(), None)),
+        Err(e) => return Err(to_slate_error(e)),
+    };
+    let version = UpdateVersion {
+        e_tag: result.meta.e_tag.clone(),
+        version: result.meta.version.clone(),
+    };
+    let bytes = result.bytes().await.map_err(to_slate_error)?;
+    let metadata = serde_json::from_slice(&bytes)
+        .map_err(|e| SlateError::data(format!("invalid database metadata: {}", e)))?;
+    Ok((metadata, Some(version)))
+}
+
+/// Replace the database labels if the metadata object is still at `version`.
+///
+/// Returns `false` when another writer got there first and the caller should
+/// re-read and try again. Stores without conditional updates, such as the
+/// local filesystem, fall back to an unconditional overwrite.
+async fn write_metadata(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &Path,
+    metadata: &BTreeMap<String, String>,
+    version: Option<UpdateVersion>,
+) -> Result<bool, SlateError> {
+    let bytes = bytes::Bytes::from(
+        serde_json::to_vec_pretty(metadata)
+            .map_err(|e| SlateError::internal(format!("failed to encode metadata: {}", e)))?,
+    );
+    let location = path.clone().join(METADATA_FILE);
+    let mode = match version {
+        Some(version) => PutMode::Update(version),
+        None => PutMode::Create,
+    };
+    match object_store
+        .put_opts(&location, bytes.clone().into(), mode.into())
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(ObjectStoreError::Precondition { .. } | ObjectStoreError::AlreadyExists { .. }) => {
+            Ok(false)
+        }
+        Err(ObjectStoreError::NotImplemented { .. }) => {
+            object_store
+                .put(&location, bytes.into())
+                .await
+                .map_err(to_slate_error)?;
+            Ok(true)
+        }
+        Err(e) => Err(to_slate_error(e)),
+    }
+}
+
+/// The size of every object under `path`, by location.
+async fn inventory(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &Path,
+) -> Result<HashMap<Path, u64>, SlateError> {
+    object_store
+        .list(Some(path))
+        .map_ok(|meta| (meta.location, meta.size))
+        .try_collect()
+        .await
+        .map_err(to_slate_error)
+}
+
+/// Summarize the objects in `before` that are missing from `after`, grouped
+/// by the directory under `path` they were in.
+fn gc_report(
+    path: &Path,
+    before: &HashMap<Path, u64>,
+    after: &HashMap<Path, u64>,
+    duration: Duration,
+) -> Result<RHash, Error> {
+    let mut directories: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for (location, size) in before {
+        if after.contains_key(location) {
+            continue;
+        }
+        let directory = location
+            .prefix_match(path)
+            .and_then(|mut parts| parts.next())
+            .map_or_else(String::new, |part| part.as_ref().to_string());
+        let deleted = directories.entry(directory).or_default();
+        deleted.0 += 1;
+        deleted.1 += size;
+    }
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let by_directory = ruby.hash_new();
+    for (directory, (objects, bytes)) in &directories {
+        let deleted = ruby.hash_new();
+        deleted.aset(ruby.to_symbol("objects"), *objects)?;
+        deleted.aset(ruby.to_symbol("bytes"), *bytes)?;
+        by_directory.aset(directory.as_str(), deleted)?;
+    }
+
+    let hash = ruby.hash_new();
+    let objects: u64 = directories.values().map(|(objects, _)| objects).sum();
+    let bytes: u64 = directories.values().map(|(_, bytes)| bytes).sum();
+    hash.aset(ruby.to_symbol("deleted_objects"), objects)?;
+    hash.aset(ruby.to_symbol("deleted_bytes"), bytes)?;
+    hash.aset(ruby.to_symbol("duration"), duration.as_secs_f64())?;
+    hash.aset(ruby.to_symbol("directories"), by_directory)?;
+    Ok(hash)
+}
+
+/// Split GC options into one pass per enabled directory, named after it.
+fn gc_passes(opts: GarbageCollectorOptions) -> Vec<(&'static str, GarbageCollectorOptions)> {
+    let none = GarbageCollectorOptions {
+        manifest_options: None,
+        wal_options: None,
+        wal_fence_options: None,
+        compacted_options: None,
+        compactions_options: None,
+        detach_options: None,
+        metric_level: opts.metric_level,
+    };
+    let passes = [
+        (
+            "manifest",
+            GarbageCollectorOptions {
+                manifest_options: opts.manifest_options,
+                ..none.clone()
+            },
+        ),
+        (
+            "wal",
+            GarbageCollectorOptions {
+                wal_options: opts.wal_options,
+                wal_fence_options: opts.wal_fence_options,
+                ..none.clone()
+            },
+        ),
+        (
+            "compacted",
+            GarbageCollectorOptions {
+                compacted_options: opts.compacted_options,
+                ..none.clone()
+            },
+        ),
+        (
+            "compactions",
+            GarbageCollectorOptions {
+                compactions_options: opts.compactions_options,
+                ..none.clone()
+            },
+        ),
+        (
+            "detach",
+            GarbageCollectorOptions {
+                detach_options: opts.detach_options,
+                ..none
+            },
+        ),
+    ];
+    passes
+        .into_iter()
+        .filter(|(_, pass)| !pass.is_empty())
+        .collect()
+}
+
+/// Summary of an incremental backup run.
+pub struct BackupReport {
+    manifest_id: u64,
+    copied_ssts: usize,
+    copied_wal_ssts: usize,
+    copied_bytes: u64,
+}
+
+/// Collect the ids of every compacted SST referenced by a manifest.
+///
+/// The manifest is walked in its serialized form so that both L0 and sorted
+/// run SSTs are found without depending on the manifest's internal types.
+fn referenced_ssts(manifest: &VersionedManifest) -> Result<BTreeSet<String>, SlateError> {
+    fn walk(value: &serde_json::Value, ids: &mut BTreeSet<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(id)) = map.get("Compacted") {
+                    ids.insert(id.clone());
+                }
+                map.values().for_each(|v| walk(v, ids));
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|v| walk(v, ids)),
+            _ => {}
+        }
+    }
+
+    let value = serde_json::to_value(manifest)
+        .map_err(|e| SlateError::internal(format!("failed to serialize manifest: {}", e)))?;
+    let mut ids = BTreeSet::new();
+    walk(&value, &mut ids);
+    Ok(ids)
+}
+
+/// Copy a single object between stores, returning the number of bytes copied,
+/// or `None` if the source object does not exist.
+///
+/// A copy that fails because either store is unavailable or throttling is
+/// retried with backoff as `retry` allows, so a transient failure repeats
+/// only this object rather than the whole backup.
+async fn copy_object(
+    source: &Arc<dyn ObjectStore>,
+    dest: &Arc<dyn ObjectStore>,
+    location: &Path,
+    retry: &RetryPolicy,
+) -> Result<Option<u64>, SlateError> {
+    retry::run(Some(retry), move || async move {
+        let bytes = match source.get(location).await {
+            Ok(result) => result.bytes().await.map_err(to_slate_error)?,
+            Err(ObjectStoreError::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(to_slate_error(e)),
+        };
+        let len = bytes.len() as u64;
+        dest.put(location, bytes.into())
+            .await
+            .map_err(to_slate_error)?;
+        Ok(Some(len))
+    })
+    .await
+}
+
+/// Copy the SSTs added since `since` plus the latest manifest to `dest`.
+///
+/// A short-lived checkpoint pins the manifest being backed up so garbage
+/// collection cannot delete its SSTs while they are being copied. The manifest
+/// is written last, so an interrupted run never leaves the destination pointing
+/// at SSTs that were not copied. `progress` is called after each SST.
+///
+/// The copied manifest is the one that adds the checkpoint, so the checkpoint
+/// is then deleted from the destination as well as the source; otherwise it
+/// would outlive this run in the backup. `since` names a manifest in the
+/// destination, where the previous run left it.
+fn incremental_backup(
+    admin: &slatedb::admin::Admin,
+    path: &Path,
+    source: &Arc<dyn ObjectStore>,
+    dest: &Arc<dyn ObjectStore>,
+    clock: Option<Arc<dyn SystemClock>>,
+    since: Option<u64>,
+    progress: &Progress,
+) -> Result<BackupReport, Error> {
+    let backup = build_admin(path.as_ref(), dest.clone(), clock);
+    let (previous, checkpoint) = block_on_result(async {
+        let previous = match since {
+            Some(id) => referenced_ssts(
+                &backup
+                    .read_manifest(Some(id))
+                    .await?
+                    .ok_or_else(|| SlateError::invalid(format!("manifest {} not found", id)))?,
+            )?,
+            None => BTreeSet::new(),
+        };
+
+        let checkpoint = admin
+            .create_detached_checkpoint(&CheckpointOptions {
+                lifetime: Some(BACKUP_CHECKPOINT_LIFETIME),
+                source: None,
+                name: Some("incremental-backup".to_string()),
+            })
+            .await?;
+        Ok((previous, checkpoint))
+    })?;
+
+    let result = copy_backup(
+        admin,
+        path,
+        source,
+        dest,
+        &previous,
+        checkpoint.manifest_id,
+        progress,
+    )
+    .and_then(|report| {
+        block_on_result(backup.delete_checkpoint(checkpoint.id))?;
+        Ok(report)
+    });
+
+    // A failed copy is the error worth reporting; the checkpoint expires on
+    // its own if it cannot be deleted.
+    let deleted = block_on_result(admin.delete_checkpoint(checkpoint.id));
+    let report = result?;
+    deleted?;
+    Ok(report)
+}
+
+/// Copy the SSTs of manifest `manifest_id` missing from `previous`, the WAL
+/// SSTs after it and finally the manifest itself.
+fn copy_backup(
+    admin: &slatedb::admin::Admin,
+    path: &Path,
+    source: &Arc<dyn ObjectStore>,
+    dest: &Arc<dyn ObjectStore>,
+    previous: &BTreeSet<String>,
+    manifest_id: u64,
+    progress: &Progress,
+) -> Result<BackupReport, Error> {
+    let manifest = block_on_result(async {
+        admin
+            .read_manifest(Some(manifest_id))
+            .await?
+            .ok_or_else(|| SlateError::internal(format!("manifest {} not found", manifest_id)))
+    })?;
+
+    let mut report = BackupReport {
+        manifest_id,
+        copied_ssts: 0,
+        copied_wal_ssts: 0,
+        copied_bytes: 0,
+    };
+    let report_progress = |phase: &str, report: &BackupReport, total: u64| {
+        progress.report_phase(
+            Some(phase),
+            &[
+                ("copied_ssts", report.copied_ssts as u64),
+                ("total_ssts", total),
+                ("copied_wal_ssts", report.copied_wal_ssts as u64),
+                ("copied_bytes", report.copied_bytes),
+            ],
+            Some(progress::percent(report.copied_ssts as u64, total)),
+        )
+    };
+
+    let ssts: Vec<String> = referenced_ssts(&manifest)
+        .map_err(map_error)?
+        .difference(previous)
+        .cloned()
+        .collect();
+    let total = ssts.len() as u64;
+    let retry = RetryPolicy::default();
+    for id in ssts {
+        let location = path.clone().join("compacted").join(format!("{}.sst", id));
+        let copied = block_on_result(async {
+            copy_object(source, dest, &location, &retry)
+                .await?
+                .ok_or_else(|| {
+                    SlateError::data(format!("SST {} referenced by manifest is missing", id))
+                })
         })?;
+        report.copied_ssts += 1;
+        report.copied_bytes += copied;
+        report_progress("ssts", &report, total)?;
+    }
+
+    block_on_result(async {
+        // WAL SSTs after the manifest's replay point hold writes that have not
+        // reached L0 yet. Their ids are contiguous, so copy until the first gap.
+        let mut wal_id = manifest.replay_after_wal_id() + 1;
+        loop {
+            let location = path.clone().join("wal").join(format!("{:020}.sst", wal_id));
+            match copy_object(source, dest, &location, &retry).await? {
+                Some(copied) => {
+                    report.copied_wal_ssts += 1;
+                    report.copied_bytes += copied;
+                    wal_id += 1;
+                }
+                None => break,
+            }
+        }
 
+        let location = path
+            .clone()
+            .join("manifest")
+            .join(format!("{:020}.manifest", manifest_id));
+        let copied = copy_object(source, dest, &location, &retry)
+            .await?
+            .ok_or_else(|| SlateError::internal(format!("manifest {} not found", manifest_id)))?;
+        report.copied_bytes += copied;
         Ok(())
+    })?;
+    report_progress("manifest", &report, total)?;
+
+    Ok(report)
+}
+
+/// Result of verifying a backup.
+pub struct VerifyReport {
+    manifest_id: u64,
+    checked_ssts: usize,
+    missing_ssts: Vec<String>,
+    keys: u64,
+    bytes: u64,
+    fingerprint: Option<String>,
+    error: Option<String>,
+}
+
+/// Order-sensitive FNV-1a fingerprint over every key/value pair in a scan.
+///
+/// Lengths are mixed in before each key and value so that moving bytes between
+/// adjacent entries changes the fingerprint.
+struct Fingerprint(u64);
+
+impl Fingerprint {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for chunk in [&(bytes.len() as u64).to_le_bytes()[..], bytes] {
+            for byte in chunk {
+                self.0 ^= u64::from(*byte);
+                self.0 = self.0.wrapping_mul(Self::PRIME);
+            }
+        }
+    }
+}
+
+/// Check that a backup's SSTs exist and that every entry can be read back.
+///
+/// Nothing is written to the backup. Read failures (missing files,
+/// checksum mismatches) are recorded in the report rather than returned as
+/// errors, so a failed verification still describes what was checked. `progress` is called once the SSTs have been
+/// checked and then every `VERIFY_PROGRESS_KEYS` keys.
+fn verify_backup(
+    path: &Path,
+    backup: Arc<dyn ObjectStore>,
+    checkpoint_id: Option<uuid::Uuid>,
+    progress: &Progress,
+) -> Result<VerifyReport, Error> {
+    let mut report = block_on_result(check_backup_ssts(path, &backup, checkpoint_id))?;
+    progress.report_phase(
+        Some("ssts"),
+        &[
+            ("checked_ssts", report.checked_ssts as u64),
+            ("missing_ssts", report.missing_ssts.len() as u64),
+        ],
+        None,
+    )?;
+    if !report.missing_ssts.is_empty() {
+        return Ok(report);
+    }
+
+    // Without a checkpoint id the reader registers a checkpoint of its own,
+    // which the overlay keeps out of the backup.
+    let opened = block_on(DbReader::open(
+        path.clone(),
+        Arc::new(OverlayStore::new(backup)),
+        checkpoint_id,
+        DbReaderOptions::default(),
+    ))?;
+    let reader = match opened {
+        Ok(reader) => reader,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return Ok(report);
+        }
+    };
+
+    let mut fingerprint = Fingerprint::new();
+    let scanned = scan_backup(&reader, &mut report, &mut fingerprint, progress);
+    let closed = block_on_result(reader.close());
+    match scanned? {
+        Ok(()) => report.fingerprint = Some(format!("{:016x}", fingerprint.0)),
+        Err(e) => report.error = Some(e.to_string()),
+    }
+    closed?;
+
+    Ok(report)
+}
+
+/// Find the manifest to verify and check that every SST it references
+/// exists in the backup.
+async fn check_backup_ssts(
+    path: &Path,
+    backup: &Arc<dyn ObjectStore>,
+    checkpoint_id: Option<uuid::Uuid>,
+) -> Result<VerifyReport, SlateError> {
+    let admin = AdminBuilder::new(path.clone(), backup.clone()).build();
+
+    let manifest_id = match checkpoint_id {
+        Some(id) => admin
+            .list_checkpoints(None)
+            .await?
+            .into_iter()
+            .find(|cp| cp.id == id)
+            .map(|cp| cp.manifest_id)
+            .ok_or_else(|| SlateError::invalid(format!("checkpoint {} not found", id)))?,
+        None => admin
+            .read_manifest(None)
+            .await?
+            .ok_or_else(|| SlateError::invalid("no manifest found at backup location".into()))?
+            .id(),
+    };
+    let manifest = admin
+        .read_manifest(Some(manifest_id))
+        .await?
+        .ok_or_else(|| SlateError::data(format!("manifest {} not found", manifest_id)))?;
+
+    let ssts = referenced_ssts(&manifest)?;
+    let mut report = VerifyReport {
+        manifest_id,
+        checked_ssts: ssts.len(),
+        missing_ssts: Vec::new(),
+        keys: 0,
+        bytes: 0,
+        fingerprint: None,
+        error: None,
+    };
+
+    for id in ssts {
+        let location = path.clone().join("compacted").join(format!("{}.sst", id));
+        match backup.head(&location).await {
+            Ok(_) => {}
+            Err(ObjectStoreError::NotFound { .. }) => report.missing_ssts.push(id),
+            Err(e) => return Err(to_slate_error(e)),
+        }
+    }
+    Ok(report)
+}
+
+/// Read every entry of the backup into `fingerprint`, in chunks of
+/// `VERIFY_PROGRESS_KEYS` keys with progress reported between them.
+///
+/// The outer error is an exception raised by the progress block; the inner
+/// one is a read failure, which belongs in the report.
+fn scan_backup(
+    reader: &DbReader,
+    report: &mut VerifyReport,
+    fingerprint: &mut Fingerprint,
+    progress: &Progress,
+) -> Result<Result<(), SlateError>, Error> {
+    let mut iter = match block_on(reader.scan(..))? {
+        Ok(iter) => iter,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    loop {
+        let chunk: Result<bool, SlateError> = block_on(async {
+            for _ in 0..VERIFY_PROGRESS_KEYS {
+                let Some(kv) = iter.next().await? else {
+                    return Ok(true);
+                };
+                fingerprint.update(&kv.key);
+                fingerprint.update(&kv.value);
+                report.keys += 1;
+                report.bytes += (kv.key.len() + kv.value.len()) as u64;
+            }
+            Ok(false)
+        })?;
+
+        let done = match chunk {
+            Ok(done) => done,
+            Err(e) => return Ok(Err(e)),
+        };
+        progress.report_phase(
+            Some("entries"),
+            &[("keys", report.keys), ("bytes", report.bytes)],
+            None,
+        )?;
+        if done {
+            return Ok(Ok(()));
+        }
     }
 }
 
@@ -270,16 +1295,33 @@ pub fn define_admin_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), E
     let class = module.define_class("Admin", ruby.class_object())?;
 
     // Class methods
-    class.define_singleton_method("_new", function!(Admin::new, 2))?;
+    class.define_singleton_method("_new", function!(Admin::new, 3))?;
 
     // Instance methods
     class.define_method("_read_manifest", method!(Admin::read_manifest, 1))?;
     class.define_method("_list_manifests", method!(Admin::list_manifests, 2))?;
     class.define_method("_create_checkpoint", method!(Admin::create_checkpoint, 1))?;
+    class.define_method(
+        "_create_checkpoint_replacing",
+        method!(Admin::create_checkpoint_replacing, 1),
+    )?;
     class.define_method("_list_checkpoints", method!(Admin::list_checkpoints, 1))?;
     class.define_method("_refresh_checkpoint", method!(Admin::refresh_checkpoint, 2))?;
+    class.define_method("_hold_checkpoint", method!(Admin::hold_checkpoint, 2))?;
+    class.define_method("_active_writer", method!(Admin::active_writer, 0))?;
     class.define_method("_delete_checkpoint", method!(Admin::delete_checkpoint, 1))?;
-    class.define_method("_run_gc", method!(Admin::run_gc, 1))?;
+    class.define_method("_run_gc", method!(Admin::run_gc, 2))?;
+    class.define_method("_incremental_backup", method!(Admin::incremental_backup, 3))?;
+    class.define_method("_verify_backup", method!(Admin::verify_backup, 3))?;
+    class.define_method("_export", method!(Admin::export, 3))?;
+    class.define_method("_import", method!(Admin::import, 3))?;
+    class.define_method("_rewrap_keys", method!(Admin::rewrap_keys, 2))?;
+    class.define_method("_sample_stats", method!(Admin::sample_stats, 1))?;
+    class.define_method("_prefix_stats", method!(Admin::prefix_stats, 2))?;
+    class.define_method("_expired_stats", method!(Admin::expired_stats, 1))?;
+    class.define_method("_purge_expired", method!(Admin::purge_expired, 1))?;
+    class.define_method("_metadata", method!(Admin::metadata, 0))?;
+    class.define_method("_set_metadata", method!(Admin::set_metadata, 1))?;
 
     Ok(())
 }
@@ -0,0 +1,362 @@
+use flatbuffers::{FlatBufferBuilder, Push, TableFinishedWIPOffset, UnionWIPOffset, WIPOffset};
+use magnus::{Error, RHash};
+use slatedb::KeyValue;
+
+use crate::errors::invalid_argument_error;
+use crate::utils::get_optional;
+
+/// Rows per record batch when `batch_size` is not given.
+pub const DEFAULT_BATCH_SIZE: usize = 65_536;
+
+/// Key and value bytes after which a record batch is cut short, keeping
+/// 32-bit offsets well in range.
+const MAX_BATCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// Arrow IPC metadata version V5.
+const METADATA_VERSION: i16 = 4;
+
+// MessageHeader union members.
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+
+// Type union members.
+const TYPE_INT: u8 = 2;
+const TYPE_BINARY: u8 = 4;
+const TYPE_UTF8: u8 = 5;
+const TYPE_TIMESTAMP: u8 = 10;
+
+/// TimeUnit.MILLISECOND, the unit of SlateDB timestamps.
+const TIME_UNIT_MILLISECOND: i16 = 1;
+
+/// Which part of an entry a column holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Source {
+    Key,
+    Value,
+    Seq,
+    CreateTs,
+    ExpireTs,
+}
+
+/// The Arrow type of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColumnType {
+    Utf8,
+    Binary,
+    UInt64,
+    Int64,
+    Timestamp,
+}
+
+#[derive(Clone, Debug)]
+struct Column {
+    name: String,
+    source: Source,
+    data_type: ColumnType,
+}
+
+impl Column {
+    fn parse(name: String, data_type: &str) -> Result<Self, Error> {
+        let source = match name.as_str() {
+            "key" => Source::Key,
+            "value" => Source::Value,
+            "seq" => Source::Seq,
+            "create_ts" => Source::CreateTs,
+            "expire_ts" => Source::ExpireTs,
+            other => {
+                return Err(invalid_argument_error(&format!(
+                    "unknown schema column: {} (expected key, value, seq, create_ts or expire_ts)",
+                    other
+                )))
+            }
+        };
+        let data_type = match (source, data_type) {
+            (Source::Key | Source::Value, "utf8") => ColumnType::Utf8,
+            (Source::Key | Source::Value, "binary") => ColumnType::Binary,
+            (Source::Seq, "uint64") => ColumnType::UInt64,
+            (Source::CreateTs | Source::ExpireTs, "timestamp") => ColumnType::Timestamp,
+            (Source::CreateTs | Source::ExpireTs, "int64") => ColumnType::Int64,
+            (_, other) => {
+                return Err(invalid_argument_error(&format!(
+                    "invalid type for schema column {}: {}",
+                    name, other
+                )))
+            }
+        };
+        Ok(Self {
+            name,
+            source,
+            data_type,
+        })
+    }
+
+    /// Only `expire_ts` has nulls, for entries without a TTL.
+    fn nullable(&self) -> bool {
+        self.source == Source::ExpireTs
+    }
+}
+
+/// The columns `scan_arrow` writes, from its `schema` option: pairs of
+/// column name and type, in order. Without it, keys are written as Utf8 and
+/// values as Binary.
+#[derive(Clone, Debug)]
+pub struct ArrowSchema {
+    columns: Vec<Column>,
+}
+
+impl ArrowSchema {
+    /// Parse the `schema` option.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let Some(pairs) = get_optional::<Vec<(String, String)>>(kwargs, "schema")? else {
+            return Ok(Self {
+                columns: vec![
+                    Column::parse("key".to_string(), "utf8")?,
+                    Column::parse("value".to_string(), "binary")?,
+                ],
+            });
+        };
+        if pairs.is_empty() {
+            return Err(invalid_argument_error(
+                "schema must have at least one column",
+            ));
+        }
+
+        let mut columns: Vec<Column> = Vec::with_capacity(pairs.len());
+        for (name, data_type) in pairs {
+            let column = Column::parse(name, &data_type)?;
+            if columns.iter().any(|c| c.source == column.source) {
+                return Err(invalid_argument_error(&format!(
+                    "duplicate schema column: {}",
+                    column.name
+                )));
+            }
+            columns.push(column);
+        }
+        Ok(Self { columns })
+    }
+}
+
+/// A FieldNode or Buffer struct: two little-endian 64-bit integers.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+struct Pair64(i64, i64);
+
+impl Push for Pair64 {
+    type Output = Pair64;
+
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        dst[..8].copy_from_slice(&self.0.to_le_bytes());
+        dst[8..16].copy_from_slice(&self.1.to_le_bytes());
+    }
+}
+
+/// Writes scanned entries as an Arrow IPC stream: a schema message, one
+/// record batch message per `batch_size` rows, and an end-of-stream marker.
+///
+/// The stream can be read by any Arrow implementation, e.g.
+/// `Arrow::Table.load(Arrow::Buffer.new(bytes), format: :arrow_streaming)`
+/// with red-arrow or `pyarrow.ipc.open_stream` in Python.
+pub struct ArrowStreamWriter {
+    schema: ArrowSchema,
+    batch_size: usize,
+    rows: Vec<KeyValue>,
+    row_bytes: usize,
+    out: Vec<u8>,
+}
+
+impl ArrowStreamWriter {
+    pub fn new(schema: ArrowSchema, batch_size: usize) -> Self {
+        let mut writer = Self {
+            schema,
+            batch_size,
+            rows: Vec::new(),
+            row_bytes: 0,
+            out: Vec::new(),
+        };
+        writer.write_schema();
+        writer
+    }
+
+    /// Add an entry, writing a record batch once enough have been added.
+    pub fn push(&mut self, kv: KeyValue) {
+        self.row_bytes += kv.key.len() + kv.value.len();
+        self.rows.push(kv);
+        if self.rows.len() >= self.batch_size || self.row_bytes >= MAX_BATCH_BYTES {
+            self.write_record_batch();
+        }
+    }
+
+    /// Write any remaining entries and the end-of-stream marker.
+    pub fn finish(mut self) -> Vec<u8> {
+        if !self.rows.is_empty() {
+            self.write_record_batch();
+        }
+        self.out.extend_from_slice(&u32::MAX.to_le_bytes());
+        self.out.extend_from_slice(&0u32.to_le_bytes());
+        self.out
+    }
+
+    fn write_schema(&mut self) {
+        let mut fbb = FlatBufferBuilder::new();
+        let mut fields = Vec::with_capacity(self.schema.columns.len());
+        for column in &self.schema.columns {
+            let name = fbb.create_string(&column.name);
+            let (type_type, data_type) = column_type(&mut fbb, column.data_type);
+            let children = fbb.create_vector::<WIPOffset<TableFinishedWIPOffset>>(&[]);
+
+            let start = fbb.start_table();
+            fbb.push_slot_always(4, name);
+            fbb.push_slot::<bool>(6, column.nullable(), false);
+            fbb.push_slot::<u8>(8, type_type, 0);
+            fbb.push_slot_always(10, data_type);
+            fbb.push_slot_always(14, children);
+            fields.push(fbb.end_table(start));
+        }
+        let fields = fbb.create_vector(&fields);
+
+        let start = fbb.start_table();
+        fbb.push_slot_always(6, fields);
+        let schema = fbb.end_table(start);
+
+        self.write_message(fbb, HEADER_SCHEMA, schema.as_union_value(), &[]);
+    }
+
+    fn write_record_batch(&mut self) {
+        let rows = std::mem::take(&mut self.rows);
+        self.row_bytes = 0;
+
+        let mut body = Vec::new();
+        let mut nodes = Vec::with_capacity(self.schema.columns.len());
+        let mut buffers = Vec::new();
+        for column in &self.schema.columns {
+            let null_count = match column.source {
+                Source::ExpireTs => rows.iter().filter(|kv| kv.expire_ts.is_none()).count(),
+                _ => 0,
+            };
+            nodes.push(Pair64(rows.len() as i64, null_count as i64));
+
+            let validity = if null_count > 0 {
+                let mut bitmap = vec![0u8; rows.len().div_ceil(8)];
+                for (i, kv) in rows.iter().enumerate() {
+                    if kv.expire_ts.is_some() {
+                        bitmap[i / 8] |= 1 << (i % 8);
+                    }
+                }
+                bitmap
+            } else {
+                Vec::new()
+            };
+            buffers.push(append_buffer(&mut body, &validity));
+
+            match column.source {
+                Source::Key | Source::Value => {
+                    let mut offsets = Vec::with_capacity((rows.len() + 1) * 4);
+                    let mut data = Vec::new();
+                    offsets.extend_from_slice(&0i32.to_le_bytes());
+                    for kv in &rows {
+                        let bytes = if column.source == Source::Key {
+                            &kv.key
+                        } else {
+                            &kv.value
+                        };
+                        if column.data_type == ColumnType::Utf8 {
+                            data.extend_from_slice(String::from_utf8_lossy(bytes).as_bytes());
+                        } else {
+                            data.extend_from_slice(bytes);
+                        }
+                        offsets.extend_from_slice(&(data.len() as i32).to_le_bytes());
+                    }
+                    buffers.push(append_buffer(&mut body, &offsets));
+                    buffers.push(append_buffer(&mut body, &data));
+                }
+                Source::Seq | Source::CreateTs | Source::ExpireTs => {
+                    let mut values = Vec::with_capacity(rows.len() * 8);
+                    for kv in &rows {
+                        let value = match column.source {
+                            Source::Seq => kv.seq as i64,
+                            Source::CreateTs => kv.create_ts,
+                            _ => kv.expire_ts.unwrap_or(0),
+                        };
+                        values.extend_from_slice(&value.to_le_bytes());
+                    }
+                    buffers.push(append_buffer(&mut body, &values));
+                }
+            }
+        }
+
+        let mut fbb = FlatBufferBuilder::new();
+        let nodes = fbb.create_vector(&nodes);
+        let buffers = fbb.create_vector(&buffers);
+        let start = fbb.start_table();
+        fbb.push_slot::<i64>(4, rows.len() as i64, 0);
+        fbb.push_slot_always(6, nodes);
+        fbb.push_slot_always(8, buffers);
+        let batch = fbb.end_table(start);
+
+        self.write_message(fbb, HEADER_RECORD_BATCH, batch.as_union_value(), &body);
+    }
+
+    /// Write an encapsulated message: continuation marker, metadata length,
+    /// the Message flatbuffer padded to 8 bytes, then the body.
+    fn write_message(
+        &mut self,
+        mut fbb: FlatBufferBuilder,
+        header_type: u8,
+        header: WIPOffset<UnionWIPOffset>,
+        body: &[u8],
+    ) {
+        let start = fbb.start_table();
+        fbb.push_slot::<i64>(10, body.len() as i64, 0);
+        fbb.push_slot_always(8, header);
+        fbb.push_slot::<i16>(4, METADATA_VERSION, 0);
+        fbb.push_slot::<u8>(6, header_type, 0);
+        let message = fbb.end_table(start);
+        fbb.finish(message, None);
+
+        let metadata = fbb.finished_data();
+        let padded = (metadata.len() + 8).next_multiple_of(8) - 8;
+        self.out.extend_from_slice(&u32::MAX.to_le_bytes());
+        self.out.extend_from_slice(&(padded as i32).to_le_bytes());
+        self.out.extend_from_slice(metadata);
+        self.out.resize(self.out.len() + padded - metadata.len(), 0);
+        self.out.extend_from_slice(body);
+    }
+}
+
+/// The Type union member and table for a column type.
+fn column_type(
+    fbb: &mut FlatBufferBuilder,
+    data_type: ColumnType,
+) -> (u8, WIPOffset<UnionWIPOffset>) {
+    match data_type {
+        ColumnType::Utf8 => (TYPE_UTF8, empty_table(fbb)),
+        ColumnType::Binary => (TYPE_BINARY, empty_table(fbb)),
+        ColumnType::UInt64 | ColumnType::Int64 => {
+            let start = fbb.start_table();
+            fbb.push_slot::<i32>(4, 64, 0);
+            fbb.push_slot::<bool>(6, data_type == ColumnType::Int64, false);
+            (TYPE_INT, fbb.end_table(start).as_union_value())
+        }
+        ColumnType::Timestamp => {
+            let timezone = fbb.create_string("UTC");
+            let start = fbb.start_table();
+            fbb.push_slot::<i16>(4, TIME_UNIT_MILLISECOND, 0);
+            fbb.push_slot_always(6, timezone);
+            (TYPE_TIMESTAMP, fbb.end_table(start).as_union_value())
+        }
+    }
+}
+
+fn empty_table(fbb: &mut FlatBufferBuilder) -> WIPOffset<UnionWIPOffset> {
+    let start = fbb.start_table();
+    fbb.end_table(start).as_union_value()
+}
+
+/// Append a buffer to a record batch body, padded to 8 bytes, and return
+/// its offset and unpadded length.
+fn append_buffer(body: &mut Vec<u8>, bytes: &[u8]) -> Pair64 {
+    let offset = body.len();
+    body.extend_from_slice(bytes);
+    body.resize(body.len().next_multiple_of(8), 0);
+    Pair64(offset as i64, bytes.len() as i64)
+}
@@ -0,0 +1,599 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::ops::Bound;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use magnus::block::Proc;
+use magnus::prelude::*;
+use magnus::{Error, RHash, RString, Ruby, Value};
+use serde_json::{Map, Value as JsonValue};
+use slatedb::config::{DbReaderOptions, PutOptions, ScanOptions, Ttl, WriteOptions};
+use slatedb::object_store::path::Path;
+use slatedb::object_store::ObjectStore;
+use slatedb::{Db, DbReader, Error as SlateError, KeyValue, WriteBatch};
+use slatedb_common::clock::SystemClock;
+
+use crate::clock;
+use crate::errors::{invalid_argument_error, map_error};
+use crate::progress::{self, Progress};
+use crate::runtime::{block_on, block_on_result};
+use crate::utils::{get_optional, rstring_to_bytes};
+
+/// Entries per export chunk or import batch when `batch_size` is not given.
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// Bytes read from an import source at a time.
+const READ_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// The first line of a CSV export.
+const CSV_HEADER: &[u8] = b"key,value,expire_ts\n";
+
+/// A logical export format, from the `format` option.
+///
+/// Both formats hold one entry per line (or CSV record) with its key, value
+/// and, for entries with a TTL, its expiry time in milliseconds since the
+/// Unix epoch. JSON Lines stores keys and values that are not valid UTF-8
+/// base64-encoded, under `key_base64` and `value_base64`; CSV holds text only.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Jsonl,
+    Csv,
+}
+
+impl Format {
+    /// Parse the `format` option, defaulting to JSON Lines.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        match get_optional::<String>(kwargs, "format")?.as_deref() {
+            None | Some("jsonl") => Ok(Self::Jsonl),
+            Some("csv") => Ok(Self::Csv),
+            Some(other) => Err(invalid_argument_error(&format!(
+                "invalid format: {} (expected jsonl or csv)",
+                other
+            ))),
+        }
+    }
+
+    /// Append one entry to `out`.
+    fn write_entry(self, out: &mut Vec<u8>, kv: &KeyValue) -> Result<(), SlateError> {
+        match self {
+            Self::Jsonl => {
+                out.push(b'{');
+                write_json_bytes(out, "key", &kv.key);
+                out.push(b',');
+                write_json_bytes(out, "value", &kv.value);
+                if let Some(expire_ts) = kv.expire_ts {
+                    out.extend_from_slice(format!(",\"expire_ts\":{}", expire_ts).as_bytes());
+                }
+                out.push(b'}');
+            }
+            Self::Csv => {
+                for (name, bytes) in [("key", &kv.key), ("value", &kv.value)] {
+                    let text = std::str::from_utf8(bytes).map_err(|_| {
+                        SlateError::invalid(format!(
+                            "{} of {:?} is not valid UTF-8; export binary data with format: :jsonl",
+                            name,
+                            String::from_utf8_lossy(&kv.key)
+                        ))
+                    })?;
+                    write_csv_field(out, text);
+                    out.push(b',');
+                }
+                if let Some(expire_ts) = kv.expire_ts {
+                    out.extend_from_slice(expire_ts.to_string().as_bytes());
+                }
+            }
+        }
+        out.push(b'\n');
+        Ok(())
+    }
+}
+
+/// Append `bytes` as the JSON member `name`, or base64-encoded as
+/// `{name}_base64` if they are not valid UTF-8.
+fn write_json_bytes(out: &mut Vec<u8>, name: &str, bytes: &[u8]) {
+    let (name, text) = match std::str::from_utf8(bytes) {
+        Ok(text) => (JsonValue::from(name), JsonValue::from(text)),
+        Err(_) => (
+            JsonValue::from(format!("{}_base64", name)),
+            JsonValue::from(BASE64.encode(bytes)),
+        ),
+    };
+    out.extend_from_slice(format!("{}:{}", name, text).as_bytes());
+}
+
+/// Append a CSV field, quoting it if needed.
+fn write_csv_field(out: &mut Vec<u8>, text: &str) {
+    if text.contains([',', '"', '\n', '\r']) {
+        out.push(b'"');
+        out.extend_from_slice(text.replace('"', "\"\"").as_bytes());
+        out.push(b'"');
+    } else {
+        out.extend_from_slice(text.as_bytes());
+    }
+}
+
+/// An entry read from an import.
+struct Entry {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    expire_ts: Option<i64>,
+}
+
+/// Splits an import stream into entries, holding a partial line or record
+/// until the rest of it has been read.
+struct Parser {
+    format: Format,
+    pending: Vec<u8>,
+    line: usize,
+}
+
+impl Parser {
+    fn new(format: Format) -> Self {
+        Self {
+            format,
+            pending: Vec::new(),
+            line: 0,
+        }
+    }
+
+    /// Parse the complete entries in `data`, or every remaining entry at the
+    /// end of the stream.
+    fn feed(&mut self, data: &[u8], eof: bool) -> Result<Vec<Entry>, SlateError> {
+        self.pending.extend_from_slice(data);
+        let pending = std::mem::take(&mut self.pending);
+        let mut entries = Vec::new();
+        let mut rest = pending.as_slice();
+
+        while let Some((record, consumed)) = self.next_record(rest, eof) {
+            rest = &rest[consumed..];
+            self.line += 1;
+            let entry = match self.format {
+                Format::Jsonl => parse_json_line(record),
+                Format::Csv => parse_csv_record(record, self.line == 1),
+            }
+            .map_err(|reason| {
+                SlateError::invalid(format!("cannot import line {}: {}", self.line, reason))
+            })?;
+            entries.extend(entry);
+        }
+
+        self.pending = rest.to_vec();
+        Ok(entries)
+    }
+
+    /// Find the first line or record in `data`, returning it without its line
+    /// ending and the number of bytes it used. CSV records end at the first
+    /// line break outside quotes.
+    fn next_record<'a>(&self, data: &'a [u8], eof: bool) -> Option<(&'a [u8], usize)> {
+        let mut quoted = false;
+        for (i, &byte) in data.iter().enumerate() {
+            match byte {
+                b'"' if self.format == Format::Csv => quoted = !quoted,
+                b'\n' if !quoted => {
+                    let record = &data[..i];
+                    return Some((record.strip_suffix(b"\r").unwrap_or(record), i + 1));
+                }
+                _ => {}
+            }
+        }
+        (eof && !data.is_empty()).then_some((data, data.len()))
+    }
+}
+
+/// Parse a JSON Lines entry; blank lines hold none.
+fn parse_json_line(line: &[u8]) -> Result<Option<Entry>, String> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    let object: Map<String, JsonValue> =
+        serde_json::from_slice(line).map_err(|e| format!("invalid JSON: {}", e))?;
+
+    let bytes = |name: &str| -> Result<Vec<u8>, String> {
+        if let Some(text) = object.get(name) {
+            let text = text.as_str().ok_or(format!("{} must be a string", name))?;
+            return Ok(text.as_bytes().to_vec());
+        }
+        let encoded_name = format!("{}_base64", name);
+        let encoded = object
+            .get(&encoded_name)
+            .ok_or(format!("missing {}", name))?
+            .as_str()
+            .ok_or(format!("{} must be a string", encoded_name))?;
+        BASE64
+            .decode(encoded)
+            .map_err(|e| format!("invalid {}: {}", encoded_name, e))
+    };
+    let expire_ts = match object.get("expire_ts") {
+        None | Some(JsonValue::Null) => None,
+        Some(ts) => Some(ts.as_i64().ok_or("expire_ts must be an integer")?),
+    };
+
+    Ok(Some(Entry {
+        key: bytes("key")?,
+        value: bytes("value")?,
+        expire_ts,
+    }))
+}
+
+/// Parse a CSV record of key, value and optional expire_ts. The header
+/// record, if present, holds none.
+fn parse_csv_record(record: &[u8], first: bool) -> Result<Option<Entry>, String> {
+    let record = std::str::from_utf8(record).map_err(|_| "not valid UTF-8".to_string())?;
+    let fields = split_csv_record(record)?;
+    if first && fields.first().map(String::as_str) == Some("key") {
+        return Ok(None);
+    }
+
+    let mut fields = fields.into_iter();
+    let (Some(key), Some(value)) = (fields.next(), fields.next()) else {
+        return Err("expected key,value[,expire_ts]".to_string());
+    };
+    let expire_ts = match fields.next().as_deref() {
+        None | Some("") => None,
+        Some(ts) => Some(
+            ts.parse::<i64>()
+                .map_err(|_| format!("invalid expire_ts: {:?}", ts))?,
+        ),
+    };
+    if fields.next().is_some() {
+        return Err("expected key,value[,expire_ts]".to_string());
+    }
+
+    Ok(Some(Entry {
+        key: key.into_bytes(),
+        value: value.into_bytes(),
+        expire_ts,
+    }))
+}
+
+/// Split a CSV record into fields, unquoting quoted ones.
+fn split_csv_record(record: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = record.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', false) if field.is_empty() => quoted = true,
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => {
+                quoted = false;
+                if !matches!(chars.peek(), None | Some(',')) {
+                    return Err("unexpected character after closing quote".to_string());
+                }
+            }
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Where an export is written.
+enum Sink {
+    File(String, BufWriter<File>),
+    Io(Value),
+}
+
+impl Sink {
+    fn open(dest: Value) -> Result<Self, Error> {
+        if let Some(path) = RString::from_value(dest) {
+            let path = path.to_string()?;
+            let file = File::create(&path).map_err(|e| io_error(&path, e))?;
+            return Ok(Self::File(path, BufWriter::new(file)));
+        }
+        if dest.respond_to("write", false)? {
+            return Ok(Self::Io(dest));
+        }
+        Err(invalid_argument_error(
+            "export destination must be a path or respond to write",
+        ))
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        match self {
+            Self::File(path, file) => {
+                block_on(async { file.write_all(data) })?.map_err(|e| io_error(path, e))
+            }
+            Self::Io(io) => {
+                let ruby = Ruby::get().expect("Ruby runtime not available");
+                io.funcall::<_, _, Value>("write", (ruby.str_from_slice(data),))?;
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            Self::File(path, mut file) => {
+                block_on(async { file.flush() })?.map_err(|e| io_error(&path, e))
+            }
+            Self::Io(_) => Ok(()),
+        }
+    }
+}
+
+/// Where an import is read from.
+enum Source {
+    File(String, File, u64),
+    Io(Value),
+}
+
+impl Source {
+    fn open(source: Value) -> Result<Self, Error> {
+        if let Some(path) = RString::from_value(source) {
+            let path = path.to_string()?;
+            let file = File::open(&path).map_err(|e| io_error(&path, e))?;
+            let size = file.metadata().map_err(|e| io_error(&path, e))?.len();
+            return Ok(Self::File(path, file, size));
+        }
+        if source.respond_to("read", false)? {
+            return Ok(Self::Io(source));
+        }
+        Err(invalid_argument_error(
+            "import source must be a path or respond to read",
+        ))
+    }
+
+    /// Read the next chunk, or an empty one at the end of the stream.
+    fn read(&mut self) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::File(path, file, _) => {
+                let mut chunk = vec![0; READ_CHUNK_BYTES];
+                let len =
+                    block_on(async { file.read(&mut chunk) })?.map_err(|e| io_error(path, e))?;
+                chunk.truncate(len);
+                Ok(chunk)
+            }
+            Self::Io(io) => {
+                let chunk: Option<RString> = io.funcall("read", (READ_CHUNK_BYTES,))?;
+                Ok(chunk.map(rstring_to_bytes).unwrap_or_default())
+            }
+        }
+    }
+
+    /// Percent of the source read, when its size is known.
+    fn percent(&self, read: u64) -> Option<f64> {
+        match self {
+            Self::File(_, _, size) => Some(progress::percent(read, *size)),
+            Self::Io(_) => None,
+        }
+    }
+}
+
+fn io_error(path: &str, e: std::io::Error) -> Error {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    Error::new(ruby.exception_io_error(), format!("{}: {}", path, e))
+}
+
+fn batch_size_from_kwargs(kwargs: &RHash) -> Result<usize, Error> {
+    match get_optional::<usize>(kwargs, "batch_size")? {
+        None => Ok(DEFAULT_BATCH_SIZE),
+        Some(0) => Err(invalid_argument_error("batch_size must be positive")),
+        Some(size) => Ok(size),
+    }
+}
+
+/// The key range to export, from the `range_start`, `range_end` and
+/// `range_exclusive` options.
+fn range_from_kwargs(kwargs: &RHash) -> Result<(Bound<Vec<u8>>, Bound<Vec<u8>>), Error> {
+    let start = get_optional::<String>(kwargs, "range_start")?;
+    let end = get_optional::<String>(kwargs, "range_end")?;
+    let exclusive = get_optional::<bool>(kwargs, "range_exclusive")?.unwrap_or(false);
+
+    let start = start.map_or(Bound::Unbounded, |s| Bound::Included(s.into_bytes()));
+    let end = match end {
+        None => Bound::Unbounded,
+        Some(e) if exclusive => Bound::Excluded(e.into_bytes()),
+        Some(e) => Bound::Included(e.into_bytes()),
+    };
+    Ok((start, end))
+}
+
+/// Export the database at `path` to a file or IO.
+///
+/// Entries are read through a `DbReader`, so the export sees a consistent
+/// view and doesn't disturb the writer, in chunks of `batch_size` entries.
+/// Each chunk is formatted with the GVL released; the GVL is held only to
+/// write to an IO and to report progress.
+///
+/// # Returns
+/// Hash with entries and bytes
+pub fn export(
+    path: &Path,
+    object_store: &Arc<dyn ObjectStore>,
+    dest: Value,
+    kwargs: RHash,
+    progress: Option<Proc>,
+) -> Result<RHash, Error> {
+    let format = Format::from_kwargs(&kwargs)?;
+    let batch_size = batch_size_from_kwargs(&kwargs)?;
+    let range = range_from_kwargs(&kwargs)?;
+    let progress = Progress::new(progress);
+    let mut sink = Sink::open(dest)?;
+    let reader = block_on_result(DbReader::open(
+        path.clone(),
+        object_store.clone(),
+        None,
+        DbReaderOptions::default(),
+    ))?;
+    let written = export_entries(&reader, range, format, batch_size, &mut sink, &progress);
+    let closed = block_on_result(reader.close());
+    let (entries, bytes) = written?;
+    closed?;
+    sink.finish()?;
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = ruby.hash_new();
+    hash.aset(ruby.to_symbol("entries"), entries)?;
+    hash.aset(ruby.to_symbol("bytes"), bytes)?;
+    Ok(hash)
+}
+
+/// Import an export into the database at `path`.
+///
+/// Opens the database as its writer, which fences any other writer, and
+/// writes entries in batches of `batch_size` without waiting for each to be
+/// durable, then flushes. Entries whose `expire_ts` has passed are skipped.
+///
+/// # Returns
+/// Hash with entries, skipped and bytes
+pub fn import(
+    path: &Path,
+    object_store: &Arc<dyn ObjectStore>,
+    source: Value,
+    kwargs: RHash,
+    progress: Option<Proc>,
+) -> Result<RHash, Error> {
+    let format = Format::from_kwargs(&kwargs)?;
+    let batch_size = batch_size_from_kwargs(&kwargs)?;
+    let progress = Progress::new(progress);
+    let mut source = Source::open(source)?;
+    let clock = clock::test_clock();
+    let mut builder = Db::builder(path.clone(), object_store.clone());
+    if let Some(clock) = clock.clone() {
+        builder = builder.with_system_clock(clock);
+    }
+    let db = block_on_result(builder.build())?;
+    let imported = import_entries(
+        &db,
+        clock.as_ref(),
+        &mut source,
+        format,
+        batch_size,
+        &progress,
+    )
+    .and_then(|counts| block_on_result(db.flush()).map(|_| counts));
+    let closed = block_on_result(db.close());
+    let (entries, skipped, bytes) = imported?;
+    closed?;
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = ruby.hash_new();
+    hash.aset(ruby.to_symbol("entries"), entries)?;
+    hash.aset(ruby.to_symbol("skipped"), skipped)?;
+    hash.aset(ruby.to_symbol("bytes"), bytes)?;
+    Ok(hash)
+}
+
+/// Write the entries in `range` to `sink` in chunks of `batch_size`,
+/// returning the number of entries and bytes written.
+fn export_entries(
+    reader: &DbReader,
+    range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    format: Format,
+    batch_size: usize,
+    sink: &mut Sink,
+    progress: &Progress,
+) -> Result<(u64, u64), Error> {
+    let mut iter = block_on_result(reader.scan_with_options(range, &ScanOptions::default()))?;
+
+    if format == Format::Csv {
+        sink.write(CSV_HEADER)?;
+    }
+    let (mut entries, mut bytes) = (0u64, 0u64);
+    loop {
+        let (chunk, count, done) = block_on_result(async {
+            let mut chunk = Vec::new();
+            let mut count = 0;
+            while count < batch_size {
+                let Some(kv) = iter.next().await? else {
+                    return Ok((chunk, count, true));
+                };
+                format.write_entry(&mut chunk, &kv)?;
+                count += 1;
+            }
+            Ok((chunk, count, false))
+        })?;
+
+        sink.write(&chunk)?;
+        entries += count as u64;
+        bytes += chunk.len() as u64;
+        progress.report(&[("entries", entries), ("bytes", bytes)], None)?;
+        if done {
+            break;
+        }
+    }
+
+    Ok((entries, bytes))
+}
+
+/// Write the entries read from `source` to `db` in batches of
+/// `batch_size`, returning the number of entries written and skipped and
+/// bytes read. Entries that have expired by `clock` are skipped.
+fn import_entries(
+    db: &Db,
+    clock: Option<&Arc<dyn SystemClock>>,
+    source: &mut Source,
+    format: Format,
+    batch_size: usize,
+    progress: &Progress,
+) -> Result<(u64, u64, u64), Error> {
+    let mut parser = Parser::new(format);
+    let (mut entries, mut skipped, mut bytes) = (0u64, 0u64, 0u64);
+    let mut pending = Vec::new();
+    loop {
+        let chunk = source.read()?;
+        let eof = chunk.is_empty();
+        bytes += chunk.len() as u64;
+        pending.extend(parser.feed(&chunk, eof).map_err(map_error)?);
+
+        while pending.len() >= batch_size || (eof && !pending.is_empty()) {
+            let batch: Vec<Entry> = pending.drain(..batch_size.min(pending.len())).collect();
+            let (written, expired) = block_on_result(write_entries(db, clock, batch))?;
+            entries += written;
+            skipped += expired;
+            progress.report(
+                &[("entries", entries), ("skipped", skipped), ("bytes", bytes)],
+                source.percent(bytes),
+            )?;
+        }
+        if eof {
+            break;
+        }
+    }
+
+    Ok((entries, skipped, bytes))
+}
+
+/// Write a batch of imported entries, returning how many were written and how
+/// many were skipped because they have expired.
+async fn write_entries(
+    db: &Db,
+    clock: Option<&Arc<dyn SystemClock>>,
+    entries: Vec<Entry>,
+) -> Result<(u64, u64), SlateError> {
+    let now = clock::now_millis(clock);
+
+    let mut batch = WriteBatch::new();
+    let (mut written, mut expired) = (0, 0);
+    for entry in entries {
+        let ttl = match entry.expire_ts {
+            Some(ts) if ts <= now => {
+                expired += 1;
+                continue;
+            }
+            Some(ts) => Ttl::ExpireAt(ts),
+            None => Ttl::NoExpiry,
+        };
+        batch.put_with_options(&entry.key, &entry.value, &PutOptions { ttl });
+        written += 1;
+    }
+
+    let write_opts = WriteOptions {
+        await_durable: false,
+        seqnum: 0,
+    };
+    db.write_with_options(batch, &write_opts).await?;
+    Ok((written, expired))
+}
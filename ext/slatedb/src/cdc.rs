@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use magnus::{method, Error, RHash, Ruby, Symbol, Value};
+use slatedb::object_store::ObjectStore;
+use slatedb::{Error as SlateError, RowEntry, ValueDeletable, WalReader};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::codec::Codec;
+use crate::errors::map_error;
+use crate::runtime::{block_on, block_on_result, spawn};
+
+/// Number of changes a watcher queues before its background task waits for
+/// the Ruby thread to catch up.
+const WATCH_QUEUE_SIZE: usize = 1024;
+
+/// Reads committed changes from a database's WAL files, in write order.
+pub struct WalTail {
+    reader: WalReader,
+    /// Only changes with a greater sequence number are returned.
+    since_seq: u64,
+    /// The next WAL file to read.
+    next_wal_id: u64,
+    /// Entries read from the current WAL file, in sequence order.
+    pending: VecDeque<RowEntry>,
+}
+
+impl WalTail {
+    /// Tail the WAL of the database at `path` from after `since_seq`.
+    pub fn new(path: &str, object_store: Arc<dyn ObjectStore>, since_seq: u64) -> Self {
+        Self {
+            reader: WalReader::new(path, object_store),
+            since_seq,
+            next_wal_id: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Get the next change, or None once every WAL file written so far has
+    /// been read. Later calls return changes written since.
+    pub async fn next(&mut self) -> Result<Option<RowEntry>, SlateError> {
+        loop {
+            if let Some(entry) = self.pending.pop_front() {
+                return Ok(Some(entry));
+            }
+
+            let files = self.reader.list(self.next_wal_id..).await?;
+            let Some(file) = files.into_iter().next() else {
+                return Ok(None);
+            };
+
+            // WAL files are sorted by key; changes are returned in the order
+            // they were written.
+            let mut entries = Vec::new();
+            let mut iter = file.iterator().await?;
+            while let Some(entry) = iter.next().await? {
+                if entry.seq > self.since_seq {
+                    entries.push(entry);
+                }
+            }
+            entries.sort_by_key(|entry| entry.seq);
+
+            self.pending = entries.into();
+            self.next_wal_id = file.next_id();
+        }
+    }
+}
+
+/// Describe a change as `(op, value)`, where value is decoded with `codec`
+/// and is None for deletes.
+fn change_op(value: ValueDeletable, codec: Codec) -> Result<(&'static str, Option<Value>), Error> {
+    Ok(match value {
+        ValueDeletable::Value(value) => ("put", Some(codec.decode(&value)?)),
+        ValueDeletable::Merge(value) => ("merge", Some(codec.decode(&value)?)),
+        ValueDeletable::Tombstone => ("delete", None),
+    })
+}
+
+/// Ruby wrapper for a stream of committed changes.
+///
+/// This struct is exposed to Ruby as `SlateDb::ChangeStream` and is returned
+/// by `Database#changes`. It tails the database's WAL files in object
+/// storage, so it sees writes once they are durable in the WAL, and only
+/// while those WAL files are retained.
+#[magnus::wrap(class = "SlateDb::ChangeStream", free_immediately, size)]
+pub struct ChangeStream {
+    tail: Arc<Mutex<WalTail>>,
+    last_seq: AtomicU64,
+    /// How values are decoded; see the `codec` open option.
+    codec: Codec,
+}
+
+impl ChangeStream {
+    /// Stream the changes to the database at `path` with a sequence number
+    /// greater than `since_seq`.
+    pub fn new(path: &str, object_store: Arc<dyn ObjectStore>, since_seq: u64) -> Self {
+        Self {
+            tail: Arc::new(Mutex::new(WalTail::new(path, object_store, since_seq))),
+            last_seq: AtomicU64::new(since_seq),
+            codec: Codec::Raw,
+        }
+    }
+
+    /// Set the codec used to decode values.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Get the next change.
+    ///
+    /// Returns a hash with `:key`, `:value`, `:op` (`:put`, `:delete` or
+    /// `:merge`), `:seq` and `:timestamp`, or nil when the stream has caught
+    /// up with the WAL. Later calls return changes written since.
+    pub fn next_change(&self) -> Result<Option<RHash>, Error> {
+        let tail = self.tail.clone();
+        let entry = block_on_result(async move { tail.lock().await.next().await })?;
+
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+        self.last_seq.fetch_max(entry.seq, Ordering::SeqCst);
+
+        self.entry_to_hash(entry).map(Some)
+    }
+
+    /// The sequence number of the last change returned, or `since_seq` if
+    /// none has been. Pass it as `since_seq` to resume a stream.
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq.load(Ordering::SeqCst)
+    }
+
+    fn entry_to_hash(&self, entry: RowEntry) -> Result<RHash, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let (op, value) = change_op(entry.value, self.codec)?;
+
+        let hash = ruby.hash_new();
+        hash.aset(
+            ruby.to_symbol("key"),
+            String::from_utf8_lossy(&entry.key).to_string(),
+        )?;
+        hash.aset(ruby.to_symbol("value"), value)?;
+        hash.aset(ruby.to_symbol("op"), ruby.to_symbol(op))?;
+        hash.aset(ruby.to_symbol("seq"), entry.seq)?;
+        hash.aset(ruby.to_symbol("timestamp"), entry.create_ts)?;
+        Ok(hash)
+    }
+}
+
+/// A change delivered to a watcher, or the error that stopped it.
+type WatchEvent = Result<RowEntry, SlateError>;
+
+/// Ruby wrapper for a background watch on a key prefix.
+///
+/// This struct is exposed to Ruby as `SlateDb::Watcher` and is returned by
+/// `Database#watch`. A runtime task tails the WAL and queues matching
+/// changes; a Ruby thread takes them with `next_event`, which waits without
+/// holding the GVL and converts each change once the GVL is reacquired, so
+/// the Ruby block never runs on a runtime thread.
+#[magnus::wrap(class = "SlateDb::Watcher", free_immediately, size)]
+pub struct Watcher {
+    events: Arc<Mutex<mpsc::Receiver<WatchEvent>>>,
+    task: JoinHandle<()>,
+    stopped: AtomicBool,
+    /// How values are decoded; see the `codec` open option.
+    codec: Codec,
+}
+
+impl Watcher {
+    /// Start watching for changes to keys beginning with `prefix`, checking
+    /// the WAL every `interval` once caught up.
+    pub fn start(mut tail: WalTail, prefix: Vec<u8>, interval: Duration) -> Self {
+        let (sender, events) = mpsc::channel(WATCH_QUEUE_SIZE);
+        let task = spawn(async move {
+            loop {
+                match tail.next().await {
+                    Ok(Some(entry)) => {
+                        if entry.key.starts_with(&prefix) && sender.send(Ok(entry)).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(None) => tokio::time::sleep(interval).await,
+                    Err(e) => {
+                        let _ = sender.send(Err(e)).await;
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            events: Arc::new(Mutex::new(events)),
+            task,
+            stopped: AtomicBool::new(false),
+            codec: Codec::Raw,
+        }
+    }
+
+    /// Set the codec used to decode values.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Wait for the next change.
+    ///
+    /// Returns `[key, value, op]`, or nil once the watcher has been stopped.
+    /// Raises the error that stopped the watcher, if any.
+    pub fn next_event(&self) -> Result<Option<(String, Option<Value>, Symbol)>, Error> {
+        let events = self.events.clone();
+        let event = block_on(async move { events.lock().await.recv().await })?;
+        if self.stopped.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        match event {
+            None => Ok(None),
+            Some(Err(e)) => Err(map_error(e)),
+            Some(Ok(entry)) => {
+                let ruby = Ruby::get().expect("Ruby runtime not available");
+                let key = String::from_utf8_lossy(&entry.key).to_string();
+                let (op, value) = change_op(entry.value, self.codec)?;
+                Ok(Some((key, value, ruby.to_symbol(op))))
+            }
+        }
+    }
+
+    /// Stop watching. Queued changes are discarded.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.task.abort();
+    }
+
+    /// Whether the watcher has stopped, either by `stop` or because reading
+    /// the WAL failed.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst) || self.task.is_finished()
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Define the ChangeStream class on the SlateDb module.
+pub fn define_change_stream_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("ChangeStream", ruby.class_object())?;
+
+    class.define_method("next_change", method!(ChangeStream::next_change, 0))?;
+    class.define_method("last_seq", method!(ChangeStream::last_seq, 0))?;
+
+    Ok(())
+}
+
+/// Define the Watcher class on the SlateDb module.
+pub fn define_watcher_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("Watcher", ruby.class_object())?;
+
+    class.define_method("next_event", method!(Watcher::next_event, 0))?;
+    class.define_method("_stop", method!(Watcher::stop, 0))?;
+    class.define_method("stopped?", method!(Watcher::is_stopped, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use magnus::{method, Error, Ruby};
+use slatedb::admin::Admin;
+use slatedb::ErrorKind;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::runtime::{block_on_result, spawn};
+
+/// Ruby wrapper for a checkpoint kept alive by a runtime task.
+///
+/// This struct is exposed to Ruby as `SlateDb::CheckpointLease` and is
+/// returned by `Admin#hold_checkpoint`. The task extends the checkpoint's
+/// lifetime on a fixed interval until the lease is released or dropped.
+/// Failed refreshes are retried on the next interval, except when the
+/// checkpoint no longer exists.
+#[magnus::wrap(class = "SlateDb::CheckpointLease", free_immediately, size)]
+pub struct CheckpointLease {
+    id: Uuid,
+    task: JoinHandle<()>,
+    refreshes: Arc<AtomicU64>,
+    last_error: Arc<Mutex<Option<String>>>,
+    released: AtomicBool,
+}
+
+impl CheckpointLease {
+    /// Refresh checkpoint `id` to expire `lifetime` from now, then keep
+    /// doing so every `interval`.
+    ///
+    /// The first refresh runs before returning, so a missing checkpoint
+    /// raises here rather than in the background.
+    pub fn hold(
+        admin: Arc<Admin>,
+        id: Uuid,
+        interval: Duration,
+        lifetime: Duration,
+    ) -> Result<Self, Error> {
+        block_on_result(admin.refresh_checkpoint(id, Some(lifetime)))?;
+
+        let refreshes = Arc::new(AtomicU64::new(1));
+        let last_error = Arc::new(Mutex::new(None));
+        let task = {
+            let refreshes = refreshes.clone();
+            let last_error = last_error.clone();
+            spawn(async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let result = admin.refresh_checkpoint(id, Some(lifetime)).await;
+                    let mut error = last_error.lock().expect("lease mutex poisoned");
+                    match result {
+                        Ok(()) => {
+                            refreshes.fetch_add(1, Ordering::SeqCst);
+                            *error = None;
+                        }
+                        Err(e) => {
+                            // A missing checkpoint surfaces as a data error.
+                            let missing = e.kind() == ErrorKind::Data;
+                            *error = Some(e.to_string());
+                            if missing {
+                                return;
+                            }
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            id,
+            task,
+            refreshes,
+            last_error,
+            released: AtomicBool::new(false),
+        })
+    }
+
+    /// The id of the held checkpoint.
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// The number of successful refreshes, including the first.
+    pub fn refresh_count(&self) -> u64 {
+        self.refreshes.load(Ordering::SeqCst)
+    }
+
+    /// The message of the most recent failed refresh, or nil if the last
+    /// refresh succeeded.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .expect("lease mutex poisoned")
+            .clone()
+    }
+
+    /// Whether the lease is still refreshing the checkpoint: false once
+    /// released, or once the checkpoint was found to be gone.
+    pub fn is_active(&self) -> bool {
+        !self.released.load(Ordering::SeqCst) && !self.task.is_finished()
+    }
+
+    /// Stop refreshing. The checkpoint then expires at the end of its
+    /// current lifetime.
+    pub fn release(&self) {
+        self.released.store(true, Ordering::SeqCst);
+        self.task.abort();
+    }
+}
+
+impl Drop for CheckpointLease {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Define the CheckpointLease class on the SlateDb module.
+pub fn define_checkpoint_lease_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("CheckpointLease", ruby.class_object())?;
+
+    class.define_method("id", method!(CheckpointLease::id, 0))?;
+    class.define_method("refresh_count", method!(CheckpointLease::refresh_count, 0))?;
+    class.define_method("last_error", method!(CheckpointLease::last_error, 0))?;
+    class.define_method("active?", method!(CheckpointLease::is_active, 0))?;
+    class.define_method("release", method!(CheckpointLease::release, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use magnus::prelude::*;
+use magnus::{function, method, Error, Ruby};
+use slatedb_common::clock::{SystemClock, SystemClockTicker};
+
+use crate::errors::invalid_argument_error;
+
+/// The clock databases, readers and admins opened from now on use; see
+/// `SlateDb.test_clock=`.
+static TEST_CLOCK: Mutex<Option<Arc<ManualSystemClock>>> = Mutex::new(None);
+
+/// A wall clock that only moves when told to.
+///
+/// Everything SlateDB stamps with the time reads it: TTL expiry, checkpoint
+/// expiry and the ages garbage collection compares with `min_age`. Sleeps and
+/// tickers still wait in real time, so background flushes, compaction and
+/// garbage collection keep running on their usual schedule.
+#[derive(Debug)]
+struct ManualSystemClock {
+    /// Milliseconds since the epoch.
+    now: AtomicI64,
+}
+
+impl SystemClock for ManualSystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        let now = self.now.load(Ordering::SeqCst);
+        DateTime::from_timestamp_millis(now).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    fn sleep<'a>(&'a self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn ticker<'a>(&'a self, duration: Duration) -> SystemClockTicker<'a> {
+        SystemClockTicker::new(self, duration)
+    }
+}
+
+/// Ruby wrapper for a manually advanced clock.
+///
+/// This struct is exposed to Ruby as `SlateDb::ManualClock`.
+#[magnus::wrap(class = "SlateDb::ManualClock", free_immediately, size)]
+pub struct ManualClock {
+    inner: Arc<ManualSystemClock>,
+}
+
+impl ManualClock {
+    /// Create a clock stopped at `now_ms` milliseconds since the epoch.
+    pub fn new(now_ms: i64) -> Self {
+        Self {
+            inner: Arc::new(ManualSystemClock {
+                now: AtomicI64::new(now_ms),
+            }),
+        }
+    }
+
+    /// The clock's time in milliseconds since the epoch.
+    pub fn now_ms(&self) -> i64 {
+        self.inner.now.load(Ordering::SeqCst)
+    }
+
+    /// Move the clock forward by `ms` milliseconds.
+    ///
+    /// # Returns
+    /// The new time in milliseconds since the epoch
+    pub fn advance_ms(&self, ms: i64) -> Result<i64, Error> {
+        if ms < 0 {
+            return Err(invalid_argument_error(
+                "cannot move a ManualClock backwards",
+            ));
+        }
+        Ok(self.inner.now.fetch_add(ms, Ordering::SeqCst) + ms)
+    }
+
+    /// Set the clock to `now_ms` milliseconds since the epoch.
+    ///
+    /// SlateDB requires its clock to be monotonic, so the clock can only
+    /// move forward.
+    pub fn set_ms(&self, now_ms: i64) -> Result<(), Error> {
+        self.inner
+            .now
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |now| {
+                (now_ms >= now).then_some(now_ms)
+            })
+            .map(|_| ())
+            .map_err(|now| {
+                invalid_argument_error(&format!(
+                    "cannot move a ManualClock backwards from {} to {}",
+                    now, now_ms
+                ))
+            })
+    }
+}
+
+/// The clock to build databases, readers and admins with, if a test clock
+/// is set.
+pub fn test_clock() -> Option<Arc<dyn SystemClock>> {
+    TEST_CLOCK
+        .lock()
+        .expect("test clock mutex poisoned")
+        .clone()
+        .map(|clock| clock as Arc<dyn SystemClock>)
+}
+
+/// The current time in milliseconds since the epoch, by the clock a handle
+/// was built with, or the system clock if it has none.
+pub fn now_millis(clock: Option<&Arc<dyn SystemClock>>) -> i64 {
+    match clock {
+        Some(clock) => clock.now().timestamp_millis(),
+        None => system_now_millis(),
+    }
+}
+
+/// The current time in milliseconds since the epoch, by the system clock.
+pub fn system_now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64)
+}
+
+/// Set or clear the clock used by handles opened from now on.
+fn set_test_clock(clock: Option<&ManualClock>) {
+    *TEST_CLOCK.lock().expect("test clock mutex poisoned") = clock.map(|clock| clock.inner.clone());
+}
+
+/// Define the ManualClock class and test clock functions on the SlateDb
+/// module.
+pub fn define_clock_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("ManualClock", ruby.class_object())?;
+    class.define_singleton_method("_new", function!(ManualClock::new, 1))?;
+    class.define_method("now_ms", method!(ManualClock::now_ms, 0))?;
+    class.define_method("_advance_ms", method!(ManualClock::advance_ms, 1))?;
+    class.define_method("_set_ms", method!(ManualClock::set_ms, 1))?;
+
+    module.define_module_function("_set_test_clock", function!(set_test_clock, 1))?;
+    Ok(())
+}
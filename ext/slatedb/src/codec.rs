@@ -0,0 +1,495 @@
+use magnus::encoding::EncodingCapable;
+use magnus::prelude::*;
+use magnus::r_hash::ForEach;
+use magnus::value::{Qfalse, Qtrue};
+use magnus::{Error, Float, Integer, RArray, RHash, RModule, RString, Ruby, Symbol, Value};
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::errors::{data_error, invalid_argument_error};
+use crate::utils::get_optional;
+use msgpack::{Reader as MsgpackReader, Token};
+
+/// Deepest nesting of Arrays and Hashes a value may have, so a structure
+/// that contains itself fails instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// How a handle stores values, from the `codec` open option.
+///
+/// With a codec other than `Raw`, values passed to `put` can be any object
+/// the codec supports and `get` and scans return the decoded objects, with
+/// no intermediate Ruby String. Keys are always strings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    /// Values are Strings, stored as their bytes.
+    #[default]
+    Raw,
+    /// JSON text. Hashes decode with String keys and Symbols encode as
+    /// Strings, as with `JSON.generate` and `JSON.parse`.
+    Json,
+    /// MessagePack. Binary (ASCII-8BIT) Strings round-trip as binary.
+    Msgpack,
+    /// Ruby's Marshal format, for any marshalable object. Only load data
+    /// written by a trusted process: Marshal can instantiate any class.
+    Marshal,
+}
+
+impl Codec {
+    /// Parse the `codec` open option.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        match get_optional::<String>(kwargs, "codec")?.as_deref() {
+            None | Some("raw") => Ok(Self::Raw),
+            Some("json") => Ok(Self::Json),
+            Some("msgpack") => Ok(Self::Msgpack),
+            Some("marshal") => Ok(Self::Marshal),
+            Some(other) => Err(invalid_argument_error(&format!(
+                "invalid codec: {} (expected :raw, :json, :msgpack or :marshal)",
+                other
+            ))),
+        }
+    }
+
+    /// Serialize a value for storage.
+    pub fn encode(self, value: Value) -> Result<Vec<u8>, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        match self {
+            Self::Raw => Ok(String::try_convert(value)?.into_bytes()),
+            Self::Json => {
+                let json = to_json(&ruby, value, 0)?;
+                serde_json::to_vec(&json)
+                    .map_err(|e| invalid_argument_error(&format!("cannot encode JSON: {}", e)))
+            }
+            Self::Msgpack => {
+                let mut out = Vec::new();
+                write_msgpack(&ruby, &mut out, value, 0)?;
+                Ok(out)
+            }
+            Self::Marshal => {
+                let dumped: RString = marshal(&ruby)?.funcall("dump", (value,))?;
+                Ok(unsafe { dumped.as_slice() }.to_vec())
+            }
+        }
+    }
+
+    /// Deserialize a stored value.
+    ///
+    /// An empty value decodes as an empty String under every codec, as no
+    /// codec encodes anything to zero bytes. Present-but-empty markers
+    /// written by other clients then read back as `""` rather than failing.
+    pub fn decode(self, bytes: &[u8]) -> Result<Value, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        if bytes.is_empty() {
+            return Ok(ruby.str_new("").as_value());
+        }
+        match self {
+            Self::Raw => Ok(ruby.str_new(&String::from_utf8_lossy(bytes)).as_value()),
+            Self::Json => {
+                let json: JsonValue = serde_json::from_slice(bytes)
+                    .map_err(|e| data_error(&format!("cannot decode JSON value: {}", e)))?;
+                from_json(&ruby, json)
+            }
+            Self::Msgpack => {
+                let mut reader = MsgpackReader::new(bytes);
+                let value = read_msgpack(&ruby, &mut reader, 0)?;
+                if !reader.is_empty() {
+                    return Err(data_error(
+                        "cannot decode MessagePack value: trailing bytes",
+                    ));
+                }
+                Ok(value)
+            }
+            Self::Marshal => marshal(&ruby)?
+                .funcall("load", (ruby.str_from_slice(bytes),))
+                .map_err(|e| data_error(&format!("cannot decode Marshal value: {}", e))),
+        }
+    }
+}
+
+fn marshal(ruby: &Ruby) -> Result<RModule, Error> {
+    ruby.class_object().const_get("Marshal")
+}
+
+fn check_depth(depth: usize) -> Result<(), Error> {
+    if depth > MAX_DEPTH {
+        return Err(invalid_argument_error(&format!(
+            "value is nested more than {} levels deep",
+            MAX_DEPTH
+        )));
+    }
+    Ok(())
+}
+
+fn unsupported(value: Value, format: &str) -> Error {
+    invalid_argument_error(&format!(
+        "cannot encode {} as {}",
+        unsafe { value.classname() },
+        format
+    ))
+}
+
+fn ruby_bool(ruby: &Ruby, b: bool) -> Value {
+    if b {
+        ruby.qtrue().as_value()
+    } else {
+        ruby.qfalse().as_value()
+    }
+}
+
+fn to_json(ruby: &Ruby, value: Value, depth: usize) -> Result<JsonValue, Error> {
+    check_depth(depth)?;
+    if value.is_nil() {
+        return Ok(JsonValue::Null);
+    }
+    if Qtrue::from_value(value).is_some() {
+        return Ok(JsonValue::Bool(true));
+    }
+    if Qfalse::from_value(value).is_some() {
+        return Ok(JsonValue::Bool(false));
+    }
+    if let Some(int) = Integer::from_value(value) {
+        let number = match int.to_i64() {
+            Ok(n) => Number::from(n),
+            Err(_) => Number::from(int.to_u64()?),
+        };
+        return Ok(JsonValue::Number(number));
+    }
+    if let Some(float) = Float::from_value(value) {
+        return Number::from_f64(float.to_f64())
+            .map(JsonValue::Number)
+            .ok_or_else(|| invalid_argument_error("cannot encode NaN or Infinity as JSON"));
+    }
+    if let Some(string) = RString::from_value(value) {
+        return Ok(JsonValue::String(string.to_string()?));
+    }
+    if let Some(symbol) = Symbol::from_value(value) {
+        return Ok(JsonValue::String(symbol.name()?.into_owned()));
+    }
+    if let Some(array) = RArray::from_value(value) {
+        let items = array.to_vec::<Value>()?;
+        return items
+            .into_iter()
+            .map(|item| to_json(ruby, item, depth + 1))
+            .collect::<Result<_, _>>()
+            .map(JsonValue::Array);
+    }
+    if let Some(hash) = RHash::from_value(value) {
+        let mut object = Map::new();
+        hash.foreach(|key: Value, item: Value| {
+            let key = if let Some(key) = RString::from_value(key) {
+                key.to_string()?
+            } else if let Some(key) = Symbol::from_value(key) {
+                key.name()?.into_owned()
+            } else {
+                return Err(invalid_argument_error(
+                    "JSON object keys must be Strings or Symbols",
+                ));
+            };
+            object.insert(key, to_json(ruby, item, depth + 1)?);
+            Ok(ForEach::Continue)
+        })?;
+        return Ok(JsonValue::Object(object));
+    }
+    Err(unsupported(value, "JSON"))
+}
+
+fn from_json(ruby: &Ruby, json: JsonValue) -> Result<Value, Error> {
+    Ok(match json {
+        JsonValue::Null => ruby.qnil().as_value(),
+        JsonValue::Bool(b) => ruby_bool(ruby, b),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                ruby.integer_from_i64(i).as_value()
+            } else if let Some(u) = n.as_u64() {
+                ruby.integer_from_u64(u).as_value()
+            } else {
+                ruby.float_from_f64(n.as_f64().unwrap_or(f64::NAN))
+                    .as_value()
+            }
+        }
+        JsonValue::String(s) => ruby.str_new(&s).as_value(),
+        JsonValue::Array(items) => {
+            let array = ruby.ary_new_capa(items.len());
+            for item in items {
+                array.push(from_json(ruby, item)?)?;
+            }
+            array.as_value()
+        }
+        JsonValue::Object(object) => {
+            let hash = ruby.hash_new_capa(object.len());
+            for (key, item) in object {
+                hash.aset(ruby.str_new(&key), from_json(ruby, item)?)?;
+            }
+            hash.as_value()
+        }
+    })
+}
+
+fn write_msgpack(ruby: &Ruby, out: &mut Vec<u8>, value: Value, depth: usize) -> Result<(), Error> {
+    check_depth(depth)?;
+    if value.is_nil() {
+        msgpack::write_nil(out);
+    } else if Qtrue::from_value(value).is_some() {
+        msgpack::write_bool(out, true);
+    } else if Qfalse::from_value(value).is_some() {
+        msgpack::write_bool(out, false);
+    } else if let Some(int) = Integer::from_value(value) {
+        match int.to_i64() {
+            Ok(n) => msgpack::write_int(out, n),
+            Err(_) => msgpack::write_uint(out, int.to_u64()?),
+        }
+    } else if let Some(float) = Float::from_value(value) {
+        msgpack::write_f64(out, float.to_f64());
+    } else if let Some(string) = RString::from_value(value) {
+        let bytes = unsafe { string.as_slice() };
+        if string.enc_get() == ruby.ascii8bit_encindex() {
+            msgpack::write_bin(out, bytes);
+        } else {
+            msgpack::write_str(out, bytes);
+        }
+    } else if let Some(symbol) = Symbol::from_value(value) {
+        msgpack::write_str(out, symbol.name()?.as_bytes());
+    } else if let Some(array) = RArray::from_value(value) {
+        let items = array.to_vec::<Value>()?;
+        msgpack::write_array_len(out, items.len());
+        for item in items {
+            write_msgpack(ruby, out, item, depth + 1)?;
+        }
+    } else if let Some(hash) = RHash::from_value(value) {
+        msgpack::write_map_len(out, hash.len());
+        hash.foreach(|key: Value, item: Value| {
+            write_msgpack(ruby, out, key, depth + 1)?;
+            write_msgpack(ruby, out, item, depth + 1)?;
+            Ok(ForEach::Continue)
+        })?;
+    } else {
+        return Err(unsupported(value, "MessagePack"));
+    }
+    Ok(())
+}
+
+fn read_msgpack(ruby: &Ruby, reader: &mut MsgpackReader, depth: usize) -> Result<Value, Error> {
+    if depth > MAX_DEPTH {
+        return Err(data_error(&format!(
+            "cannot decode MessagePack value: nested more than {} levels deep",
+            MAX_DEPTH
+        )));
+    }
+    let token = reader
+        .next()
+        .map_err(|e| data_error(&format!("cannot decode MessagePack value: {}", e)))?;
+    Ok(match token {
+        Token::Nil => ruby.qnil().as_value(),
+        Token::Bool(b) => ruby_bool(ruby, b),
+        Token::Int(n) => ruby.integer_from_i64(n).as_value(),
+        Token::Uint(n) => ruby.integer_from_u64(n).as_value(),
+        Token::Float(f) => ruby.float_from_f64(f).as_value(),
+        Token::Str(bytes) => ruby.enc_str_new(bytes, ruby.utf8_encoding()).as_value(),
+        Token::Bin(bytes) => ruby.str_from_slice(bytes).as_value(),
+        Token::Array(len) => {
+            let array = ruby.ary_new_capa(len.min(reader.remaining()));
+            for _ in 0..len {
+                array.push(read_msgpack(ruby, reader, depth + 1)?)?;
+            }
+            array.as_value()
+        }
+        Token::Map(len) => {
+            let hash = ruby.hash_new_capa(len.min(reader.remaining()));
+            for _ in 0..len {
+                let key = read_msgpack(ruby, reader, depth + 1)?;
+                let item = read_msgpack(ruby, reader, depth + 1)?;
+                hash.aset(key, item)?;
+            }
+            hash.as_value()
+        }
+    })
+}
+
+/// The subset of MessagePack the codec reads and writes: every type except
+/// extensions (including timestamps).
+mod msgpack {
+    pub fn write_nil(out: &mut Vec<u8>) {
+        out.push(0xc0);
+    }
+
+    pub fn write_bool(out: &mut Vec<u8>, b: bool) {
+        out.push(if b { 0xc3 } else { 0xc2 });
+    }
+
+    pub fn write_int(out: &mut Vec<u8>, n: i64) {
+        if n >= 0 {
+            write_uint(out, n as u64);
+        } else if n >= -32 {
+            out.push(n as u8);
+        } else if n >= i8::MIN as i64 {
+            out.push(0xd0);
+            out.push(n as u8);
+        } else if n >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend_from_slice(&(n as i16).to_be_bytes());
+        } else if n >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend_from_slice(&(n as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+
+    pub fn write_uint(out: &mut Vec<u8>, n: u64) {
+        if n < 0x80 {
+            out.push(n as u8);
+        } else if n <= u8::MAX as u64 {
+            out.push(0xcc);
+            out.push(n as u8);
+        } else if n <= u16::MAX as u64 {
+            out.push(0xcd);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        } else if n <= u32::MAX as u64 {
+            out.push(0xce);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        } else {
+            out.push(0xcf);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+
+    pub fn write_f64(out: &mut Vec<u8>, f: f64) {
+        out.push(0xcb);
+        out.extend_from_slice(&f.to_be_bytes());
+    }
+
+    pub fn write_str(out: &mut Vec<u8>, bytes: &[u8]) {
+        let len = bytes.len();
+        if len < 32 {
+            out.push(0xa0 | len as u8);
+        } else {
+            write_len(out, len, [0xd9, 0xda, 0xdb]);
+        }
+        out.extend_from_slice(bytes);
+    }
+
+    pub fn write_bin(out: &mut Vec<u8>, bytes: &[u8]) {
+        write_len(out, bytes.len(), [0xc4, 0xc5, 0xc6]);
+        out.extend_from_slice(bytes);
+    }
+
+    pub fn write_array_len(out: &mut Vec<u8>, len: usize) {
+        if len < 16 {
+            out.push(0x90 | len as u8);
+        } else {
+            write_len(out, len, [0x00, 0xdc, 0xdd]);
+        }
+    }
+
+    pub fn write_map_len(out: &mut Vec<u8>, len: usize) {
+        if len < 16 {
+            out.push(0x80 | len as u8);
+        } else {
+            write_len(out, len, [0x00, 0xde, 0xdf]);
+        }
+    }
+
+    /// Write a length with the 8, 16 or 32-bit marker. Arrays and maps have
+    /// no 8-bit form, so theirs is never used.
+    fn write_len(out: &mut Vec<u8>, len: usize, [m8, m16, m32]: [u8; 3]) {
+        if len <= u8::MAX as usize && m8 != 0x00 {
+            out.push(m8);
+            out.push(len as u8);
+        } else if len <= u16::MAX as usize {
+            out.push(m16);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            out.push(m32);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+
+    pub enum Token<'a> {
+        Nil,
+        Bool(bool),
+        Int(i64),
+        Uint(u64),
+        Float(f64),
+        Str(&'a [u8]),
+        Bin(&'a [u8]),
+        Array(usize),
+        Map(usize),
+    }
+
+    pub struct Reader<'a> {
+        bytes: &'a [u8],
+    }
+
+    impl<'a> Reader<'a> {
+        pub fn new(bytes: &'a [u8]) -> Self {
+            Self { bytes }
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.bytes.is_empty()
+        }
+
+        /// Bytes left to read, an upper bound on the number of values left.
+        pub fn remaining(&self) -> usize {
+            self.bytes.len()
+        }
+
+        fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+            if self.bytes.len() < n {
+                return Err("unexpected end of data".to_string());
+            }
+            let (taken, rest) = self.bytes.split_at(n);
+            self.bytes = rest;
+            Ok(taken)
+        }
+
+        fn take_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+            Ok(self.take(N)?.try_into().expect("slice has length N"))
+        }
+
+        fn take_len(&mut self, size: usize) -> Result<usize, String> {
+            Ok(match size {
+                1 => self.take_array::<1>()?[0] as usize,
+                2 => u16::from_be_bytes(self.take_array()?) as usize,
+                _ => u32::from_be_bytes(self.take_array()?) as usize,
+            })
+        }
+
+        pub fn next(&mut self) -> Result<Token<'a>, String> {
+            let marker = self.take_array::<1>()?[0];
+            Ok(match marker {
+                0x00..=0x7f => Token::Uint(marker as u64),
+                0x80..=0x8f => Token::Map((marker & 0x0f) as usize),
+                0x90..=0x9f => Token::Array((marker & 0x0f) as usize),
+                0xa0..=0xbf => Token::Str(self.take((marker & 0x1f) as usize)?),
+                0xc0 => Token::Nil,
+                0xc2 => Token::Bool(false),
+                0xc3 => Token::Bool(true),
+                0xc4..=0xc6 => {
+                    let len = self.take_len(1 << (marker - 0xc4))?;
+                    Token::Bin(self.take(len)?)
+                }
+                0xca => Token::Float(f32::from_be_bytes(self.take_array()?) as f64),
+                0xcb => Token::Float(f64::from_be_bytes(self.take_array()?)),
+                0xcc => Token::Uint(self.take_array::<1>()?[0] as u64),
+                0xcd => Token::Uint(u16::from_be_bytes(self.take_array()?) as u64),
+                0xce => Token::Uint(u32::from_be_bytes(self.take_array()?) as u64),
+                0xcf => Token::Uint(u64::from_be_bytes(self.take_array()?)),
+                0xd0 => Token::Int(i8::from_be_bytes(self.take_array()?) as i64),
+                0xd1 => Token::Int(i16::from_be_bytes(self.take_array()?) as i64),
+                0xd2 => Token::Int(i32::from_be_bytes(self.take_array()?) as i64),
+                0xd3 => Token::Int(i64::from_be_bytes(self.take_array()?)),
+                0xd9..=0xdb => {
+                    let len = self.take_len(1 << (marker - 0xd9))?;
+                    Token::Str(self.take(len)?)
+                }
+                0xdc | 0xdd => Token::Array(self.take_len(2 << (marker - 0xdc))?),
+                0xde | 0xdf => Token::Map(self.take_len(2 << (marker - 0xde))?),
+                0xe0..=0xff => Token::Int(marker as i8 as i64),
+                0xc7..=0xc9 | 0xd4..=0xd8 => {
+                    return Err("extension types are not supported".to_string())
+                }
+                0xc1 => return Err("invalid marker 0xc1".to_string()),
+            })
+        }
+    }
+}
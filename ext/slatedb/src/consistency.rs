@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use magnus::{Error, RHash};
+use slatedb::DbMetadataOps;
+
+use crate::errors::{closed_error, timeout_error};
+use crate::runtime::block_on;
+use crate::utils::get_optional;
+
+/// How long a read given `min_seq` waits when it doesn't pass
+/// `min_seq_timeout_ms`.
+const DEFAULT_MIN_SEQ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The write a read must be able to see, from the `min_seq` read option.
+///
+/// `min_seq` is a consistency token returned by a write with
+/// `return_token: true`. A write is visible to readers in other processes
+/// once it is durable, so the read waits for the handle's durable sequence
+/// number to reach the token. A Reader only learns of new writes when it
+/// polls the manifest and WAL, so its wait is bounded below by
+/// `manifest_poll_interval`.
+pub struct MinSeq {
+    seq: u64,
+    timeout: Duration,
+}
+
+impl MinSeq {
+    /// Parse the `min_seq` and `min_seq_timeout_ms` read options, returning
+    /// None without `min_seq`.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Option<Self>, Error> {
+        let Some(seq) = get_optional::<u64>(kwargs, "min_seq")? else {
+            return Ok(None);
+        };
+        let timeout = get_optional::<u64>(kwargs, "min_seq_timeout_ms")?
+            .map_or(DEFAULT_MIN_SEQ_TIMEOUT, Duration::from_millis);
+        Ok(Some(Self { seq, timeout }))
+    }
+
+    /// Block until `handle` has seen the write with sequence number `seq`.
+    ///
+    /// Raises TimeoutError if it hasn't within the timeout, and ClosedError
+    /// if the handle closes first.
+    pub fn wait<M: DbMetadataOps + ?Sized>(&self, handle: &M) -> Result<(), Error> {
+        if handle.status().durable_seq >= self.seq {
+            return Ok(());
+        }
+
+        let seq = self.seq;
+        let mut status = handle.subscribe();
+        let outcome = block_on(async {
+            let visible = async {
+                status
+                    .wait_for(|s| s.durable_seq >= seq || s.close_reason.is_some())
+                    .await
+                    .map(|s| s.durable_seq >= seq)
+                    .unwrap_or(false)
+            };
+            tokio::time::timeout(self.timeout, visible).await.ok()
+        })?;
+
+        match outcome {
+            Some(true) => Ok(()),
+            Some(false) => Err(closed_error(&format!(
+                "closed before sequence number {} became visible",
+                seq
+            ))),
+            None => Err(timeout_error(&format!(
+                "sequence number {} did not become visible within {}ms",
+                seq,
+                self.timeout.as_millis()
+            ))),
+        }
+    }
+}
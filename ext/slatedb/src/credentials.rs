@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use magnus::{function, Error};
+use slatedb::object_store::aws::{AwsCredential, AwsCredentialProvider};
+use slatedb::object_store::{CredentialProvider, Error as ObjectStoreError};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use crate::errors::invalid_argument_error;
+use crate::runtime::{block_on, ensure_main_ractor, is_forked};
+
+/// How long `_next_credential_request` waits for a request before returning
+/// nil, so the Ruby thread serving requests stays responsive to interrupts.
+const REQUEST_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait for the Ruby provider before failing the request, in
+/// case no thread is serving requests.
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Credentials are refreshed this long before they expire, so a request
+/// signed just before expiry does not reach the store after it.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+static REQUEST_QUEUE: std::sync::Mutex<Option<RequestQueue>> = std::sync::Mutex::new(None);
+static PENDING: std::sync::Mutex<Option<HashMap<u64, oneshot::Sender<Fetched>>>> =
+    std::sync::Mutex::new(None);
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// The provider used for S3 stores opened without `credentials_provider:`.
+static DEFAULT_PROVIDER: std::sync::Mutex<Option<u64>> = std::sync::Mutex::new(None);
+
+/// Credentials returned by a Ruby provider, or the reason it failed.
+type Fetched = Result<(AwsCredential, Option<SystemTime>), String>;
+
+/// The queue between credential providers and the Ruby thread serving them.
+///
+/// Each process gets its own queue, for the same reason as the log queue:
+/// the parent's serving thread does not exist in a forked child.
+struct RequestQueue {
+    pid: u32,
+    sender: mpsc::UnboundedSender<(u64, u64)>,
+    requests: Arc<Mutex<mpsc::UnboundedReceiver<(u64, u64)>>>,
+}
+
+impl RequestQueue {
+    fn new() -> Self {
+        let (sender, requests) = mpsc::unbounded_channel();
+        Self {
+            pid: std::process::id(),
+            sender,
+            requests: Arc::new(Mutex::new(requests)),
+        }
+    }
+}
+
+/// Ask the Ruby provider with id `provider` for credentials.
+///
+/// Runs on runtime threads, which must not call Ruby, so the request is
+/// queued for a Ruby thread calling `_next_credential_request` and the reply
+/// comes back through a oneshot channel.
+async fn request_credentials(provider: u64) -> Fetched {
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    let (reply, response) = oneshot::channel();
+    PENDING
+        .lock()
+        .expect("credential requests poisoned")
+        .get_or_insert_with(HashMap::new)
+        .insert(token, reply);
+
+    let sent = {
+        let mut queue = REQUEST_QUEUE.lock().expect("credential queue poisoned");
+        let queue = queue.get_or_insert_with(RequestQueue::new);
+        if is_forked(queue.pid) {
+            *queue = RequestQueue::new();
+        }
+        queue.sender.send((token, provider)).is_ok()
+    };
+
+    let result = if sent {
+        match tokio::time::timeout(PROVIDER_TIMEOUT, response).await {
+            Ok(Ok(fetched)) => fetched,
+            Ok(Err(_)) => Err("credentials provider dropped the request".to_string()),
+            Err(_) => Err(format!(
+                "credentials provider did not respond within {}s",
+                PROVIDER_TIMEOUT.as_secs()
+            )),
+        }
+    } else {
+        Err("credentials provider is not running".to_string())
+    };
+    take_pending(token);
+    result
+}
+
+fn take_pending(token: u64) -> Option<oneshot::Sender<Fetched>> {
+    PENDING
+        .lock()
+        .expect("credential requests poisoned")
+        .as_mut()
+        .and_then(|pending| pending.remove(&token))
+}
+
+/// S3 credentials from a Ruby block, cached until shortly before they expire.
+///
+/// object_store asks for credentials before signing each request, so only a
+/// missing or expiring credential reaches Ruby. Concurrent requests wait for
+/// a single refresh.
+#[derive(Debug)]
+pub struct RubyCredentialProvider {
+    provider: u64,
+    cached: Mutex<Option<(Arc<AwsCredential>, Option<SystemTime>)>>,
+}
+
+impl RubyCredentialProvider {
+    pub fn new(provider: u64) -> AwsCredentialProvider {
+        Arc::new(Self {
+            provider,
+            cached: Mutex::new(None),
+        })
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for RubyCredentialProvider {
+    type Credential = AwsCredential;
+
+    async fn get_credential(&self) -> slatedb::object_store::Result<Arc<AwsCredential>> {
+        let mut cached = self.cached.lock().await;
+        if let Some((credential, expires_at)) = cached.as_ref() {
+            let fresh = expires_at.is_none_or(|at| SystemTime::now() + REFRESH_MARGIN < at);
+            if fresh {
+                return Ok(credential.clone());
+            }
+        }
+
+        let (credential, expires_at) =
+            request_credentials(self.provider)
+                .await
+                .map_err(|message| ObjectStoreError::Generic {
+                    store: "S3",
+                    source: format!("credentials provider failed: {}", message).into(),
+                })?;
+        let credential = Arc::new(credential);
+        *cached = Some((credential.clone(), expires_at));
+        Ok(credential)
+    }
+}
+
+/// The provider for S3 stores opened without `credentials_provider:`.
+pub fn default_provider() -> Option<u64> {
+    *DEFAULT_PROVIDER.lock().expect("default provider poisoned")
+}
+
+/// Set or clear the provider for S3 stores opened without
+/// `credentials_provider:`.
+fn set_default_credentials_provider(provider: Option<u64>) -> Result<(), Error> {
+    if provider.is_some() {
+        ensure_main_ractor("credentials_provider")?;
+    }
+    *DEFAULT_PROVIDER.lock().expect("default provider poisoned") = provider;
+    Ok(())
+}
+
+/// Wait briefly for the next credential request.
+///
+/// Returns `[token, provider_id]`, or nil if no request arrived in time.
+fn next_credential_request() -> Result<Option<(u64, u64)>, Error> {
+    let requests = {
+        let mut queue = REQUEST_QUEUE.lock().expect("credential queue poisoned");
+        let queue = queue.get_or_insert_with(RequestQueue::new);
+        if is_forked(queue.pid) {
+            *queue = RequestQueue::new();
+        }
+        queue.requests.clone()
+    };
+    block_on(async {
+        tokio::time::timeout(REQUEST_POLL_INTERVAL, async {
+            requests.lock().await.recv().await
+        })
+        .await
+        .ok()
+        .flatten()
+    })
+}
+
+/// Answer a credential request with the provider's credentials.
+/// `expires_at` is in seconds since the epoch.
+///
+/// Raises InvalidArgumentError if `expires_at` is not a representable time
+/// (e.g. infinite); the serving thread then rejects the request.
+fn resolve_credential_request(
+    token: u64,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    expires_at: Option<f64>,
+) -> Result<(), Error> {
+    let credential = AwsCredential {
+        key_id: access_key_id,
+        secret_key: secret_access_key,
+        token: session_token,
+    };
+    let expires_at = expires_at
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs.max(0.0))
+                .ok()
+                .and_then(|since_epoch| UNIX_EPOCH.checked_add(since_epoch))
+                .ok_or_else(|| {
+                    invalid_argument_error(&format!("invalid credentials expires_at: {}", secs))
+                })
+        })
+        .transpose()?;
+    if let Some(reply) = take_pending(token) {
+        let _ = reply.send(Ok((credential, expires_at)));
+    }
+    Ok(())
+}
+
+/// Fail a credential request, for example because the provider raised.
+fn reject_credential_request(token: u64, message: String) {
+    if let Some(reply) = take_pending(token) {
+        let _ = reply.send(Err(message));
+    }
+}
+
+/// Define the credentials provider functions on the SlateDb module.
+pub fn define_credentials_functions(module: &magnus::RModule) -> Result<(), Error> {
+    module.define_module_function(
+        "_set_default_credentials_provider",
+        function!(set_default_credentials_provider, 1),
+    )?;
+    module.define_module_function(
+        "_next_credential_request",
+        function!(next_credential_request, 0),
+    )?;
+    module.define_module_function(
+        "_resolve_credential_request",
+        function!(resolve_credential_request, 5),
+    )?;
+    module.define_module_function(
+        "_reject_credential_request",
+        function!(reject_credential_request, 2),
+    )?;
+    Ok(())
+}
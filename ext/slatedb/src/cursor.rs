@@ -0,0 +1,105 @@
+use std::ops::Bound;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64;
+use base64::Engine;
+use magnus::Error;
+use serde_json::{json, Value as JsonValue};
+
+use crate::errors::invalid_argument_error;
+use crate::iterator::ScanRange;
+
+/// Version of the token format, bumped if its layout changes.
+const CURSOR_VERSION: u64 = 1;
+
+/// Where a scan stopped: the part of its range it has not yet returned.
+///
+/// Encoded as an opaque token by `Iterator#cursor` and decoded by
+/// `Database#scan_from_cursor`. The token is URL-safe base64 over JSON and
+/// holds only keys, so it stays valid across process restarts; it does not
+/// pin a snapshot, so a resumed scan sees writes made in between.
+#[derive(Clone)]
+pub struct ScanCursor {
+    pub range: ScanRange,
+    pub descending: bool,
+    /// Whether keys are returned relative to the range's prefix, as
+    /// namespace scans do.
+    pub namespaced: bool,
+}
+
+impl ScanCursor {
+    /// Record that the scan has moved to `position` in iteration order.
+    pub fn advance(&mut self, position: Bound<Vec<u8>>) {
+        self.range.narrow(position, self.descending);
+    }
+
+    /// Encode the cursor as an opaque token.
+    pub fn encode(&self) -> String {
+        let (prefix, start, end) = match &self.range {
+            ScanRange::Range(start, end) => (None, start, end),
+            ScanRange::Prefix(prefix, (start, end)) => (Some(prefix), start, end),
+        };
+        let token = json!({
+            "v": CURSOR_VERSION,
+            "order": if self.descending { "desc" } else { "asc" },
+            "prefix": prefix.map(|prefix| BASE64.encode(prefix)),
+            "start": encode_bound(start),
+            "end": encode_bound(end),
+            "namespaced": self.namespaced,
+        });
+        BASE64.encode(token.to_string())
+    }
+
+    /// Decode a token returned by `encode`.
+    pub fn decode(token: &str) -> Result<Self, Error> {
+        let invalid = || invalid_argument_error("invalid scan cursor");
+        let json = BASE64.decode(token).map_err(|_| invalid())?;
+        let token: JsonValue = serde_json::from_slice(&json).map_err(|_| invalid())?;
+        if token["v"].as_u64() != Some(CURSOR_VERSION) {
+            return Err(invalid());
+        }
+
+        let start = decode_bound(&token["start"]).ok_or_else(invalid)?;
+        let end = decode_bound(&token["end"]).ok_or_else(invalid)?;
+        let range = match &token["prefix"] {
+            JsonValue::Null => ScanRange::Range(start, end),
+            prefix => {
+                let prefix = prefix
+                    .as_str()
+                    .and_then(|prefix| BASE64.decode(prefix).ok())
+                    .ok_or_else(invalid)?;
+                ScanRange::Prefix(prefix, (start, end))
+            }
+        };
+        let descending = match token["order"].as_str() {
+            Some("asc") => false,
+            Some("desc") => true,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            range,
+            descending,
+            namespaced: token["namespaced"].as_bool().unwrap_or(false),
+        })
+    }
+}
+
+/// Encode a bound as null, `{"inc": key}` or `{"exc": key}`.
+fn encode_bound(bound: &Bound<Vec<u8>>) -> JsonValue {
+    match bound {
+        Bound::Unbounded => JsonValue::Null,
+        Bound::Included(key) => json!({ "inc": BASE64.encode(key) }),
+        Bound::Excluded(key) => json!({ "exc": BASE64.encode(key) }),
+    }
+}
+
+/// Decode a bound written by `encode_bound`, or None if it is malformed.
+fn decode_bound(bound: &JsonValue) -> Option<Bound<Vec<u8>>> {
+    let key = |encoded: &JsonValue| BASE64.decode(encoded.as_str()?).ok();
+    match bound {
+        JsonValue::Null => Some(Bound::Unbounded),
+        bound if bound.get("inc").is_some() => key(&bound["inc"]).map(Bound::Included),
+        bound if bound.get("exc").is_some() => key(&bound["exc"]).map(Bound::Excluded),
+        _ => None,
+    }
+}
@@ -1,58 +1,299 @@
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-
+use std::future::Future;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use magnus::block::Proc;
 use magnus::prelude::*;
-use magnus::{function, method, Error, RHash, Ruby};
+use magnus::value::Opaque;
+use magnus::{
+    function, method, DataTypeFunctions, Error, RArray, RHash, RString, Ruby, Symbol, TryConvert,
+    TypedData, Value,
+};
+use slatedb::admin::{AdminBuilder, CloneSourceSpec};
 use slatedb::config::{
-    DurabilityLevel, MergeOptions, PutOptions, ReadOptions, ScanOptions, Ttl, WriteOptions,
+    DurabilityLevel, FlushOptions, FlushType, MergeOptions, PutOptions, ReadOptions, ScanOptions,
+    Settings, Ttl, WriteOptions,
 };
+use slatedb::db_stats::{L0_FLUSH_BYTES, TOTAL_MEM_SIZE_BYTES, WAL_FLUSH_BYTES};
 use slatedb::object_store::memory::InMemory;
-use slatedb::{Db, IsolationLevel, IterationOrder, KeyValue};
-
-use crate::errors::invalid_argument_error;
-use crate::iterator::Iterator;
+use slatedb::object_store::ObjectStore;
+use slatedb::{
+    BlockTransformer, CloseReason, Db, Error as SlateError, ErrorKind, IsolationLevel,
+    IterationOrder, KeyValue, MergeOperator,
+};
+use slatedb_common::clock::SystemClock;
+use slatedb_common::metrics::DefaultMetricsRecorder;
+
+use crate::arrow::{self, ArrowSchema, ArrowStreamWriter};
+use crate::cdc::{ChangeStream, WalTail, Watcher};
+use crate::clock;
+use crate::codec::Codec;
+use crate::consistency::MinSeq;
+use crate::cursor::ScanCursor;
+use crate::encryption::AesGcmTransformer;
+use crate::errors::{
+    closed_error, data_error, internal_error, invalid_argument_error, is_transaction_error,
+    map_error, timeout_error,
+};
+use crate::fault;
+use crate::future::Future as RubyFuture;
+use crate::health;
+use crate::iterator::{
+    auto_read_ahead_from_kwargs, entry_shape_from_kwargs, AdaptiveReadAhead, EntryShape, Iterator,
+    ScanRange, ViewInfo,
+};
+use crate::limits::SizeLimits;
 use crate::merge_ops::{parse_merge_operator, parse_merge_operator_proc};
-use crate::metrics::Metrics;
-use crate::runtime::block_on_result;
+use crate::metrics::{counter_total, gauge_value, stats_to_hash, LatencyStats, Metrics};
+use crate::progress::{percent, Progress};
+use crate::rate_limit::RateLimit;
+use crate::read_defaults::ReadDefaults;
+use crate::reader::Reader;
+use crate::recovery::{PendingReplay, RecoveryMode, RecoveryReport};
+use crate::retry::{self, RetryPolicy};
+use crate::runtime::{
+    block_on_result, ensure_main_ractor, ensure_same_process, is_forked, RuntimeRef,
+};
+use crate::shutdown::{ensure_running, register_database};
 use crate::snapshot::Snapshot;
+use crate::store_stats::StoreStats;
+use crate::telemetry::trace_span;
+use crate::thread_mode::ThreadMode;
 use crate::transaction::Transaction;
-use crate::utils::{get_optional, prefix_subrange_from_kwargs, resolve_object_store};
+use crate::utils::{
+    get_optional, get_positive, prefix_subrange_from_kwargs, resolve_object_store, ttl_from_kwargs,
+    StoreOptions,
+};
+use crate::value_buffer;
 use crate::write_batch::WriteBatch;
+use crate::write_result::WriteReturn;
+use crate::writer::{read_writer_info, register_writer};
+
+/// Default upper bound on the size of a value produced by `append` (16 MiB).
+const DEFAULT_APPEND_MAX_SIZE: usize = 16 * 1024 * 1024;
+
+/// Number of times `append` (unless given `retries`), `put_if_absent` and
+/// batches with a `delete_range` retry after a conflict.
+pub const CONFLICT_MAX_RETRIES: usize = 5;
+
+/// Delay before the first retry of a `transaction` block after a conflict.
+pub const TRANSACTION_RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+
+/// Upper bound on the delay between retries of a `transaction` block.
+pub const TRANSACTION_RETRY_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Entries per page when `Database#page` is not given a limit.
+const DEFAULT_PAGE_LIMIT: usize = 100;
+
+/// Operations per chunk when `write_chunked` is not given `max_ops`.
+const DEFAULT_CHUNK_OPS: usize = 10_000;
+
+/// Key and value bytes per chunk when `write_chunked` is not given
+/// `max_bytes` (4 MiB).
+const DEFAULT_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Tracks the Ruby threads blocked on a database's operations.
+#[derive(Default)]
+struct PendingOps {
+    count: AtomicUsize,
+    /// Threshold above which `hook` is called, and the hook itself. The
+    /// proc is kept alive by the Ruby Database object.
+    overload: Mutex<Option<(usize, Opaque<Proc>)>>,
+}
+
+/// Decrements the pending operation count when dropped.
+struct PendingGuard<'a>(&'a PendingOps);
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl PendingOps {
+    /// Count an operation as pending until the returned guard is dropped.
+    ///
+    /// If the count now exceeds the overload threshold, the hook is called
+    /// with it before the operation starts. An error raised by the hook
+    /// aborts the operation.
+    fn enter(&self) -> Result<PendingGuard<'_>, Error> {
+        let pending = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+        let guard = PendingGuard(self);
+
+        let overload = *self.overload.lock().expect("overload mutex poisoned");
+        if let Some((threshold, hook)) = overload {
+            if pending > threshold {
+                let ruby = Ruby::get().expect("Ruby runtime not available");
+                ruby.get_inner(hook).call::<_, Value>((pending,))?;
+            }
+        }
+
+        Ok(guard)
+    }
+}
 
 /// Ruby wrapper for SlateDB database.
 ///
 /// This struct is exposed to Ruby as `SlateDb::Database`.
-#[magnus::wrap(class = "SlateDb::Database", free_immediately, size)]
+#[derive(TypedData)]
+#[magnus(class = "SlateDb::Database", free_immediately, size)]
 pub struct Database {
-    inner: Arc<Db>,
+    /// Replaced by `reopen`; see `db`.
+    inner: RwLock<Arc<Db>>,
+    /// What the Db was built from, so `reopen` can build it again.
+    config: OpenConfig,
+    path: String,
+    object_store: Arc<dyn ObjectStore>,
     metrics: Arc<Mutex<HashMap<String, i64>>>,
+    recorder: Arc<DefaultMetricsRecorder>,
+    latency: Arc<LatencyStats>,
+    /// Requests made to the object store, by request class.
+    store_stats: Arc<StoreStats>,
+    pending: PendingOps,
+    /// Retries for get, put and scan when object storage is unavailable.
+    retry: Option<RetryPolicy>,
+    /// How values are encoded; see the `codec` open option.
+    codec: Codec,
+    /// Largest keys and values accepted by writes.
+    limits: SizeLimits,
+    /// Read tuning applied to gets and scans that don't pass their own.
+    read_defaults: ReadDefaults,
+    /// Block transformer the database was opened with, reused by readers
+    /// opened from it.
+    encryption: Option<Arc<dyn BlockTransformer>>,
+    /// Identifies this handle in the database's writer record.
+    client_id: String,
+    /// Writer epoch this handle claimed when it last opened.
+    writer_epoch: AtomicU64,
+    /// What the database recovered from its WAL when it last opened.
+    recovery: Mutex<RecoveryReport>,
+    /// The process that opened the database; see `ensure_same_process`.
+    pid: u32,
+    /// Set when a forced `close` abandons the Db, which is left open
+    /// underneath; the handle then acts closed until `reopen`.
+    abandoned: AtomicBool,
+    /// Which threads may use the handle; see the `thread_safe` open option.
+    thread_mode: ThreadMode,
+    /// The runtime operations and background tasks run on; see the
+    /// `dedicated_runtime` open option.
+    runtime: RuntimeRef,
+}
+
+/// The settings, merge operator, WAL recovery mode and clock a database
+/// was opened with.
+struct OpenConfig {
+    settings: Option<Settings>,
+    merge_operator: Option<Arc<dyn MergeOperator + Send + Sync>>,
+    recovery: RecoveryMode,
+    /// The test clock set when the database was opened, if any.
+    clock: Option<Arc<dyn SystemClock>>,
+}
+
+impl DataTypeFunctions for Database {
+    /// Report the handle plus its unflushed WAL and immutable memtable bytes,
+    /// as SlateDB last measured them when applying write backpressure.
+    fn size(&self) -> usize {
+        let unflushed = if self.db().status().close_reason.is_some() {
+            0
+        } else {
+            gauge_value(&self.recorder.snapshot(), TOTAL_MEM_SIZE_BYTES).max(0) as usize
+        };
+        std::mem::size_of::<Self>() + self.path.capacity() + unflushed
+    }
 }
 
 impl Database {
+    /// The open Db. Operations take it once and use it throughout, so one
+    /// that overlaps a `reopen` finishes against the Db it started with.
+    fn db(&self) -> Arc<Db> {
+        self.inner.read().expect("database lock poisoned").clone()
+    }
+
+    /// Raise unless the handle may be used here: in the process that
+    /// opened it and, if it is exclusive, on its owner thread, and not after
+    /// a forced close abandoned it.
+    fn ensure_usable(&self) -> Result<(), Error> {
+        ensure_same_process(self.pid)?;
+        self.thread_mode.ensure_owner()?;
+        self.ensure_not_abandoned()
+    }
+
+    /// Raise ClosedError if a forced `close` abandoned the Db.
+    fn ensure_not_abandoned(&self) -> Result<(), Error> {
+        if self.abandoned.load(Ordering::SeqCst) {
+            return Err(closed_error("database is closed"));
+        }
+        Ok(())
+    }
+
+    /// Run a database operation, counting it as pending while it blocks.
+    fn block_on<F, T>(&self, future: F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, SlateError>>,
+    {
+        self.thread_mode.ensure_owner()?;
+        self.block_on_any_thread(future)
+    }
+
+    /// Like `block_on`, but allowed from any thread of an exclusive handle,
+    /// for operations such as `close` that another thread may need to run.
+    fn block_on_any_thread<F, T>(&self, future: F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, SlateError>>,
+    {
+        ensure_same_process(self.pid)?;
+        self.ensure_not_abandoned()?;
+        let _guard = self.pending.enter()?;
+        self.runtime.block_on_result(future)
+    }
+
+    /// Run an operation built by `op`, retrying it under the database's
+    /// `retry` policy.
+    fn block_on_retrying<F, Fut, T>(&self, op: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, SlateError>>,
+    {
+        self.block_on(retry::run(self.retry.as_ref(), op))
+    }
+
     fn increment_metric(&self, name: &str) {
         let mut metrics = self.metrics.lock().expect("metrics mutex poisoned");
         *metrics.entry(name.to_string()).or_insert(0) += 1;
     }
 
-    fn key_value_to_hash(kv: KeyValue) -> Result<RHash, Error> {
+    /// The current time in milliseconds since the epoch, by the clock the
+    /// database was opened with.
+    fn now_millis(&self) -> i64 {
+        clock::now_millis(self.config.clock.as_ref())
+    }
+
+    fn key_value_to_hash(&self, kv: KeyValue) -> Result<RHash, Error> {
         let ruby = Ruby::get().expect("Ruby runtime not available");
         let hash = ruby.hash_new();
         hash.aset(
             ruby.to_symbol("key"),
             String::from_utf8_lossy(&kv.key).to_string(),
         )?;
-        hash.aset(
-            ruby.to_symbol("value"),
-            String::from_utf8_lossy(&kv.value).to_string(),
-        )?;
+        hash.aset(ruby.to_symbol("value"), self.codec.decode(&kv.value)?)?;
         hash.aset(ruby.to_symbol("seq"), kv.seq)?;
         hash.aset(ruby.to_symbol("create_ts"), kv.create_ts)?;
         hash.aset(ruby.to_symbol("expire_ts"), kv.expire_ts)?;
+        let now = self.now_millis();
+        hash.aset(
+            ruby.to_symbol("ttl"),
+            kv.expire_ts.map(|expire_ts| remaining_ttl(expire_ts, now)),
+        )?;
         Ok(hash)
     }
 
-    fn read_options_from_kwargs(kwargs: &RHash) -> Result<ReadOptions, Error> {
-        let mut opts = ReadOptions::default();
+    /// Read options from a call's kwargs, on top of the database's defaults.
+    fn read_options_from_kwargs(&self, kwargs: &RHash) -> Result<ReadOptions, Error> {
+        let mut opts = self.read_defaults.read_options();
 
         if let Some(df) = get_optional::<String>(kwargs, "durability_filter")? {
             opts.durability_filter = match df.as_str() {
@@ -78,6 +319,15 @@ impl Database {
         Ok(opts)
     }
 
+    /// Wait for the write named by the `min_seq` read option, if given, to
+    /// be visible.
+    fn await_min_seq(&self, kwargs: &RHash) -> Result<(), Error> {
+        match MinSeq::from_kwargs(kwargs)? {
+            Some(min_seq) => min_seq.wait(&*self.db()),
+            None => Ok(()),
+        }
+    }
+
     fn write_options_from_kwargs(kwargs: &RHash) -> Result<WriteOptions, Error> {
         let await_durable = get_optional::<bool>(kwargs, "await_durable")?.unwrap_or(true);
         let seqnum = get_optional::<u64>(kwargs, "seqnum")?.unwrap_or(0);
@@ -88,38 +338,222 @@ impl Database {
         })
     }
 
+    /// Scan options from a call's kwargs, on top of the database's defaults.
+    fn scan_options_from_kwargs(&self, kwargs: &RHash) -> Result<ScanOptions, Error> {
+        let mut opts = self.read_defaults.scan_options();
+
+        // Parse durability_filter
+        if let Some(df) = get_optional::<String>(kwargs, "durability_filter")? {
+            opts.durability_filter = match df.as_str() {
+                "remote" => DurabilityLevel::Remote,
+                "memory" => DurabilityLevel::Memory,
+                other => {
+                    return Err(invalid_argument_error(&format!(
+                        "invalid durability_filter: {} (expected 'remote' or 'memory')",
+                        other
+                    )))
+                }
+            };
+        }
+
+        // Parse dirty
+        if let Some(dirty) = get_optional::<bool>(kwargs, "dirty")? {
+            opts.dirty = dirty;
+        }
+
+        // Parse read_ahead_bytes
+        if let Some(rab) = get_optional::<usize>(kwargs, "read_ahead_bytes")? {
+            opts.read_ahead_bytes = rab;
+        }
+
+        // Parse cache_blocks
+        if let Some(cb) = get_optional::<bool>(kwargs, "cache_blocks")? {
+            opts.cache_blocks = cb;
+        }
+
+        // Parse max_fetch_tasks
+        if let Some(mft) = get_optional::<usize>(kwargs, "max_fetch_tasks")? {
+            opts.max_fetch_tasks = mft;
+        }
+        if let Some(order) = get_optional::<String>(kwargs, "order")? {
+            opts.order = match order.as_str() {
+                "ascending" | "asc" => IterationOrder::Ascending,
+                "descending" | "desc" => IterationOrder::Descending,
+                other => {
+                    return Err(invalid_argument_error(&format!(
+                        "invalid order: {} (expected 'asc' or 'desc')",
+                        other
+                    )))
+                }
+            };
+        }
+
+        Ok(opts)
+    }
+
+    /// Build database settings from open kwargs.
+    ///
+    /// Returns `None` when no setting was supplied so SlateDB's defaults apply.
+    fn settings_from_kwargs(kwargs: &RHash) -> Result<Option<Settings>, Error> {
+        let default_ttl = get_optional::<u64>(kwargs, "default_ttl_ms")?;
+        let l0_sst_size_bytes = get_positive(kwargs, "l0_sst_size_bytes")?;
+        let l0_max_ssts = get_positive(kwargs, "l0_max_ssts")?;
+        let max_unflushed_bytes = get_positive(kwargs, "max_unflushed_bytes")?;
+        if default_ttl.is_none()
+            && l0_sst_size_bytes.is_none()
+            && l0_max_ssts.is_none()
+            && max_unflushed_bytes.is_none()
+        {
+            return Ok(None);
+        }
+
+        let defaults = Settings::default();
+        Ok(Some(Settings {
+            default_ttl,
+            l0_sst_size_bytes: l0_sst_size_bytes.unwrap_or(defaults.l0_sst_size_bytes),
+            l0_max_ssts: l0_max_ssts.unwrap_or(defaults.l0_max_ssts),
+            max_unflushed_bytes: max_unflushed_bytes.unwrap_or(defaults.max_unflushed_bytes),
+            ..defaults
+        }))
+    }
+
     /// Open a database at the given path.
     ///
     /// # Arguments
     /// * `path` - The path identifier for the database
     /// * `url` - Optional object store URL (e.g., "s3://bucket/path")
-    /// * `kwargs` - Additional options (merge_operator, merge_operator_proc)
+    /// * `kwargs` - Additional options (merge_operator, merge_operator_proc, default_ttl_ms,
+    ///   retry, codec, encryption, max_key_bytes, max_value_bytes, store_options,
+    ///   max_requests_per_second, max_concurrent_requests, read_ahead_bytes, cache_blocks,
+    ///   max_fetch_tasks, l0_sst_size_bytes, l0_max_ssts, max_unflushed_bytes, thread_safe,
+    ///   dedicated_runtime, worker_threads)
     ///
     /// # Returns
     /// A new Database instance
     pub fn open(path: String, url: Option<String>, kwargs: RHash) -> Result<Self, Error> {
+        ensure_running()?;
+
         // Try string-based merge operator first, then proc-based
         let merge_operator = parse_merge_operator(&kwargs)?.or(parse_merge_operator_proc(&kwargs)?);
+        let settings = Self::settings_from_kwargs(&kwargs)?;
+        let retry = RetryPolicy::from_kwargs(&kwargs)?;
+        let codec = Codec::from_kwargs(&kwargs)?;
+        let limits = SizeLimits::from_kwargs(&kwargs)?;
+        let encryption = AesGcmTransformer::from_kwargs(&kwargs)?;
+        let store_options = StoreOptions::from_kwargs(&kwargs)?;
+        let rate_limit = RateLimit::from_kwargs(&kwargs)?;
+        let read_defaults = ReadDefaults::from_kwargs(&kwargs)?;
+        let thread_mode = ThreadMode::from_kwargs(&kwargs)?;
+        let runtime = RuntimeRef::from_kwargs(&kwargs)?;
+        let recorder = Arc::new(DefaultMetricsRecorder::new());
+        let store_stats = Arc::new(StoreStats::default());
+        let client_id = uuid::Uuid::new_v4().to_string();
+
+        let recovery = RecoveryMode::from_kwargs(&kwargs)?;
+        if recovery == RecoveryMode::Tolerant && encryption.is_some() {
+            // Telling a corrupt WAL SST from an encrypted one needs the key.
+            return Err(invalid_argument_error(
+                "wal_recovery: :tolerant cannot be combined with encryption",
+            ));
+        }
+        let config = OpenConfig {
+            settings,
+            merge_operator,
+            recovery,
+            clock: clock::test_clock(),
+        };
 
-        let db = block_on_result(async {
-            let object_store: Arc<dyn slatedb::object_store::ObjectStore> =
-                if let Some(ref url_str) = url {
-                    resolve_object_store(url_str)?
-                } else {
-                    Arc::new(InMemory::new())
-                };
-
-            let mut builder = Db::builder(path, object_store);
-            if let Some(merge_operator) = merge_operator {
-                builder = builder.with_merge_operator(merge_operator);
-            }
-
-            builder.build().await
+        // Built on the database's runtime, so its background tasks run there.
+        let (db, object_store, writer_epoch, recovery) = runtime.block_on_result(async {
+            let object_store: Arc<dyn ObjectStore> = if let Some(ref url_str) = url {
+                resolve_object_store(url_str, &store_options)?
+            } else {
+                Arc::new(InMemory::new())
+            };
+            let object_store = rate_limit.wrap(store_stats.wrap(fault::wrap(object_store)));
+            let (db, epoch, recovery) = build_db(
+                &path,
+                &object_store,
+                &config,
+                &recorder,
+                &encryption,
+                &client_id,
+            )
+            .await?;
+            Ok((db, object_store, epoch, recovery))
         })?;
 
+        let inner = Arc::new(db);
+        register_database(&inner);
+
         Ok(Self {
-            inner: Arc::new(db),
+            inner: RwLock::new(inner),
+            config,
+            path,
+            object_store,
             metrics: Arc::new(Mutex::new(HashMap::new())),
+            recorder,
+            latency: Arc::new(LatencyStats::default()),
+            store_stats,
+            pending: PendingOps::default(),
+            retry,
+            codec,
+            limits,
+            read_defaults,
+            encryption,
+            client_id,
+            writer_epoch: AtomicU64::new(writer_epoch),
+            recovery: Mutex::new(recovery),
+            pid: std::process::id(),
+            abandoned: AtomicBool::new(false),
+            thread_mode,
+            runtime,
+        })
+    }
+
+    /// Create a database at `path` that is a copy-on-write clone of another
+    /// database in the same object store.
+    ///
+    /// The clone's manifest references the parent's SSTs rather than copying
+    /// them; only the parent's WAL is copied. Does nothing if `path` already
+    /// holds a clone of the same parent.
+    ///
+    /// # Arguments
+    /// * `path` - The path identifier for the new database
+    /// * `url` - Object store URL holding both databases
+    /// * `kwargs` - Options: parent_path (required), parent_checkpoint (UUID
+    ///   string; the parent's latest state if omitted), store_options
+    pub fn create_clone(path: String, url: String, kwargs: RHash) -> Result<(), Error> {
+        ensure_running()?;
+
+        let parent_path = get_optional::<String>(&kwargs, "parent_path")?
+            .filter(|parent| !parent.is_empty())
+            .ok_or_else(|| invalid_argument_error("parent_path is required"))?;
+        if parent_path == path {
+            return Err(invalid_argument_error(
+                "a database cannot be cloned onto its own path",
+            ));
+        }
+        let parent_checkpoint = get_optional::<String>(&kwargs, "parent_checkpoint")?
+            .map(|id| {
+                uuid::Uuid::parse_str(&id).map_err(|e| {
+                    invalid_argument_error(&format!("invalid parent_checkpoint: {}", e))
+                })
+            })
+            .transpose()?;
+        let store_options = StoreOptions::from_kwargs(&kwargs)?;
+
+        block_on_result(async {
+            let object_store = resolve_object_store(&url, &store_options)?;
+            let source: CloneSourceSpec = match parent_checkpoint {
+                Some(id) => CloneSourceSpec::with_checkpoint(parent_path.as_str(), id),
+                None => CloneSourceSpec::new(parent_path.as_str()),
+            };
+            AdminBuilder::new(path.as_str(), object_store)
+                .build()
+                .create_clone_builder_from_source(source)
+                .build()
+                .await
         })
     }
 
@@ -129,19 +563,20 @@ impl Database {
     /// * `key` - The key to look up
     ///
     /// # Returns
-    /// The value as a String, or nil if not found
-    pub fn get(&self, key: String) -> Result<Option<String>, Error> {
+    /// The value, decoded by the database's codec, or nil if not found
+    pub fn get(&self, key: String) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let opts = ReadOptions::default();
+        let opts = self.read_defaults.read_options();
 
-        let result =
-            block_on_result(async { self.inner.get_with_options(key.as_bytes(), &opts).await })?;
+        let _timer = self.latency.start("get");
+        let db = self.db();
+        let result = self.block_on_retrying(|| db.get_with_options(key.as_bytes(), &opts))?;
         self.increment_metric("db.get.count");
 
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        result.map(|b| self.codec.decode(&b)).transpose()
     }
 
     /// Get a value by key with options.
@@ -151,19 +586,84 @@ impl Database {
     /// * `kwargs` - Keyword arguments (durability_filter, dirty, cache_blocks)
     ///
     /// # Returns
-    /// The value as a String, or nil if not found
-    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<String>, Error> {
+    /// The value, decoded by the database's codec, or nil if not found
+    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let opts = Self::read_options_from_kwargs(&kwargs)?;
+        let opts = self.read_options_from_kwargs(&kwargs)?;
+        self.await_min_seq(&kwargs)?;
 
-        let result =
-            block_on_result(async { self.inner.get_with_options(key.as_bytes(), &opts).await })?;
+        let _timer = self.latency.start("get");
+        let _span = trace_span("get", &kwargs)?;
+        let db = self.db();
+        let result = self.block_on_retrying(|| db.get_with_options(key.as_bytes(), &opts))?;
         self.increment_metric("db.get_with_options.count");
 
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        result.map(|b| self.codec.decode(&b)).transpose()
+    }
+
+    /// Get a value by key as a read-only `IO::Buffer` over the bytes SlateDB
+    /// returned, without copying them into a String.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `kwargs` - Keyword arguments (durability_filter, dirty, cache_blocks)
+    ///
+    /// # Returns
+    /// The stored bytes, which the codec does not decode, or nil if not found
+    pub fn get_buffer(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        Ok(self
+            .read_value(&key, &kwargs)?
+            .map(|bytes| value_buffer::io_buffer(&ruby, bytes)))
+    }
+
+    /// Get a value by key, copying it into the writable `IO::Buffer` `buffer`.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `buffer` - The IO::Buffer to copy the stored bytes into
+    /// * `kwargs` - Keyword arguments (durability_filter, dirty, cache_blocks)
+    ///
+    /// # Returns
+    /// The number of bytes copied, or nil if not found
+    pub fn get_into(
+        &self,
+        key: String,
+        buffer: Value,
+        kwargs: RHash,
+    ) -> Result<Option<usize>, Error> {
+        self.read_value(&key, &kwargs)?
+            .map(|bytes| value_buffer::copy_into(buffer, &bytes))
+            .transpose()
+    }
+
+    /// Get the size of a value in bytes, without copying it into Ruby.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `kwargs` - Keyword arguments (durability_filter, dirty, cache_blocks)
+    ///
+    /// # Returns
+    /// The size of the stored value, 0 for an empty value, or nil if not found
+    pub fn value_size(&self, key: String, kwargs: RHash) -> Result<Option<usize>, Error> {
+        Ok(self.read_value(&key, &kwargs)?.map(|bytes| bytes.len()))
+    }
+
+    fn read_value(&self, key: &str, kwargs: &RHash) -> Result<Option<Bytes>, Error> {
+        if key.is_empty() {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+
+        let opts = self.read_options_from_kwargs(kwargs)?;
+
+        let _timer = self.latency.start("get");
+        let db = self.db();
+        let result = self.block_on_retrying(|| db.get_with_options(key.as_bytes(), &opts))?;
+        self.increment_metric("db.get.count");
+        Ok(result)
     }
 
     /// Get a key-value pair with metadata by key.
@@ -172,21 +672,21 @@ impl Database {
     /// * `key` - The key to look up
     ///
     /// # Returns
-    /// A Hash with key, value, seq, create_ts, and expire_ts, or nil if not found
+    /// A Hash with key, value, seq, create_ts, expire_ts and ttl (the
+    /// milliseconds left before expiry), or nil if not found
     pub fn get_key_value(&self, key: String) -> Result<Option<RHash>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let opts = ReadOptions::default();
-        let result = block_on_result(async {
-            self.inner
-                .get_key_value_with_options(key.as_bytes(), &opts)
-                .await
-        })?;
+        let opts = self.read_defaults.read_options();
+        let _timer = self.latency.start("get");
+        let db = self.db();
+        let result =
+            self.block_on_retrying(|| db.get_key_value_with_options(key.as_bytes(), &opts))?;
         self.increment_metric("db.get_key_value.count");
 
-        result.map(Self::key_value_to_hash).transpose()
+        result.map(|kv| self.key_value_to_hash(kv)).transpose()
     }
 
     /// Get a key-value pair with metadata by key with options.
@@ -196,7 +696,8 @@ impl Database {
     /// * `kwargs` - Keyword arguments (durability_filter, dirty, cache_blocks)
     ///
     /// # Returns
-    /// A Hash with key, value, seq, create_ts, and expire_ts, or nil if not found
+    /// A Hash with key, value, seq, create_ts, expire_ts and ttl (the
+    /// milliseconds left before expiry), or nil if not found
     pub fn get_key_value_with_options(
         &self,
         key: String,
@@ -206,15 +707,14 @@ impl Database {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let opts = Self::read_options_from_kwargs(&kwargs)?;
-        let result = block_on_result(async {
-            self.inner
-                .get_key_value_with_options(key.as_bytes(), &opts)
-                .await
-        })?;
+        let opts = self.read_options_from_kwargs(&kwargs)?;
+        let _timer = self.latency.start("get");
+        let db = self.db();
+        let result =
+            self.block_on_retrying(|| db.get_key_value_with_options(key.as_bytes(), &opts))?;
         self.increment_metric("db.get_key_value_with_options.count");
 
-        result.map(Self::key_value_to_hash).transpose()
+        result.map(|kv| self.key_value_to_hash(kv)).transpose()
     }
 
     /// Get a value by key as raw bytes.
@@ -229,10 +729,11 @@ impl Database {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let opts = ReadOptions::default();
+        let opts = self.read_defaults.read_options();
 
-        let result =
-            block_on_result(async { self.inner.get_with_options(key.as_bytes(), &opts).await })?;
+        let _timer = self.latency.start("get");
+        let db = self.db();
+        let result = self.block_on_retrying(|| db.get_with_options(key.as_bytes(), &opts))?;
         self.increment_metric("db.get_bytes.count");
 
         Ok(result.map(|b| b.to_vec()))
@@ -242,11 +743,13 @@ impl Database {
     ///
     /// # Arguments
     /// * `key` - The key to store
-    /// * `value` - The value to store
-    pub fn put(&self, key: String, value: String) -> Result<(), Error> {
+    /// * `value` - The value to store, encoded by the database's codec
+    pub fn put(&self, key: String, value: Value) -> Result<(), Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
         let put_opts = PutOptions { ttl: Ttl::Default };
 
@@ -255,10 +758,10 @@ impl Database {
             seqnum: 0,
         };
 
-        block_on_result(async {
-            self.inner
-                .put_with_options(key.as_bytes(), value.as_bytes(), &put_opts, &write_opts)
-                .await
+        let _timer = self.latency.start("put");
+        let db = self.db();
+        self.block_on_retrying(|| {
+            db.put_with_options(key.as_bytes(), &value, &put_opts, &write_opts)
         })?;
         self.increment_metric("db.put.count");
 
@@ -269,33 +772,40 @@ impl Database {
     ///
     /// # Arguments
     /// * `key` - The key to store
-    /// * `value` - The value to store
+    /// * `value` - The value to store, encoded by the database's codec
     /// * `kwargs` - Keyword arguments (ttl, await_durable, seqnum)
-    pub fn put_with_options(&self, key: String, value: String, kwargs: RHash) -> Result<(), Error> {
+    pub fn put_with_options(
+        &self,
+        key: String,
+        value: Value,
+        kwargs: RHash,
+    ) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
         // Parse ttl
-        let ttl = get_optional::<u64>(&kwargs, "ttl")?;
         let put_opts = PutOptions {
-            ttl: match ttl {
-                Some(ms) => Ttl::ExpireAfter(ms),
-                None => Ttl::Default,
-            },
+            ttl: ttl_from_kwargs(&kwargs)?,
         };
 
         // Parse await_durable
         let write_opts = Self::write_options_from_kwargs(&kwargs)?;
-
-        block_on_result(async {
-            self.inner
-                .put_with_options(key.as_bytes(), value.as_bytes(), &put_opts, &write_opts)
-                .await
+        let write_return = WriteReturn::from_kwargs(&kwargs)?;
+
+        let started = Instant::now();
+        let _timer = self.latency.start("put");
+        let _span = trace_span("put", &kwargs)?;
+        let db = self.db();
+        let handle = self.block_on_retrying(|| {
+            db.put_with_options(key.as_bytes(), &value, &put_opts, &write_opts)
         })?;
         self.increment_metric("db.put_with_options.count");
 
-        Ok(())
+        let bytes = key.len() + value.len();
+        Ok(write_return.build(&db, &handle, &write_opts, bytes, started))
     }
 
     /// Delete a key.
@@ -306,14 +816,16 @@ impl Database {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        self.limits.check_key(key.as_bytes())?;
 
         let write_opts = WriteOptions {
             await_durable: true,
             seqnum: 0,
         };
 
-        block_on_result(async {
-            self.inner
+        let _timer = self.latency.start("delete");
+        self.block_on(async {
+            self.db()
                 .delete_with_options(key.as_bytes(), &write_opts)
                 .await
         })?;
@@ -327,21 +839,27 @@ impl Database {
     /// # Arguments
     /// * `key` - The key to delete
     /// * `kwargs` - Keyword arguments (await_durable, seqnum)
-    pub fn delete_with_options(&self, key: String, kwargs: RHash) -> Result<(), Error> {
+    pub fn delete_with_options(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        self.limits.check_key(key.as_bytes())?;
 
         let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+        let write_return = WriteReturn::from_kwargs(&kwargs)?;
 
-        block_on_result(async {
-            self.inner
+        let started = Instant::now();
+        let _timer = self.latency.start("delete");
+        let _span = trace_span("delete", &kwargs)?;
+        let handle = self.block_on(async {
+            self.db()
                 .delete_with_options(key.as_bytes(), &write_opts)
                 .await
         })?;
         self.increment_metric("db.delete_with_options.count");
 
-        Ok(())
+        let bytes = key.len();
+        Ok(write_return.build(&self.db(), &handle, &write_opts, bytes, started))
     }
 
     /// Scan a range of keys.
@@ -357,19 +875,30 @@ impl Database {
             return Err(invalid_argument_error("start key cannot be empty"));
         }
 
-        let opts = ScanOptions::default();
+        let opts = self.read_defaults.scan_options();
 
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
-
-        let iter = block_on_result(async {
-            match end_bytes {
-                Some(end) => self.inner.scan_with_options(start_bytes..end, &opts).await,
-                None => self.inner.scan_with_options(start_bytes.., &opts).await,
+        let range = ScanRange::Range(
+            Bound::Included(start_bytes.clone()),
+            end_bytes.clone().map_or(Bound::Unbounded, Bound::Excluded),
+        );
+
+        let db = self.db();
+        let view = ViewInfo::capture(&*db);
+        let _timer = self.latency.start("scan");
+        let iter = self.block_on_retrying(|| async {
+            let start = start_bytes.clone();
+            match &end_bytes {
+                Some(end) => db.scan_with_options(start..end.clone(), &opts).await,
+                None => db.scan_with_options(start.., &opts).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone())
+            .with_cursor(range, opts.order))
     }
 
     /// Scan a range of keys with options.
@@ -391,65 +920,80 @@ impl Database {
             return Err(invalid_argument_error("start key cannot be empty"));
         }
 
-        let mut opts = ScanOptions::default();
-
-        // Parse durability_filter
-        if let Some(df) = get_optional::<String>(&kwargs, "durability_filter")? {
-            opts.durability_filter = match df.as_str() {
-                "remote" => DurabilityLevel::Remote,
-                "memory" => DurabilityLevel::Memory,
-                other => {
-                    return Err(invalid_argument_error(&format!(
-                        "invalid durability_filter: {} (expected 'remote' or 'memory')",
-                        other
-                    )))
-                }
-            };
-        }
+        let opts = self.scan_options_from_kwargs(&kwargs)?;
+        self.await_min_seq(&kwargs)?;
 
-        // Parse dirty
-        if let Some(dirty) = get_optional::<bool>(&kwargs, "dirty")? {
-            opts.dirty = dirty;
-        }
+        let shape = entry_shape_from_kwargs(&kwargs)?;
+        let range = ScanRange::Range(
+            Bound::Included(start.into_bytes()),
+            end_key.map_or(Bound::Unbounded, |end| Bound::Excluded(end.into_bytes())),
+        );
 
-        // Parse read_ahead_bytes
-        if let Some(rab) = get_optional::<usize>(&kwargs, "read_ahead_bytes")? {
-            opts.read_ahead_bytes = rab;
-        }
+        let _timer = self.latency.start("scan");
+        let _span = trace_span("scan", &kwargs)?;
+        self.scan_range(range, opts, &kwargs)
+            .map(|iter| iter.with_shape(shape))
+    }
 
-        // Parse cache_blocks
-        if let Some(cb) = get_optional::<bool>(&kwargs, "cache_blocks")? {
-            opts.cache_blocks = cb;
+    /// Scan a range of keys into an Arrow IPC stream.
+    ///
+    /// Entries are copied straight from the scan into Arrow buffers without
+    /// creating Ruby objects for them, and the whole scan runs without the GVL.
+    ///
+    /// # Arguments
+    /// * `start` - The start key (inclusive)
+    /// * `end_key` - Optional end key (exclusive)
+    /// * `kwargs` - Keyword arguments (schema, batch_size, limit, durability_filter, dirty,
+    ///   read_ahead_bytes, cache_blocks, max_fetch_tasks, order)
+    ///
+    /// # Returns
+    /// A binary String holding the stream
+    pub fn scan_arrow(
+        &self,
+        start: String,
+        end_key: Option<String>,
+        kwargs: RHash,
+    ) -> Result<RString, Error> {
+        if start.is_empty() {
+            return Err(invalid_argument_error("start key cannot be empty"));
         }
 
-        // Parse max_fetch_tasks
-        if let Some(mft) = get_optional::<usize>(&kwargs, "max_fetch_tasks")? {
-            opts.max_fetch_tasks = mft;
+        let opts = self.scan_options_from_kwargs(&kwargs)?;
+        let schema = ArrowSchema::from_kwargs(&kwargs)?;
+        let batch_size =
+            get_optional::<usize>(&kwargs, "batch_size")?.unwrap_or(arrow::DEFAULT_BATCH_SIZE);
+        if batch_size == 0 {
+            return Err(invalid_argument_error("batch_size must be positive"));
         }
-        if let Some(order) = get_optional::<String>(&kwargs, "order")? {
-            opts.order = match order.as_str() {
-                "ascending" | "asc" => IterationOrder::Ascending,
-                "descending" | "desc" => IterationOrder::Descending,
-                other => {
-                    return Err(invalid_argument_error(&format!(
-                        "invalid order: {} (expected 'asc' or 'desc')",
-                        other
-                    )))
+        let limit = get_optional::<usize>(&kwargs, "limit")?;
+
+        let _timer = self.latency.start("scan");
+        let _span = trace_span("scan", &kwargs)?;
+        let stream = self.block_on(async {
+            let start = start.into_bytes();
+            let mut iter = match end_key {
+                Some(end) => {
+                    self.db()
+                        .scan_with_options(start..end.into_bytes(), &opts)
+                        .await?
                 }
+                None => self.db().scan_with_options(start.., &opts).await?,
             };
-        }
-
-        let start_bytes = start.into_bytes();
-        let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
-            match end_bytes {
-                Some(end) => self.inner.scan_with_options(start_bytes..end, &opts).await,
-                None => self.inner.scan_with_options(start_bytes.., &opts).await,
+            let mut writer = ArrowStreamWriter::new(schema, batch_size);
+            let mut rows = 0;
+            while limit.is_none_or(|limit| rows < limit) {
+                let Some(kv) = iter.next().await? else {
+                    break;
+                };
+                writer.push(kv);
+                rows += 1;
             }
+            Ok(writer.finish())
         })?;
 
-        Ok(Iterator::new(iter))
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        Ok(ruby.str_from_slice(&stream))
     }
 
     /// Scan all keys with a given prefix.
@@ -464,14 +1008,18 @@ impl Database {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
 
-        let opts = ScanOptions::default();
-        let iter = block_on_result(async {
-            self.inner
-                .scan_prefix_with_options(prefix.as_bytes(), .., &opts)
-                .await
-        })?;
-
-        Ok(Iterator::new(iter))
+        let opts = self.read_defaults.scan_options();
+        let db = self.db();
+        let view = ViewInfo::capture(&*db);
+        let _timer = self.latency.start("scan");
+        let iter =
+            self.block_on_retrying(|| db.scan_prefix_with_options(prefix.as_bytes(), .., &opts))?;
+
+        let range = ScanRange::Prefix(prefix.into_bytes(), (Bound::Unbounded, Bound::Unbounded));
+        Ok(Iterator::new(iter, view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone())
+            .with_cursor(range, opts.order))
     }
 
     /// Scan all keys with a given prefix with options.
@@ -491,57 +1039,170 @@ impl Database {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
 
-        let mut opts = ScanOptions::default();
+        let opts = self.scan_options_from_kwargs(&kwargs)?;
+        self.await_min_seq(&kwargs)?;
 
-        if let Some(df) = get_optional::<String>(&kwargs, "durability_filter")? {
-            opts.durability_filter = match df.as_str() {
-                "remote" => DurabilityLevel::Remote,
-                "memory" => DurabilityLevel::Memory,
-                other => {
-                    return Err(invalid_argument_error(&format!(
-                        "invalid durability_filter: {} (expected 'remote' or 'memory')",
-                        other
-                    )))
-                }
-            };
-        }
+        let subrange = prefix_subrange_from_kwargs(&kwargs)?;
+        let shape = entry_shape_from_kwargs(&kwargs)?;
+        let _timer = self.latency.start("scan");
+        let _span = trace_span("scan", &kwargs)?;
+        let range = ScanRange::Prefix(prefix.into_bytes(), subrange);
+        self.scan_range(range, opts, &kwargs)
+            .map(|iter| iter.with_shape(shape))
+    }
 
-        if let Some(dirty) = get_optional::<bool>(&kwargs, "dirty")? {
-            opts.dirty = dirty;
+    /// Resume a scan from a token returned by `Iterator#cursor`.
+    ///
+    /// The scan continues over the rest of the original range, in its
+    /// original order, reading the database as it is now.
+    ///
+    /// # Arguments
+    /// * `token` - The cursor token
+    /// * `kwargs` - Keyword arguments as for `scan_with_options`, except
+    ///   `order`, which comes from the cursor
+    ///
+    /// # Returns
+    /// An Iterator over the rest of the range
+    pub fn scan_from_cursor(&self, token: String, kwargs: RHash) -> Result<Iterator, Error> {
+        let cursor = ScanCursor::decode(&token)?;
+        let mut opts = self.scan_options_from_kwargs(&kwargs)?;
+        opts.order = if cursor.descending {
+            IterationOrder::Descending
+        } else {
+            IterationOrder::Ascending
+        };
+        let shape = entry_shape_from_kwargs(&kwargs)?;
+
+        let _timer = self.latency.start("scan");
+        let _span = trace_span("scan", &kwargs)?;
+        let iter = self.scan_range(cursor.range.clone(), opts, &kwargs)?;
+        Ok(match cursor.range {
+            ScanRange::Prefix(prefix, _) if cursor.namespaced => iter.with_namespace(prefix),
+            _ => iter,
         }
+        .with_shape(shape))
+    }
 
-        if let Some(rab) = get_optional::<usize>(&kwargs, "read_ahead_bytes")? {
-            opts.read_ahead_bytes = rab;
+    /// Open a scan over `range`, adaptive if the kwargs ask for
+    /// `read_ahead: :auto`, that can hand out a cursor.
+    fn scan_range(
+        &self,
+        range: ScanRange,
+        opts: ScanOptions,
+        kwargs: &RHash,
+    ) -> Result<Iterator, Error> {
+        let order = opts.order;
+        if auto_read_ahead_from_kwargs(kwargs)? {
+            return Ok(self
+                .scan_adaptive(range.clone(), opts)?
+                .with_cursor(range, order));
         }
 
-        if let Some(cb) = get_optional::<bool>(&kwargs, "cache_blocks")? {
-            opts.cache_blocks = cb;
-        }
+        let db = self.db();
+        let view = ViewInfo::capture(&*db);
+        let iter = self.block_on_retrying(|| async {
+            match &range {
+                ScanRange::Range(start, end) => {
+                    db.scan_with_options((start.clone(), end.clone()), &opts)
+                        .await
+                }
+                ScanRange::Prefix(prefix, subrange) => {
+                    db.scan_prefix_with_options(prefix, subrange.clone(), &opts)
+                        .await
+                }
+            }
+        })?;
 
-        if let Some(mft) = get_optional::<usize>(&kwargs, "max_fetch_tasks")? {
-            opts.max_fetch_tasks = mft;
-        }
-        if let Some(order) = get_optional::<String>(&kwargs, "order")? {
-            opts.order = match order.as_str() {
-                "ascending" | "asc" => IterationOrder::Ascending,
-                "descending" | "desc" => IterationOrder::Descending,
-                other => {
-                    return Err(invalid_argument_error(&format!(
-                        "invalid order: {} (expected 'asc' or 'desc')",
-                        other
-                    )))
+        Ok(Iterator::new(iter, view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone())
+            .with_cursor(range, order))
+    }
+
+    /// Read one page of a scan, with a cursor for the next.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Keyword arguments (limit, prefix, start_after, cursor,
+    ///   plus the options of `scan_with_options`)
+    ///
+    /// # Returns
+    /// Hash with entries and next_cursor, which is nil on the last page
+    pub fn page(&self, kwargs: RHash) -> Result<RHash, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let limit = get_positive(&kwargs, "limit")?.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let prefix = get_optional::<String>(&kwargs, "prefix")?;
+        let start_after = get_optional::<String>(&kwargs, "start_after")?;
+        let token = get_optional::<String>(&kwargs, "cursor")?;
+        let mut opts = self.scan_options_from_kwargs(&kwargs)?;
+        let descending = matches!(opts.order, IterationOrder::Descending);
+
+        let range = match token {
+            Some(_) if prefix.is_some() || start_after.is_some() => {
+                return Err(invalid_argument_error(
+                    "cursor cannot be combined with prefix or start_after",
+                ))
+            }
+            Some(token) => {
+                let cursor = ScanCursor::decode(&token)?;
+                opts.order = if cursor.descending {
+                    IterationOrder::Descending
+                } else {
+                    IterationOrder::Ascending
+                };
+                cursor.range
+            }
+            None => {
+                let mut range = match prefix {
+                    Some(prefix) => {
+                        ScanRange::Prefix(prefix.into_bytes(), (Bound::Unbounded, Bound::Unbounded))
+                    }
+                    None => ScanRange::Range(Bound::Unbounded, Bound::Unbounded),
+                };
+                if let Some(key) = start_after {
+                    if !start_page_after(&mut range, key.into_bytes(), descending) {
+                        return page_to_hash(ruby.ary_new(), None);
+                    }
                 }
-            };
+                range
+            }
+        };
+        let shape = EntryShape {
+            limit: None,
+            ..entry_shape_from_kwargs(&kwargs)?
+        };
+
+        let _timer = self.latency.start("scan");
+        let _span = trace_span("page", &kwargs)?;
+        let iter = self.scan_range(range, opts, &kwargs)?.with_shape(shape);
+        let entries = ruby.ary_new_capa(limit);
+        while entries.len() < limit {
+            match iter.next_entry()? {
+                Some(entry) => entries.push(entry)?,
+                None => return page_to_hash(entries, None),
+            }
         }
+        // Only hand out a cursor if there is something after this page.
+        let cursor = iter.cursor()?;
+        let more = iter.next_entry()?.is_some();
+        iter.close()?;
+        page_to_hash(entries, more.then_some(cursor))
+    }
 
-        let subrange = prefix_subrange_from_kwargs(&kwargs)?;
-        let iter = block_on_result(async {
-            self.inner
-                .scan_prefix_with_options(prefix.as_bytes(), subrange, &opts)
-                .await
+    /// Open a `read_ahead: :auto` scan over `range`.
+    ///
+    /// The scan reads from a fresh snapshot so that it can be reopened with a
+    /// larger read-ahead without observing later writes.
+    fn scan_adaptive(&self, range: ScanRange, opts: ScanOptions) -> Result<Iterator, Error> {
+        let (iter, view, adaptive) = self.block_on(async {
+            let snapshot = self.db().snapshot().await?;
+            let view = ViewInfo::capture_at(&*self.db(), snapshot.seq());
+            let (adaptive, iter) = AdaptiveReadAhead::open(snapshot, range, opts).await?;
+            Ok((iter, view, adaptive))
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::with_adaptive_read_ahead(iter, view, adaptive)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Write a batch of operations atomically.
@@ -549,8 +1210,9 @@ impl Database {
     /// # Arguments
     /// * `batch` - The WriteBatch to write
     pub fn write(&self, batch: &WriteBatch) -> Result<(), Error> {
-        let batch_inner = batch.take()?;
-        block_on_result(async { self.inner.write(batch_inner).await })?;
+        let pending = batch.take(&self.limits, self.codec)?;
+        let _timer = self.latency.start("write");
+        self.block_on(pending.write(&self.db(), &WriteOptions::default()))?;
         Ok(())
     }
 
@@ -559,29 +1221,169 @@ impl Database {
     /// # Arguments
     /// * `batch` - The WriteBatch to write
     /// * `kwargs` - Keyword arguments (await_durable, seqnum)
-    pub fn write_with_options(&self, batch: &WriteBatch, kwargs: RHash) -> Result<(), Error> {
+    pub fn write_with_options(
+        &self,
+        batch: &WriteBatch,
+        kwargs: RHash,
+    ) -> Result<Option<Value>, Error> {
         let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+        let write_return = WriteReturn::from_kwargs(&kwargs)?;
 
-        let batch_inner = batch.take()?;
+        let pending = batch.take(&self.limits, self.codec)?;
+        let bytes = pending.bytes();
 
-        block_on_result(async {
-            self.inner
-                .write_with_options(batch_inner, &write_opts)
-                .await
-        })?;
+        let started = Instant::now();
+        let _timer = self.latency.start("write");
+        let _span = trace_span("write", &kwargs)?;
+        let handle = self.block_on(pending.write(&self.db(), &write_opts))?;
 
-        Ok(())
+        Ok(write_return.build(&self.db(), &handle, &write_opts, bytes, started))
+    }
+
+    /// Write a batch as several smaller atomic batches, in order.
+    ///
+    /// Each chunk is resolved and written before the next is built, so only
+    /// one chunk's SlateDB batch is in memory at a time. If a chunk fails the
+    /// chunks before it stay written and the rest of the batch is dropped.
+    ///
+    /// # Arguments
+    /// * `batch` - The WriteBatch to write
+    /// * `kwargs` - Keyword arguments (max_ops, max_bytes, await_durable)
+    /// * `progress` - Optional block called after each chunk with its
+    ///   :chunk number, :chunks, :ops, :bytes and :seq
+    ///
+    /// # Returns
+    /// The number of chunks written
+    pub fn write_chunked(
+        &self,
+        batch: &WriteBatch,
+        kwargs: RHash,
+        progress: Option<Proc>,
+    ) -> Result<usize, Error> {
+        self.ensure_usable()?;
+        let max_ops = get_positive(&kwargs, "max_ops")?.unwrap_or(DEFAULT_CHUNK_OPS);
+        let max_bytes = get_positive(&kwargs, "max_bytes")?.unwrap_or(DEFAULT_CHUNK_BYTES);
+        let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+        let progress = Progress::new(progress);
+
+        let chunks = batch
+            .take(&self.limits, self.codec)?
+            .split(max_ops, max_bytes);
+        let total = chunks.len();
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let ops = chunk.op_count();
+            let bytes = chunk.bytes();
+            let handle = {
+                let _timer = self.latency.start("write");
+                self.block_on(chunk.write(&self.db(), &write_opts))?
+            };
+            progress.report(
+                &[
+                    ("chunk", index as u64 + 1),
+                    ("chunks", total as u64),
+                    ("ops", ops as u64),
+                    ("bytes", bytes as u64),
+                    ("seq", handle.seqnum()),
+                ],
+                Some(percent(index as u64 + 1, total as u64)),
+            )?;
+        }
+        self.increment_metric("db.write_chunked.count");
+        Ok(total)
+    }
+
+    /// Start a get in the background.
+    ///
+    /// # Arguments
+    /// * `key` - The key to look up
+    /// * `kwargs` - Keyword arguments (durability_filter, dirty, cache_blocks)
+    ///
+    /// # Returns
+    /// A Future resolving to the value, decoded by the database's codec, or
+    /// nil if not found
+    pub fn get_async(&self, key: String, kwargs: RHash) -> Result<RubyFuture, Error> {
+        self.ensure_usable()?;
+        if key.is_empty() {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+
+        let opts = self.read_options_from_kwargs(&kwargs)?;
+        let db = self.db();
+        self.increment_metric("db.get_async.count");
+
+        Ok(RubyFuture::spawn_decoded(
+            &self.runtime,
+            self.codec,
+            async move {
+                let value = db.get_with_options(key.as_bytes(), &opts).await?;
+                Ok(value.map(|b| b.to_vec()))
+            },
+        ))
+    }
+
+    /// Start a put in the background.
+    ///
+    /// # Arguments
+    /// * `key` - The key to store
+    /// * `value` - The value to store
+    /// * `kwargs` - Keyword arguments (ttl, await_durable, seqnum)
+    ///
+    /// # Returns
+    /// A Future resolving to nil once the write completes
+    pub fn put_async(&self, key: String, value: Value, kwargs: RHash) -> Result<RubyFuture, Error> {
+        self.ensure_usable()?;
+        if key.is_empty() {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
+
+        let put_opts = PutOptions {
+            ttl: ttl_from_kwargs(&kwargs)?,
+        };
+        let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+        let db = self.db();
+        self.increment_metric("db.put_async.count");
+
+        Ok(RubyFuture::spawn(&self.runtime, async move {
+            db.put_with_options(key.as_bytes(), &value, &put_opts, &write_opts)
+                .await?;
+            Ok(None)
+        }))
+    }
+
+    /// Start writing a batch in the background.
+    ///
+    /// # Arguments
+    /// * `batch` - The WriteBatch to write
+    /// * `kwargs` - Keyword arguments (await_durable, seqnum)
+    ///
+    /// # Returns
+    /// A Future resolving to nil once the write completes
+    pub fn write_async(&self, batch: &WriteBatch, kwargs: RHash) -> Result<RubyFuture, Error> {
+        self.ensure_usable()?;
+        let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+        let pending = batch.take(&self.limits, self.codec)?;
+        let db = self.db();
+        self.increment_metric("db.write_async.count");
+
+        Ok(RubyFuture::spawn(&self.runtime, async move {
+            pending.write(&db, &write_opts).await?;
+            Ok(None)
+        }))
     }
 
     /// Merge a value into the database.
     ///
     /// # Arguments
     /// * `key` - The key to merge into
-    /// * `value` - The merge operand to apply
-    pub fn merge(&self, key: String, value: String) -> Result<(), Error> {
+    /// * `value` - The merge operand to apply, encoded by the database's codec
+    pub fn merge(&self, key: String, value: Value) -> Result<(), Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
         let merge_opts = MergeOptions { ttl: Ttl::Default };
 
@@ -590,9 +1392,10 @@ impl Database {
             seqnum: 0,
         };
 
-        block_on_result(async {
-            self.inner
-                .merge_with_options(key.as_bytes(), value.as_bytes(), &merge_opts, &write_opts)
+        let _timer = self.latency.start("merge");
+        self.block_on(async {
+            self.db()
+                .merge_with_options(key.as_bytes(), &value, &merge_opts, &write_opts)
                 .await
         })?;
 
@@ -603,45 +1406,283 @@ impl Database {
     ///
     /// # Arguments
     /// * `key` - The key to merge into
-    /// * `value` - The merge operand to apply
+    /// * `value` - The merge operand to apply, encoded by the database's codec
     /// * `kwargs` - Keyword arguments (ttl, await_durable, seqnum)
     pub fn merge_with_options(
         &self,
         key: String,
-        value: String,
+        value: Value,
         kwargs: RHash,
-    ) -> Result<(), Error> {
+    ) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
-        let ttl = get_optional::<u64>(&kwargs, "ttl")?;
         let merge_opts = MergeOptions {
-            ttl: match ttl {
-                Some(ms) => Ttl::ExpireAfter(ms),
-                None => Ttl::Default,
-            },
+            ttl: ttl_from_kwargs(&kwargs)?,
         };
 
         let write_opts = Self::write_options_from_kwargs(&kwargs)?;
-
-        block_on_result(async {
-            self.inner
-                .merge_with_options(key.as_bytes(), value.as_bytes(), &merge_opts, &write_opts)
+        let write_return = WriteReturn::from_kwargs(&kwargs)?;
+
+        let started = Instant::now();
+        let _timer = self.latency.start("merge");
+        let _span = trace_span("merge", &kwargs)?;
+        let handle = self.block_on(async {
+            self.db()
+                .merge_with_options(key.as_bytes(), &value, &merge_opts, &write_opts)
                 .await
         })?;
 
-        Ok(())
+        let bytes = key.len() + value.len();
+        Ok(write_return.build(&self.db(), &handle, &write_opts, bytes, started))
+    }
+
+    /// Append bytes to the value stored at a key.
+    ///
+    /// The read-modify-write runs inside a serializable transaction and is
+    /// retried with backoff on conflict, so concurrent appends to the same key
+    /// never lose data. A missing key is treated as an empty value and gets
+    /// the default TTL; an existing value keeps its expiry. Encoded values
+    /// cannot be appended to, so this raises unless the codec is `:raw`.
+    ///
+    /// # Arguments
+    /// * `key` - The key to append to
+    /// * `value` - The bytes to append
+    /// * `kwargs` - Keyword arguments (max_size, retries, await_durable)
+    ///
+    /// # Returns
+    /// The length of the value after the append
+    pub fn append(&self, key: String, value: String, kwargs: RHash) -> Result<usize, Error> {
+        if key.is_empty() {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+        if self.codec != Codec::Raw {
+            return Err(invalid_argument_error(
+                "append requires the :raw codec; encoded values cannot be appended to",
+            ));
+        }
+        self.limits.check(key.as_bytes(), value.as_bytes())?;
+
+        let max_size = get_optional::<usize>(&kwargs, "max_size")?
+            .unwrap_or(DEFAULT_APPEND_MAX_SIZE)
+            .min(self.limits.max_value_bytes());
+        let max_retries =
+            get_optional::<usize>(&kwargs, "retries")?.unwrap_or(CONFLICT_MAX_RETRIES);
+        let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+
+        let new_len = self.block_on(async {
+            let mut attempt = 0;
+            loop {
+                let txn = self
+                    .db()
+                    .begin(IsolationLevel::SerializableSnapshot)
+                    .await?;
+                let existing = txn.get_key_value(key.as_bytes()).await?;
+                let ttl = match &existing {
+                    Some(kv) => kv.expire_ts.map_or(Ttl::NoExpiry, Ttl::ExpireAt),
+                    None => Ttl::Default,
+                };
+                let mut current = existing.map(|kv| kv.value.to_vec()).unwrap_or_default();
+
+                let new_len = current.len() + value.len();
+                if new_len > max_size {
+                    txn.rollback();
+                    return Err(SlateError::invalid(format!(
+                        "appended value would be {} bytes, exceeding max_size of {} bytes",
+                        new_len, max_size
+                    )));
+                }
+
+                current.extend_from_slice(value.as_bytes());
+                txn.put_with_options(key.as_bytes(), &current, &PutOptions { ttl })?;
+
+                match txn.commit_with_options(&write_opts).await {
+                    Ok(_) => return Ok(new_len),
+                    Err(e)
+                        if matches!(e.kind(), ErrorKind::Transaction) && attempt < max_retries =>
+                    {
+                        attempt += 1;
+                        tokio::time::sleep(retry::backoff(
+                            TRANSACTION_RETRY_BASE_DELAY,
+                            TRANSACTION_RETRY_MAX_DELAY,
+                            attempt as u32,
+                            true,
+                        ))
+                        .await;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })?;
+        self.increment_metric("db.append.count");
+
+        Ok(new_len)
+    }
+
+    /// Store a value only if the key has no live value.
+    ///
+    /// The check and the write run inside a serializable transaction that is
+    /// retried on conflict, so when several callers race for the same key
+    /// exactly one of them writes it. Expired values count as absent.
+    ///
+    /// # Arguments
+    /// * `key` - The key to store
+    /// * `value` - The value to store, encoded by the database's codec
+    /// * `kwargs` - Keyword arguments (ttl, await_durable)
+    ///
+    /// # Returns
+    /// true if the value was written, false if the key already had one
+    pub fn put_if_absent(&self, key: String, value: Value, kwargs: RHash) -> Result<bool, Error> {
+        if key.is_empty() {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
+
+        let put_opts = PutOptions {
+            ttl: ttl_from_kwargs(&kwargs)?,
+        };
+        let write_opts = Self::write_options_from_kwargs(&kwargs)?;
+
+        let _timer = self.latency.start("put_if_absent");
+        let written = self.block_on(async {
+            let mut attempt = 0;
+            loop {
+                let txn = self
+                    .db()
+                    .begin(IsolationLevel::SerializableSnapshot)
+                    .await?;
+                if txn.get(key.as_bytes()).await?.is_some() {
+                    txn.rollback();
+                    return Ok(false);
+                }
+                txn.put_with_options(key.as_bytes(), &value, &put_opts)?;
+
+                match txn.commit_with_options(&write_opts).await {
+                    Ok(_) => return Ok(true),
+                    Err(e)
+                        if matches!(e.kind(), ErrorKind::Transaction)
+                            && attempt < CONFLICT_MAX_RETRIES =>
+                    {
+                        attempt += 1;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })?;
+        self.increment_metric("db.put_if_absent.count");
+
+        Ok(written)
+    }
+
+    /// Write one chunk of a `put_chunked` as a single batch, without waiting
+    /// for it to be durable.
+    ///
+    /// # Arguments
+    /// * `pairs` - An Array of `[key, value]` pairs
+    /// * `after` - The last key of the previous chunk, if any
+    /// * `kwargs` - Keyword arguments (sorted, ttl)
+    ///
+    /// # Returns
+    /// The last key of the chunk, to pass as `after` with the next chunk
+    pub fn put_chunk(
+        &self,
+        pairs: RArray,
+        after: Option<String>,
+        kwargs: RHash,
+    ) -> Result<Option<String>, Error> {
+        let sorted = get_optional::<bool>(&kwargs, "sorted")?.unwrap_or(true);
+        let put_opts = PutOptions {
+            ttl: ttl_from_kwargs(&kwargs)?,
+        };
+
+        let mut batch = slatedb::WriteBatch::new();
+        let mut last = after;
+        for pair in pairs.into_iter() {
+            let (key, value): (String, Value) = TryConvert::try_convert(pair)?;
+            if key.is_empty() {
+                return Err(invalid_argument_error("key cannot be empty"));
+            }
+            if let Some(prev) = last.as_ref().filter(|prev| sorted && key <= **prev) {
+                return Err(invalid_argument_error(&format!(
+                    "keys must be in ascending order with sorted: true, got {:?} after {:?}",
+                    key, prev
+                )));
+            }
+            let value = self.codec.encode(value)?;
+            self.limits.check(key.as_bytes(), &value)?;
+            batch.put_with_options(key.as_bytes(), &value, &put_opts);
+            last = Some(key);
+        }
+
+        let write_opts = WriteOptions {
+            await_durable: false,
+            seqnum: 0,
+        };
+        let _timer = self.latency.start("write");
+        self.block_on(self.db().write_with_options(batch, &write_opts))?;
+        self.increment_metric("db.put_chunked.count");
+        Ok(last)
+    }
+
+    /// Get a value by key within the namespace `prefix`.
+    pub fn namespace_get(
+        &self,
+        prefix: String,
+        key: String,
+        kwargs: RHash,
+    ) -> Result<Option<Value>, Error> {
+        self.get_with_options(namespaced_key(&prefix, &key)?, kwargs)
+    }
+
+    /// Store a key-value pair within the namespace `prefix`.
+    pub fn namespace_put(
+        &self,
+        prefix: String,
+        key: String,
+        value: Value,
+        kwargs: RHash,
+    ) -> Result<Option<Value>, Error> {
+        self.put_with_options(namespaced_key(&prefix, &key)?, value, kwargs)
+    }
+
+    /// Delete a key within the namespace `prefix`.
+    pub fn namespace_delete(
+        &self,
+        prefix: String,
+        key: String,
+        kwargs: RHash,
+    ) -> Result<Option<Value>, Error> {
+        self.delete_with_options(namespaced_key(&prefix, &key)?, kwargs)
+    }
+
+    /// Scan the keys within the namespace `prefix`, returning them without
+    /// it. Takes the options of `scan_prefix_with_options`, with the
+    /// sub-range relative to the namespace.
+    pub fn namespace_scan(&self, prefix: String, kwargs: RHash) -> Result<Iterator, Error> {
+        let namespace = prefix.clone().into_bytes();
+        Ok(self
+            .scan_prefix_with_options(prefix, kwargs)?
+            .with_namespace(namespace))
     }
 
     /// Begin a new transaction.
     ///
     /// # Arguments
     /// * `isolation` - Optional isolation level ("snapshot" or "serializable")
+    /// * `read_only` - Whether to reject writes; a read-only transaction uses
+    ///   snapshot isolation, so its reads are not tracked for conflicts
     ///
     /// # Returns
     /// A new Transaction instance
-    pub fn begin_transaction(&self, isolation: Option<String>) -> Result<Transaction, Error> {
+    pub fn begin_transaction(
+        &self,
+        isolation: Option<String>,
+        read_only: bool,
+    ) -> Result<Transaction, Error> {
         let isolation_level = match isolation.as_deref().unwrap_or("snapshot") {
             "snapshot" | "si" => IsolationLevel::Snapshot,
             "serializable" | "ssi" | "serializable_snapshot" => {
@@ -654,9 +1695,74 @@ impl Database {
                 )))
             }
         };
+        if read_only && isolation_level == IsolationLevel::SerializableSnapshot {
+            return Err(invalid_argument_error(
+                "read_only cannot be combined with serializable isolation",
+            ));
+        }
+
+        let txn = self.block_on(async { self.db().begin(isolation_level).await })?;
+        let view = ViewInfo::capture_at(&*self.db(), txn.seqnum());
+        let db = self.db();
+        let latency = self.latency.clone();
+        Ok(Transaction::new(db, latency, txn, view, isolation_level)
+            .with_codec(self.codec)
+            .with_limits(self.limits)
+            .with_runtime(self.runtime.clone())
+            .with_clock(self.config.clock.clone())
+            .with_read_only(read_only))
+    }
+
+    /// Run a block in a transaction, committing it if the block returns and
+    /// rolling it back if the block raises.
+    ///
+    /// After a `TransactionError` (a conflict) the block is re-run in a fresh
+    /// transaction, up to `retries` times, waiting with exponential backoff
+    /// and jitter between attempts.
+    ///
+    /// # Arguments
+    /// * `isolation` - Optional isolation level; see `begin_transaction`
+    /// * `read_only` - Whether to run the block in a read-only transaction
+    /// * `retries` - Number of times to retry after a conflict
+    /// * `block` - Called with each transaction
+    ///
+    /// # Returns
+    /// The result of the block
+    pub fn transaction(
+        &self,
+        isolation: Option<String>,
+        read_only: bool,
+        retries: usize,
+        block: Proc,
+    ) -> Result<Value, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let mut attempt = 0;
+        loop {
+            let txn = ruby.obj_wrap(self.begin_transaction(isolation.clone(), read_only)?);
+            let result = block
+                .call::<_, Value>((txn,))
+                .and_then(|value| txn.commit().map(|_| value));
+            let err = match result {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
 
-        let txn = block_on_result(async { self.inner.begin(isolation_level).await })?;
-        Ok(Transaction::new(txn))
+            txn.rollback()?;
+            if attempt >= retries || !is_transaction_error(&err) {
+                return Err(err);
+            }
+            attempt += 1;
+            let delay = retry::backoff(
+                TRANSACTION_RETRY_BASE_DELAY,
+                TRANSACTION_RETRY_MAX_DELAY,
+                attempt as u32,
+                true,
+            );
+            self.block_on(async {
+                tokio::time::sleep(delay).await;
+                Ok::<_, SlateError>(())
+            })?;
+        }
     }
 
     /// Create a snapshot for consistent reads.
@@ -664,8 +1770,33 @@ impl Database {
     /// # Returns
     /// A new Snapshot instance
     pub fn snapshot(&self) -> Result<Snapshot, Error> {
-        let snap = block_on_result(async { self.inner.snapshot().await })?;
-        Ok(Snapshot::new(snap))
+        let snap = self.block_on(async { self.db().snapshot().await })?;
+        let view = ViewInfo::capture_at(&*self.db(), snap.seq());
+        Ok(Snapshot::new(snap, view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone())
+            .with_read_defaults(self.read_defaults))
+    }
+
+    /// Open a read-only view of the database as of a past time.
+    ///
+    /// Reads at the newest checkpoint created at or before `as_of`, so the
+    /// view is only as fine-grained as the database's checkpoint history.
+    ///
+    /// # Arguments
+    /// * `as_of` - Wall-clock time in seconds since the epoch
+    ///
+    /// # Returns
+    /// A Reader pinned to that checkpoint
+    pub fn snapshot_at(&self, as_of: f64) -> Result<Reader, Error> {
+        self.ensure_usable()?;
+        Reader::open_as_of(
+            &self.path,
+            self.object_store.clone(),
+            as_of,
+            self.codec,
+            self.encryption.clone(),
+        )
     }
 
     /// Create a checkpoint of the database.
@@ -688,8 +1819,8 @@ impl Database {
             name,
         };
 
-        let result = block_on_result(async {
-            self.inner
+        let result = self.block_on(async {
+            self.db()
                 .create_checkpoint(CheckpointScope::Durable, &options)
                 .await
         })?;
@@ -702,22 +1833,527 @@ impl Database {
         Ok(hash)
     }
 
+    /// Describe this handle as the database's writer.
+    ///
+    /// # Returns
+    /// Hash with client_id and epoch (this handle's), manifest_id and
+    /// last_manifest_write (seconds since the epoch) of the latest manifest,
+    /// and fenced, true once another writer has claimed a newer epoch
+    pub fn writer_info(&self) -> Result<RHash, Error> {
+        let info = self
+            .block_on(async { read_writer_info(&self.object_store, &self.path).await })?
+            .ok_or_else(|| data_error("database has no manifest"))?;
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = info.to_hash(&ruby)?;
+        hash.aset(ruby.to_symbol("client_id"), self.client_id.as_str())?;
+        let epoch = self.writer_epoch.load(Ordering::SeqCst);
+        hash.aset(ruby.to_symbol("epoch"), epoch)?;
+        hash.aset(ruby.to_symbol("fenced"), info.epoch > epoch)?;
+
+        Ok(hash)
+    }
+
     /// Flush the database to ensure durability.
-    pub fn flush(&self) -> Result<(), Error> {
-        block_on_result(async { self.inner.flush().await })?;
+    ///
+    /// # Arguments
+    /// * `kwargs` - Keyword arguments (target, timeout_ms). `target` is
+    ///   "wal" to write the WAL buffer to object storage or "remote" to also
+    ///   write the memtable to L0; by default the WAL is flushed, or the
+    ///   memtable when the WAL is disabled.
+    ///
+    /// # Returns
+    /// A hash with `:target`, `:durable_seq`, `:flushed_seq` and `:bytes`,
+    /// the WAL and L0 bytes written while the flush ran
+    pub fn flush(&self, kwargs: RHash) -> Result<RHash, Error> {
+        let flush_type = match get_optional::<String>(&kwargs, "target")?.as_deref() {
+            None => None,
+            Some("wal") => Some(FlushType::Wal),
+            Some("remote") => Some(FlushType::MemTable),
+            Some(other) => {
+                return Err(invalid_argument_error(&format!(
+                    "invalid target: {} (expected :wal or :remote)",
+                    other
+                )))
+            }
+        };
+        let timeout_ms = get_optional::<u64>(&kwargs, "timeout_ms")?;
+
+        let flushed_bytes = || {
+            let snapshot = self.recorder.snapshot();
+            counter_total(&snapshot, WAL_FLUSH_BYTES) + counter_total(&snapshot, L0_FLUSH_BYTES)
+        };
+        let bytes_before = flushed_bytes();
+
+        let flush = async {
+            match &flush_type {
+                Some(flush_type) => {
+                    let flush_type = flush_type.clone();
+                    self.db()
+                        .flush_with_options(FlushOptions { flush_type })
+                        .await
+                }
+                None => self.db().flush().await,
+            }
+        };
+        match timeout_ms {
+            None => self.block_on(flush)?,
+            Some(timeout_ms) => {
+                let deadline = Duration::from_millis(timeout_ms);
+                let result = self.block_on(async {
+                    Ok::<_, SlateError>(tokio::time::timeout(deadline, flush).await)
+                })?;
+                match result {
+                    Ok(flushed) => flushed.map_err(map_error)?,
+                    Err(_) => {
+                        return Err(timeout_error(&format!(
+                            "flush did not complete within {}ms",
+                            timeout_ms
+                        )))
+                    }
+                }
+            }
+        }
+
+        let target = match flush_type {
+            Some(FlushType::MemTable) => "remote",
+            _ => "wal",
+        };
+        let status = self.db().status();
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("target"), ruby.to_symbol(target))?;
+        hash.aset(ruby.to_symbol("durable_seq"), status.durable_seq)?;
+        hash.aset(
+            ruby.to_symbol("flushed_seq"),
+            status.current_manifest.last_l0_seq(),
+        )?;
+        hash.aset(
+            ruby.to_symbol("bytes"),
+            flushed_bytes().saturating_sub(bytes_before),
+        )?;
+
+        Ok(hash)
+    }
+
+    /// Raise InvalidArgumentError if the database is encrypted, for `op`s
+    /// that read the WAL directly.
+    fn ensure_unencrypted_wal(&self, op: &str) -> Result<(), Error> {
+        if self.encryption.is_some() {
+            return Err(invalid_argument_error(&format!(
+                "{} cannot be combined with encryption",
+                op
+            )));
+        }
+        Ok(())
+    }
+
+    /// Stream the committed changes with a sequence number greater than
+    /// `since_seq`, read from the WAL.
+    ///
+    /// Raises InvalidArgumentError on an encrypted database: the WAL reader
+    /// cannot decrypt WAL blocks.
+    pub fn changes(&self, kwargs: RHash) -> Result<ChangeStream, Error> {
+        self.ensure_unencrypted_wal("changes")?;
+        let since_seq = get_optional::<u64>(&kwargs, "since_seq")?.unwrap_or(0);
+        let object_store = self.object_store.clone();
+        Ok(ChangeStream::new(&self.path, object_store, since_seq).with_codec(self.codec))
+    }
+
+    /// Watch for changes to keys beginning with `prefix`.
+    ///
+    /// Reports changes made after `since_seq`, which defaults to the
+    /// current durable sequence number, checking the WAL every
+    /// `interval_ms` (default 100) once caught up. Raises
+    /// InvalidArgumentError on an encrypted database, as for `changes`.
+    pub fn watch(&self, prefix: String, kwargs: RHash) -> Result<Watcher, Error> {
+        self.ensure_unencrypted_wal("watch")?;
+        let since_seq = match get_optional::<u64>(&kwargs, "since_seq")? {
+            Some(seq) => seq,
+            None => self.db().status().durable_seq,
+        };
+        let interval_ms = get_optional::<u64>(&kwargs, "interval_ms")?.unwrap_or(100);
+
+        let tail = WalTail::new(&self.path, self.object_store.clone(), since_seq);
+        let interval = std::time::Duration::from_millis(interval_ms);
+        Ok(Watcher::start(tail, prefix.into_bytes(), interval).with_codec(self.codec))
+    }
+
+    /// The error that closed the database in the background, if any.
+    ///
+    /// Background tasks, such as WAL and memtable flushes or compaction,
+    /// close the database when they fail, and so does being fenced by a
+    /// newer writer. Returns that error as an exception, without raising
+    /// it, or nil while the database is open or after a clean close.
+    pub fn background_error(&self) -> Result<Option<Value>, Error> {
+        ensure_same_process(self.pid)?;
+        match self.db().status().close_reason {
+            None | Some(CloseReason::Clean) => Ok(None),
+            // Operations on a closed database fail with the error it closed
+            // with; opening a snapshot is the cheapest of them.
+            Some(_) => Ok(self
+                .runtime
+                .block_on_result(self.db().snapshot())
+                .err()
+                .and_then(|e| e.value())),
+        }
+    }
+
+    /// Wait until the database closes, then return `background_error`.
+    ///
+    /// Waits without holding the GVL; `on_background_error` calls this
+    /// from a dedicated Ruby thread.
+    pub fn wait_for_background_error(&self) -> Result<Option<Value>, Error> {
+        ensure_same_process(self.pid)?;
+        let mut status = self.db().subscribe();
+        self.runtime.block_on(async move {
+            // An error means the status sender is gone, so the database is
+            // closed either way.
+            let _ = status.wait_for(|s| s.close_reason.is_some()).await;
+        })?;
+        self.background_error()
+    }
+
+    /// Probe the object store behind the database, for readiness checks.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (timeout_ms)
+    ///
+    /// # Returns
+    /// Hash with healthy, latency, manifest_id, latest_manifest_id,
+    /// manifests_behind, last_manifest_write, staleness and error
+    pub fn health(&self, kwargs: RHash) -> Result<RHash, Error> {
+        ensure_same_process(self.pid)?;
+        let status = self.db().status();
+        health::check(
+            &self.object_store,
+            &self.path,
+            status.current_manifest.id(),
+            status.close_reason.is_some(),
+            &kwargs,
+        )
+    }
+
+    /// Report how far writes have progressed towards object storage.
+    ///
+    /// Returns a hash with `:last_seq`, the sequence number of the latest
+    /// write applied in memory; `:durable_seq`, the latest write durable in
+    /// the WAL; `:flushed_seq`, the latest write flushed to L0 and recorded
+    /// in the manifest; and `:manifest_id`.
+    pub fn durability_status(&self) -> Result<RHash, Error> {
+        let snapshot = self.block_on(async { self.db().snapshot().await })?;
+        let status = self.db().status();
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("last_seq"), snapshot.seq())?;
+        hash.aset(ruby.to_symbol("durable_seq"), status.durable_seq)?;
+        hash.aset(
+            ruby.to_symbol("flushed_seq"),
+            status.current_manifest.last_l0_seq(),
+        )?;
+        hash.aset(ruby.to_symbol("manifest_id"), status.current_manifest.id())?;
+
+        Ok(hash)
+    }
+
+    /// The number of operations currently waiting on this database.
+    pub fn pending_ops(&self) -> usize {
+        self.pending.count.load(Ordering::SeqCst)
+    }
+
+    /// Set or clear the hook called when pending operations exceed
+    /// `threshold`.
+    pub fn set_overload_hook(&self, threshold: usize, hook: Option<Proc>) -> Result<(), Error> {
+        if hook.is_some() {
+            ensure_main_ractor("on_overload")?;
+        }
+        let hook = hook.map(|hook| (threshold, Opaque::from(hook)));
+        let overload = &self.pending.overload;
+        *overload.lock().expect("overload mutex poisoned") = hook;
         Ok(())
     }
 
+    /// Return SlateDB's internal metrics as a nested hash.
+    ///
+    /// Covers the memtable, L0, flushes, compaction, the block cache, object
+    /// store requests and garbage collection. See `stats_to_hash` for the
+    /// layout.
+    pub fn stats(&self) -> Result<RHash, Error> {
+        stats_to_hash(&self.recorder.snapshot())
+    }
+
+    /// Return per-operation latency recorded by the bindings.
+    ///
+    /// Maps each operation that has run (`:get`, `:put`, `:delete`, `:merge`,
+    /// `:write`, `:scan` and `:commit`) to its `:count` and its `:avg`,
+    /// `:p50`, `:p95`, `:p99` and `:max` latency in milliseconds.
+    pub fn latency_stats(&self) -> Result<RHash, Error> {
+        self.latency.to_hash()
+    }
+
+    /// Return the requests the database has made to its object store.
+    ///
+    /// Maps each request class (`:get`, `:put`, `:list` and `:delete`) to
+    /// its `:requests`, `:bytes` and `:errors` since the database was opened
+    /// or `reset_stats` was last called. See `StoreStats`.
+    pub fn object_store_stats(&self) -> Result<RHash, Error> {
+        self.store_stats.to_hash()
+    }
+
+    /// Discard the latency and object store requests recorded so far.
+    pub fn reset_stats(&self) {
+        self.latency.reset();
+        self.store_stats.reset();
+    }
+
     /// Return the database metrics registry.
     pub fn metrics(&self) -> Result<Metrics, Error> {
         Ok(Metrics::new(self.metrics.clone()))
     }
 
     /// Close the database.
-    pub fn close(&self) -> Result<(), Error> {
-        block_on_result(async { self.inner.close().await })?;
+    ///
+    /// Without `timeout_ms`, waits for the close however long it takes and
+    /// returns nil. With it, raises TimeoutError if the close hasn't finished
+    /// in time, leaving it to finish in the background, or with `force`
+    /// abandons it instead: writes not yet durable in the WAL are dropped,
+    /// and the handle raises ClosedError until `reopen`. Closing an abandoned
+    /// database does nothing. Any thread may close a database opened with
+    /// `thread_safe: :exclusive`.
+    ///
+    /// # Returns
+    /// With `timeout_ms`, a hash with clean, last_seq, durable_seq and
+    /// dropped_seqs
+    pub fn close(&self, kwargs: RHash) -> Result<Option<RHash>, Error> {
+        // The parent process still owns a database inherited across fork.
+        if is_forked(self.pid) || self.abandoned.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let force = get_optional::<bool>(&kwargs, "force")?.unwrap_or(false);
+        let Some(timeout_ms) = get_optional::<u64>(&kwargs, "timeout_ms")? else {
+            if force {
+                return Err(invalid_argument_error("force requires timeout_ms"));
+            }
+            self.block_on_any_thread(async { self.db().close().await })?;
+            return Ok(None);
+        };
+
+        // The latest write, to count what an abandoned close drops. A closed
+        // database has nothing left to write, so close reports the error.
+        let status = self.db().status();
+        let last_seq = match status.close_reason {
+            None => self
+                .block_on_any_thread(async { self.db().snapshot().await })?
+                .seq(),
+            Some(_) => status.durable_seq,
+        };
+
+        // Close on the runtime, so a close that times out keeps running
+        // unless it is abandoned.
+        let db = self.db();
+        let mut closing = self.runtime.spawn(async move { db.close().await });
+        let deadline = Duration::from_millis(timeout_ms);
+        let result = self.block_on_any_thread(async {
+            Ok::<_, SlateError>(tokio::time::timeout(deadline, &mut closing).await)
+        })?;
+        let clean = match result {
+            Ok(Ok(closed)) => closed.map(|_| true).map_err(map_error)?,
+            Ok(Err(e)) => return Err(internal_error(&format!("close failed: {}", e))),
+            Err(_) if force => {
+                // Stops the memtable flush and the remaining task shutdowns.
+                // Background tasks still running stop with the process, or
+                // when `reopen` fences the Db.
+                closing.abort();
+                self.abandoned.store(true, Ordering::SeqCst);
+                false
+            }
+            Err(_) => {
+                return Err(timeout_error(&format!(
+                    "close did not complete within {}ms; it continues in the background",
+                    timeout_ms
+                )))
+            }
+        };
+
+        let durable_seq = self.db().status().durable_seq;
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("clean"), clean)?;
+        hash.aset(ruby.to_symbol("last_seq"), last_seq)?;
+        hash.aset(ruby.to_symbol("durable_seq"), durable_seq)?;
+        // Each write, batch or commit takes one sequence number, however
+        // many keys it wrote.
+        hash.aset(
+            ruby.to_symbol("dropped_seqs"),
+            if clean {
+                0
+            } else {
+                last_seq.saturating_sub(durable_seq)
+            },
+        )?;
+        Ok(Some(hash))
+    }
+
+    /// Close the database and open it again with the same configuration.
+    ///
+    /// The new Db reads the latest manifest, replays the WAL and starts with
+    /// empty caches, and this handle claims a new writer epoch. Works on a
+    /// database that is closed, including one closed by a background error
+    /// or abandoned by a forced close. Snapshots, transactions and iterators
+    /// taken from the old Db stay closed.
+    pub fn reopen(&self) -> Result<(), Error> {
+        ensure_same_process(self.pid)?;
+        self.thread_mode.ensure_owner()?;
+        ensure_running()?;
+
+        // An abandoned Db is not closed again; the new Db fences it. The
+        // handle stays abandoned if the new Db cannot be built.
+        let abandoned = self.abandoned.swap(false, Ordering::SeqCst);
+        let old = self.db();
+        if !abandoned && !matches!(old.status().close_reason, Some(CloseReason::Clean)) {
+            // A failed flush is recovered from the WAL by the new Db, so
+            // closing is best effort.
+            let _ = self.block_on(async { Ok::<_, SlateError>(old.close().await) });
+        }
+
+        let built = self.block_on(build_db(
+            &self.path,
+            &self.object_store,
+            &self.config,
+            &self.recorder,
+            &self.encryption,
+            &self.client_id,
+        ));
+        let (db, epoch, recovery) = built.inspect_err(|_| {
+            self.abandoned.fetch_or(abandoned, Ordering::SeqCst);
+        })?;
+        let db = Arc::new(db);
+        register_database(&db);
+        *self.inner.write().expect("database lock poisoned") = db;
+        self.writer_epoch.store(epoch, Ordering::SeqCst);
+        *self.recovery.lock().expect("recovery mutex poisoned") = recovery;
         Ok(())
     }
+
+    /// Which threads may use the handle: :shared or :exclusive.
+    pub fn thread_safe(&self) -> Symbol {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        ruby.to_symbol(self.thread_mode.name())
+    }
+
+    /// Whether the database runs on a runtime of its own rather than the
+    /// shared one.
+    pub fn dedicated_runtime(&self) -> bool {
+        self.runtime.is_dedicated()
+    }
+
+    /// What the database recovered from its WAL when it last opened.
+    pub fn recovery_info(&self) -> Result<RHash, Error> {
+        self.recovery
+            .lock()
+            .expect("recovery mutex poisoned")
+            .to_hash()
+    }
+}
+
+/// Build the Db at `path` and record this handle as its writer.
+///
+/// With tolerant WAL recovery, a Db that fails to open because a WAL SST
+/// can't be read is built again after the unreadable tail is deleted.
+///
+/// # Returns
+/// The Db, the writer epoch it claimed and what it recovered from the WAL
+async fn build_db(
+    path: &str,
+    object_store: &Arc<dyn ObjectStore>,
+    config: &OpenConfig,
+    recorder: &Arc<DefaultMetricsRecorder>,
+    encryption: &Option<Arc<dyn BlockTransformer>>,
+    client_id: &str,
+) -> Result<(Db, u64, RecoveryReport), SlateError> {
+    let build = || {
+        let mut builder = Db::builder(path.to_string(), object_store.clone())
+            .with_metrics_recorder(recorder.clone());
+        if let Some(settings) = config.settings.clone() {
+            builder = builder.with_settings(settings);
+        }
+        if let Some(merge_operator) = config.merge_operator.clone() {
+            builder = builder.with_merge_operator(merge_operator);
+        }
+        if let Some(encryption) = encryption.clone() {
+            builder = builder.with_block_transformer(encryption);
+        }
+        if let Some(clock) = config.clock.clone() {
+            builder = builder.with_system_clock(clock);
+        }
+        builder.build()
+    };
+
+    let started = Instant::now();
+    let pending = PendingReplay::read(path, object_store).await?;
+    let (db, truncated) = match build().await {
+        Ok(db) => (db, Vec::new()),
+        Err(e)
+            if config.recovery == RecoveryMode::Tolerant && matches!(e.kind(), ErrorKind::Data) =>
+        {
+            let truncated = pending.truncate_corrupt_tail(path, object_store).await?;
+            if truncated.is_empty() {
+                return Err(e);
+            }
+            (build().await?, truncated)
+        }
+        Err(e) => return Err(e),
+    };
+    let recovery = pending.report(
+        config.recovery,
+        db.status().durable_seq,
+        truncated,
+        started.elapsed(),
+    );
+
+    match register_writer(object_store, path, client_id).await {
+        Ok(epoch) => Ok((db, epoch, recovery)),
+        Err(e) => {
+            let _ = db.close().await;
+            Err(e)
+        }
+    }
+}
+
+/// The full key for `key` within the namespace `prefix`.
+fn namespaced_key(prefix: &str, key: &str) -> Result<String, Error> {
+    if key.is_empty() {
+        return Err(invalid_argument_error("key cannot be empty"));
+    }
+    Ok([prefix, key].concat())
+}
+
+/// Narrow a page's range to the keys after `key` in iteration order.
+/// Returns false if no key in the range comes after it.
+fn start_page_after(range: &mut ScanRange, key: Vec<u8>, descending: bool) -> bool {
+    if let ScanRange::Prefix(prefix, _) = range {
+        if !key.starts_with(prefix) {
+            // Either every key under the prefix comes after `key`, or none does.
+            return (key < *prefix) != descending;
+        }
+    }
+    range.narrow(Bound::Excluded(key), descending);
+    true
+}
+
+/// The result of `Database#page`.
+fn page_to_hash(entries: RArray, next_cursor: Option<String>) -> Result<RHash, Error> {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = ruby.hash_new();
+    hash.aset(ruby.to_symbol("entries"), entries)?;
+    hash.aset(ruby.to_symbol("next_cursor"), next_cursor)?;
+    Ok(hash)
+}
+
+/// Milliseconds left before `expire_ts`, or 0 if it has passed by `now`.
+fn remaining_ttl(expire_ts: i64, now: i64) -> i64 {
+    (expire_ts - now).max(0)
 }
 
 /// Define the Database class on the SlateDb module.
@@ -726,10 +2362,14 @@ pub fn define_database_class(ruby: &Ruby, module: &magnus::RModule) -> Result<()
 
     // Class methods
     class.define_singleton_method("_open", function!(Database::open, 3))?;
+    class.define_singleton_method("_create_clone", function!(Database::create_clone, 3))?;
 
     // Instance methods - simple versions
     class.define_method("_get", method!(Database::get, 1))?;
     class.define_method("_get_with_options", method!(Database::get_with_options, 2))?;
+    class.define_method("_get_buffer", method!(Database::get_buffer, 2))?;
+    class.define_method("_value_size", method!(Database::value_size, 2))?;
+    class.define_method("_get_into", method!(Database::get_into, 3))?;
     class.define_method("_get_key_value", method!(Database::get_key_value, 1))?;
     class.define_method(
         "_get_key_value_with_options",
@@ -748,33 +2388,78 @@ pub fn define_database_class(ruby: &Ruby, module: &magnus::RModule) -> Result<()
         "_scan_with_options",
         method!(Database::scan_with_options, 3),
     )?;
+    class.define_method("_scan_arrow", method!(Database::scan_arrow, 3))?;
     class.define_method("_scan_prefix", method!(Database::scan_prefix, 1))?;
     class.define_method(
         "_scan_prefix_with_options",
         method!(Database::scan_prefix_with_options, 2),
     )?;
+    class.define_method("_scan_from_cursor", method!(Database::scan_from_cursor, 2))?;
+    class.define_method("_page", method!(Database::page, 1))?;
     class.define_method("_write", method!(Database::write, 1))?;
     class.define_method(
         "_write_with_options",
         method!(Database::write_with_options, 2),
     )?;
+    class.define_method("_get_async", method!(Database::get_async, 2))?;
+    class.define_method("_put_async", method!(Database::put_async, 3))?;
+    class.define_method("_write_async", method!(Database::write_async, 2))?;
+    class.define_method("_write_chunked", method!(Database::write_chunked, 3))?;
     class.define_method("_merge", method!(Database::merge, 2))?;
     class.define_method(
         "_merge_with_options",
         method!(Database::merge_with_options, 3),
     )?;
+    class.define_method("_append", method!(Database::append, 3))?;
+    class.define_method("_put_if_absent", method!(Database::put_if_absent, 3))?;
+    class.define_method("_put_chunk", method!(Database::put_chunk, 3))?;
+    class.define_method("_namespace_get", method!(Database::namespace_get, 3))?;
+    class.define_method("_namespace_put", method!(Database::namespace_put, 4))?;
+    class.define_method("_namespace_delete", method!(Database::namespace_delete, 3))?;
+    class.define_method("_namespace_scan", method!(Database::namespace_scan, 2))?;
     class.define_method(
         "_begin_transaction",
-        method!(Database::begin_transaction, 1),
+        method!(Database::begin_transaction, 2),
     )?;
+    class.define_method("_transaction", method!(Database::transaction, 4))?;
     class.define_method("_snapshot", method!(Database::snapshot, 0))?;
+    class.define_method("_snapshot_at", method!(Database::snapshot_at, 1))?;
+    class.define_method("_writer_info", method!(Database::writer_info, 0))?;
     class.define_method(
         "_create_checkpoint",
         method!(Database::create_checkpoint, 1),
     )?;
-    class.define_method("flush", method!(Database::flush, 0))?;
+    class.define_method("_flush", method!(Database::flush, 1))?;
     class.define_method("_metrics", method!(Database::metrics, 0))?;
-    class.define_method("close", method!(Database::close, 0))?;
+    class.define_method("stats", method!(Database::stats, 0))?;
+    class.define_method("latency_stats", method!(Database::latency_stats, 0))?;
+    class.define_method(
+        "object_store_stats",
+        method!(Database::object_store_stats, 0),
+    )?;
+    class.define_method("reset_stats", method!(Database::reset_stats, 0))?;
+    class.define_method("durability_status", method!(Database::durability_status, 0))?;
+    class.define_method("_health", method!(Database::health, 1))?;
+    class.define_method("_background_error", method!(Database::background_error, 0))?;
+    class.define_method(
+        "_wait_for_background_error",
+        method!(Database::wait_for_background_error, 0),
+    )?;
+    class.define_method("_changes", method!(Database::changes, 1))?;
+    class.define_method("_watch", method!(Database::watch, 2))?;
+    class.define_method("pending_ops", method!(Database::pending_ops, 0))?;
+    class.define_method(
+        "_set_overload_hook",
+        method!(Database::set_overload_hook, 2),
+    )?;
+    class.define_method("_close", method!(Database::close, 1))?;
+    class.define_method("_reopen", method!(Database::reopen, 0))?;
+    class.define_method("recovery_info", method!(Database::recovery_info, 0))?;
+    class.define_method("thread_safe", method!(Database::thread_safe, 0))?;
+    class.define_method(
+        "dedicated_runtime?",
+        method!(Database::dedicated_runtime, 0),
+    )?;
 
     Ok(())
 }
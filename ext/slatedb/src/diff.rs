@@ -0,0 +1,161 @@
+use std::cmp::Ordering;
+use std::ops::Bound;
+use std::sync::Arc;
+
+use magnus::{function, method, Error, RArray, RHash, Ruby};
+use slatedb::{DbIterator, KeyValue};
+use tokio::sync::Mutex;
+
+use crate::codec::Codec;
+use crate::errors::invalid_argument_error;
+use crate::runtime::RuntimeRef;
+use crate::snapshot::Snapshot;
+use crate::utils::get_optional;
+
+/// One side of a diff: a scan of a snapshot and the entry it is on.
+struct Side {
+    iter: DbIterator,
+    head: Option<KeyValue>,
+    done: bool,
+    /// The runtime of the snapshot's database.
+    runtime: RuntimeRef,
+}
+
+impl Side {
+    /// The entry the scan is on, reading the next one if it has moved past.
+    fn peek(&mut self) -> Result<Option<&KeyValue>, Error> {
+        if self.head.is_none() && !self.done {
+            self.head = self.runtime.block_on_result(self.iter.next())?;
+            self.done = self.head.is_none();
+        }
+        Ok(self.head.as_ref())
+    }
+}
+
+/// A difference between two snapshots at one key.
+enum Change {
+    Added(KeyValue),
+    Removed(KeyValue),
+    Changed(KeyValue, KeyValue),
+}
+
+/// Ruby wrapper for the differences between two snapshots.
+///
+/// This struct is exposed to Ruby as `SlateDb::SnapshotDiff`. It merges
+/// ascending scans of both snapshots, so changes come in key order and
+/// nothing but the two current entries is held in memory. Each scan runs on
+/// the runtime of its snapshot's database.
+#[magnus::wrap(class = "SlateDb::SnapshotDiff", free_immediately, size)]
+pub struct SnapshotDiff {
+    sides: Arc<Mutex<(Side, Side)>>,
+    /// The runtime to wait for the sides on, the old snapshot's.
+    runtime: RuntimeRef,
+    old_codec: Codec,
+    new_codec: Codec,
+}
+
+impl SnapshotDiff {
+    /// Diff the keys in `range` from snapshot `old` to snapshot `new`.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (start, end, exclude_end): the range, unbounded
+    ///   where a bound is omitted
+    pub fn new(old: &Snapshot, new: &Snapshot, kwargs: RHash) -> Result<Self, Error> {
+        let range = range_from_kwargs(&kwargs)?;
+        let (old_snapshot, new_snapshot) = (old.snapshot()?, new.snapshot()?);
+        let (old_runtime, new_runtime) = (old.runtime(), new.runtime());
+        let old_iter = old_runtime.block_on_result(old_snapshot.scan(range.clone()))?;
+        let new_iter = new_runtime.block_on_result(new_snapshot.scan(range))?;
+        let side = |iter, runtime| Side {
+            iter,
+            head: None,
+            done: false,
+            runtime,
+        };
+
+        Ok(Self {
+            sides: Arc::new(Mutex::new((
+                side(old_iter, old_runtime.clone()),
+                side(new_iter, new_runtime),
+            ))),
+            runtime: old_runtime,
+            old_codec: old.codec(),
+            new_codec: new.codec(),
+        })
+    }
+
+    /// Get the next difference.
+    ///
+    /// # Returns
+    /// `[op, key, old_value, new_value]`, where op is :added, :removed or
+    /// :changed and a missing value is nil; or nil once both scans are done
+    pub fn next_change(&self) -> Result<Option<RArray>, Error> {
+        // Held while each side blocks on its runtime, so concurrent callers
+        // wait here without holding the GVL.
+        let mut sides = self.runtime.block_on(self.sides.clone().lock_owned())?;
+        let (old, new) = &mut *sides;
+        let change = loop {
+            let order = match (old.peek()?, new.peek()?) {
+                (None, None) => return Ok(None),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(o), Some(n)) => o.key.cmp(&n.key),
+            };
+            match order {
+                Ordering::Less => break Change::Removed(old.head.take().expect("peeked")),
+                Ordering::Greater => break Change::Added(new.head.take().expect("peeked")),
+                Ordering::Equal => {
+                    let o = old.head.take().expect("peeked");
+                    let n = new.head.take().expect("peeked");
+                    if o.value != n.value {
+                        break Change::Changed(o, n);
+                    }
+                }
+            }
+        };
+        drop(sides);
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let (op, key, old_value, new_value) = match change {
+            Change::Added(n) => ("added", n.key, None, Some(n.value)),
+            Change::Removed(o) => ("removed", o.key, Some(o.value), None),
+            Change::Changed(o, n) => ("changed", o.key, Some(o.value), Some(n.value)),
+        };
+        let entry = ruby.ary_new_capa(4);
+        entry.push(ruby.to_symbol(op))?;
+        entry.push(ruby.str_new(&String::from_utf8_lossy(&key)))?;
+        entry.push(old_value.map(|v| self.old_codec.decode(&v)).transpose()?)?;
+        entry.push(new_value.map(|v| self.new_codec.decode(&v)).transpose()?)?;
+        Ok(Some(entry))
+    }
+}
+
+/// The key range to diff, from the start, end and exclude_end options.
+fn range_from_kwargs(kwargs: &RHash) -> Result<(Bound<Vec<u8>>, Bound<Vec<u8>>), Error> {
+    let start = get_optional::<String>(kwargs, "start")?;
+    let end = get_optional::<String>(kwargs, "end")?;
+    let exclude_end = get_optional::<bool>(kwargs, "exclude_end")?.unwrap_or(false);
+    if start.as_deref() == Some("") {
+        return Err(invalid_argument_error("range cannot start at an empty key"));
+    }
+
+    let start = start.map_or(Bound::Unbounded, |start| {
+        Bound::Included(start.into_bytes())
+    });
+    let end = match end {
+        None => Bound::Unbounded,
+        Some(end) if exclude_end => Bound::Excluded(end.into_bytes()),
+        Some(end) => Bound::Included(end.into_bytes()),
+    };
+    Ok((start, end))
+}
+
+/// Define the SnapshotDiff class on the SlateDb module.
+pub fn define_snapshot_diff_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("SnapshotDiff", ruby.class_object())?;
+
+    class.define_singleton_method("_new", function!(SnapshotDiff::new, 3))?;
+    class.define_method("next_change", method!(SnapshotDiff::next_change, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use magnus::{method, Error, RHash, Ruby};
+use slatedb::Db;
+
+use crate::errors::closed_error;
+use crate::runtime::{block_on, ensure_same_process};
+use crate::utils::get_optional;
+
+/// Ruby wrapper for tracking when a write becomes durable.
+///
+/// This struct is exposed to Ruby as `SlateDb::DurabilityHandle` and is
+/// returned by writes and commits called with `return_handle: true`. A write
+/// is durable once the database's durable sequence number reaches the
+/// write's sequence number.
+#[magnus::wrap(class = "SlateDb::DurabilityHandle", free_immediately, size)]
+pub struct DurabilityHandle {
+    db: Arc<Db>,
+    seq: u64,
+    /// The process that made the write.
+    pid: u32,
+}
+
+impl DurabilityHandle {
+    /// Track the write with sequence number `seq` in `db`.
+    pub fn new(db: Arc<Db>, seq: u64) -> Self {
+        Self {
+            db,
+            seq,
+            pid: std::process::id(),
+        }
+    }
+
+    /// Raise ForkedProcessError if the handle is used after `fork`: it tracks
+    /// the parent's database, whose background tasks do not run in the child.
+    fn ensure_usable(&self) -> Result<(), Error> {
+        ensure_same_process(self.pid)
+    }
+
+    /// The sequence number of the tracked write.
+    pub fn seq(&self) -> Result<u64, Error> {
+        self.ensure_usable()?;
+        Ok(self.seq)
+    }
+
+    /// Whether the write is durable in object storage.
+    pub fn is_durable(&self) -> Result<bool, Error> {
+        self.ensure_usable()?;
+        Ok(self.db.status().durable_seq >= self.seq)
+    }
+
+    /// Wait for the write to become durable.
+    ///
+    /// Returns true once it is durable, or false if `timeout_ms` elapses
+    /// first. Waits indefinitely without `timeout_ms`. Raises ClosedError if
+    /// the database closes before the write became durable.
+    pub fn wait(&self, kwargs: RHash) -> Result<bool, Error> {
+        self.ensure_usable()?;
+        let timeout = get_optional::<u64>(&kwargs, "timeout_ms")?.map(Duration::from_millis);
+        let seq = self.seq;
+        let mut status = self.db.subscribe();
+
+        let outcome = block_on(async {
+            let durable = async {
+                status
+                    .wait_for(|s| s.durable_seq >= seq || s.close_reason.is_some())
+                    .await
+                    .map(|s| s.durable_seq >= seq)
+                    .unwrap_or(false)
+            };
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, durable).await.ok(),
+                None => Some(durable.await),
+            }
+        })?;
+
+        match outcome {
+            Some(true) => Ok(true),
+            Some(false) => Err(closed_error(
+                "database closed before the write became durable",
+            )),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Define the DurabilityHandle class on the SlateDb module.
+pub fn define_durability_handle_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("DurabilityHandle", ruby.class_object())?;
+
+    class.define_method("seq", method!(DurabilityHandle::seq, 0))?;
+    class.define_method("durable?", method!(DurabilityHandle::is_durable, 0))?;
+    class.define_method("_wait", method!(DurabilityHandle::wait, 1))?;
+
+    Ok(())
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::error;
+use magnus::block::Proc;
+use magnus::{Error, RHash, RString, Ruby, Symbol};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use slatedb::admin::Admin;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::ObjectStore;
+use slatedb::{BlockTransformer, CompactionWorkerBuilder};
+
+use crate::clock;
+use crate::errors::{invalid_argument_error, map_error};
+use crate::expired;
+use crate::merge_ops::parse_merge_operator;
+use crate::progress::Progress;
+use crate::runtime::{self, block_on, block_on_result};
+use crate::utils::{get_optional, get_positive, rstring_to_bytes};
+
+/// First byte of every encrypted block, so the layout can change later.
+const FORMAT_VERSION: u8 = 1;
+
+/// The key id used when `encryption:` is given just a key.
+const DEFAULT_KEY_ID: &str = "default";
+
+/// Options accepted in the `encryption` hash.
+const OPTION_KEYS: [&str; 3] = ["key", "key_id", "previous_keys"];
+
+/// AES-256-GCM encryption of SST and WAL blocks.
+///
+/// Blocks are encrypted after compression, just before they are written to
+/// the object store, and decrypted after they are read, so keys and values
+/// are never stored in plaintext. Manifests, which hold no keys or values,
+/// are left as they are.
+///
+/// Each block is laid out as:
+///
+/// ```text
+/// version (1) | key id length (1) | key id | nonce (12) | ciphertext | tag (16)
+/// ```
+///
+/// The version and key id are authenticated as associated data. New blocks
+/// use the current key; blocks written with an earlier key are decrypted
+/// with the matching entry in `previous_keys` until compaction rewrites them.
+pub struct AesGcmTransformer {
+    key_id: String,
+    keys: HashMap<String, LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl AesGcmTransformer {
+    /// Parse the `encryption` open option.
+    ///
+    /// The Ruby wrapper normalizes the option to a Hash with a `key`, an
+    /// optional `key_id` and optional `previous_keys` (key id => key).
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Option<Arc<dyn BlockTransformer>>, Error> {
+        let Some(hash) = get_optional::<RHash>(kwargs, "encryption")? else {
+            return Ok(None);
+        };
+
+        let names: Vec<Symbol> = hash.funcall("keys", ())?;
+        for name in names {
+            let name = name.name()?;
+            if !OPTION_KEYS.contains(&name.as_ref()) {
+                return Err(invalid_argument_error(&format!(
+                    "unknown encryption option: {}",
+                    name
+                )));
+            }
+        }
+
+        let key = get_optional::<RString>(&hash, "key")?
+            .ok_or_else(|| invalid_argument_error("encryption requires a key"))?;
+        let key_id =
+            get_optional::<String>(&hash, "key_id")?.unwrap_or_else(|| DEFAULT_KEY_ID.to_string());
+        let previous = get_optional::<HashMap<String, RString>>(&hash, "previous_keys")?;
+
+        let mut keys = HashMap::new();
+        for (id, key) in previous.unwrap_or_default() {
+            if id == key_id {
+                return Err(invalid_argument_error(&format!(
+                    "previous_keys must not contain the current key_id {:?}",
+                    id
+                )));
+            }
+            keys.insert(id.clone(), aead_key(&id, key)?);
+        }
+        keys.insert(key_id.clone(), aead_key(&key_id, key)?);
+
+        Ok(Some(Arc::new(Self {
+            key_id,
+            keys,
+            rng: SystemRandom::new(),
+        })))
+    }
+
+    /// Decrypt a block written by `encode`.
+    fn decrypt(&self, data: &[u8]) -> Result<Bytes, String> {
+        match data.first() {
+            Some(&FORMAT_VERSION) => {}
+            Some(version) => {
+                return Err(format!(
+                    "unsupported encrypted block version {}; was it written without encryption?",
+                    version
+                ))
+            }
+            None => return Err("encrypted block is empty".to_string()),
+        }
+
+        let header_len = 2 + *data.get(1).unwrap_or(&0) as usize;
+        if data.len() < header_len + NONCE_LEN {
+            return Err("encrypted block is truncated".to_string());
+        }
+
+        let key_id = String::from_utf8_lossy(&data[2..header_len]);
+        let key = self.keys.get(key_id.as_ref()).ok_or_else(|| {
+            format!(
+                "block was encrypted with unknown key_id {:?}; pass that key in previous_keys",
+                key_id
+            )
+        })?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[header_len..header_len + NONCE_LEN]);
+        let mut sealed = data[header_len + NONCE_LEN..].to_vec();
+        let len = key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&data[..header_len]),
+                &mut sealed,
+            )
+            .map_err(|_| {
+                format!(
+                    "cannot decrypt block with key_id {:?}: wrong key or corrupted data",
+                    key_id
+                )
+            })?
+            .len();
+        sealed.truncate(len);
+        Ok(Bytes::from(sealed))
+    }
+}
+
+/// Validate a key id and build the AES-256-GCM key for it.
+fn aead_key(id: &str, key: RString) -> Result<LessSafeKey, Error> {
+    if id.is_empty() || id.len() > u8::MAX as usize {
+        return Err(invalid_argument_error(&format!(
+            "encryption key_id must be 1 to {} bytes, got {:?}",
+            u8::MAX,
+            id
+        )));
+    }
+    let bytes = rstring_to_bytes(key);
+    let key = UnboundKey::new(&AES_256_GCM, &bytes).map_err(|_| {
+        invalid_argument_error(&format!(
+            "encryption key {:?} must be 32 bytes, got {}",
+            id,
+            bytes.len()
+        ))
+    })?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[async_trait]
+impl BlockTransformer for AesGcmTransformer {
+    async fn encode(&self, data: Bytes) -> Result<Bytes, slatedb::Error> {
+        let key = &self.keys[&self.key_id];
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| slatedb::Error::internal("cannot generate a nonce".to_string()))?;
+
+        let header_len = 2 + self.key_id.len();
+        let mut block =
+            Vec::with_capacity(header_len + NONCE_LEN + data.len() + AES_256_GCM.tag_len());
+        block.push(FORMAT_VERSION);
+        block.push(self.key_id.len() as u8);
+        block.extend_from_slice(self.key_id.as_bytes());
+        block.extend_from_slice(&nonce);
+
+        let mut sealed = data.to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&block[..header_len]),
+            &mut sealed,
+        )
+        .map_err(|_| slatedb::Error::internal("cannot encrypt block".to_string()))?;
+        block.extend_from_slice(&sealed);
+        Ok(Bytes::from(block))
+    }
+
+    async fn decode(&self, data: Bytes) -> Result<Bytes, slatedb::Error> {
+        // SlateDB reports any failure as "error transforming block", so log
+        // the reason, which says which key is missing or wrong.
+        self.decrypt(&data).map_err(|reason| {
+            error!("{}", reason);
+            slatedb::Error::data(reason)
+        })
+    }
+}
+
+/// Re-encrypt every block of the database at `path` with a new key by
+/// compacting the whole tree.
+///
+/// Submits a full compaction, as `purge_expired` does, and runs a
+/// standalone compaction worker with the `encryption` option built by the
+/// Ruby wrapper (the new key, with the old one in `previous_keys`) until the
+/// compaction finishes. A worker only claims scheduled jobs; it neither
+/// opens the database as its writer nor takes over as its compactor, so the
+/// live writer is not fenced. That writer's compactor schedules the job,
+/// and its own worker may claim it instead, which is why the writer must
+/// already be running with the new key.
+///
+/// # Returns
+/// Hash with id, status, l0_ssts, sorted_runs, destination, bytes and
+/// key_id; or nil if the database has nothing compacted to rewrite
+pub fn rewrap_keys(
+    admin: &Admin,
+    path: &Path,
+    object_store: &Arc<dyn ObjectStore>,
+    kwargs: RHash,
+    progress: Option<Proc>,
+) -> Result<Option<RHash>, Error> {
+    let encryption = AesGcmTransformer::from_kwargs(&kwargs)?
+        .ok_or_else(|| invalid_argument_error("rewrap_keys requires encryption keys"))?;
+    let key_id = get_optional::<RHash>(&kwargs, "encryption")?
+        .map(|hash| get_optional::<String>(&hash, "key_id"))
+        .transpose()?
+        .flatten();
+    let timeout = get_positive(&kwargs, "timeout_ms")?.map(|ms| Duration::from_millis(ms as u64));
+    let progress = Progress::new(progress);
+
+    let mut builder = CompactionWorkerBuilder::new(path.clone(), object_store.clone())
+        .with_block_transformer(encryption);
+    if let Some(merge_operator) = parse_merge_operator(&kwargs)? {
+        builder = builder.with_merge_operator(merge_operator);
+    }
+    if let Some(clock) = clock::test_clock() {
+        builder = builder.with_system_clock(clock);
+    }
+
+    let Some(full) = expired::submit_full_compaction(admin)? else {
+        return Ok(None);
+    };
+    let worker = Arc::new(block_on_result(builder.build())?);
+    let running = runtime::spawn({
+        let worker = worker.clone();
+        async move { worker.run().await }
+    });
+    let mut bytes = 0;
+    let waited = expired::wait_for_compaction(admin, &full.compaction, "rewrap", timeout, |c| {
+        bytes = c.bytes_processed();
+        progress.report(&[("bytes", bytes)], None)
+    });
+    // Stopping hands a job the worker still holds back to other workers.
+    let stopped = block_on_result(worker.stop());
+    let joined = block_on(running)?;
+    let status = waited?;
+    stopped?;
+    if let Ok(Err(e)) = joined {
+        return Err(map_error(e));
+    }
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = full.to_hash(&ruby, &status)?;
+    hash.aset(ruby.to_symbol("bytes"), bytes)?;
+    hash.aset(ruby.to_symbol("key_id"), key_id)?;
+    Ok(Some(hash))
+}
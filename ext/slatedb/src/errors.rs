@@ -1,144 +1,345 @@
+use std::error::Error as StdError;
+use std::sync::Arc;
+
 use magnus::prelude::*;
-use magnus::{Error, ExceptionClass, Ruby};
+use magnus::value::Opaque;
+use magnus::{gc, Error, ExceptionClass, RHash, Ruby, Value};
+use once_cell::sync::OnceCell;
+use slatedb::object_store::Error as ObjectStoreError;
 use slatedb::Error as SlateError;
-use slatedb::ErrorKind;
-use std::cell::RefCell;
+use slatedb::{CloseReason, ErrorKind};
+
+/// Fragments of the responses object stores send when rejecting requests
+/// for exceeding a rate limit (S3, GCS and Azure respectively).
+const THROTTLE_MARKERS: &[&str] = &[
+    "429 Too Many Requests",
+    "503 Slow Down",
+    "SlowDown",
+    "rateLimitExceeded",
+    "ServerBusy",
+];
 
-// Store exception classes in thread-local storage for access during error mapping
-thread_local! {
-    static SLATE_DB_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
-    static TRANSACTION_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
-    static CLOSED_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
-    static UNAVAILABLE_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
-    static INVALID_ARGUMENT_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
-    static DATA_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
-    static INTERNAL_ERROR: RefCell<Option<ExceptionClass>> = const { RefCell::new(None) };
+/// SlateDB's exception classes, defined once when the extension loads.
+///
+/// Shared by every thread and Ractor: errors are mapped on whichever thread
+/// ran the operation, not only the one that required the gem. Each class is
+/// registered with the GC so it stays valid even if its constant is removed.
+struct ExceptionClasses {
+    transaction: Opaque<ExceptionClass>,
+    closed: Opaque<ExceptionClass>,
+    fenced: Opaque<ExceptionClass>,
+    unavailable: Opaque<ExceptionClass>,
+    throttled: Opaque<ExceptionClass>,
+    invalid_argument: Opaque<ExceptionClass>,
+    data: Opaque<ExceptionClass>,
+    internal: Opaque<ExceptionClass>,
+    commit_timeout: Opaque<ExceptionClass>,
+    forked_process: Opaque<ExceptionClass>,
+    timeout: Opaque<ExceptionClass>,
+    thread_ownership: Opaque<ExceptionClass>,
 }
 
+static EXCEPTION_CLASSES: OnceCell<ExceptionClasses> = OnceCell::new();
+
 /// Define SlateDB exception classes under the SlateDb module.
 ///
 /// Exception hierarchy:
 /// - SlateDb::Error (base class, inherits from StandardError)
 ///   - SlateDb::TransactionError
 ///   - SlateDb::ClosedError
+///     - SlateDb::FencedError
 ///   - SlateDb::UnavailableError
+///     - SlateDb::ThrottledError
 ///   - SlateDb::InvalidArgumentError
 ///   - SlateDb::DataError
 ///   - SlateDb::InternalError
+///   - SlateDb::CommitTimeoutError
+///   - SlateDb::ForkedProcessError
+///   - SlateDb::TimeoutError
+///   - SlateDb::ThreadOwnershipError
 pub fn define_exceptions(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
     let standard_error = ruby.exception_standard_error();
 
     // Define base SlateDb::Error
     let slate_error = module.define_error("Error", standard_error)?;
-    SLATE_DB_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(slate_error);
-    });
+    gc::register_mark_object(slate_error);
 
     // Define specific error types
-    let transaction_error = module.define_error("TransactionError", slate_error)?;
-    TRANSACTION_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(transaction_error);
-    });
-
-    let closed_error = module.define_error("ClosedError", slate_error)?;
-    CLOSED_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(closed_error);
-    });
-
-    let unavailable_error = module.define_error("UnavailableError", slate_error)?;
-    UNAVAILABLE_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(unavailable_error);
-    });
-
-    let invalid_argument_error = module.define_error("InvalidArgumentError", slate_error)?;
-    INVALID_ARGUMENT_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(invalid_argument_error);
-    });
-
-    let data_error = module.define_error("DataError", slate_error)?;
-    DATA_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(data_error);
-    });
-
-    let internal_error = module.define_error("InternalError", slate_error)?;
-    INTERNAL_ERROR.with(|cell| {
-        *cell.borrow_mut() = Some(internal_error);
-    });
+    let define = |name: &str, parent: ExceptionClass| -> Result<ExceptionClass, Error> {
+        let class = module.define_error(name, parent)?;
+        gc::register_mark_object(class);
+        Ok(class)
+    };
+    let closed = define("ClosedError", slate_error)?;
+    let unavailable = define("UnavailableError", slate_error)?;
+    let classes = ExceptionClasses {
+        transaction: define("TransactionError", slate_error)?.into(),
+        closed: closed.into(),
+        fenced: define("FencedError", closed)?.into(),
+        unavailable: unavailable.into(),
+        throttled: define("ThrottledError", unavailable)?.into(),
+        invalid_argument: define("InvalidArgumentError", slate_error)?.into(),
+        data: define("DataError", slate_error)?.into(),
+        internal: define("InternalError", slate_error)?.into(),
+        commit_timeout: define("CommitTimeoutError", slate_error)?.into(),
+        forked_process: define("ForkedProcessError", slate_error)?.into(),
+        timeout: define("TimeoutError", slate_error)?.into(),
+        thread_ownership: define("ThreadOwnershipError", slate_error)?.into(),
+    };
+    let _ = EXCEPTION_CLASSES.set(classes);
 
     Ok(())
 }
 
-/// Map a SlateDB error to the appropriate Ruby exception.
-pub fn map_error(err: SlateError) -> Error {
-    let msg = format!("{}", err);
+/// Create an exception of the class chosen by `class`.
+///
+/// `code` names the specific condition and backs `SlateDb::Error#code`,
+/// from which `#kind` and `#retryable?` are derived.
+fn new_error(
+    class: fn(&ExceptionClasses) -> Opaque<ExceptionClass>,
+    code: &str,
+    msg: String,
+) -> Error {
+    build_error(class, code, msg, None)
+}
+
+fn build_error(
+    class: fn(&ExceptionClasses) -> Opaque<ExceptionClass>,
+    code: &str,
+    msg: String,
+    details: Option<&ErrorDetails>,
+) -> Error {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let classes = EXCEPTION_CLASSES
+        .get()
+        .expect("exception classes not defined");
+    let class = ruby.get_inner(class(classes));
+    let exception = match class.new_instance((msg,)) {
+        Ok(exception) => exception,
+        Err(e) => return e,
+    };
+    let mut ivars = vec![("@code", ruby.to_symbol(code).as_value())];
+    if let Some(details) = details {
+        match details.to_hash(&ruby) {
+            Ok(hash) => ivars.push(("@details", hash.as_value())),
+            Err(e) => return e,
+        }
+    }
+    for (name, value) in ivars {
+        if let Err(e) = exception.funcall::<_, _, Value>("instance_variable_set", (name, value)) {
+            return e;
+        }
+    }
+    exception.into()
+}
+
+/// Whether an unavailable error was the object store rejecting requests for
+/// exceeding its rate limit, rather than a network or service failure.
+pub fn is_throttled(msg: &str) -> bool {
+    THROTTLE_MARKERS.iter().any(|marker| msg.contains(marker))
+}
+
+/// Whether a Ruby error is a `SlateDb::TransactionError`, e.g. a commit that
+/// lost a conflict with another transaction.
+pub fn is_transaction_error(err: &Error) -> bool {
     let ruby = Ruby::get().expect("Ruby runtime not available");
+    EXCEPTION_CLASSES
+        .get()
+        .is_some_and(|classes| err.is_kind_of(ruby.get_inner(classes.transaction)))
+}
+
+/// What the object store reported about a failed request, recovered from
+/// the chain of errors behind a SlateDB error. Backs `SlateDb::Error#details`.
+#[derive(Clone, Debug, Default)]
+struct ErrorDetails {
+    store: Option<String>,
+    path: Option<String>,
+    method: Option<String>,
+    uri: Option<String>,
+    status: Option<u16>,
+    request_id: Option<String>,
+    /// Messages of the underlying errors, outermost first.
+    causes: Vec<String>,
+}
+
+impl ErrorDetails {
+    fn new(err: &SlateError) -> Self {
+        let mut details = Self::default();
+        let mut source = StdError::source(err);
+        while let Some(cause) = source {
+            details.record(cause);
+            source = cause.source();
+        }
+        details
+    }
+
+    fn record(&mut self, cause: &(dyn StdError + 'static)) {
+        let msg = cause.to_string();
+
+        let store_error = cause
+            .downcast_ref::<ObjectStoreError>()
+            .or_else(|| cause.downcast_ref::<Arc<ObjectStoreError>>().map(|e| &**e));
+        match store_error {
+            Some(ObjectStoreError::Generic { store, .. }) => {
+                self.store.get_or_insert_with(|| store.to_string());
+            }
+            Some(
+                ObjectStoreError::NotFound { path, .. }
+                | ObjectStoreError::AlreadyExists { path, .. }
+                | ObjectStoreError::Precondition { path, .. }
+                | ObjectStoreError::NotModified { path, .. }
+                | ObjectStoreError::PermissionDenied { path, .. }
+                | ObjectStoreError::Unauthenticated { path, .. },
+            ) => {
+                self.path.get_or_insert_with(|| path.clone());
+            }
+            _ => {}
+        }
+
+        // object_store's HTTP errors are private types, so their request and
+        // response are read back from their messages:
+        // "Error performing GET <uri> in 1.2s - Server returned non-2xx
+        // status code: 403 Forbidden: <body>"
+        if let Some(request) = msg.strip_prefix("Error performing ") {
+            let mut parts = request.splitn(3, ' ');
+            if let (Some(method), Some(uri)) = (parts.next(), parts.next()) {
+                self.method.get_or_insert_with(|| method.to_string());
+                if uri != "REDACTED" {
+                    self.uri.get_or_insert_with(|| uri.to_string());
+                }
+            }
+        }
+        if let Some((_, rest)) = msg.split_once("status code: ") {
+            if let Some(status) = rest.get(..3).and_then(|code| code.parse().ok()) {
+                self.status.get_or_insert(status);
+            }
+        }
+        if self.request_id.is_none() {
+            self.request_id = request_id(&msg);
+        }
+
+        if self.causes.last() != Some(&msg) {
+            self.causes.push(msg);
+        }
+    }
 
-    match err.kind() {
-        ErrorKind::Transaction => TRANSACTION_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.clone()))
-        }),
-        ErrorKind::Closed(_) => CLOSED_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.clone()))
-        }),
-        ErrorKind::Unavailable => UNAVAILABLE_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.clone()))
-        }),
-        ErrorKind::Invalid => INVALID_ARGUMENT_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_arg_error(), msg.clone()))
-        }),
-        ErrorKind::Data => DATA_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.clone()))
-        }),
-        ErrorKind::Internal => INTERNAL_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.clone()))
-        }),
-        _ => INTERNAL_ERROR.with(|cell| {
-            cell.borrow()
-                .map(|exc| Error::new(exc, msg.clone()))
-                .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.clone()))
-        }),
+    fn to_hash(&self, ruby: &Ruby) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        let strings = [
+            ("store", &self.store),
+            ("path", &self.path),
+            ("method", &self.method),
+            ("uri", &self.uri),
+            ("request_id", &self.request_id),
+        ];
+        for (key, value) in strings {
+            if let Some(value) = value {
+                hash.aset(ruby.to_symbol(key), value.as_str())?;
+            }
+        }
+        if let Some(status) = self.status {
+            hash.aset(ruby.to_symbol("status"), status)?;
+        }
+        hash.aset(
+            ruby.to_symbol("causes"),
+            ruby.ary_from_iter(self.causes.iter().map(String::as_str)),
+        )?;
+        hash.freeze();
+        Ok(hash)
     }
 }
 
+/// The request id in an object store's error response: `<RequestId>` in
+/// S3's XML body, or the `RequestId:` line Azure includes in its message.
+fn request_id(msg: &str) -> Option<String> {
+    let id = if let Some((_, rest)) = msg.split_once("<RequestId>") {
+        rest.split_once("</RequestId>").map(|(id, _)| id)
+    } else {
+        msg.split_once("RequestId:")
+            .map(|(_, rest)| rest.split_whitespace().next().unwrap_or_default())
+    };
+    id.map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+/// A SlateDB error kept as plain data, for errors that outlive the SlateDB
+/// error they came from, such as a future's result, which may be raised more
+/// than once and from another thread.
+#[derive(Clone, Debug)]
+pub struct CapturedError {
+    kind: ErrorKind,
+    msg: String,
+    details: ErrorDetails,
+}
+
+impl CapturedError {
+    pub fn new(err: &SlateError) -> Self {
+        Self {
+            kind: err.kind(),
+            msg: err.to_string(),
+            details: ErrorDetails::new(err),
+        }
+    }
+
+    /// The Ruby exception for this error.
+    pub fn to_error(&self) -> Error {
+        let (class, code): (fn(&ExceptionClasses) -> Opaque<ExceptionClass>, _) = match self.kind {
+            ErrorKind::Transaction => (|c| c.transaction, "transaction_conflict"),
+            ErrorKind::Closed(CloseReason::Fenced) => (|c| c.fenced, "fenced"),
+            ErrorKind::Closed(CloseReason::Panic) => (|c| c.closed, "background_panic"),
+            ErrorKind::Closed(_) => (|c| c.closed, "closed"),
+            ErrorKind::Unavailable if is_throttled(&self.msg) => (|c| c.throttled, "throttled"),
+            ErrorKind::Unavailable => (|c| c.unavailable, "unavailable"),
+            ErrorKind::Invalid => (|c| c.invalid_argument, "invalid_argument"),
+            ErrorKind::Data => (|c| c.data, "data"),
+            _ => (|c| c.internal, "internal"),
+        };
+        build_error(class, code, self.msg.clone(), Some(&self.details))
+    }
+}
+
+/// Map a SlateDB error to the appropriate Ruby exception.
+pub fn map_error(err: SlateError) -> Error {
+    CapturedError::new(&err).to_error()
+}
+
 /// Create an InvalidArgumentError with the given message.
 pub fn invalid_argument_error(msg: &str) -> Error {
-    let ruby = Ruby::get().expect("Ruby runtime not available");
-    INVALID_ARGUMENT_ERROR.with(|cell| {
-        cell.borrow()
-            .map(|exc| Error::new(exc, msg.to_string()))
-            .unwrap_or_else(|| Error::new(ruby.exception_arg_error(), msg.to_string()))
-    })
+    new_error(|c| c.invalid_argument, "invalid_argument", msg.to_string())
+}
+
+/// Create a DataError with the given message.
+pub fn data_error(msg: &str) -> Error {
+    new_error(|c| c.data, "data", msg.to_string())
 }
 
 /// Create an InternalError with the given message.
-#[allow(dead_code)]
 pub fn internal_error(msg: &str) -> Error {
-    let ruby = Ruby::get().expect("Ruby runtime not available");
-    INTERNAL_ERROR.with(|cell| {
-        cell.borrow()
-            .map(|exc| Error::new(exc, msg.to_string()))
-            .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.to_string()))
-    })
+    new_error(|c| c.internal, "internal", msg.to_string())
+}
+
+/// Create a CommitTimeoutError with the given message.
+pub fn commit_timeout_error(msg: &str) -> Error {
+    new_error(|c| c.commit_timeout, "commit_timeout", msg.to_string())
 }
 
 /// Create a ClosedError with the given message.
 pub fn closed_error(msg: &str) -> Error {
-    let ruby = Ruby::get().expect("Ruby runtime not available");
-    CLOSED_ERROR.with(|cell| {
-        cell.borrow()
-            .map(|exc| Error::new(exc, msg.to_string()))
-            .unwrap_or_else(|| Error::new(ruby.exception_runtime_error(), msg.to_string()))
-    })
+    new_error(|c| c.closed, "closed", msg.to_string())
+}
+
+/// Create a ForkedProcessError with the given message.
+pub fn forked_process_error(msg: &str) -> Error {
+    new_error(|c| c.forked_process, "forked_process", msg.to_string())
+}
+
+/// Create a TimeoutError with the given message.
+pub fn timeout_error(msg: &str) -> Error {
+    new_error(|c| c.timeout, "timeout", msg.to_string())
+}
+
+/// Create a ThreadOwnershipError with the given message.
+pub fn thread_ownership_error(msg: &str) -> Error {
+    new_error(|c| c.thread_ownership, "thread_ownership", msg.to_string())
 }
@@ -0,0 +1,325 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use magnus::{Error, RHash, Ruby};
+use slatedb::admin::Admin;
+use slatedb::compactor::{Compaction, CompactionSpec, CompactionStatus, SourceId};
+use slatedb::manifest::{SsTableHandle, SsTableId, SsTableView};
+use slatedb::object_store::path::Path;
+use slatedb::object_store::ObjectStore;
+use slatedb::{Error as SlateError, SstReader, ValueDeletable};
+use slatedb_common::clock::SystemClock;
+
+use crate::clock;
+use crate::errors::{internal_error, timeout_error};
+use crate::runtime::{block_on, block_on_result};
+use crate::utils::{get_optional, get_positive};
+
+/// How often `wait_for_compaction` re-reads the compaction it is waiting on.
+const COMPACTION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Rows read from one level of the tree, and how many of them had expired.
+#[derive(Default)]
+struct Tally {
+    ssts: usize,
+    entries: u64,
+    bytes: u64,
+    expired_entries: u64,
+    expired_bytes: u64,
+    reclaimable_entries: u64,
+    reclaimable_bytes: u64,
+}
+
+impl Tally {
+    fn add(&mut self, other: &Tally) {
+        self.ssts += other.ssts;
+        self.entries += other.entries;
+        self.bytes += other.bytes;
+        self.expired_entries += other.expired_entries;
+        self.expired_bytes += other.expired_bytes;
+        self.reclaimable_entries += other.reclaimable_entries;
+        self.reclaimable_bytes += other.reclaimable_bytes;
+    }
+
+    fn to_hash(&self, ruby: &Ruby) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("ssts"), self.ssts)?;
+        hash.aset(ruby.to_symbol("entries"), self.entries)?;
+        hash.aset(ruby.to_symbol("bytes"), self.bytes)?;
+        hash.aset(ruby.to_symbol("expired_entries"), self.expired_entries)?;
+        hash.aset(ruby.to_symbol("expired_bytes"), self.expired_bytes)?;
+        hash.aset(
+            ruby.to_symbol("reclaimable_entries"),
+            self.reclaimable_entries,
+        )?;
+        hash.aset(ruby.to_symbol("reclaimable_bytes"), self.reclaimable_bytes)?;
+        Ok(hash)
+    }
+}
+
+/// The times, in milliseconds since the epoch, rows are judged expired at.
+struct Clock {
+    /// The time the caller asked about.
+    now: i64,
+    /// The time compaction judges expiry at.
+    compaction: i64,
+}
+
+/// Count the rows in the database's compacted SSTs whose TTL has passed.
+///
+/// Every L0 and sorted run SST in the latest manifest is read block by
+/// block. A value or merge operand counts as expired once its expiry time is
+/// at or before `now`. Compaction judges expiry by the database's clock
+/// instead: the time of the latest write flushed to L0, reported as
+/// `compaction_clock`. Expired rows at or before it are reclaimable, and
+/// the next compaction to read them drops them; the rest wait for a newer
+/// write to reach L0. Rows still in the memtable or WAL are not counted.
+///
+/// # Arguments
+/// * `kwargs` - Options (now: milliseconds since the epoch to judge expiry
+///   at, the current time if omitted)
+///
+/// # Returns
+/// Hash with now, compaction_clock, duration, the ssts, entries, bytes,
+/// expired_entries, expired_bytes, reclaimable_entries and
+/// reclaimable_bytes across the database, and the same counts for l0 and
+/// for each of sorted_runs
+pub fn expired_stats(
+    admin: &Admin,
+    path: &Path,
+    object_store: &Arc<dyn ObjectStore>,
+    clock: Option<&Arc<dyn SystemClock>>,
+    kwargs: RHash,
+) -> Result<RHash, Error> {
+    let now = get_optional::<i64>(&kwargs, "now")?.unwrap_or_else(|| clock::now_millis(clock));
+    let started = Instant::now();
+    let manifest = block_on_result(admin.read_manifest(None))?;
+    let reader = SstReader::new(path.clone(), object_store.clone(), None, None);
+    let clock = Clock {
+        now,
+        compaction: manifest.as_ref().map_or(0, |m| m.last_l0_clock_tick()),
+    };
+
+    // A sorted run can hold several views over one SST; read each SST once.
+    let mut seen = HashSet::new();
+    let l0 = tally_views(
+        &reader,
+        manifest.iter().flat_map(|m| m.l0()),
+        &clock,
+        &mut seen,
+    )?;
+    let mut sorted_runs = Vec::new();
+    for run in manifest.iter().flat_map(|m| m.compacted()) {
+        let tally = tally_views(&reader, &run.sst_views, &clock, &mut seen)?;
+        sorted_runs.push((run.id, tally));
+    }
+
+    let mut total = Tally::default();
+    total.add(&l0);
+    sorted_runs.iter().for_each(|(_, tally)| total.add(tally));
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = total.to_hash(&ruby)?;
+    hash.aset(ruby.to_symbol("now"), clock.now)?;
+    hash.aset(ruby.to_symbol("compaction_clock"), clock.compaction)?;
+    hash.aset(ruby.to_symbol("duration"), started.elapsed().as_secs_f64())?;
+    hash.aset(ruby.to_symbol("l0"), l0.to_hash(&ruby)?)?;
+    let runs = ruby.ary_new_capa(sorted_runs.len());
+    for (id, tally) in sorted_runs {
+        let run = tally.to_hash(&ruby)?;
+        run.aset(ruby.to_symbol("id"), id)?;
+        runs.push(run)?;
+    }
+    hash.aset(ruby.to_symbol("sorted_runs"), runs)?;
+    Ok(hash)
+}
+
+/// Tally the rows of the SSTs behind `views` not already in `seen`, one
+/// `block_on` per SST so interrupts are checked between them.
+fn tally_views<'a>(
+    reader: &SstReader,
+    views: impl IntoIterator<Item = &'a SsTableView>,
+    clock: &Clock,
+    seen: &mut HashSet<SsTableId>,
+) -> Result<Tally, Error> {
+    let mut tally = Tally::default();
+    for view in views {
+        if !seen.insert(view.sst.id) {
+            continue;
+        }
+        block_on_result(tally_sst(reader, view.sst.clone(), clock, &mut tally))?;
+    }
+    Ok(tally)
+}
+
+async fn tally_sst(
+    reader: &SstReader,
+    handle: SsTableHandle,
+    clock: &Clock,
+    tally: &mut Tally,
+) -> Result<(), SlateError> {
+    let sst = reader.open_with_handle(handle)?;
+    let blocks = sst.index().await?.len();
+    tally.ssts += 1;
+    for block in 0..blocks {
+        for row in sst.read_block(block).await? {
+            let bytes = (row.key.len() + row.value.len()) as u64;
+            tally.entries += 1;
+            tally.bytes += bytes;
+            let expire_ts = match row.value {
+                ValueDeletable::Tombstone => None,
+                _ => row.expire_ts,
+            };
+            if expire_ts.is_some_and(|ts| ts <= clock.now) {
+                tally.expired_entries += 1;
+                tally.expired_bytes += bytes;
+            }
+            if expire_ts.is_some_and(|ts| ts <= clock.compaction) {
+                tally.reclaimable_entries += 1;
+                tally.reclaimable_bytes += bytes;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Submit a compaction that rewrites the whole tree, dropping expired rows.
+///
+/// Every L0 SST and sorted run in the latest manifest is merged into the
+/// lowest sorted run, which drops the rows `expired_stats` reports as
+/// reclaimable. The compaction is run by the writer's compactor, so the
+/// database must be open somewhere for it to make progress; the space the
+/// old SSTs held is freed once garbage collection deletes them.
+///
+/// # Arguments
+/// * `kwargs` - Options (wait: block until the compaction finishes,
+///   timeout_ms: how long to wait before raising TimeoutError)
+///
+/// # Returns
+/// Hash with id, status (:submitted, or once waited on :completed, or
+/// :finished if a later compaction has already replaced its record),
+/// l0_ssts, sorted_runs and destination; or nil if the database has
+/// nothing compacted to rewrite
+pub fn purge_expired(admin: &Admin, kwargs: RHash) -> Result<Option<RHash>, Error> {
+    let wait = get_optional::<bool>(&kwargs, "wait")?.unwrap_or(false);
+    let timeout = get_positive(&kwargs, "timeout_ms")?.map(|ms| Duration::from_millis(ms as u64));
+
+    let Some(full) = submit_full_compaction(admin)? else {
+        return Ok(None);
+    };
+    let mut status = status_name(full.compaction.status());
+    if wait {
+        status = wait_for_compaction(admin, &full.compaction, "purge", timeout, |_| Ok(()))?;
+    }
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    full.to_hash(&ruby, &status).map(Some)
+}
+
+/// A compaction of the whole tree, as submitted by `submit_full_compaction`.
+pub struct FullCompaction {
+    pub compaction: Compaction,
+    l0_ssts: usize,
+    sorted_runs: usize,
+    destination: u32,
+}
+
+impl FullCompaction {
+    /// Hash with id, the given status, l0_ssts, sorted_runs and destination.
+    pub fn to_hash(&self, ruby: &Ruby, status: &str) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("id"), self.compaction.id().to_string())?;
+        hash.aset(ruby.to_symbol("status"), ruby.to_symbol(status))?;
+        hash.aset(ruby.to_symbol("l0_ssts"), self.l0_ssts)?;
+        hash.aset(ruby.to_symbol("sorted_runs"), self.sorted_runs)?;
+        hash.aset(ruby.to_symbol("destination"), self.destination)?;
+        Ok(hash)
+    }
+}
+
+/// Submit a compaction merging every L0 SST and sorted run in the latest
+/// manifest into the lowest sorted run, or return `None` if the database
+/// has nothing compacted.
+pub fn submit_full_compaction(admin: &Admin) -> Result<Option<FullCompaction>, Error> {
+    let Some(manifest) = block_on_result(admin.read_manifest(None))? else {
+        return Ok(None);
+    };
+    let l0: Vec<SourceId> = manifest
+        .l0()
+        .iter()
+        .map(|view| SourceId::SstView(view.id))
+        .collect();
+    let runs: Vec<u32> = manifest.compacted().iter().map(|run| run.id).collect();
+    if l0.is_empty() && runs.is_empty() {
+        return Ok(None);
+    }
+    // Sorted runs are merged into the lowest; L0 alone becomes run 0.
+    let destination = runs.iter().copied().min().unwrap_or(0);
+    let (l0_ssts, sorted_runs) = (l0.len(), runs.len());
+    let sources = l0
+        .into_iter()
+        .chain(runs.into_iter().map(SourceId::SortedRun))
+        .collect();
+
+    let compaction =
+        block_on_result(admin.submit_compaction(CompactionSpec::new(sources, destination)))?;
+    Ok(Some(FullCompaction {
+        compaction,
+        l0_ssts,
+        sorted_runs,
+        destination,
+    }))
+}
+
+/// Block until `compaction` is no longer active, calling `on_poll` with
+/// its latest record between reads.
+///
+/// # Returns
+/// The name of its final status, or "finished" if a later compaction has
+/// already replaced its record
+pub fn wait_for_compaction(
+    admin: &Admin,
+    compaction: &Compaction,
+    purpose: &str,
+    timeout: Option<Duration>,
+    mut on_poll: impl FnMut(&Compaction) -> Result<(), Error>,
+) -> Result<String, Error> {
+    let id = compaction.id();
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        // Only the latest finished compaction is kept, so one that has
+        // disappeared finished and was replaced by a later one.
+        let Some(current) = block_on_result(admin.read_compaction(id, None))? else {
+            return Ok("finished".to_string());
+        };
+        if !current.active() {
+            if current.status() == CompactionStatus::Failed {
+                return Err(internal_error(&format!(
+                    "{} compaction {} failed",
+                    purpose, id
+                )));
+            }
+            return Ok(status_name(current.status()));
+        }
+        on_poll(&current)?;
+
+        let mut interval = COMPACTION_POLL_INTERVAL;
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(timeout_error(&format!(
+                    "timed out waiting for the {} compaction",
+                    purpose
+                )));
+            }
+            interval = interval.min(remaining);
+        }
+        block_on(tokio::time::sleep(interval))?;
+    }
+}
+
+/// The name of a compaction status, as reported to Ruby.
+fn status_name(status: CompactionStatus) -> String {
+    format!("{:?}", status).to_lowercase()
+}
@@ -0,0 +1,443 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt};
+use magnus::prelude::*;
+use magnus::{function, method, Error, RHash, Ruby};
+use once_cell::sync::Lazy;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{
+    self, CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions, Result,
+};
+
+use crate::errors::invalid_argument_error;
+use crate::utils::{get_optional, get_positive};
+
+/// Faults injected with `SlateDb::Testing.inject_fault`, checked by every
+/// object store request in the order they were added.
+static FAULTS: Lazy<Mutex<Vec<Arc<Fault>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Whether any fault is registered, so requests skip the lock when none is.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// The object store requests a fault can target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Put,
+    Get,
+    Head,
+    Delete,
+    List,
+    Copy,
+    Rename,
+    Any,
+}
+
+impl Op {
+    fn parse(name: &str) -> Result<Self, Error> {
+        match name {
+            "put" => Ok(Self::Put),
+            "get" => Ok(Self::Get),
+            "head" => Ok(Self::Head),
+            "delete" => Ok(Self::Delete),
+            "list" => Ok(Self::List),
+            "copy" => Ok(Self::Copy),
+            "rename" => Ok(Self::Rename),
+            "any" => Ok(Self::Any),
+            other => Err(invalid_argument_error(&format!(
+                "invalid fault operation: {} (expected :put, :get, :head, :delete, :list, :copy, \
+                 :rename or :any)",
+                other
+            ))),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Put => "put",
+            Self::Get => "get",
+            Self::Head => "head",
+            Self::Delete => "delete",
+            Self::List => "list",
+            Self::Copy => "copy",
+            Self::Rename => "rename",
+            Self::Any => "any",
+        }
+    }
+}
+
+/// The error a fault fails requests with.
+#[derive(Clone, Copy, Debug)]
+enum FaultError {
+    /// A transient failure, raised as UnavailableError.
+    Unavailable,
+    /// The store rejecting requests for exceeding its rate limit, raised as
+    /// ThrottledError.
+    Throttled,
+    /// A missing object, raised as DataError.
+    NotFound,
+}
+
+impl FaultError {
+    fn parse(name: &str) -> Result<Self, Error> {
+        match name {
+            "unavailable" => Ok(Self::Unavailable),
+            "throttled" => Ok(Self::Throttled),
+            "not_found" => Ok(Self::NotFound),
+            other => Err(invalid_argument_error(&format!(
+                "invalid fault error: {} (expected :unavailable, :throttled or :not_found)",
+                other
+            ))),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Unavailable => "unavailable",
+            Self::Throttled => "throttled",
+            Self::NotFound => "not_found",
+        }
+    }
+
+    fn to_object_store_error(self, op: Op, location: Option<&Path>) -> object_store::Error {
+        let location = location.map(Path::to_string).unwrap_or_default();
+        let message = match self {
+            Self::Unavailable => format!("injected fault: {} {} unavailable", op.name(), location),
+            // Worded like S3's throttling response, so it maps to ThrottledError.
+            Self::Throttled => format!("injected fault: {} {} 503 Slow Down", op.name(), location),
+            Self::NotFound => {
+                return object_store::Error::NotFound {
+                    source: format!("injected fault: {} {} not found", op.name(), location).into(),
+                    path: location,
+                }
+            }
+        };
+        object_store::Error::Generic {
+            store: "FaultInjection",
+            source: message.into(),
+        }
+    }
+}
+
+/// A fault injected into object store requests.
+#[derive(Debug)]
+struct Fault {
+    op: Op,
+    error: Option<FaultError>,
+    probability: f64,
+    delay: Option<Duration>,
+    /// Only requests for locations containing this are affected.
+    path: Option<String>,
+    /// How many more requests the fault may affect, if limited.
+    remaining: Option<AtomicU64>,
+    hits: AtomicU64,
+}
+
+impl Fault {
+    fn from_kwargs(op: String, kwargs: &RHash) -> Result<Self, Error> {
+        let op = Op::parse(&op)?;
+        let error = get_optional::<String>(kwargs, "error")?
+            .map(|error| FaultError::parse(&error))
+            .transpose()?;
+        let probability = get_optional::<f64>(kwargs, "probability")?.unwrap_or(1.0);
+        if !(probability > 0.0 && probability <= 1.0) {
+            return Err(invalid_argument_error(&format!(
+                "probability must be greater than 0 and at most 1, got {}",
+                probability
+            )));
+        }
+        let delay = get_optional::<u64>(kwargs, "delay_ms")?.map(Duration::from_millis);
+        if error.is_none() && delay.is_none() {
+            return Err(invalid_argument_error(
+                "a fault needs an error, a delay_ms or both",
+            ));
+        }
+        Ok(Self {
+            op,
+            error,
+            probability,
+            delay,
+            path: get_optional::<String>(kwargs, "path")?,
+            remaining: get_positive(kwargs, "times")?.map(|times| AtomicU64::new(times as u64)),
+            hits: AtomicU64::new(0),
+        })
+    }
+
+    fn is_active(&self) -> bool {
+        self.remaining
+            .as_ref()
+            .is_none_or(|remaining| remaining.load(Ordering::SeqCst) > 0)
+    }
+
+    fn matches(&self, op: Op, location: Option<&Path>) -> bool {
+        if self.op != Op::Any && self.op != op {
+            return false;
+        }
+        match (&self.path, location) {
+            (None, _) => true,
+            (Some(path), Some(location)) => location.as_ref().contains(path.as_str()),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Whether the fault affects a matching request, counting it if so.
+    fn fire(&self) -> bool {
+        if self.probability < 1.0 {
+            let random = RandomState::new().build_hasher().finish();
+            if random as f64 / u64::MAX as f64 >= self.probability {
+                return false;
+            }
+        }
+        if let Some(remaining) = &self.remaining {
+            let taken =
+                remaining.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+            if taken.is_err() {
+                return false;
+            }
+        }
+        self.hits.fetch_add(1, Ordering::SeqCst);
+        true
+    }
+}
+
+/// Apply the first registered fault that fires for a request: wait out its
+/// delay, then fail with its error, if any.
+async fn check(op: Op, location: Option<&Path>) -> Result<()> {
+    if !ACTIVE.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    let fired = FAULTS
+        .lock()
+        .expect("fault registry poisoned")
+        .iter()
+        .find(|fault| fault.matches(op, location) && fault.fire())
+        .map(|fault| (fault.delay, fault.error));
+    let Some((delay, error)) = fired else {
+        return Ok(());
+    };
+    if let Some(delay) = delay {
+        tokio::time::sleep(delay).await;
+    }
+    match error {
+        Some(error) => Err(error.to_object_store_error(op, location)),
+        None => Ok(()),
+    }
+}
+
+/// Wrap `store` so requests through it are subject to injected faults.
+///
+/// Every database, reader and admin store is wrapped, so faults injected
+/// after a handle was opened still apply to it. Without faults a request
+/// costs one atomic load.
+pub fn wrap(store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+    Arc::new(FaultInjectingStore { inner: store })
+}
+
+/// An object store that fails or delays requests matching an injected
+/// fault, and passes everything else through to the store it wraps.
+#[derive(Debug)]
+struct FaultInjectingStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl fmt::Display for FaultInjectingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FaultInjectingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FaultInjectingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        check(Op::Put, Some(location)).await?;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        check(Op::Put, Some(location)).await?;
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let op = if options.head { Op::Head } else { Op::Get };
+        check(op, Some(location)).await?;
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        check(Op::Get, Some(location)).await?;
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, Result<Path>>,
+    ) -> BoxStream<'static, Result<Path>> {
+        self.inner
+            .delete_stream(
+                locations
+                    .then(|location| async move {
+                        let location = location?;
+                        check(Op::Delete, Some(&location)).await?;
+                        Ok(location)
+                    })
+                    .boxed(),
+            )
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let inner = self.inner.clone();
+        let prefix = prefix.cloned();
+        async move {
+            match check(Op::List, prefix.as_ref()).await {
+                Ok(()) => inner.list(prefix.as_ref()),
+                Err(e) => futures::stream::once(async { Err(e) }).boxed(),
+            }
+        }
+        .into_stream()
+        .flatten()
+        .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let inner = self.inner.clone();
+        let prefix = prefix.cloned();
+        let offset = offset.clone();
+        async move {
+            match check(Op::List, prefix.as_ref()).await {
+                Ok(()) => inner.list_with_offset(prefix.as_ref(), &offset),
+                Err(e) => futures::stream::once(async { Err(e) }).boxed(),
+            }
+        }
+        .into_stream()
+        .flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        check(Op::List, prefix).await?;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, options: CopyOptions) -> Result<()> {
+        check(Op::Copy, Some(to)).await?;
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, options: RenameOptions) -> Result<()> {
+        check(Op::Rename, Some(to)).await?;
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+/// Ruby handle for an injected fault.
+///
+/// This struct is exposed to Ruby as `SlateDb::Testing::Fault`.
+#[magnus::wrap(class = "SlateDb::Testing::Fault", free_immediately, size)]
+pub struct FaultHandle {
+    inner: Arc<Fault>,
+}
+
+impl FaultHandle {
+    /// Register a fault for object store requests made from now on.
+    ///
+    /// # Arguments
+    /// * `op` - The request to target (put, get, head, delete, list, copy,
+    ///   rename or any)
+    /// * `kwargs` - Options (error, probability, delay_ms, path, times)
+    pub fn inject(op: String, kwargs: RHash) -> Result<Self, Error> {
+        let fault = Arc::new(Fault::from_kwargs(op, &kwargs)?);
+        let mut faults = FAULTS.lock().expect("fault registry poisoned");
+        faults.push(fault.clone());
+        ACTIVE.store(true, Ordering::SeqCst);
+        Ok(Self { inner: fault })
+    }
+
+    pub fn op(&self) -> String {
+        self.inner.op.name().to_string()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.inner.error.map(|error| error.name().to_string())
+    }
+
+    /// How many requests the fault has affected.
+    pub fn hits(&self) -> u64 {
+        self.inner.hits.load(Ordering::SeqCst)
+    }
+
+    /// Whether the fault is registered and has requests left to affect.
+    pub fn is_active(&self) -> bool {
+        self.is_registered() && self.inner.is_active()
+    }
+
+    /// Stop the fault affecting requests.
+    ///
+    /// # Returns
+    /// Whether the fault was still registered
+    pub fn remove(&self) -> bool {
+        let mut faults = FAULTS.lock().expect("fault registry poisoned");
+        let before = faults.len();
+        faults.retain(|fault| !Arc::ptr_eq(fault, &self.inner));
+        ACTIVE.store(!faults.is_empty(), Ordering::SeqCst);
+        faults.len() < before
+    }
+
+    fn is_registered(&self) -> bool {
+        FAULTS
+            .lock()
+            .expect("fault registry poisoned")
+            .iter()
+            .any(|fault| Arc::ptr_eq(fault, &self.inner))
+    }
+}
+
+/// Remove every injected fault.
+///
+/// # Returns
+/// The number of faults removed
+fn clear_faults() -> usize {
+    let mut faults = FAULTS.lock().expect("fault registry poisoned");
+    let removed = faults.len();
+    faults.clear();
+    ACTIVE.store(false, Ordering::SeqCst);
+    removed
+}
+
+/// Define the SlateDb::Testing module and its Fault class.
+pub fn define_testing_module(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let testing = module.define_module("Testing")?;
+    testing.define_module_function("_inject_fault", function!(FaultHandle::inject, 2))?;
+    testing.define_module_function("clear_faults", function!(clear_faults, 0))?;
+
+    let class = testing.define_class("Fault", ruby.class_object())?;
+    class.define_method("_op", method!(FaultHandle::op, 0))?;
+    class.define_method("_error", method!(FaultHandle::error, 0))?;
+    class.define_method("hits", method!(FaultHandle::hits, 0))?;
+    class.define_method("active?", method!(FaultHandle::is_active, 0))?;
+    class.define_method("remove", method!(FaultHandle::remove, 0))?;
+    Ok(())
+}
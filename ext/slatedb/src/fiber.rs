@@ -0,0 +1,92 @@
+use std::future::Future;
+use std::io::{Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use magnus::prelude::*;
+use magnus::rb_sys::FromRawValue;
+use magnus::{Error, KwArgs, Ruby, Value};
+use rb_sys::rb_fiber_scheduler_current;
+use tokio::runtime::Runtime;
+
+use crate::errors::internal_error;
+
+/// The scheduler of the current fiber, or None if the fiber is blocking or
+/// no scheduler is set.
+pub fn current_scheduler() -> Option<Value> {
+    let scheduler = unsafe { Value::from_raw(rb_fiber_scheduler_current()) };
+    (!scheduler.is_nil()).then_some(scheduler)
+}
+
+/// Wakes a waiting fiber by making its pipe readable.
+struct PipeWaker(UnixStream);
+
+impl Wake for PipeWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // A full pipe already has a wakeup pending.
+        let _ = (&self.0).write_all(&[1]);
+    }
+}
+
+/// Drive a future to completion on the calling thread, yielding the current
+/// fiber to `scheduler` whenever the future is pending.
+///
+/// Parking the native thread would stall every fiber on it, so the future
+/// is polled here and, between polls, the fiber waits on a pipe through the
+/// scheduler's `io_wait` hook. The future's waker writes to the pipe, so the
+/// scheduler resumes the fiber once there is progress to make.
+///
+/// If the scheduler raises while the fiber waits (e.g. the task is stopped),
+/// the future is dropped, cancelling the operation, and the error returned.
+pub fn block_on<F, T>(
+    ruby: &Ruby,
+    rt: &Runtime,
+    scheduler: Value,
+    mut future: Pin<&mut F>,
+) -> Result<T, Error>
+where
+    F: Future<Output = T>,
+{
+    let pipe_error = |e: std::io::Error| internal_error(&format!("failed to create pipe: {}", e));
+    let (mut reader, writer) = UnixStream::pair().map_err(pipe_error)?;
+    reader.set_nonblocking(true).map_err(pipe_error)?;
+    writer.set_nonblocking(true).map_err(pipe_error)?;
+
+    let options = ruby.hash_new();
+    options.aset(ruby.to_symbol("autoclose"), false)?;
+    let io: Value = ruby
+        .class_io()
+        .funcall("for_fd", (reader.as_raw_fd(), KwArgs(options)))?;
+    let readable: Value = ruby.class_io().const_get("READABLE")?;
+
+    let waker = Waker::from(Arc::new(PipeWaker(writer)));
+    let mut cx = Context::from_waker(&waker);
+    let result = loop {
+        // Entered only while polling: other fibers on this thread enter the
+        // runtime too, and guards must be dropped in the order they were made.
+        let poll = {
+            let _runtime = rt.enter();
+            future.as_mut().poll(&mut cx)
+        };
+        if let Poll::Ready(value) = poll {
+            break Ok(value);
+        }
+
+        if let Err(e) = scheduler.funcall::<_, _, Value>("io_wait", (io, readable, ruby.qnil())) {
+            break Err(e);
+        }
+        let mut buf = [0u8; 64];
+        while matches!(reader.read(&mut buf), Ok(n) if n > 0) {}
+    };
+
+    // Closes the IO object only; the pipe is closed when `reader` drops.
+    let _: Value = io.funcall("close", ())?;
+    result
+}
@@ -0,0 +1,208 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use magnus::prelude::*;
+use magnus::{function, method, Error, Ruby, Value};
+use slatedb::Error as SlateError;
+use tokio::sync::{mpsc, watch};
+
+use crate::codec::Codec;
+use crate::errors::{internal_error, timeout_error, CapturedError};
+use crate::runtime::{block_on, is_forked, RuntimeRef};
+
+/// How long `_next_completed` waits for a completed future before returning
+/// nil, so the Ruby thread dispatching callbacks can be stopped.
+const COMPLETION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static COMPLETIONS: Mutex<Option<CompletionQueue>> = Mutex::new(None);
+
+/// The value of a finished operation, or its error. Kept as plain data so
+/// `await` can raise the same error every time it is called.
+type Outcome = Result<Option<Vec<u8>>, CapturedError>;
+
+/// Ids of completed futures with `on_complete` callbacks, waiting for the
+/// Ruby dispatcher thread.
+///
+/// Each process gets its own queue: the receiver's lock is usually held by
+/// the parent's dispatcher thread, which does not exist in a forked child.
+struct CompletionQueue {
+    pid: u32,
+    sender: mpsc::UnboundedSender<u64>,
+    ids: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<u64>>>,
+}
+
+impl CompletionQueue {
+    fn new() -> Self {
+        let (sender, ids) = mpsc::unbounded_channel();
+        Self {
+            pid: std::process::id(),
+            sender,
+            ids: Arc::new(tokio::sync::Mutex::new(ids)),
+        }
+    }
+}
+
+/// Run `f` with this process's completion queue.
+fn with_completions<T>(f: impl FnOnce(&CompletionQueue) -> T) -> T {
+    let mut queue = COMPLETIONS.lock().expect("completion queue poisoned");
+    if !matches!(queue.as_ref(), Some(current) if !is_forked(current.pid)) {
+        *queue = Some(CompletionQueue::new());
+    }
+    f(queue.as_ref().expect("completion queue initialized"))
+}
+
+#[derive(Default)]
+struct State {
+    outcome: Option<Outcome>,
+    /// Whether the dispatcher should be told when the operation finishes.
+    watched: bool,
+}
+
+/// Ruby wrapper for an operation running in the background.
+///
+/// This struct is exposed to Ruby as `SlateDb::Future` and is returned by
+/// `get_async`, `put_async` and `write_async`. The operation runs on the
+/// database's runtime without holding a Ruby thread.
+#[magnus::wrap(class = "SlateDb::Future", free_immediately, size)]
+pub struct Future {
+    id: u64,
+    state: Arc<Mutex<State>>,
+    done: watch::Receiver<bool>,
+    /// Decodes the operation's value when it is awaited.
+    codec: Codec,
+}
+
+impl Future {
+    /// Start `operation` on `runtime` and return a future for its result.
+    pub fn spawn<F>(runtime: &RuntimeRef, operation: F) -> Self
+    where
+        F: std::future::Future<Output = Result<Option<Vec<u8>>, SlateError>> + Send + 'static,
+    {
+        Self::spawn_decoded(runtime, Codec::Raw, operation)
+    }
+
+    /// Start `operation` on `runtime` and return a future whose value is
+    /// decoded with `codec`.
+    pub fn spawn_decoded<F>(runtime: &RuntimeRef, codec: Codec, operation: F) -> Self
+    where
+        F: std::future::Future<Output = Result<Option<Vec<u8>>, SlateError>> + Send + 'static,
+    {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let state = Arc::new(Mutex::new(State::default()));
+        let (finished, done) = watch::channel(false);
+
+        let task_state = state.clone();
+        runtime.spawn(async move {
+            let outcome = operation.await.map_err(|e| CapturedError::new(&e));
+            let watched = {
+                let mut state = task_state.lock().expect("future state poisoned");
+                state.outcome = Some(outcome);
+                state.watched
+            };
+            let _ = finished.send(true);
+            if watched {
+                with_completions(|queue| queue.sender.send(id).ok());
+            }
+        });
+
+        Self {
+            id,
+            state,
+            done,
+            codec,
+        }
+    }
+
+    /// The id reported to the dispatcher when the operation finishes.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Whether the operation has finished, successfully or not.
+    pub fn is_fulfilled(&self) -> bool {
+        *self.done.borrow()
+    }
+
+    /// Wait for the operation to finish.
+    ///
+    /// Returns its value, or raises its error. Raises TimeoutError if
+    /// `timeout_ms` elapses first; the operation keeps running.
+    pub fn await_result(&self, timeout_ms: Option<u64>) -> Result<Option<Value>, Error> {
+        let mut done = self.done.clone();
+        let finished = block_on(async move {
+            let wait = async {
+                let _ = done.wait_for(|done| *done).await;
+            };
+            match timeout_ms {
+                Some(ms) => tokio::time::timeout(Duration::from_millis(ms), wait)
+                    .await
+                    .is_ok(),
+                None => {
+                    wait.await;
+                    true
+                }
+            }
+        })?;
+        if !finished {
+            return Err(timeout_error(&format!(
+                "operation did not complete within {}ms",
+                timeout_ms.unwrap_or_default()
+            )));
+        }
+
+        // Decoded outside the lock: Marshal can run arbitrary Ruby code.
+        let outcome = self
+            .state
+            .lock()
+            .expect("future state poisoned")
+            .outcome
+            .clone();
+        match outcome {
+            Some(Ok(value)) => value.map(|b| self.codec.decode(&b)).transpose(),
+            Some(Err(error)) => Err(error.to_error()),
+            None => Err(internal_error("operation was abandoned before it finished")),
+        }
+    }
+
+    /// Report this future to the dispatcher once the operation finishes, or
+    /// now if it already has.
+    pub fn watch(&self) {
+        let mut state = self.state.lock().expect("future state poisoned");
+        if state.outcome.is_some() {
+            with_completions(|queue| queue.sender.send(self.id).ok());
+        } else {
+            state.watched = true;
+        }
+    }
+}
+
+/// Wait briefly for a watched future to finish.
+///
+/// Returns its id, or nil if none finished in time.
+fn next_completed() -> Result<Option<u64>, Error> {
+    let ids = with_completions(|queue| queue.ids.clone());
+    block_on(async {
+        tokio::time::timeout(COMPLETION_POLL_INTERVAL, async {
+            ids.lock().await.recv().await
+        })
+        .await
+        .ok()
+        .flatten()
+    })
+}
+
+/// Define the Future class on the SlateDb module.
+pub fn define_future_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("Future", ruby.class_object())?;
+
+    class.define_singleton_method("_next_completed", function!(next_completed, 0))?;
+
+    class.define_method("id", method!(Future::id, 0))?;
+    class.define_method("fulfilled?", method!(Future::is_fulfilled, 0))?;
+    class.define_method("_await", method!(Future::await_result, 1))?;
+    class.define_method("_watch", method!(Future::watch, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::TryStreamExt;
+use magnus::{Error, RHash, Ruby};
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{ObjectMeta, ObjectStore, ObjectStoreExt};
+use slatedb::Error as SlateError;
+
+use crate::runtime::block_on;
+use crate::utils::{get_optional, to_slate_error};
+
+/// How long a probe waits for the object store when no `timeout_ms` is given.
+const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Probe the object store behind an open Database or Reader.
+///
+/// Lists the manifests newer than the one the handle is using, or heads
+/// that manifest if there are none. This touches only manifest metadata, so
+/// it is cheap enough for readiness probes and doesn't show up as a read.
+///
+/// # Arguments
+/// * `manifest_id` - The manifest the handle is using
+/// * `closed` - Whether the handle has been closed
+/// * `kwargs` - Options (timeout_ms)
+///
+/// # Returns
+/// Hash with healthy, latency, manifest_id, latest_manifest_id,
+/// manifests_behind, last_manifest_write, staleness and error
+pub fn check(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &str,
+    manifest_id: u64,
+    closed: bool,
+    kwargs: &RHash,
+) -> Result<RHash, Error> {
+    let timeout = get_optional::<u64>(kwargs, "timeout_ms")?
+        .map_or(DEFAULT_PROBE_TIMEOUT, Duration::from_millis);
+
+    let started = Instant::now();
+    let probed = block_on(async {
+        tokio::time::timeout(timeout, latest_manifest(object_store, path, manifest_id)).await
+    })?;
+    let latency = started.elapsed().as_secs_f64();
+
+    let (latest, error) = match probed {
+        Ok(Ok(latest)) => (Some(latest), None),
+        Ok(Err(e)) => (None, Some(e.to_string())),
+        Err(_) => (
+            None,
+            Some(format!(
+                "object store did not respond within {} ms",
+                timeout.as_millis()
+            )),
+        ),
+    };
+    let error = error.or_else(|| closed.then(|| "handle is closed".to_string()));
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = ruby.hash_new();
+    hash.aset(ruby.to_symbol("healthy"), error.is_none())?;
+    hash.aset(ruby.to_symbol("latency"), latency)?;
+    hash.aset(ruby.to_symbol("manifest_id"), manifest_id)?;
+    let latest_id = latest.as_ref().map(|(id, _)| *id);
+    hash.aset(ruby.to_symbol("latest_manifest_id"), latest_id)?;
+    hash.aset(
+        ruby.to_symbol("manifests_behind"),
+        latest_id.map(|id| id.saturating_sub(manifest_id)),
+    )?;
+    let written = latest
+        .as_ref()
+        .map(|(_, meta)| meta.last_modified.timestamp_millis() as f64 / 1000.0);
+    hash.aset(ruby.to_symbol("last_manifest_write"), written)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    hash.aset(
+        ruby.to_symbol("staleness"),
+        written.map(|written| (now - written).max(0.0)),
+    )?;
+    hash.aset(ruby.to_symbol("error"), error)?;
+    Ok(hash)
+}
+
+/// The id and metadata of the newest manifest at or after `manifest_id`.
+async fn latest_manifest(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &str,
+    manifest_id: u64,
+) -> Result<(u64, ObjectMeta), SlateError> {
+    let dir = Path::from(path).join("manifest");
+    let current = dir.clone().join(format!("{:020}.manifest", manifest_id));
+
+    let mut newest: Option<(u64, ObjectMeta)> = None;
+    let mut newer = object_store.list_with_offset(Some(&dir), &current);
+    while let Some(meta) = newer.try_next().await.map_err(to_slate_error)? {
+        let Some(id) = meta
+            .location
+            .filename()
+            .and_then(|name| name.strip_suffix(".manifest"))
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        if newest.as_ref().is_none_or(|(newest, _)| id > *newest) {
+            newest = Some((id, meta));
+        }
+    }
+
+    match newest {
+        Some(newest) => Ok(newest),
+        None => {
+            let meta = object_store.head(&current).await.map_err(to_slate_error)?;
+            Ok((manifest_id, meta))
+        }
+    }
+}
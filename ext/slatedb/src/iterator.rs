@@ -1,12 +1,28 @@
+use std::borrow::Cow;
+use std::ffi::{c_char, c_long};
+use std::ops::Bound;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use magnus::prelude::*;
-use magnus::{method, Error, Ruby};
-use slatedb::DbIterator;
+use magnus::rb_sys::FromRawValue;
+use magnus::{method, DataTypeFunctions, Error, RArray, RHash, RString, Ruby, TypedData, Value};
+use rb_sys::{rb_ascii8bit_encoding, rb_enc_interned_str, rb_utf8_encoding};
+use slatedb::config::{ScanOptions, SstBlockSize};
+use slatedb::{DbIterator, DbMetadataOps, DbSnapshot, IterationOrder, KeyValue};
 use tokio::sync::Mutex;
 
+use crate::codec::Codec;
+use crate::cursor::ScanCursor;
 use crate::errors::{internal_error, invalid_argument_error, map_error};
-use crate::runtime::block_on;
+use crate::runtime::{ensure_same_process, is_forked, RuntimeRef};
+use crate::utils::{get_optional, PrefixSubrange};
+
+/// Read-ahead used when a `read_ahead: :auto` scan is first opened.
+const AUTO_READ_AHEAD_INITIAL: usize = 64 * 1024;
+
+/// Largest read-ahead a `read_ahead: :auto` scan grows to.
+const AUTO_READ_AHEAD_MAX: usize = 16 * 1024 * 1024;
 
 /// Result type for raw byte key-value pairs.
 type ByteKvResult = Result<Option<(Vec<u8>, Vec<u8>)>, Error>;
@@ -17,110 +33,580 @@ enum IteratorError {
     Slate(slatedb::Error),
 }
 
+/// The version of the data an iterator reads from.
+///
+/// Captured when the iterator is created. Database and reader scans record
+/// the manifest id and durable sequence observed just before the scan was
+/// opened; snapshot and transaction scans additionally record the exact
+/// sequence number they read at.
+#[derive(Clone, Copy, Default)]
+pub struct ViewInfo {
+    pub manifest_id: Option<u64>,
+    pub seq: Option<u64>,
+    pub durable_seq: Option<u64>,
+}
+
+impl ViewInfo {
+    /// Capture the current manifest id and durable sequence of a handle.
+    pub fn capture<M: DbMetadataOps + ?Sized>(handle: &M) -> Self {
+        let status = handle.status();
+        Self {
+            manifest_id: Some(status.current_manifest.id()),
+            seq: None,
+            durable_seq: Some(status.durable_seq),
+        }
+    }
+
+    /// Capture the view of a handle pinned at a specific sequence number.
+    pub fn capture_at<M: DbMetadataOps + ?Sized>(handle: &M, seq: u64) -> Self {
+        Self {
+            seq: Some(seq),
+            ..Self::capture(handle)
+        }
+    }
+}
+
+/// Parse the `read_ahead` option, returning true for `read_ahead: :auto`.
+pub fn auto_read_ahead_from_kwargs(kwargs: &RHash) -> Result<bool, Error> {
+    match get_optional::<String>(kwargs, "read_ahead")?.as_deref() {
+        None => Ok(false),
+        Some("auto") => Ok(true),
+        Some(other) => Err(invalid_argument_error(&format!(
+            "invalid read_ahead: {} (expected :auto; use read_ahead_bytes for a fixed size)",
+            other
+        ))),
+    }
+}
+
+/// How an iterator returns its entries.
+#[derive(Clone, Copy, Default)]
+pub struct EntryShape {
+    /// Stop after this many entries.
+    pub limit: Option<usize>,
+    /// Return only keys rather than `[key, value]` pairs.
+    pub keys_only: bool,
+    /// Return binary (ASCII-8BIT) strings holding the exact bytes, instead
+    /// of UTF-8 strings with invalid sequences replaced.
+    pub binary: bool,
+    /// Return frozen strings, with keys interned so every scan returning a
+    /// key shares one String.
+    pub frozen: bool,
+    /// Append each entry's sequence number, so consumers rescanning a range
+    /// can tell a re-read of an entry from a new write to its key.
+    pub seq: bool,
+}
+
+impl EntryShape {
+    fn to_ruby_string(self, ruby: &Ruby, bytes: &[u8]) -> RString {
+        let string = if self.binary {
+            ruby.str_from_slice(bytes)
+        } else {
+            ruby.str_new(&String::from_utf8_lossy(bytes))
+        };
+        if self.frozen {
+            string.freeze();
+        }
+        string
+    }
+
+    fn to_ruby_key(self, ruby: &Ruby, bytes: &[u8]) -> RString {
+        if !self.frozen {
+            return self.to_ruby_string(ruby, bytes);
+        }
+        // Looking the key up in Ruby's table of interned strings only
+        // allocates a String the first time a key is seen.
+        let (key, encoding) = if self.binary {
+            (Cow::Borrowed(bytes), unsafe { rb_ascii8bit_encoding() })
+        } else {
+            let key = match String::from_utf8_lossy(bytes) {
+                Cow::Borrowed(key) => Cow::Borrowed(key.as_bytes()),
+                Cow::Owned(key) => Cow::Owned(key.into_bytes()),
+            };
+            (key, unsafe { rb_utf8_encoding() })
+        };
+        let interned = unsafe {
+            Value::from_raw(rb_enc_interned_str(
+                key.as_ptr() as *const c_char,
+                key.len() as c_long,
+                encoding,
+            ))
+        };
+        RString::from_value(interned).expect("interned key is a String")
+    }
+}
+
+/// Parse the `limit`, `keys_only`, `binary`, `frozen` and `seq` scan options.
+pub fn entry_shape_from_kwargs(kwargs: &RHash) -> Result<EntryShape, Error> {
+    Ok(EntryShape {
+        limit: get_optional::<usize>(kwargs, "limit")?,
+        keys_only: get_optional::<bool>(kwargs, "keys_only")?.unwrap_or(false),
+        binary: get_optional::<bool>(kwargs, "binary")?.unwrap_or(false),
+        frozen: get_optional::<bool>(kwargs, "frozen")?.unwrap_or(false),
+        seq: get_optional::<bool>(kwargs, "seq")?.unwrap_or(false),
+    })
+}
+
+/// The keys covered by a scan, kept so an adaptive scan can be reopened.
+#[derive(Clone)]
+pub enum ScanRange {
+    Range(Bound<Vec<u8>>, Bound<Vec<u8>>),
+    Prefix(Vec<u8>, PrefixSubrange),
+}
+
+impl ScanRange {
+    /// Shrink the range so that it resumes at `position` in iteration order.
+    pub fn narrow(&mut self, position: Bound<Vec<u8>>, descending: bool) {
+        match self {
+            ScanRange::Range(start, end) => {
+                if descending {
+                    *end = position;
+                } else {
+                    *start = position;
+                }
+            }
+            ScanRange::Prefix(prefix, (start, end)) => {
+                let suffix = position.map(|key| key[prefix.len()..].to_vec());
+                if descending {
+                    *end = suffix;
+                } else {
+                    *start = suffix;
+                }
+            }
+        }
+    }
+}
+
+/// Adaptive read-ahead for `read_ahead: :auto` scans.
+///
+/// The scan starts with a small read-ahead so short scans don't over-fetch.
+/// Each time the caller has consumed twice the current read-ahead, the scan
+/// is reopened just past the last returned key with four times the
+/// read-ahead, up to [`AUTO_READ_AHEAD_MAX`]. Scans read from a snapshot so
+/// every reopened scan sees the same data.
+pub struct AdaptiveReadAhead {
+    snapshot: Arc<DbSnapshot>,
+    range: ScanRange,
+    opts: ScanOptions,
+    consumed: usize,
+}
+
+impl AdaptiveReadAhead {
+    /// Open an adaptive scan over `range` of `snapshot`.
+    pub async fn open(
+        snapshot: Arc<DbSnapshot>,
+        range: ScanRange,
+        mut opts: ScanOptions,
+    ) -> Result<(Self, DbIterator), slatedb::Error> {
+        opts.read_ahead_bytes = AUTO_READ_AHEAD_INITIAL;
+        let adaptive = Self {
+            snapshot,
+            range,
+            opts,
+            consumed: 0,
+        };
+        let iter = adaptive.scan().await?;
+        Ok((adaptive, iter))
+    }
+
+    async fn scan(&self) -> Result<DbIterator, slatedb::Error> {
+        match &self.range {
+            ScanRange::Range(start, end) => {
+                self.snapshot
+                    .scan_with_options((start.clone(), end.clone()), &self.opts)
+                    .await
+            }
+            ScanRange::Prefix(prefix, subrange) => {
+                self.snapshot
+                    .scan_prefix_with_options(prefix, subrange.clone(), &self.opts)
+                    .await
+            }
+        }
+    }
+
+    /// Record a returned entry, reopening the scan with a larger read-ahead
+    /// once enough has been consumed. Returns the replacement iterator, if any.
+    async fn record(&mut self, kv: &KeyValue) -> Result<Option<DbIterator>, slatedb::Error> {
+        self.consumed += kv.key.len() + kv.value.len();
+        if self.opts.read_ahead_bytes >= AUTO_READ_AHEAD_MAX
+            || self.consumed < self.opts.read_ahead_bytes * 2
+        {
+            return Ok(None);
+        }
+
+        self.narrow(Bound::Excluded(kv.key.to_vec()));
+        self.opts.read_ahead_bytes = (self.opts.read_ahead_bytes * 4).min(AUTO_READ_AHEAD_MAX);
+        self.consumed = 0;
+        self.scan().await.map(Some)
+    }
+
+    /// Keep the range in step with a successful seek.
+    fn seeked(&mut self, key: &[u8]) {
+        match self.opts.order {
+            IterationOrder::Ascending => self.narrow(Bound::Included(key.to_vec())),
+            // Seek positions are only tracked for ascending scans; stop
+            // growing rather than risk reopening at the wrong position.
+            IterationOrder::Descending => self.opts.read_ahead_bytes = AUTO_READ_AHEAD_MAX,
+        }
+    }
+
+    /// Shrink the range so that it resumes at `position` in iteration order.
+    fn narrow(&mut self, position: Bound<Vec<u8>>) {
+        let descending = matches!(self.opts.order, IterationOrder::Descending);
+        self.range.narrow(position, descending);
+    }
+}
+
 /// Ruby wrapper for SlateDB iterator.
 ///
 /// This struct is exposed to Ruby as `SlateDb::Iterator`.
 /// It includes Enumerable support via the `each` method implemented in Ruby.
-#[magnus::wrap(class = "SlateDb::Iterator", free_immediately, size)]
+#[derive(TypedData)]
+#[magnus(class = "SlateDb::Iterator", free_immediately, size)]
 pub struct Iterator {
     inner: Arc<Mutex<Option<DbIterator>>>,
+    adaptive: Arc<Mutex<Option<AdaptiveReadAhead>>>,
+    view: ViewInfo,
+    shape: EntryShape,
+    codec: Codec,
+    /// Namespace prefix stripped from returned keys and added to seeks.
+    namespace: Vec<u8>,
+    returned: AtomicUsize,
+    /// Unread part of the scan's range, for iterators that can hand out a
+    /// cursor.
+    cursor: std::sync::Mutex<Option<ScanCursor>>,
+    /// The runtime of the database the scan reads from.
+    runtime: RuntimeRef,
+    /// The process that opened the scan.
+    pid: u32,
+}
+
+impl DataTypeFunctions for Iterator {
+    /// Report the handle plus the blocks its scan buffers: the current
+    /// read-ahead for `read_ahead: :auto` scans, otherwise one SST block.
+    fn size(&self) -> usize {
+        let block = SstBlockSize::default().as_bytes();
+        let buffered = match self.inner.try_lock() {
+            Ok(inner) if inner.is_none() => 0,
+            _ => match self.adaptive.try_lock() {
+                Ok(adaptive) => adaptive
+                    .as_ref()
+                    .map_or(block, |adaptive| adaptive.opts.read_ahead_bytes.max(block)),
+                Err(_) => block,
+            },
+        };
+        std::mem::size_of::<Self>()
+            + std::mem::size_of::<DbIterator>()
+            + self.namespace.capacity()
+            + buffered
+    }
 }
 
 impl Iterator {
-    /// Create a new Iterator from a DbIterator.
-    pub fn new(iter: DbIterator) -> Self {
+    /// Create a new Iterator from a DbIterator and the view it reads from.
+    pub fn new(iter: DbIterator, view: ViewInfo) -> Self {
         Self {
             inner: Arc::new(Mutex::new(Some(iter))),
+            adaptive: Arc::new(Mutex::new(None)),
+            view,
+            shape: EntryShape::default(),
+            codec: Codec::Raw,
+            namespace: Vec::new(),
+            returned: AtomicUsize::new(0),
+            cursor: std::sync::Mutex::new(None),
+            runtime: RuntimeRef::default(),
+            pid: std::process::id(),
         }
     }
 
-    /// Get the next key-value pair.
-    ///
-    /// Returns [key, value] as an array, or nil if iteration is complete.
-    pub fn next_entry(&self) -> Result<Option<(String, String)>, Error> {
+    /// Create an Iterator whose read-ahead adapts as it is consumed.
+    pub fn with_adaptive_read_ahead(
+        iter: DbIterator,
+        view: ViewInfo,
+        adaptive: AdaptiveReadAhead,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Some(iter))),
+            adaptive: Arc::new(Mutex::new(Some(adaptive))),
+            view,
+            shape: EntryShape::default(),
+            codec: Codec::Raw,
+            namespace: Vec::new(),
+            returned: AtomicUsize::new(0),
+            cursor: std::sync::Mutex::new(None),
+            runtime: RuntimeRef::default(),
+            pid: std::process::id(),
+        }
+    }
+
+    /// Set how the iterator returns its entries.
+    pub fn with_shape(mut self, shape: EntryShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Set the codec used to decode entry values.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Run the scan on the database's runtime.
+    pub fn with_runtime(mut self, runtime: RuntimeRef) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Scope the iterator to a namespace: keys are returned without
+    /// `prefix`, and seeks are relative to it. The scan must be limited to
+    /// keys starting with `prefix`.
+    pub fn with_namespace(mut self, prefix: Vec<u8>) -> Self {
+        self.namespace = prefix;
+        if let Some(cursor) = self.cursor.get_mut().expect("cursor mutex poisoned") {
+            cursor.namespaced = true;
+        }
+        self
+    }
+
+    /// Track the iterator's position in `range`, so that `cursor` can
+    /// describe where to resume.
+    pub fn with_cursor(mut self, range: ScanRange, order: IterationOrder) -> Self {
+        self.cursor = std::sync::Mutex::new(Some(ScanCursor {
+            range,
+            descending: matches!(order, IterationOrder::Descending),
+            namespaced: !self.namespace.is_empty(),
+        }));
+        self
+    }
+
+    /// Raise ForkedProcessError if the iterator is used after `fork`: its
+    /// scan belongs to the parent's database, whose background tasks do not
+    /// run in the child.
+    fn ensure_usable(&self) -> Result<(), Error> {
+        ensure_same_process(self.pid)
+    }
+
+    /// Advance the underlying iterator, growing adaptive read-ahead as needed.
+    fn next_kv(&self) -> Result<Option<KeyValue>, Error> {
+        Ok(self.next_kvs(1)?.pop())
+    }
+
+    /// Advance the underlying iterator up to `max` times in one `block_on`,
+    /// stopping early at the end of the scan or the iterator's limit.
+    fn next_kvs(&self, max: usize) -> Result<Vec<KeyValue>, Error> {
+        let max = match self.shape.limit {
+            Some(limit) => max.min(limit.saturating_sub(self.returned.load(Ordering::SeqCst))),
+            None => max,
+        };
+        if max == 0 {
+            return Ok(Vec::new());
+        }
+
         let inner = self.inner.clone();
+        let adaptive = self.adaptive.clone();
 
-        let result = block_on(async {
+        let result = self.runtime.block_on(async {
             let mut guard = inner.lock().await;
-            match guard.as_mut() {
-                Some(iter) => iter.next().await.map_err(IteratorError::Slate),
-                None => Err(IteratorError::Closed),
+            let mut adaptive = adaptive.lock().await;
+            let mut kvs = Vec::new();
+            while kvs.len() < max {
+                let iter = guard.as_mut().ok_or(IteratorError::Closed)?;
+                let Some(kv) = iter.next().await.map_err(IteratorError::Slate)? else {
+                    break;
+                };
+
+                if let Some(adaptive) = adaptive.as_mut() {
+                    if let Some(reopened) =
+                        adaptive.record(&kv).await.map_err(IteratorError::Slate)?
+                    {
+                        *guard = Some(reopened);
+                    }
+                }
+                kvs.push(kv);
             }
-        });
 
-        let kv = match result {
-            Ok(kv) => kv,
-            Err(IteratorError::Closed) => return Err(internal_error("iterator has been closed")),
-            Err(IteratorError::Slate(e)) => return Err(map_error(e)),
+            Ok::<_, IteratorError>(kvs)
+        })?;
+
+        match result {
+            Ok(kvs) => {
+                if let Some(last) = kvs.last() {
+                    self.returned.fetch_add(kvs.len(), Ordering::SeqCst);
+                    if let Some(cursor) =
+                        self.cursor.lock().expect("cursor mutex poisoned").as_mut()
+                    {
+                        cursor.advance(Bound::Excluded(last.key.to_vec()));
+                    }
+                }
+                Ok(kvs)
+            }
+            Err(IteratorError::Closed) => Err(internal_error("iterator has been closed")),
+            Err(IteratorError::Slate(e)) => Err(map_error(e)),
+        }
+    }
+
+    /// Convert an entry to what `next_entry` returns for it.
+    fn to_entry(&self, ruby: &Ruby, kv: KeyValue) -> Result<Value, Error> {
+        let key = self
+            .shape
+            .to_ruby_key(ruby, &kv.key[self.namespace.len()..]);
+        let mut entry = vec![key.as_value()];
+        if !self.shape.keys_only {
+            entry.push(match self.codec {
+                Codec::Raw => self.shape.to_ruby_string(ruby, &kv.value).as_value(),
+                codec => codec.decode(&kv.value)?,
+            });
+        }
+        if self.shape.seq {
+            entry.push(ruby.into_value(kv.seq));
+        }
+
+        Ok(match entry.as_slice() {
+            [key] => *key,
+            entry => ruby.ary_new_from_values(entry).as_value(),
+        })
+    }
+
+    /// Get the next key-value pair.
+    ///
+    /// Returns [key, value] as an array, just the key for `keys_only`
+    /// iterators, or nil if iteration is complete. With `seq`, the entry's
+    /// sequence number follows: [key, value, seq], or [key, seq].
+    pub fn next_entry(&self) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
+        let Some(kv) = self.next_kv()? else {
+            return Ok(None);
         };
 
-        Ok(kv.map(|kv| {
-            (
-                String::from_utf8_lossy(&kv.key).to_string(),
-                String::from_utf8_lossy(&kv.value).to_string(),
-            )
-        }))
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        self.to_entry(&ruby, kv).map(Some)
+    }
+
+    /// Get up to `max` entries at once, reading them in a single trip into
+    /// the runtime.
+    ///
+    /// # Returns
+    /// Array of entries shaped as for `next_entry`, empty once iteration is
+    /// complete
+    pub fn next_batch(&self, max: usize) -> Result<RArray, Error> {
+        self.ensure_usable()?;
+        if max == 0 {
+            return Err(invalid_argument_error("batch_size must be at least 1"));
+        }
+        let kvs = self.next_kvs(max)?;
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let batch = ruby.ary_new_capa(kvs.len());
+        for kv in kvs {
+            batch.push(self.to_entry(&ruby, kv)?)?;
+        }
+        Ok(batch)
     }
 
     /// Get the next key-value pair as raw bytes.
     ///
     /// Returns [key, value] as byte arrays, or nil if iteration is complete.
     pub fn next_entry_bytes(&self) -> ByteKvResult {
-        let inner = self.inner.clone();
-
-        let result = block_on(async {
-            let mut guard = inner.lock().await;
-            match guard.as_mut() {
-                Some(iter) => iter.next().await.map_err(IteratorError::Slate),
-                None => Err(IteratorError::Closed),
-            }
-        });
-
-        let kv = match result {
-            Ok(kv) => kv,
-            Err(IteratorError::Closed) => return Err(internal_error("iterator has been closed")),
-            Err(IteratorError::Slate(e)) => return Err(map_error(e)),
-        };
-
-        Ok(kv.map(|kv| (kv.key.to_vec(), kv.value.to_vec())))
+        self.ensure_usable()?;
+        Ok(self
+            .next_kv()?
+            .map(|kv| (kv.key[self.namespace.len()..].to_vec(), kv.value.to_vec())))
     }
 
     /// Seek to a specific key position.
     ///
     /// After seeking, `next` will return entries starting from the given key.
     pub fn seek(&self, key: String) -> Result<(), Error> {
+        self.ensure_usable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let key = [self.namespace.as_slice(), key.as_bytes()].concat();
+        let seeked = key.clone();
 
         let inner = self.inner.clone();
+        let adaptive = self.adaptive.clone();
 
-        let result = block_on(async {
+        let result = self.runtime.block_on(async {
             let mut guard = inner.lock().await;
-            match guard.as_mut() {
-                Some(iter) => iter
-                    .seek(key.as_bytes())
-                    .await
-                    .map_err(IteratorError::Slate),
-                None => Err(IteratorError::Closed),
+            let iter = guard.as_mut().ok_or(IteratorError::Closed)?;
+            iter.seek(key.as_slice())
+                .await
+                .map_err(IteratorError::Slate)?;
+
+            if let Some(adaptive) = adaptive.lock().await.as_mut() {
+                adaptive.seeked(&key);
             }
-        });
+
+            Ok::<_, IteratorError>(())
+        })?;
 
         match result {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                let mut cursor = self.cursor.lock().expect("cursor mutex poisoned");
+                match cursor.as_mut() {
+                    Some(tracked) if !tracked.descending => {
+                        tracked.advance(Bound::Included(seeked))
+                    }
+                    // As with adaptive read-ahead, seek positions are only
+                    // tracked for ascending scans.
+                    _ => *cursor = None,
+                }
+                Ok(())
+            }
             Err(IteratorError::Closed) => Err(internal_error("iterator has been closed")),
             Err(IteratorError::Slate(e)) => Err(map_error(e)),
         }
     }
 
+    /// Describe the version of the data this iterator reads from.
+    ///
+    /// Returns a hash with `:manifest_id`, `:seq` and `:durable_seq`. Values
+    /// that are unknown for the iterator's source are nil.
+    pub fn view_info(&self) -> Result<RHash, Error> {
+        self.ensure_usable()?;
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("manifest_id"), self.view.manifest_id)?;
+        hash.aset(ruby.to_symbol("seq"), self.view.seq)?;
+        hash.aset(ruby.to_symbol("durable_seq"), self.view.durable_seq)?;
+        Ok(hash)
+    }
+
+    /// An opaque token for the rest of the scan, to be passed to
+    /// `Database#scan_from_cursor`.
+    ///
+    /// The token resumes just after the last returned entry, or at the last
+    /// seek if nothing has been returned since.
+    pub fn cursor(&self) -> Result<String, Error> {
+        self.ensure_usable()?;
+        self.cursor
+            .lock()
+            .expect("cursor mutex poisoned")
+            .as_ref()
+            .map(ScanCursor::encode)
+            .ok_or_else(|| {
+                invalid_argument_error(
+                    "cursor is only available for Database scans, and not after seeking a descending scan",
+                )
+            })
+    }
+
     /// Close the iterator and release resources.
+    ///
+    /// Does nothing in a forked child; the parent still owns the scan.
     pub fn close(&self) -> Result<(), Error> {
+        if is_forked(self.pid) {
+            return Ok(());
+        }
         let inner = self.inner.clone();
 
-        block_on(async {
+        let adaptive = self.adaptive.clone();
+
+        self.runtime.block_on(async {
             let mut guard = inner.lock().await;
             *guard = None;
-        });
+            *adaptive.lock().await = None;
+        })?;
 
         Ok(())
     }
@@ -133,7 +619,10 @@ pub fn define_iterator_class(ruby: &Ruby, module: &magnus::RModule) -> Result<()
     // Instance methods
     class.define_method("next_entry", method!(Iterator::next_entry, 0))?;
     class.define_method("next_entry_bytes", method!(Iterator::next_entry_bytes, 0))?;
+    class.define_method("_next_batch", method!(Iterator::next_batch, 1))?;
     class.define_method("seek", method!(Iterator::seek, 1))?;
+    class.define_method("view_info", method!(Iterator::view_info, 0))?;
+    class.define_method("cursor", method!(Iterator::cursor, 0))?;
     class.define_method("close", method!(Iterator::close, 0))?;
 
     Ok(())
@@ -0,0 +1,237 @@
+use magnus::prelude::*;
+use magnus::value::{Qfalse, Qnil, Qtrue};
+use magnus::{function, Error, Float, Integer, RArray, RModule, RString, Ruby, Time, Value};
+
+use crate::errors::invalid_argument_error;
+use crate::utils::rstring_to_bytes;
+
+/// Width of an encoded integer, float or timestamp: 64 bits as hex.
+const FIXED_WIDTH: usize = 16;
+
+/// Flipping the sign bit makes two's complement order match unsigned order.
+const SIGN_BIT: u64 = 1 << 63;
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+// Tuple element tags. Elements of different types sort by tag.
+const TAG_NIL: u8 = b'0';
+const TAG_FALSE: u8 = b'1';
+const TAG_TRUE: u8 = b'2';
+const TAG_FLOAT: u8 = b'f';
+const TAG_INT: u8 = b'i';
+const TAG_STRING: u8 = b's';
+const TAG_TIME: u8 = b't';
+
+// Strings in tuples end with NUL NUL, and a NUL inside a string is written as
+// NUL SOH, so a string sorts before any longer string it is a prefix of.
+const STRING_END: [u8; 2] = [0, 0];
+const ESCAPED_NUL: [u8; 2] = [0, 1];
+
+fn encode_u64(bits: u64) -> String {
+    format!("{:016x}", bits)
+}
+
+fn decode_u64(hex: &[u8], what: &str) -> Result<u64, Error> {
+    std::str::from_utf8(hex)
+        .ok()
+        .filter(|hex| hex.len() == FIXED_WIDTH)
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| invalid_argument_error(&format!("invalid encoded {}", what)))
+}
+
+fn int_bits(value: i64) -> u64 {
+    value as u64 ^ SIGN_BIT
+}
+
+fn int_from_bits(bits: u64) -> i64 {
+    (bits ^ SIGN_BIT) as i64
+}
+
+/// Negative floats have all bits flipped so larger magnitudes sort first;
+/// positive floats only have the sign bit set. -0.0 encodes as 0.0.
+fn float_bits(value: f64) -> u64 {
+    let bits = if value == 0.0 { 0 } else { value.to_bits() };
+    if bits & SIGN_BIT != 0 {
+        !bits
+    } else {
+        bits | SIGN_BIT
+    }
+}
+
+fn float_from_bits(bits: u64) -> f64 {
+    if bits & SIGN_BIT != 0 {
+        f64::from_bits(bits ^ SIGN_BIT)
+    } else {
+        f64::from_bits(!bits)
+    }
+}
+
+/// Nanoseconds since the Unix epoch, which covers the years 1677 to 2262.
+fn time_nanos(time: Time) -> Result<i64, Error> {
+    let ts = time.timespec()?;
+    ts.tv_sec
+        .checked_mul(NANOS_PER_SEC)
+        .and_then(|nanos| nanos.checked_add(ts.tv_nsec))
+        .ok_or_else(|| invalid_argument_error("time is outside the years 1677 to 2262"))
+}
+
+fn time_from_nanos(ruby: &Ruby, nanos: i64) -> Result<Time, Error> {
+    ruby.time_nano_new(
+        nanos.div_euclid(NANOS_PER_SEC),
+        nanos.rem_euclid(NANOS_PER_SEC),
+    )
+}
+
+fn encode_int(value: i64) -> String {
+    encode_u64(int_bits(value))
+}
+
+fn decode_int(encoded: RString) -> Result<i64, Error> {
+    Ok(int_from_bits(decode_u64(
+        &rstring_to_bytes(encoded),
+        "integer",
+    )?))
+}
+
+fn encode_float(value: f64) -> String {
+    encode_u64(float_bits(value))
+}
+
+fn decode_float(encoded: RString) -> Result<f64, Error> {
+    Ok(float_from_bits(decode_u64(
+        &rstring_to_bytes(encoded),
+        "float",
+    )?))
+}
+
+fn encode_time(time: Time) -> Result<String, Error> {
+    Ok(encode_int(time_nanos(time)?))
+}
+
+fn decode_time(ruby: &Ruby, encoded: RString) -> Result<Time, Error> {
+    time_from_nanos(ruby, decode_int(encoded)?)
+}
+
+/// Encode an Array of nil, true, false, Integer, Float, Time and String
+/// values. Tuples sort element by element, and the encoding of a tuple is a
+/// prefix of the encoding of any tuple that extends it.
+fn encode_tuple(values: RArray) -> Result<String, Error> {
+    let mut out = Vec::new();
+    for value in values.into_iter() {
+        encode_element(&mut out, value)?;
+    }
+    // Every element is valid UTF-8, and so is the separator between them.
+    Ok(String::from_utf8(out).expect("encoded tuple is UTF-8"))
+}
+
+fn encode_element(out: &mut Vec<u8>, value: Value) -> Result<(), Error> {
+    if Qnil::from_value(value).is_some() {
+        out.push(TAG_NIL);
+    } else if Qfalse::from_value(value).is_some() {
+        out.push(TAG_FALSE);
+    } else if Qtrue::from_value(value).is_some() {
+        out.push(TAG_TRUE);
+    } else if let Some(int) = Integer::from_value(value) {
+        out.push(TAG_INT);
+        out.extend_from_slice(encode_int(int.to_i64()?).as_bytes());
+    } else if let Some(float) = Float::from_value(value) {
+        out.push(TAG_FLOAT);
+        out.extend_from_slice(encode_float(float.to_f64()).as_bytes());
+    } else if let Some(time) = Time::from_value(value) {
+        out.push(TAG_TIME);
+        out.extend_from_slice(encode_time(time)?.as_bytes());
+    } else if let Some(string) = RString::from_value(value) {
+        let string = string.to_string()?;
+        out.push(TAG_STRING);
+        for byte in string.bytes() {
+            if byte == 0 {
+                out.extend_from_slice(&ESCAPED_NUL);
+            } else {
+                out.push(byte);
+            }
+        }
+        out.extend_from_slice(&STRING_END);
+    } else {
+        return Err(invalid_argument_error(&format!(
+            "cannot encode {} in a key tuple",
+            unsafe { value.classname() }
+        )));
+    }
+    Ok(())
+}
+
+/// Decode the fixed-width element at the start of `rest` and advance past it.
+fn take_fixed(rest: &mut &[u8], what: &str) -> Result<u64, Error> {
+    let slice: &[u8] = *rest;
+    if slice.len() < FIXED_WIDTH {
+        return Err(invalid_argument_error("invalid encoded tuple"));
+    }
+    let (hex, tail) = slice.split_at(FIXED_WIDTH);
+    *rest = tail;
+    decode_u64(hex, what)
+}
+
+fn decode_tuple(ruby: &Ruby, encoded: RString) -> Result<RArray, Error> {
+    let bytes = rstring_to_bytes(encoded);
+    let invalid = || invalid_argument_error("invalid encoded tuple");
+    let values = ruby.ary_new();
+    let mut rest = bytes.as_slice();
+
+    while let Some((&tag, tail)) = rest.split_first() {
+        rest = tail;
+        match tag {
+            TAG_NIL => values.push(ruby.qnil())?,
+            TAG_FALSE => values.push(false)?,
+            TAG_TRUE => values.push(true)?,
+            TAG_INT => values.push(int_from_bits(take_fixed(&mut rest, "integer")?))?,
+            TAG_FLOAT => values.push(float_from_bits(take_fixed(&mut rest, "float")?))?,
+            TAG_TIME => {
+                let nanos = int_from_bits(take_fixed(&mut rest, "time")?);
+                values.push(time_from_nanos(ruby, nanos)?)?
+            }
+            TAG_STRING => {
+                let mut string = Vec::new();
+                loop {
+                    match rest {
+                        [0, 0, tail @ ..] => {
+                            rest = tail;
+                            break;
+                        }
+                        [0, 1, tail @ ..] => {
+                            string.push(0);
+                            rest = tail;
+                        }
+                        [byte, tail @ ..] if *byte != 0 => {
+                            string.push(*byte);
+                            rest = tail;
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                let string = String::from_utf8(string).map_err(|_| invalid())?;
+                values.push(string)?
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(values)
+}
+
+/// Define the SlateDb::KeyEncoder module of order-preserving key encodings.
+///
+/// Encoded keys compare bytewise in the same order as the values they
+/// encode, so ranges and prefixes of them can be scanned. Every encoding is
+/// plain UTF-8, so encoded keys can be used wherever a key is accepted:
+/// integers, floats and timestamps are 16 hex digits.
+pub fn define_key_encoder_module(module: &RModule) -> Result<(), Error> {
+    let encoder = module.define_module("KeyEncoder")?;
+    encoder.define_module_function("encode_int", function!(encode_int, 1))?;
+    encoder.define_module_function("decode_int", function!(decode_int, 1))?;
+    encoder.define_module_function("encode_float", function!(encode_float, 1))?;
+    encoder.define_module_function("decode_float", function!(decode_float, 1))?;
+    encoder.define_module_function("encode_time", function!(encode_time, 1))?;
+    encoder.define_module_function("decode_time", function!(decode_time, 1))?;
+    encoder.define_module_function("encode_tuple", function!(encode_tuple, 1))?;
+    encoder.define_module_function("decode_tuple", function!(decode_tuple, 1))?;
+    Ok(())
+}
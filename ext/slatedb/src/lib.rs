@@ -17,23 +17,64 @@
 use magnus::{Error, Ruby};
 
 mod admin;
+mod arrow;
+mod backup;
+mod cdc;
+mod checkpoint_lease;
+mod clock;
+mod codec;
+mod consistency;
+mod credentials;
+mod cursor;
 mod database;
+mod diff;
+mod durability_handle;
+mod encryption;
 mod errors;
+mod expired;
+mod fault;
+#[cfg(unix)]
+mod fiber;
+mod future;
+mod health;
 mod iterator;
+mod key_encoder;
+mod limits;
+mod logging;
 mod merge_ops;
 mod metrics;
+mod overlay;
+mod progress;
+mod rate_limit;
+mod read_defaults;
 mod reader;
+mod recovery;
+mod retry;
 mod runtime;
+mod sample_stats;
+mod shutdown;
 mod snapshot;
+mod store_check;
+mod store_stats;
+mod telemetry;
+mod thread_mode;
 mod transaction;
 mod utils;
+mod value_buffer;
 mod write_batch;
+mod write_result;
+mod writer;
 
 /// Initialize the SlateDb Ruby module.
 ///
 /// This is called automatically when the native extension is loaded.
 #[magnus::init]
 fn init(ruby: &Ruby) -> Result<(), Error> {
+    // Every method is safe to call from any Ractor; only Reader and Snapshot
+    // handles can be shared between them. Methods that store a Ruby callback
+    // raise outside the main Ractor (see `runtime::ensure_main_ractor`).
+    unsafe { rb_sys::rb_ext_ractor_safe(true) };
+
     let module = ruby.define_module("SlateDb")?;
 
     // Define exception classes first
@@ -42,12 +83,28 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     // Define core classes
     database::define_database_class(ruby, &module)?;
     iterator::define_iterator_class(ruby, &module)?;
+    cdc::define_change_stream_class(ruby, &module)?;
+    cdc::define_watcher_class(ruby, &module)?;
     write_batch::define_write_batch_class(ruby, &module)?;
+    write_result::define_write_result_class(ruby, &module)?;
+    durability_handle::define_durability_handle_class(ruby, &module)?;
+    value_buffer::define_value_buffer_class(ruby, &module)?;
+    future::define_future_class(ruby, &module)?;
     transaction::define_transaction_class(ruby, &module)?;
     snapshot::define_snapshot_class(ruby, &module)?;
+    diff::define_snapshot_diff_class(ruby, &module)?;
     reader::define_reader_class(ruby, &module)?;
     admin::define_admin_class(ruby, &module)?;
+    checkpoint_lease::define_checkpoint_lease_class(ruby, &module)?;
+    clock::define_clock_class(ruby, &module)?;
     metrics::define_metrics_class(ruby, &module)?;
+    logging::define_logging_functions(&module)?;
+    credentials::define_credentials_functions(&module)?;
+    telemetry::define_tracing_functions(&module)?;
+    shutdown::define_shutdown_functions(&module)?;
+    store_check::define_store_check_functions(&module)?;
+    key_encoder::define_key_encoder_module(&module)?;
+    fault::define_testing_module(ruby, &module)?;
 
     Ok(())
 }
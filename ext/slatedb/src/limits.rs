@@ -0,0 +1,83 @@
+use magnus::{Error, RHash};
+
+use crate::errors::invalid_argument_error;
+use crate::utils::get_optional;
+
+/// Largest key SlateDB can store; key lengths are 16-bit.
+pub const MAX_KEY_BYTES: usize = u16::MAX as usize;
+
+/// Largest value SlateDB can store; value lengths are 32-bit.
+pub const MAX_VALUE_BYTES: usize = u32::MAX as usize;
+
+/// Key and value sizes a handle accepts, from the `max_key_bytes` and
+/// `max_value_bytes` open options.
+///
+/// Writes are checked before they reach the runtime: SlateDB asserts these
+/// limits deep inside a write or flush, where an oversized entry surfaces as
+/// an opaque internal error. Without the options the limits are SlateDB's.
+#[derive(Clone, Copy, Debug)]
+pub struct SizeLimits {
+    max_key_bytes: usize,
+    max_value_bytes: usize,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        Self {
+            max_key_bytes: MAX_KEY_BYTES,
+            max_value_bytes: MAX_VALUE_BYTES,
+        }
+    }
+}
+
+impl SizeLimits {
+    /// Parse the `max_key_bytes` and `max_value_bytes` open options.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        Ok(Self {
+            max_key_bytes: limit_from_kwargs(kwargs, "max_key_bytes", MAX_KEY_BYTES)?,
+            max_value_bytes: limit_from_kwargs(kwargs, "max_value_bytes", MAX_VALUE_BYTES)?,
+        })
+    }
+
+    /// The largest value accepted, in bytes.
+    pub fn max_value_bytes(&self) -> usize {
+        self.max_value_bytes
+    }
+
+    /// Raise InvalidArgumentError if `key` is too large.
+    pub fn check_key(&self, key: &[u8]) -> Result<(), Error> {
+        check("key", key.len(), "max_key_bytes", self.max_key_bytes)
+    }
+
+    /// Raise InvalidArgumentError if `key` or `value` is too large.
+    pub fn check(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.check_key(key)?;
+        check(
+            "value",
+            value.len(),
+            "max_value_bytes",
+            self.max_value_bytes,
+        )
+    }
+}
+
+fn check(what: &str, len: usize, option: &str, limit: usize) -> Result<(), Error> {
+    if len > limit {
+        return Err(invalid_argument_error(&format!(
+            "{} is {} bytes, exceeding {} of {}",
+            what, len, option, limit
+        )));
+    }
+    Ok(())
+}
+
+fn limit_from_kwargs(kwargs: &RHash, option: &str, max: usize) -> Result<usize, Error> {
+    match get_optional::<usize>(kwargs, option)? {
+        None => Ok(max),
+        Some(limit) if (1..=max).contains(&limit) => Ok(limit),
+        Some(limit) => Err(invalid_argument_error(&format!(
+            "{} must be between 1 and {}, got {}",
+            option, max, limit
+        ))),
+    }
+}
@@ -0,0 +1,169 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use magnus::prelude::*;
+use magnus::{function, Error, Ruby, Symbol};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::errors::{internal_error, invalid_argument_error};
+use crate::runtime::{block_on, ensure_main_ractor, is_forked};
+
+/// Number of log records queued before further records are dropped.
+const LOG_QUEUE_SIZE: usize = 1024;
+
+/// How long `_next_log_record` waits for a record before returning nil, so
+/// the Ruby thread draining the queue stays responsive to interrupts.
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Log targets forwarded to Ruby.
+const LOG_TARGETS: [&str; 2] = ["slatedb", "object_store"];
+
+static LOGGER_INSTALLED: AtomicBool = AtomicBool::new(false);
+static LOG_QUEUE: std::sync::Mutex<Option<LogQueue>> = std::sync::Mutex::new(None);
+static DROPPED_RECORDS: AtomicU64 = AtomicU64::new(0);
+
+/// The queue between the Rust logger and the Ruby thread draining it.
+///
+/// Each process gets its own queue: the receiver's lock is usually held by
+/// the parent's draining thread, which does not exist in a forked child.
+struct LogQueue {
+    pid: u32,
+    sender: mpsc::Sender<LogRecord>,
+    records: Arc<Mutex<mpsc::Receiver<LogRecord>>>,
+}
+
+impl LogQueue {
+    fn new() -> Self {
+        let (sender, records) = mpsc::channel(LOG_QUEUE_SIZE);
+        Self {
+            pid: std::process::id(),
+            sender,
+            records: Arc::new(Mutex::new(records)),
+        }
+    }
+}
+
+struct LogRecord {
+    level: Level,
+    target: String,
+    message: String,
+}
+
+/// Forwards SlateDB and object store log records to a bounded queue.
+///
+/// Records are logged from runtime threads that must not call Ruby, so they
+/// are queued and handed to the Ruby logger by a Ruby thread calling
+/// `_next_log_record`. SlateDB's `tracing` events arrive here through the
+/// `log` compatibility layer. Records are dropped while the queue is full.
+struct RubyLog;
+
+impl Log for RubyLog {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && LOG_TARGETS.iter().any(|t| metadata.target().starts_with(t))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let record = LogRecord {
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        let queue = LOG_QUEUE.lock().expect("log queue poisoned");
+        let sent = queue.as_ref().map(|q| q.sender.try_send(record).is_ok());
+        if sent == Some(false) {
+            DROPPED_RECORDS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the Ruby log forwarder as the process-wide Rust logger.
+///
+/// Safe to call more than once. Raises if another Rust logger was installed
+/// first.
+fn install_logger() -> Result<(), Error> {
+    ensure_main_ractor("logger")?;
+    if LOGGER_INSTALLED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    log::set_boxed_logger(Box::new(RubyLog))
+        .map_err(|e| internal_error(&format!("failed to install logger: {}", e)))?;
+    *LOG_QUEUE.lock().expect("log queue poisoned") = Some(LogQueue::new());
+    LOGGER_INSTALLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Set the most verbose level forwarded to Ruby.
+fn set_log_level(level: String) -> Result<(), Error> {
+    let filter = match level.as_str() {
+        "off" => LevelFilter::Off,
+        "error" => LevelFilter::Error,
+        "warn" => LevelFilter::Warn,
+        "info" => LevelFilter::Info,
+        "debug" => LevelFilter::Debug,
+        "trace" => LevelFilter::Trace,
+        other => {
+            return Err(invalid_argument_error(&format!(
+                "invalid log level: {} (expected off, error, warn, info, debug or trace)",
+                other
+            )))
+        }
+    };
+    log::set_max_level(filter);
+    Ok(())
+}
+
+/// Wait briefly for the next log record.
+///
+/// Returns `[level, target, message]`, or nil if no record arrived in time
+/// or no logger is installed.
+fn next_log_record() -> Result<Option<(Symbol, String, String)>, Error> {
+    let records = {
+        let mut queue = LOG_QUEUE.lock().expect("log queue poisoned");
+        let Some(queue) = queue.as_mut() else {
+            return Ok(None);
+        };
+        if is_forked(queue.pid) {
+            *queue = LogQueue::new();
+        }
+        queue.records.clone()
+    };
+    let record = block_on(async {
+        tokio::time::timeout(LOG_POLL_INTERVAL, async {
+            records.lock().await.recv().await
+        })
+        .await
+        .ok()
+        .flatten()
+    })?;
+    let Some(record) = record else {
+        return Ok(None);
+    };
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let level = record.level.as_str().to_lowercase();
+    Ok(Some((ruby.to_symbol(level), record.target, record.message)))
+}
+
+/// Number of log records dropped because the queue was full.
+fn dropped_log_records() -> u64 {
+    DROPPED_RECORDS.load(Ordering::Relaxed)
+}
+
+/// Define the logging functions on the SlateDb module.
+pub fn define_logging_functions(module: &magnus::RModule) -> Result<(), Error> {
+    module.define_module_function("_install_logger", function!(install_logger, 0))?;
+    module.define_module_function("_set_log_level", function!(set_log_level, 1))?;
+    module.define_module_function("_next_log_record", function!(next_log_record, 0))?;
+    module.define_module_function("dropped_log_records", function!(dropped_log_records, 0))?;
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::thread;
 
@@ -9,6 +10,7 @@ use magnus::{Error, RHash, Ruby, Value};
 use slatedb::{MergeOperator, MergeOperatorError};
 
 use crate::errors::invalid_argument_error;
+use crate::runtime::ensure_main_ractor;
 use crate::utils::get_optional;
 
 struct StringConcatMergeOperator;
@@ -39,6 +41,105 @@ impl MergeOperator for StringConcatMergeOperator {
     }
 }
 
+/// Adds operands to the existing value as 64-bit signed integers.
+///
+/// Values and operands are decimal strings (as written by `merge("n", "5")`
+/// or, with the JSON codec, `merge("n", 5)`). A missing value counts as
+/// zero, and sums wrap around on overflow.
+struct AddI64MergeOperator;
+
+impl AddI64MergeOperator {
+    fn parse(value: &Bytes) -> Result<i64, MergeOperatorError> {
+        std::str::from_utf8(value)
+            .ok()
+            .and_then(|s| s.trim().parse::<i64>().ok())
+            .ok_or_else(|| MergeOperatorError::Callback {
+                message: format!(
+                    "add_i64 operand is not a 64-bit integer: {:?}",
+                    String::from_utf8_lossy(value)
+                ),
+            })
+    }
+}
+
+impl MergeOperator for AddI64MergeOperator {
+    fn merge(
+        &self,
+        key: &Bytes,
+        existing_value: Option<Bytes>,
+        value: Bytes,
+    ) -> Result<Bytes, MergeOperatorError> {
+        self.merge_batch(key, existing_value, &[value])
+    }
+
+    fn merge_batch(
+        &self,
+        _key: &Bytes,
+        existing_value: Option<Bytes>,
+        operands: &[Bytes],
+    ) -> Result<Bytes, MergeOperatorError> {
+        let mut total = existing_value
+            .as_ref()
+            .map(Self::parse)
+            .transpose()?
+            .unwrap_or(0);
+        for operand in operands {
+            total = total.wrapping_add(Self::parse(operand)?);
+        }
+        Ok(Bytes::from(total.to_string()))
+    }
+}
+
+/// Unions operands into the existing value as sets.
+///
+/// Values and operands are JSON arrays (as written by `merge("tags", '["a"]')`
+/// or, with the JSON codec, `merge("tags", ["a"])`). Members keep the order
+/// they were first added in, and duplicates are dropped.
+struct SetUnionMergeOperator;
+
+impl SetUnionMergeOperator {
+    fn parse(value: &Bytes) -> Result<Vec<serde_json::Value>, MergeOperatorError> {
+        match serde_json::from_slice(value) {
+            Ok(serde_json::Value::Array(members)) => Ok(members),
+            _ => Err(MergeOperatorError::Callback {
+                message: format!(
+                    "set_union operand is not a JSON array: {:?}",
+                    String::from_utf8_lossy(value)
+                ),
+            }),
+        }
+    }
+}
+
+impl MergeOperator for SetUnionMergeOperator {
+    fn merge(
+        &self,
+        key: &Bytes,
+        existing_value: Option<Bytes>,
+        value: Bytes,
+    ) -> Result<Bytes, MergeOperatorError> {
+        self.merge_batch(key, existing_value, &[value])
+    }
+
+    fn merge_batch(
+        &self,
+        _key: &Bytes,
+        existing_value: Option<Bytes>,
+        operands: &[Bytes],
+    ) -> Result<Bytes, MergeOperatorError> {
+        let mut seen = HashSet::new();
+        let mut members = Vec::new();
+        for value in existing_value.iter().chain(operands) {
+            for member in Self::parse(value)? {
+                if seen.insert(member.to_string()) {
+                    members.push(member);
+                }
+            }
+        }
+        Ok(Bytes::from(serde_json::Value::Array(members).to_string()))
+    }
+}
+
 /// A merge operator that calls a Ruby block/proc.
 ///
 /// This stores the raw Ruby VALUE and calls it via `with_gvl` when merge
@@ -208,10 +309,12 @@ pub fn parse_merge_operator(
     };
 
     let operator: Arc<dyn MergeOperator + Send + Sync> = match merge_operator.as_str() {
-        "string_concat" | "concat" => Arc::new(StringConcatMergeOperator),
+        "string_concat" | "concat" | "append" => Arc::new(StringConcatMergeOperator),
+        "add_i64" => Arc::new(AddI64MergeOperator),
+        "set_union" => Arc::new(SetUnionMergeOperator),
         _ => {
             return Err(invalid_argument_error(&format!(
-                "invalid merge_operator: {} (expected 'string_concat', 'concat', or use merge_operator_proc for a custom block)",
+                "invalid merge_operator: {} (expected 'string_concat', 'append', 'add_i64', 'set_union', or use merge_operator_proc for a custom block)",
                 merge_operator
             )))
         }
@@ -229,6 +332,8 @@ pub fn parse_merge_operator_proc(
         return Ok(None);
     };
 
+    ensure_main_ractor("merge_operator")?;
+
     // Verify it's callable
     if !proc.respond_to("call", false).unwrap_or(false) {
         return Err(invalid_argument_error(
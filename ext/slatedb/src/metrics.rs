@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use magnus::prelude::*;
-use magnus::{method, Error, Ruby};
+use magnus::{method, Error, RHash, Ruby, Symbol, Value};
+use slatedb_common::metrics::{MetricValue, Metrics as MetricsSnapshot};
+
 /// Ruby wrapper for SlateDB metrics registry.
 ///
 /// This struct is exposed to Ruby as `SlateDb::Metrics`.
@@ -36,6 +39,209 @@ impl Metrics {
     }
 }
 
+/// Convert a snapshot of SlateDB's internal metrics to a nested Ruby hash.
+///
+/// Metric names are split on dots with the leading `slatedb` dropped, so
+/// `slatedb.db.l0_sst_count` becomes `stats[:db][:l0_sst_count]`. Labelled
+/// metrics become a hash keyed by their labels, e.g.
+/// `stats[:db][:request_count]["op=get"]`. Histograms become a hash of
+/// `:count`, `:sum`, `:min` and `:max`.
+pub fn stats_to_hash(snapshot: &MetricsSnapshot) -> Result<RHash, Error> {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let stats = ruby.hash_new();
+
+    for metric in snapshot.all() {
+        let name = metric.name.strip_prefix("slatedb.").unwrap_or(&metric.name);
+        let mut node = stats;
+        let mut segments = name.split('.').peekable();
+        while let Some(segment) = segments.next() {
+            let key = ruby.to_symbol(segment);
+            if segments.peek().is_none() && metric.labels.is_empty() {
+                node.aset(key, metric_value(&ruby, &metric.value)?)?;
+                break;
+            }
+            node = child_hash(&ruby, node, key)?;
+        }
+
+        if !metric.labels.is_empty() {
+            let mut labels = metric.labels.clone();
+            labels.sort();
+            let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{k}={v}")).collect();
+            node.aset(labels.join(","), metric_value(&ruby, &metric.value)?)?;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// The total of the counters named `name`, across all their labels.
+pub fn counter_total(snapshot: &MetricsSnapshot, name: &str) -> u64 {
+    snapshot
+        .by_name(name)
+        .iter()
+        .map(|metric| match metric.value {
+            MetricValue::Counter(count) => count,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The value of the gauge named `name`, or 0 if it has not been recorded.
+pub fn gauge_value(snapshot: &MetricsSnapshot, name: &str) -> i64 {
+    snapshot
+        .by_name(name)
+        .iter()
+        .map(|metric| match metric.value {
+            MetricValue::Gauge(value) => value,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Get the hash stored under `key` in `node`, adding an empty one if needed.
+fn child_hash(ruby: &Ruby, node: RHash, key: Symbol) -> Result<RHash, Error> {
+    if let Some(child) = node.get(key).and_then(RHash::from_value) {
+        return Ok(child);
+    }
+    let child = ruby.hash_new();
+    node.aset(key, child)?;
+    Ok(child)
+}
+
+fn metric_value(ruby: &Ruby, value: &MetricValue) -> Result<Value, Error> {
+    Ok(match value {
+        MetricValue::Counter(count) => ruby.into_value(*count),
+        MetricValue::Gauge(value) | MetricValue::UpDownCounter(value) => ruby.into_value(*value),
+        MetricValue::Histogram {
+            count,
+            sum,
+            min,
+            max,
+            ..
+        } => {
+            let hash = ruby.hash_new();
+            hash.aset(ruby.to_symbol("count"), *count)?;
+            hash.aset(ruby.to_symbol("sum"), *sum)?;
+            // Empty histograms report infinite bounds.
+            let (min, max) = if *count == 0 {
+                (None, None)
+            } else {
+                (Some(*min), Some(*max))
+            };
+            hash.aset(ruby.to_symbol("min"), min)?;
+            hash.aset(ruby.to_symbol("max"), max)?;
+            hash.as_value()
+        }
+    })
+}
+
+/// Number of recent samples per operation used to estimate percentiles.
+const LATENCY_SAMPLES: usize = 1024;
+
+/// Latency samples for one kind of operation.
+#[derive(Default)]
+struct OpLatency {
+    count: u64,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+impl OpLatency {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        if self.recent.len() == LATENCY_SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(elapsed);
+    }
+
+    /// Summarise as `count` plus `avg`, `p50`, `p95`, `p99` and `max` in
+    /// milliseconds. Percentiles cover the most recent samples only.
+    fn to_hash(&self, ruby: &Ruby) -> Result<RHash, Error> {
+        let mut sorted: Vec<Duration> = self.recent.iter().copied().collect();
+        sorted.sort();
+        let percentile = |p: f64| {
+            let rank = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len());
+            millis(sorted[rank - 1])
+        };
+
+        let avg = millis(self.total) / self.count as f64;
+
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("count"), self.count)?;
+        hash.aset(ruby.to_symbol("avg"), avg)?;
+        hash.aset(ruby.to_symbol("p50"), percentile(0.50))?;
+        hash.aset(ruby.to_symbol("p95"), percentile(0.95))?;
+        hash.aset(ruby.to_symbol("p99"), percentile(0.99))?;
+        hash.aset(ruby.to_symbol("max"), millis(self.max))?;
+        Ok(hash)
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Per-operation latency recorded by the bindings.
+///
+/// Counts, averages and maxima cover every operation since the stats were
+/// last reset; percentiles are estimated from the most recent
+/// `LATENCY_SAMPLES` operations of each kind.
+#[derive(Default)]
+pub struct LatencyStats {
+    ops: Mutex<BTreeMap<&'static str, OpLatency>>,
+}
+
+impl LatencyStats {
+    /// Start timing an operation. The latency is recorded when the returned
+    /// timer is dropped, whether or not the operation succeeded.
+    pub fn start(&self, op: &'static str) -> LatencyTimer<'_> {
+        LatencyTimer {
+            stats: self,
+            op,
+            started: Instant::now(),
+        }
+    }
+
+    fn record(&self, op: &'static str, elapsed: Duration) {
+        let mut ops = self.ops.lock().expect("latency mutex poisoned");
+        ops.entry(op).or_default().record(elapsed);
+    }
+
+    /// Return a hash of operation name to latency summary, for operations
+    /// that have run since the stats were last reset.
+    pub fn to_hash(&self) -> Result<RHash, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let ops = self.ops.lock().expect("latency mutex poisoned");
+        let hash = ruby.hash_new();
+        for (op, latency) in ops.iter() {
+            hash.aset(ruby.to_symbol(op), latency.to_hash(&ruby)?)?;
+        }
+        Ok(hash)
+    }
+
+    /// Discard all recorded latencies.
+    pub fn reset(&self) {
+        self.ops.lock().expect("latency mutex poisoned").clear();
+    }
+}
+
+/// Records the latency of an operation when dropped.
+pub struct LatencyTimer<'a> {
+    stats: &'a LatencyStats,
+    op: &'static str,
+    started: Instant,
+}
+
+impl Drop for LatencyTimer<'_> {
+    fn drop(&mut self) {
+        self.stats.record(self.op, self.started.elapsed());
+    }
+}
+
 /// Define the Metrics class on the SlateDb module.
 pub fn define_metrics_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
     let class = module.define_class("Metrics", ruby.class_object())?;
@@ -0,0 +1,140 @@
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use slatedb::object_store::memory::InMemory;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{
+    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, ObjectStoreExt, PutMode, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, RenameOptions, Result,
+};
+
+/// An object store that reads through to `base` but keeps every write in
+/// memory, so nothing is ever written to or deleted from `base`.
+///
+/// `verify_backup` opens its reader over one of these: a reader without a
+/// checkpoint id registers a checkpoint by writing a new manifest, which
+/// then lands in memory instead of in the backup being checked. Reads of
+/// an object written through the overlay see the written copy.
+#[derive(Debug)]
+pub struct OverlayStore {
+    base: Arc<dyn ObjectStore>,
+    overlay: InMemory,
+}
+
+impl OverlayStore {
+    pub fn new(base: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            base,
+            overlay: InMemory::new(),
+        }
+    }
+}
+
+impl fmt::Display for OverlayStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OverlayStore({})", self.base)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for OverlayStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        // Manifests are created with PutMode::Create, which must still fail
+        // if the base store already holds that id.
+        if matches!(opts.mode, PutMode::Create) {
+            match self.base.head(location).await {
+                Ok(_) => {
+                    return Err(ObjectStoreError::AlreadyExists {
+                        path: location.to_string(),
+                        source: "object exists in the base store".into(),
+                    })
+                }
+                Err(ObjectStoreError::NotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.overlay.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.overlay.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        match self.overlay.get_opts(location, options.clone()).await {
+            Err(ObjectStoreError::NotFound { .. }) => self.base.get_opts(location, options).await,
+            result => result,
+        }
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        match self.overlay.get_ranges(location, ranges).await {
+            Err(ObjectStoreError::NotFound { .. }) => self.base.get_ranges(location, ranges).await,
+            result => result,
+        }
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, Result<Path>>,
+    ) -> BoxStream<'static, Result<Path>> {
+        self.overlay.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.overlay
+            .list(prefix)
+            .chain(self.base.list(prefix))
+            .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.overlay
+            .list_with_offset(prefix, offset)
+            .chain(self.base.list_with_offset(prefix, offset))
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut result = self.base.list_with_delimiter(prefix).await?;
+        let overlay = self.overlay.list_with_delimiter(prefix).await?;
+        result.objects.extend(overlay.objects);
+        for common in overlay.common_prefixes {
+            if !result.common_prefixes.contains(&common) {
+                result.common_prefixes.push(common);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, _options: CopyOptions) -> Result<()> {
+        let bytes = self.get(from).await?.bytes().await?;
+        self.overlay.put(to, bytes.into()).await?;
+        Ok(())
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, _options: RenameOptions) -> Result<()> {
+        self.copy_opts(from, to, CopyOptions::default()).await?;
+        // Only the overlay's copy can be removed; one in the base stays.
+        self.overlay.delete(from).await
+    }
+}
@@ -0,0 +1,67 @@
+use magnus::block::Proc;
+use magnus::{Error, Ruby};
+
+/// Reports the progress of a long-running operation to an optional Ruby
+/// block.
+///
+/// Operations do their work in steps with the GVL released and call
+/// `report` between steps, with the GVL held, so the block can update a
+/// progress bar or log. An exception raised by the block stops the
+/// operation.
+pub struct Progress {
+    block: Option<Proc>,
+}
+
+impl Progress {
+    pub fn new(block: Option<Proc>) -> Self {
+        Self { block }
+    }
+
+    /// Call the block with a Hash of `counts` and, if known, `percent`
+    /// complete (0.0 to 100.0).
+    pub fn report(&self, counts: &[(&str, u64)], percent: Option<f64>) -> Result<(), Error> {
+        self.report_phase(None, counts, percent)
+    }
+
+    /// Like `report`, for operations that work in named phases, adding the
+    /// current `phase` to the Hash as a Symbol.
+    pub fn report_phase(
+        &self,
+        phase: Option<&str>,
+        counts: &[(&str, u64)],
+        percent: Option<f64>,
+    ) -> Result<(), Error> {
+        let Some(block) = self.block else {
+            return Ok(());
+        };
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        if let Some(phase) = phase {
+            hash.aset(ruby.to_symbol("phase"), ruby.to_symbol(phase))?;
+        }
+        for (name, count) in counts {
+            hash.aset(ruby.to_symbol(name), *count)?;
+        }
+        if let Some(percent) = percent {
+            hash.aset(ruby.to_symbol("percent"), percent.clamp(0.0, 100.0))?;
+        }
+        block.call::<_, magnus::Value>((hash,))?;
+        Ok(())
+    }
+
+    /// Whether a block was given.
+    pub fn is_enabled(&self) -> bool {
+        self.block.is_some()
+    }
+}
+
+/// Percent of `total` that `done` represents, or 100.0 if there is nothing
+/// to do.
+pub fn percent(done: u64, total: u64) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        done as f64 * 100.0 / total as f64
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt};
+use magnus::{Error, RHash};
+use slatedb::object_store::limit::LimitStore;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions, Result, UploadPart,
+};
+use tokio::time::Instant;
+
+use crate::errors::invalid_argument_error;
+use crate::utils::get_optional;
+
+/// Request limits for a database's object store, from the
+/// `max_requests_per_second` and `max_concurrent_requests` open options.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RateLimit {
+    max_requests_per_second: Option<f64>,
+    max_concurrent_requests: Option<usize>,
+}
+
+impl RateLimit {
+    /// Parse the `max_requests_per_second` and `max_concurrent_requests`
+    /// open options.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let max_requests_per_second = get_optional::<f64>(kwargs, "max_requests_per_second")?;
+        if let Some(rate) = max_requests_per_second {
+            if !(rate.is_finite() && rate > 0.0) {
+                return Err(invalid_argument_error(&format!(
+                    "max_requests_per_second must be positive, got {}",
+                    rate
+                )));
+            }
+        }
+        let max_concurrent_requests = get_optional::<usize>(kwargs, "max_concurrent_requests")?;
+        if max_concurrent_requests == Some(0) {
+            return Err(invalid_argument_error(
+                "max_concurrent_requests must be at least 1",
+            ));
+        }
+        Ok(Self {
+            max_requests_per_second,
+            max_concurrent_requests,
+        })
+    }
+
+    /// Wrap `store` so its requests respect the limits. Without limits the
+    /// store is returned as is.
+    pub fn wrap(&self, store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        let store = match self.max_requests_per_second {
+            Some(rate) => Arc::new(RateLimitedStore::new(store, rate)),
+            None => store,
+        };
+        match self.max_concurrent_requests {
+            // The concurrency limit is outermost, so a request waiting for
+            // its turn under the rate limit holds one of the slots.
+            Some(max) => Arc::new(LimitStore::new(store, max)),
+            None => store,
+        }
+    }
+}
+
+/// Hands out evenly spaced start times, one per request.
+#[derive(Debug)]
+struct Pacer {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl Pacer {
+    fn new(rate: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / rate),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait for the next free slot. Slots are reserved in call order, so
+    /// a burst of requests is spread out rather than sent at once.
+    async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().expect("rate limiter poisoned");
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// An object store that starts at most `max_requests_per_second` requests
+/// per second against the store it wraps.
+///
+/// Every call counts as one request, as does each part of a multipart
+/// upload. Listings count once, however many pages they fetch.
+#[derive(Debug)]
+pub struct RateLimitedStore {
+    inner: Arc<dyn ObjectStore>,
+    rate: f64,
+    pacer: Arc<Pacer>,
+}
+
+impl RateLimitedStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, rate: f64) -> Self {
+        Self {
+            inner,
+            rate,
+            pacer: Arc::new(Pacer::new(rate)),
+        }
+    }
+}
+
+impl fmt::Display for RateLimitedStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RateLimitedStore({}/s, {})", self.rate, self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for RateLimitedStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.pacer.wait().await;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.pacer.wait().await;
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(RateLimitedUpload {
+            upload,
+            pacer: self.pacer.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.pacer.wait().await;
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        self.pacer.wait().await;
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, Result<Path>>,
+    ) -> BoxStream<'static, Result<Path>> {
+        let inner = self.inner.clone();
+        let pacer = self.pacer.clone();
+        async move {
+            pacer.wait().await;
+            inner.delete_stream(locations)
+        }
+        .into_stream()
+        .flatten()
+        .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let inner = self.inner.clone();
+        let pacer = self.pacer.clone();
+        let prefix = prefix.cloned();
+        async move {
+            pacer.wait().await;
+            inner.list(prefix.as_ref())
+        }
+        .into_stream()
+        .flatten()
+        .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let inner = self.inner.clone();
+        let pacer = self.pacer.clone();
+        let prefix = prefix.cloned();
+        let offset = offset.clone();
+        async move {
+            pacer.wait().await;
+            inner.list_with_offset(prefix.as_ref(), &offset)
+        }
+        .into_stream()
+        .flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.pacer.wait().await;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, options: CopyOptions) -> Result<()> {
+        self.pacer.wait().await;
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, options: RenameOptions) -> Result<()> {
+        self.pacer.wait().await;
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+/// A multipart upload whose parts, completion and abort each wait their turn.
+#[derive(Debug)]
+struct RateLimitedUpload {
+    upload: Box<dyn MultipartUpload>,
+    pacer: Arc<Pacer>,
+}
+
+#[async_trait]
+impl MultipartUpload for RateLimitedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let pacer = self.pacer.clone();
+        // Cloud stores send the part when the future is first polled, so it
+        // waits for its slot; creating it here keeps the part numbers in order.
+        let part = self.upload.put_part(data);
+        async move {
+            pacer.wait().await;
+            part.await
+        }
+        .boxed()
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        self.pacer.wait().await;
+        self.upload.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.pacer.wait().await;
+        self.upload.abort().await
+    }
+}
@@ -0,0 +1,52 @@
+use magnus::{Error, RHash};
+use slatedb::config::{ReadOptions, ScanOptions};
+
+use crate::utils::{get_optional, get_positive};
+
+/// Read tuning a handle applies to every get and scan, from the
+/// `read_ahead_bytes`, `cache_blocks` and `max_fetch_tasks` open options.
+///
+/// Options passed to an individual call override these. Without the options
+/// SlateDB's defaults apply.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReadDefaults {
+    read_ahead_bytes: Option<usize>,
+    cache_blocks: Option<bool>,
+    max_fetch_tasks: Option<usize>,
+}
+
+impl ReadDefaults {
+    /// Parse the `read_ahead_bytes`, `cache_blocks` and `max_fetch_tasks`
+    /// open options.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        Ok(Self {
+            read_ahead_bytes: get_positive(kwargs, "read_ahead_bytes")?,
+            cache_blocks: get_optional(kwargs, "cache_blocks")?,
+            max_fetch_tasks: get_positive(kwargs, "max_fetch_tasks")?,
+        })
+    }
+
+    /// Options for a get that passes no options of its own.
+    pub fn read_options(&self) -> ReadOptions {
+        let mut opts = ReadOptions::default();
+        if let Some(cache_blocks) = self.cache_blocks {
+            opts.cache_blocks = cache_blocks;
+        }
+        opts
+    }
+
+    /// Options for a scan that passes no options of its own.
+    pub fn scan_options(&self) -> ScanOptions {
+        let mut opts = ScanOptions::default();
+        if let Some(read_ahead_bytes) = self.read_ahead_bytes {
+            opts.read_ahead_bytes = read_ahead_bytes;
+        }
+        if let Some(cache_blocks) = self.cache_blocks {
+            opts.cache_blocks = cache_blocks;
+        }
+        if let Some(max_fetch_tasks) = self.max_fetch_tasks {
+            opts.max_fetch_tasks = max_fetch_tasks;
+        }
+        opts
+    }
+}
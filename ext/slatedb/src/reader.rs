@@ -1,34 +1,78 @@
+use std::future::Future;
 use std::sync::Arc;
 
 use magnus::prelude::*;
-use magnus::{function, method, Error, RHash, Ruby};
+use magnus::{function, method, Error, RHash, Ruby, Value};
+use slatedb::admin::AdminBuilder;
 use slatedb::config::{DbReaderOptions, DurabilityLevel, ReadOptions, ScanOptions};
+use slatedb::object_store::ObjectStore;
+use slatedb::BlockTransformer;
 use slatedb::DbReader;
+use slatedb::Error as SlateError;
 use slatedb::IterationOrder;
+use slatedb_common::metrics::DefaultMetricsRecorder;
 
+use crate::clock::test_clock;
+use crate::codec::Codec;
+use crate::consistency::MinSeq;
+use crate::encryption::AesGcmTransformer;
 use crate::errors::invalid_argument_error;
-use crate::iterator::Iterator;
-use crate::runtime::block_on_result;
-use crate::utils::{get_optional, prefix_subrange_from_kwargs, resolve_object_store};
+use crate::fault;
+use crate::health;
+use crate::iterator::{Iterator, ViewInfo};
+use crate::metrics::stats_to_hash;
+use crate::runtime::{block_on_result, ensure_same_process, is_forked};
+use crate::shutdown::{ensure_running, register_reader};
+use crate::utils::{get_optional, prefix_subrange_from_kwargs, resolve_object_store, StoreOptions};
 
 /// Ruby wrapper for SlateDB Reader.
 ///
 /// This struct is exposed to Ruby as `SlateDb::Reader`.
 /// Provides read-only access to a database, optionally pinned to a checkpoint.
-#[magnus::wrap(class = "SlateDb::Reader", free_immediately, size)]
+/// Readers are frozen and shareable, so Ractors can read from one
+/// concurrently.
+#[magnus::wrap(class = "SlateDb::Reader", free_immediately, size, frozen_shareable)]
 pub struct Reader {
     inner: Arc<DbReader>,
+    path: String,
+    object_store: Arc<dyn ObjectStore>,
+    recorder: Arc<DefaultMetricsRecorder>,
+    /// The process that opened the reader; see `ensure_same_process`.
+    pid: u32,
+    /// How values are decoded; see the `codec` open option.
+    codec: Codec,
 }
 
 impl Reader {
+    /// Run a reader operation, raising if the reader was inherited across
+    /// fork.
+    fn block_on<F, T>(&self, future: F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, SlateError>>,
+    {
+        ensure_same_process(self.pid)?;
+        block_on_result(future)
+    }
+
+    /// Wait for the write named by the `min_seq` read option, if given, to
+    /// be visible.
+    fn await_min_seq(&self, kwargs: &RHash) -> Result<(), Error> {
+        ensure_same_process(self.pid)?;
+        match MinSeq::from_kwargs(kwargs)? {
+            Some(min_seq) => min_seq.wait(&*self.inner),
+            None => Ok(()),
+        }
+    }
+
     /// Open a reader at the given path.
     ///
     /// # Arguments
     /// * `path` - The path identifier for the database
     /// * `url` - Optional object store URL
     /// * `checkpoint_id` - Optional checkpoint UUID to read at
-    /// * `kwargs` - Additional options (manifest_poll_interval, checkpoint_lifetime,
-    ///   max_memtable_bytes, skip_wal_replay, cache_root, max_open_file_handles).
+    /// * `kwargs` - Additional options (as_of, manifest_poll_interval, checkpoint_lifetime,
+    ///   max_memtable_bytes, skip_wal_replay, cache_root, max_open_file_handles,
+    ///   codec, encryption, store_options).
     ///   The local disk cache (and therefore `max_open_file_handles`) is only active
     ///   when `cache_root` is set.
     pub fn open(
@@ -37,6 +81,8 @@ impl Reader {
         checkpoint_id: Option<String>,
         kwargs: RHash,
     ) -> Result<Self, Error> {
+        ensure_running()?;
+
         // Parse options
         let manifest_poll_interval = get_optional::<u64>(&kwargs, "manifest_poll_interval")?
             .map(std::time::Duration::from_millis);
@@ -46,6 +92,15 @@ impl Reader {
         let skip_wal_replay = get_optional::<bool>(&kwargs, "skip_wal_replay")?;
         let max_open_file_handles = get_optional::<usize>(&kwargs, "max_open_file_handles")?;
         let cache_root = get_optional::<String>(&kwargs, "cache_root")?;
+        let store_options = StoreOptions::from_kwargs(&kwargs)?;
+        let codec = Codec::from_kwargs(&kwargs)?;
+        let encryption = AesGcmTransformer::from_kwargs(&kwargs)?;
+        let as_of = get_optional::<f64>(&kwargs, "as_of")?;
+        if as_of.is_some() && checkpoint_id.is_some() {
+            return Err(invalid_argument_error(
+                "checkpoint_id and as_of cannot be combined",
+            ));
+        }
 
         // Parse checkpoint_id as UUID
         let checkpoint_uuid =
@@ -57,13 +112,20 @@ impl Reader {
                 None
             };
 
-        let reader = block_on_result(async {
+        let recorder = Arc::new(DefaultMetricsRecorder::new());
+
+        let (reader, object_store) = block_on_result(async {
             let object_store: Arc<dyn slatedb::object_store::ObjectStore> =
                 if let Some(ref url) = url {
-                    resolve_object_store(url)?
+                    resolve_object_store(url, &store_options)?
                 } else {
                     Arc::new(slatedb::object_store::memory::InMemory::new())
                 };
+            let object_store = fault::wrap(object_store);
+            let checkpoint_uuid = match as_of {
+                Some(as_of) => Some(checkpoint_as_of(&path, &object_store, as_of).await?),
+                None => checkpoint_uuid,
+            };
 
             let mut options = DbReaderOptions::default();
             if let Some(interval) = manifest_poll_interval {
@@ -85,26 +147,100 @@ impl Reader {
             if let Some(max_handles) = max_open_file_handles {
                 options.object_store_cache_options.max_open_file_handles = max_handles;
             }
-            DbReader::open(path, object_store, checkpoint_uuid, options).await
+            let mut builder = DbReader::builder(path.as_str(), object_store.clone())
+                .with_options(options)
+                .with_metrics_recorder(recorder.clone());
+            if let Some(checkpoint_id) = checkpoint_uuid {
+                builder = builder.with_checkpoint_id(checkpoint_id);
+            }
+            if let Some(encryption) = encryption {
+                builder = builder.with_block_transformer(encryption);
+            }
+            if let Some(clock) = test_clock() {
+                builder = builder.with_system_clock(clock);
+            }
+            Ok((builder.build().await?, object_store))
         })?;
 
-        Ok(Self {
-            inner: Arc::new(reader),
-        })
+        Ok(Self::from_reader(
+            reader,
+            &path,
+            object_store,
+            recorder,
+            codec,
+        ))
+    }
+
+    /// Open a reader on a database's store at the newest checkpoint created
+    /// at or before `as_of`, for `Database#snapshot_at`.
+    ///
+    /// # Arguments
+    /// * `as_of` - Wall-clock time in seconds since the epoch
+    pub(crate) fn open_as_of(
+        path: &str,
+        object_store: Arc<dyn ObjectStore>,
+        as_of: f64,
+        codec: Codec,
+        encryption: Option<Arc<dyn BlockTransformer>>,
+    ) -> Result<Self, Error> {
+        ensure_running()?;
+
+        let recorder = Arc::new(DefaultMetricsRecorder::new());
+        let reader = block_on_result(async {
+            let checkpoint_id = checkpoint_as_of(path, &object_store, as_of).await?;
+            let mut builder = DbReader::builder(path, object_store.clone())
+                .with_checkpoint_id(checkpoint_id)
+                .with_metrics_recorder(recorder.clone());
+            if let Some(encryption) = encryption {
+                builder = builder.with_block_transformer(encryption);
+            }
+            if let Some(clock) = test_clock() {
+                builder = builder.with_system_clock(clock);
+            }
+            builder.build().await
+        })?;
+
+        Ok(Self::from_reader(
+            reader,
+            path,
+            object_store,
+            recorder,
+            codec,
+        ))
+    }
+
+    fn from_reader(
+        reader: DbReader,
+        path: &str,
+        object_store: Arc<dyn ObjectStore>,
+        recorder: Arc<DefaultMetricsRecorder>,
+        codec: Codec,
+    ) -> Self {
+        let inner = Arc::new(reader);
+        register_reader(&inner);
+
+        Self {
+            inner,
+            path: path.to_string(),
+            object_store,
+            recorder,
+            pid: std::process::id(),
+            codec,
+        }
     }
 
     /// Get a value by key.
-    pub fn get(&self, key: String) -> Result<Option<String>, Error> {
+    pub fn get(&self, key: String) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let result = block_on_result(async { self.inner.get(key.as_bytes()).await })?;
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        let result = self.block_on(async { self.inner.get(key.as_bytes()).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
     }
 
     /// Get a value by key with options.
-    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<String>, Error> {
+    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
@@ -132,9 +268,10 @@ impl Reader {
             opts.cache_blocks = cb;
         }
 
+        self.await_min_seq(&kwargs)?;
         let result =
-            block_on_result(async { self.inner.get_with_options(key.as_bytes(), &opts).await })?;
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+            self.block_on(async { self.inner.get_with_options(key.as_bytes(), &opts).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
     }
 
     /// Get a value by key as raw bytes.
@@ -143,7 +280,7 @@ impl Reader {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let result = block_on_result(async { self.inner.get(key.as_bytes()).await })?;
+        let result = self.block_on(async { self.inner.get(key.as_bytes()).await })?;
         Ok(result.map(|b| b.to_vec()))
     }
 
@@ -156,14 +293,15 @@ impl Reader {
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
+        let view = ViewInfo::capture(&*self.inner);
+        let iter = self.block_on(async {
             match end_bytes {
                 Some(end) => self.inner.scan(start_bytes..end).await,
                 None => self.inner.scan(start_bytes..).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, view).with_codec(self.codec))
     }
 
     /// Scan a range of keys with options.
@@ -220,17 +358,19 @@ impl Reader {
             };
         }
 
+        self.await_min_seq(&kwargs)?;
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
+        let view = ViewInfo::capture(&*self.inner);
+        let iter = self.block_on(async {
             match end_bytes {
                 Some(end) => self.inner.scan_with_options(start_bytes..end, &opts).await,
                 None => self.inner.scan_with_options(start_bytes.., &opts).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, view).with_codec(self.codec))
     }
 
     /// Scan all keys with a given prefix.
@@ -239,10 +379,11 @@ impl Reader {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
 
+        let view = ViewInfo::capture(&*self.inner);
         let iter =
-            block_on_result(async { self.inner.scan_prefix(prefix.as_bytes(), ..).await })?;
+            self.block_on(async { self.inner.scan_prefix(prefix.as_bytes(), ..).await })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, view).with_codec(self.codec))
     }
 
     /// Scan all keys with a given prefix with options.
@@ -299,22 +440,77 @@ impl Reader {
         }
 
         let subrange = prefix_subrange_from_kwargs(&kwargs)?;
-        let iter = block_on_result(async {
+        self.await_min_seq(&kwargs)?;
+        let view = ViewInfo::capture(&*self.inner);
+        let iter = self.block_on(async {
             self.inner
                 .scan_prefix_with_options(prefix.as_bytes(), subrange, &opts)
                 .await
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, view).with_codec(self.codec))
+    }
+
+    /// Return SlateDB's internal metrics for this reader as a nested hash.
+    pub fn stats(&self) -> Result<RHash, Error> {
+        stats_to_hash(&self.recorder.snapshot())
+    }
+
+    /// Probe the object store behind the reader, for readiness checks.
+    ///
+    /// # Arguments
+    /// * `kwargs` - Options (timeout_ms)
+    ///
+    /// # Returns
+    /// Hash with healthy, latency, manifest_id, latest_manifest_id,
+    /// manifests_behind, last_manifest_write, staleness and error
+    pub fn health(&self, kwargs: RHash) -> Result<RHash, Error> {
+        ensure_same_process(self.pid)?;
+        let status = self.inner.status();
+        health::check(
+            &self.object_store,
+            &self.path,
+            status.current_manifest.id(),
+            status.close_reason.is_some(),
+            &kwargs,
+        )
     }
 
     /// Close the reader.
     pub fn close(&self) -> Result<(), Error> {
-        block_on_result(async { self.inner.close().await })?;
+        // The parent process still owns a reader inherited across fork.
+        if is_forked(self.pid) {
+            return Ok(());
+        }
+        self.block_on(async { self.inner.close().await })?;
         Ok(())
     }
 }
 
+/// Find the newest checkpoint created at or before `as_of` (seconds since
+/// the epoch), for reading a database as it was at that time.
+async fn checkpoint_as_of(
+    path: &str,
+    object_store: &Arc<dyn ObjectStore>,
+    as_of: f64,
+) -> Result<uuid::Uuid, SlateError> {
+    let as_of_ms = (as_of * 1000.0).floor() as i64;
+    let admin = AdminBuilder::new(path, object_store.clone()).build();
+    admin
+        .list_checkpoints(None)
+        .await?
+        .into_iter()
+        .filter(|cp| cp.create_time.timestamp_millis() <= as_of_ms)
+        .max_by_key(|cp| cp.create_time)
+        .map(|cp| cp.id)
+        .ok_or_else(|| {
+            SlateError::invalid(format!(
+                "no checkpoint was created at or before {} ms since the epoch",
+                as_of_ms
+            ))
+        })
+}
+
 /// Define the Reader class on the SlateDb module.
 pub fn define_reader_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
     let class = module.define_class("Reader", ruby.class_object())?;
@@ -333,6 +529,8 @@ pub fn define_reader_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(),
         "_scan_prefix_with_options",
         method!(Reader::scan_prefix_with_options, 2),
     )?;
+    class.define_method("stats", method!(Reader::stats, 0))?;
+    class.define_method("_health", method!(Reader::health, 1))?;
     class.define_method("close", method!(Reader::close, 0))?;
 
     Ok(())
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use magnus::{Error, RHash, Ruby};
+use slatedb::admin::AdminBuilder;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{ObjectStore, ObjectStoreExt};
+use slatedb::{Error as SlateError, ErrorKind, WalFile, WalReader};
+
+use crate::errors::invalid_argument_error;
+use crate::utils::{get_optional, to_slate_error};
+
+/// How a database treats a WAL SST it can't read when it opens; the
+/// `wal_recovery` open option.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RecoveryMode {
+    /// Fail to open, leaving the WAL as it is.
+    #[default]
+    Strict,
+    /// Delete the unreadable SST and every later one, then open without
+    /// the writes they held.
+    Tolerant,
+}
+
+impl RecoveryMode {
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        match get_optional::<String>(kwargs, "wal_recovery")?.as_deref() {
+            None | Some("strict") => Ok(Self::Strict),
+            Some("tolerant") => Ok(Self::Tolerant),
+            Some(other) => Err(invalid_argument_error(&format!(
+                "invalid wal_recovery: {} (expected :strict or :tolerant)",
+                other
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Tolerant => "tolerant",
+        }
+    }
+}
+
+/// The WAL a database will replay when it opens, read from its latest
+/// manifest beforehand.
+pub struct PendingReplay {
+    /// WAL SSTs up to this id are already flushed to L0.
+    replay_after_wal_id: u64,
+    /// The latest write flushed to L0.
+    flushed_seq: u64,
+    /// The epoch of the writer the database is opened over.
+    writer_epoch: u64,
+    wal_ssts: usize,
+    new_database: bool,
+}
+
+impl PendingReplay {
+    pub async fn read(path: &str, object_store: &Arc<dyn ObjectStore>) -> Result<Self, SlateError> {
+        let admin = AdminBuilder::new(path, object_store.clone()).build();
+        let Some(manifest) = admin.read_manifest(None).await? else {
+            return Ok(Self {
+                replay_after_wal_id: 0,
+                flushed_seq: 0,
+                writer_epoch: 0,
+                wal_ssts: 0,
+                new_database: true,
+            });
+        };
+        let replay_after_wal_id = manifest.replay_after_wal_id();
+        let wal_ssts = WalReader::new(path, object_store.clone())
+            .list((replay_after_wal_id + 1)..)
+            .await?
+            .len();
+        Ok(Self {
+            replay_after_wal_id,
+            flushed_seq: manifest.last_l0_seq(),
+            writer_epoch: manifest.writer_epoch(),
+            wal_ssts,
+            new_database: false,
+        })
+    }
+
+    /// Delete the first WAL SST to be replayed that can't be read, and every
+    /// SST after it, so the database opens without them.
+    ///
+    /// A writer that is still running may have acknowledged writes in those
+    /// SSTs, so nothing is deleted unless the failed open has fenced it by
+    /// claiming a newer writer epoch in the manifest.
+    ///
+    /// # Returns
+    /// The ids of the deleted SSTs, empty if every SST could be read
+    pub async fn truncate_corrupt_tail(
+        &self,
+        path: &str,
+        object_store: &Arc<dyn ObjectStore>,
+    ) -> Result<Vec<u64>, SlateError> {
+        let admin = AdminBuilder::new(path, object_store.clone()).build();
+        let epoch = admin
+            .read_manifest(None)
+            .await?
+            .map_or(0, |manifest| manifest.writer_epoch());
+        if !self.new_database && epoch <= self.writer_epoch {
+            return Err(SlateError::invalid(format!(
+                "not truncating the WAL: the writer with epoch {} has not been fenced",
+                self.writer_epoch
+            )));
+        }
+
+        let files = WalReader::new(path, object_store.clone())
+            .list((self.replay_after_wal_id + 1)..)
+            .await?;
+        let mut first_corrupt = None;
+        for (i, file) in files.iter().enumerate() {
+            if is_corrupt(file).await? {
+                first_corrupt = Some(i);
+                break;
+            }
+        }
+        let Some(first_corrupt) = first_corrupt else {
+            return Ok(Vec::new());
+        };
+
+        let mut deleted = Vec::new();
+        for file in &files[first_corrupt..] {
+            let location = Path::from(path)
+                .join("wal")
+                .join(format!("{:020}.sst", file.id));
+            object_store
+                .delete(&location)
+                .await
+                .map_err(to_slate_error)?;
+            deleted.push(file.id);
+        }
+        Ok(deleted)
+    }
+
+    /// What replaying this WAL recovered, once the database has opened with
+    /// `durable_seq` as its latest durable write.
+    pub fn report(
+        self,
+        mode: RecoveryMode,
+        durable_seq: u64,
+        truncated_wal_ssts: Vec<u64>,
+        duration: Duration,
+    ) -> RecoveryReport {
+        RecoveryReport {
+            mode,
+            new_database: self.new_database,
+            wal_ssts_replayed: self.wal_ssts.saturating_sub(truncated_wal_ssts.len()),
+            writes_replayed: durable_seq.saturating_sub(self.flushed_seq),
+            truncated_wal_ssts,
+            duration,
+        }
+    }
+}
+
+/// Whether reading every entry of `file` fails with a data error. Other
+/// errors, such as an unavailable object store, are returned.
+async fn is_corrupt(file: &WalFile) -> Result<bool, SlateError> {
+    let read = async {
+        let mut entries = file.iterator().await?;
+        while entries.next().await?.is_some() {}
+        Ok::<_, SlateError>(())
+    };
+    match read.await {
+        Ok(()) => Ok(false),
+        Err(e) if matches!(e.kind(), ErrorKind::Data) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+/// What a database recovered from its WAL when it opened.
+pub struct RecoveryReport {
+    mode: RecoveryMode,
+    new_database: bool,
+    wal_ssts_replayed: usize,
+    writes_replayed: u64,
+    truncated_wal_ssts: Vec<u64>,
+    duration: Duration,
+}
+
+impl RecoveryReport {
+    pub fn to_hash(&self) -> Result<RHash, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("mode"), ruby.to_symbol(self.mode.name()))?;
+        hash.aset(ruby.to_symbol("new_database"), self.new_database)?;
+        hash.aset(ruby.to_symbol("wal_ssts_replayed"), self.wal_ssts_replayed)?;
+        hash.aset(ruby.to_symbol("writes_replayed"), self.writes_replayed)?;
+        hash.aset(
+            ruby.to_symbol("truncated_wal_ssts"),
+            ruby.ary_from_vec(self.truncated_wal_ssts.clone()),
+        )?;
+        hash.aset(ruby.to_symbol("duration"), self.duration.as_secs_f64())?;
+        Ok(hash)
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use magnus::{Error, RHash};
+use slatedb::{Error as SlateError, ErrorKind};
+
+use crate::errors::{invalid_argument_error, is_throttled};
+use crate::utils::get_optional;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 50;
+const DEFAULT_MAX_DELAY_MS: u64 = 2_000;
+
+/// How a database retries operations that fail because object storage is
+/// unavailable or throttling requests.
+///
+/// Retries happen on the runtime, inside a single blocking call, so Ruby
+/// sees either the eventual result or the last error.
+#[derive(Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    unavailable: bool,
+    throttled: bool,
+}
+
+impl RetryPolicy {
+    /// Parse the `retry` open option, a Hash of max_attempts, base_delay_ms,
+    /// max_delay_ms, jitter and on (the error codes to retry).
+    ///
+    /// Returns `None` when the option was not given.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Option<Self>, Error> {
+        let Some(config) = get_optional::<RHash>(kwargs, "retry")? else {
+            return Ok(None);
+        };
+
+        let max_attempts = get_optional::<u32>(&config, "max_attempts")?;
+        let max_attempts = max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS);
+        if max_attempts == 0 {
+            return Err(invalid_argument_error(
+                "retry max_attempts must be at least 1",
+            ));
+        }
+        let base_delay_ms = get_optional::<u64>(&config, "base_delay_ms")?;
+        let max_delay_ms = get_optional::<u64>(&config, "max_delay_ms")?;
+
+        let mut policy = Self {
+            max_attempts,
+            base_delay: Duration::from_millis(base_delay_ms.unwrap_or(DEFAULT_BASE_DELAY_MS)),
+            max_delay: Duration::from_millis(max_delay_ms.unwrap_or(DEFAULT_MAX_DELAY_MS)),
+            jitter: get_optional::<bool>(&config, "jitter")?.unwrap_or(true),
+            unavailable: true,
+            throttled: true,
+        };
+
+        if let Some(codes) = get_optional::<Vec<String>>(&config, "on")? {
+            policy.unavailable = false;
+            policy.throttled = false;
+            for code in codes {
+                match code.as_str() {
+                    "unavailable" => policy.unavailable = true,
+                    "throttled" => policy.throttled = true,
+                    other => {
+                        return Err(invalid_argument_error(&format!(
+                            "invalid retry code: {} (expected :unavailable or :throttled)",
+                            other
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(Some(policy))
+    }
+
+    fn should_retry(&self, err: &SlateError) -> bool {
+        match err.kind() {
+            ErrorKind::Unavailable if is_throttled(&err.to_string()) => self.throttled,
+            ErrorKind::Unavailable => self.unavailable,
+            _ => false,
+        }
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        backoff(self.base_delay, self.max_delay, attempt, self.jitter)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retry unavailable and throttled errors with the `retry` option's
+    /// defaults.
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            jitter: true,
+            unavailable: true,
+            throttled: true,
+        }
+    }
+}
+
+/// Exponential backoff from `base` before retry number `attempt` (starting
+/// at 1), capped at `max`. With jitter the delay is scaled by a random factor
+/// between 0.5 and 1.
+pub fn backoff(base: Duration, max: Duration, attempt: u32, jitter: bool) -> Duration {
+    let delay = base.saturating_mul(1 << (attempt - 1).min(16)).min(max);
+    if !jitter {
+        return delay;
+    }
+
+    let random = RandomState::new().build_hasher().finish();
+    delay.mul_f64(0.5 + (random as f64 / u64::MAX as f64) / 2.0)
+}
+
+/// Run the operation built by `op`, retrying it as `policy` allows. Without
+/// a policy it runs once.
+pub async fn run<F, Fut, T>(policy: Option<&RetryPolicy>, mut op: F) -> Result<T, SlateError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SlateError>>,
+{
+    let Some(policy) = policy else {
+        return op().await;
+    };
+
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if attempt < policy.max_attempts && policy.should_retry(&e) => {
+                log::debug!(
+                    "retrying after attempt {} of {}: {}",
+                    attempt,
+                    policy.max_attempts,
+                    e
+                );
+                tokio::time::sleep(policy.delay(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -1,27 +1,110 @@
-use magnus::Error;
-use once_cell::sync::OnceCell;
+use futures::future::{select, Either};
+use futures::FutureExt;
+use magnus::prelude::*;
+use magnus::{Error, ExceptionClass, RClass, RHash, Ruby, Value};
 use rb_sys::{rb_thread_call_with_gvl, rb_thread_call_without_gvl};
 use slatedb::Error as SlateError;
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
 
-use crate::errors::map_error;
+use crate::errors::{forked_process_error, internal_error, invalid_argument_error, map_error};
+#[cfg(unix)]
+use crate::fiber;
+use crate::utils::{get_optional, get_positive};
 
-static RUNTIME: OnceCell<Runtime> = OnceCell::new();
+thread_local! {
+    /// Whether this thread has released the GVL in `without_gvl`, so that
+    /// `with_gvl` knows whether it has to take it back.
+    static GVL_RELEASED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The shared runtime, once built.
+static RUNTIME: AtomicPtr<Runtime> = AtomicPtr::new(std::ptr::null_mut());
+
+/// The id of the process that built `RUNTIME`, or 0 before it is built.
+static RUNTIME_PID: AtomicU32 = AtomicU32::new(0);
+
+/// Held while building the runtime, so concurrent first calls build it once.
+static RUNTIME_INIT: Mutex<()> = Mutex::new(());
 
 /// Get or initialize the shared Tokio runtime for all SlateDB operations.
 ///
 /// We use a multi-threaded runtime to support concurrent access from multiple
 /// Ruby threads. This is important for use with Sidekiq, Puma, and other
 /// multi-threaded Ruby applications.
+///
+/// A forked child inherits the parent's runtime but none of its worker
+/// threads, so the first call in a new process builds a fresh runtime. The
+/// inherited one cannot be shut down without those threads and is leaked.
+/// Only that rebuild takes a lock; every other call is a pid comparison.
 fn get_runtime() -> &'static Runtime {
-    RUNTIME.get_or_init(|| {
-        tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .expect("Failed to create Tokio runtime")
-    })
+    let pid = std::process::id();
+    if RUNTIME_PID.load(Ordering::Acquire) == pid {
+        // SAFETY: RUNTIME is set to a leaked runtime before RUNTIME_PID is
+        // published, and is never freed.
+        return unsafe { &*RUNTIME.load(Ordering::Acquire) };
+    }
+
+    let _init = RUNTIME_INIT.lock().expect("runtime mutex poisoned");
+    if RUNTIME_PID.load(Ordering::Acquire) != pid {
+        let rt = Box::leak(Box::new(
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create Tokio runtime"),
+        ));
+        RUNTIME.store(rt, Ordering::Release);
+        RUNTIME_PID.store(pid, Ordering::Release);
+    }
+    // SAFETY: as above; RUNTIME now holds this process's runtime.
+    unsafe { &*RUNTIME.load(Ordering::Acquire) }
+}
+
+/// Whether a handle created in process `pid` is being used in a forked child.
+pub fn is_forked(pid: u32) -> bool {
+    pid != std::process::id()
+}
+
+/// Raise ForkedProcessError if a handle created in process `pid` is used
+/// after `fork`.
+///
+/// A handle's background tasks run on the runtime of the process that opened
+/// it, and those threads do not exist in a forked child, so operations on it
+/// would hang.
+pub fn ensure_same_process(pid: u32) -> Result<(), Error> {
+    if is_forked(pid) {
+        return Err(forked_process_error(&format!(
+            "handle was opened in process {} and cannot be used after fork; open it again in this process",
+            pid
+        )));
+    }
+    Ok(())
+}
+
+/// Raise `Ractor::UnsafeError` unless called from the main Ractor.
+///
+/// The extension is Ractor-safe, but the callbacks it stores (merge operator
+/// procs, overload hooks, the logger and credentials providers) may later be
+/// called on whichever thread needs them, so `what` can only be registered
+/// from the main Ractor.
+pub fn ensure_main_ractor(what: &str) -> Result<(), Error> {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let ractor: RClass = ruby.class_object().const_get("Ractor")?;
+    let current: Value = ractor.funcall("current", ())?;
+    if current.equal(ractor.funcall::<_, _, Value>("main", ())?)? {
+        return Ok(());
+    }
+    Err(Error::new(
+        ractor.const_get::<_, ExceptionClass>("UnsafeError")?,
+        format!("{} can only be set from the main Ractor", what),
+    ))
 }
 
 /// Execute a future on the runtime, releasing the Ruby GVL while waiting.
@@ -39,12 +122,86 @@ fn get_runtime() -> &'static Runtime {
 ///
 /// For futures that return `Result<T, slatedb::Error>`, use [`block_on_result`]
 /// which handles error conversion automatically.
-pub fn block_on<F, T>(future: F) -> T
+///
+/// # Interrupts
+///
+/// The wait can be interrupted by `Thread#kill`, `Thread#raise` (and so
+/// `Timeout.timeout`) and signals such as SIGINT. Ruby runs the interrupt
+/// with the GVL re-acquired; if it raises, the future is dropped, cancelling
+/// the operation, and the exception is returned. Otherwise the wait resumes.
+/// A cancelled write may or may not have been applied.
+///
+/// # Fast path
+///
+/// The future is polled once before the GVL is released. Reads served from
+/// the memtable or block cache are usually ready on that first poll, and
+/// return without handing the GVL to another thread and waiting to take it
+/// back, which would otherwise dominate their cost.
+///
+/// # Fibers
+///
+/// Called from a non-blocking fiber under a fiber scheduler (such as the
+/// `async` gem's), the fiber yields to the scheduler while it waits instead
+/// of blocking the thread, so other fibers keep running.
+pub fn block_on<F, T>(future: F) -> Result<T, Error>
 where
     F: Future<Output = T>,
 {
-    let rt = get_runtime();
-    without_gvl(|| rt.block_on(future))
+    block_on_in(get_runtime(), future)
+}
+
+/// [`block_on`], on the runtime `rt`.
+fn block_on_in<F, T>(rt: &Runtime, future: F) -> Result<T, Error>
+where
+    F: Future<Output = T>,
+{
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let mut future = pin!(future);
+
+    #[cfg(unix)]
+    if let Some(scheduler) = fiber::current_scheduler() {
+        return fiber::block_on(&ruby, rt, scheduler, future);
+    }
+
+    let ready = {
+        let _runtime = rt.enter();
+        future.as_mut().now_or_never()
+    };
+    if let Some(result) = ready {
+        return Ok(result);
+    }
+
+    loop {
+        let interrupt = Notify::new();
+        let result = without_gvl(
+            || {
+                rt.block_on(async {
+                    match select(future.as_mut(), pin!(interrupt.notified())).await {
+                        Either::Left((result, _)) => Some(result),
+                        Either::Right(_) => None,
+                    }
+                })
+            },
+            || interrupt.notify_one(),
+        );
+        if let Some(result) = result {
+            return Ok(result);
+        }
+        ruby.thread_check_ints()?;
+    }
+}
+
+/// Run a future in the background on the runtime.
+///
+/// The future runs on a runtime thread, not a Ruby thread, so it must never
+/// call Ruby APIs (including [`with_gvl`]). Hand results back to Ruby through
+/// a channel read from a Ruby thread instead.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    get_runtime().spawn(future)
 }
 
 /// Execute a future returning `Result<T, slatedb::Error>`, converting errors to Ruby.
@@ -55,16 +212,122 @@ pub fn block_on_result<F, T>(future: F) -> Result<T, Error>
 where
     F: Future<Output = Result<T, SlateError>>,
 {
-    block_on(future).map_err(map_error)
+    block_on(future)?.map_err(map_error)
+}
+
+/// A runtime owned by one database; see the `dedicated_runtime` open option.
+struct DedicatedRuntime {
+    runtime: Option<Runtime>,
+    /// The process that built the runtime.
+    pid: u32,
+}
+
+impl Drop for DedicatedRuntime {
+    fn drop(&mut self) {
+        let Some(runtime) = self.runtime.take() else {
+            return;
+        };
+        if is_forked(self.pid) {
+            // As with the shared runtime, its threads don't exist here.
+            std::mem::forget(runtime);
+        } else {
+            // Without waiting for tasks still running, so freeing a
+            // database never blocks the thread running the GC.
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// The runtime a handle runs its operations on: the shared runtime, or the
+/// dedicated runtime of a database opened with `dedicated_runtime: true`.
+///
+/// Snapshots, transactions, iterators and futures taken from a database
+/// carry a clone, so their work stays on its runtime. A dedicated runtime
+/// stops once its last clone is dropped.
+#[derive(Clone, Default)]
+pub struct RuntimeRef(Option<Arc<DedicatedRuntime>>);
+
+impl RuntimeRef {
+    /// Parse the `dedicated_runtime` and `worker_threads` options, building
+    /// a dedicated runtime if asked for one.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let dedicated = get_optional::<bool>(kwargs, "dedicated_runtime")?.unwrap_or(false);
+        let worker_threads = get_positive(kwargs, "worker_threads")?;
+        if !dedicated {
+            if worker_threads.is_some() {
+                return Err(invalid_argument_error(
+                    "worker_threads requires dedicated_runtime: true",
+                ));
+            }
+            return Ok(Self::default());
+        }
+
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all().thread_name("slatedb-dedicated");
+        if let Some(threads) = worker_threads {
+            builder.worker_threads(threads);
+        }
+        let runtime = builder
+            .build()
+            .map_err(|e| internal_error(&format!("failed to create runtime: {}", e)))?;
+        Ok(Self(Some(Arc::new(DedicatedRuntime {
+            runtime: Some(runtime),
+            pid: std::process::id(),
+        }))))
+    }
+
+    /// Whether this is a database's own runtime rather than the shared one.
+    pub fn is_dedicated(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn get(&self) -> &Runtime {
+        match &self.0 {
+            Some(dedicated) => dedicated
+                .runtime
+                .as_ref()
+                .expect("dedicated runtime already shut down"),
+            None => get_runtime(),
+        }
+    }
+
+    /// [`block_on`], on this runtime.
+    pub fn block_on<F, T>(&self, future: F) -> Result<T, Error>
+    where
+        F: Future<Output = T>,
+    {
+        block_on_in(self.get(), future)
+    }
+
+    /// [`block_on_result`], on this runtime.
+    pub fn block_on_result<F, T>(&self, future: F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, SlateError>>,
+    {
+        self.block_on(future)?.map_err(map_error)
+    }
+
+    /// [`spawn`], on this runtime.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.get().spawn(future)
+    }
 }
 
 /// Execute a closure without holding the Ruby GVL.
 ///
 /// This releases the Global VM Lock, allowing other Ruby threads to run
 /// while this closure executes. Essential for I/O-bound operations.
-fn without_gvl<F, T>(f: F) -> T
+///
+/// `unblock` is Ruby's unblocking function: it is called from another thread
+/// when this thread is interrupted, and must make `f` return promptly.
+fn without_gvl<F, T, U>(f: F, unblock: U) -> T
 where
     F: FnOnce() -> T,
+    U: Fn() + Sync,
 {
     struct Closure<F, T> {
         f: Option<F>,
@@ -77,11 +340,19 @@ where
     {
         let closure = unsafe { &mut *(data as *mut Closure<F, T>) };
         if let Some(f) = closure.f.take() {
-            closure.result = Some(f());
+            closure.result = Some(with_gvl_released(true, f));
         }
         std::ptr::null_mut()
     }
 
+    extern "C" fn call_unblock<U>(data: *mut c_void)
+    where
+        U: Fn(),
+    {
+        let unblock = unsafe { &*(data as *const U) };
+        unblock();
+    }
+
     let mut closure = Closure {
         f: Some(f),
         result: None,
@@ -91,8 +362,8 @@ where
         rb_thread_call_without_gvl(
             Some(call_closure::<F, T>),
             &mut closure as *mut _ as *mut c_void,
-            None,
-            std::ptr::null_mut(),
+            Some(call_unblock::<U>),
+            &unblock as *const U as *mut c_void,
         );
     }
 
@@ -105,12 +376,15 @@ where
 /// into Ruby from code that has previously released the GVL (e.g., inside
 /// a future being executed by `block_on`).
 ///
+/// Runs `f` directly on a thread that still holds the GVL, as when a future
+/// calls back into Ruby during `block_on`'s first poll or under a fiber
+/// scheduler.
+///
 /// # Safety
 ///
-/// This function can ONLY be called from a Ruby thread that has previously
-/// released the GVL via `without_gvl` or `rb_thread_call_without_gvl`.
-/// Calling it from a non-Ruby thread (like a spawned Tokio task) will cause
-/// a Ruby fatal error.
+/// This function can ONLY be called from a Ruby thread. Calling it from a
+/// non-Ruby thread (like a spawned Tokio task) will cause a Ruby fatal
+/// error.
 ///
 /// # Panics
 ///
@@ -119,6 +393,10 @@ pub fn with_gvl<F, T>(f: F) -> T
 where
     F: FnOnce() -> T,
 {
+    if !GVL_RELEASED.get() {
+        return f();
+    }
+
     struct Closure<F, T> {
         f: Option<F>,
         result: Option<T>,
@@ -130,7 +408,7 @@ where
     {
         let closure = unsafe { &mut *(data as *mut Closure<F, T>) };
         if let Some(f) = closure.f.take() {
-            closure.result = Some(f());
+            closure.result = Some(with_gvl_released(false, f));
         }
         std::ptr::null_mut()
     }
@@ -149,3 +427,11 @@ where
 
     closure.result.expect("closure did not run")
 }
+
+/// Run `f` with `GVL_RELEASED` set to `released`, restoring it afterwards.
+fn with_gvl_released<T>(released: bool, f: impl FnOnce() -> T) -> T {
+    let previous = GVL_RELEASED.replace(released);
+    let result = f();
+    GVL_RELEASED.set(previous);
+    result
+}
@@ -0,0 +1,339 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::time::Instant;
+
+use magnus::{Error, RArray, RHash, Ruby};
+use slatedb::config::DbReaderOptions;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::ObjectStore;
+use slatedb::DbReader;
+
+use crate::errors::invalid_argument_error;
+use crate::runtime::block_on_result;
+use crate::utils::{get_optional, get_positive};
+
+/// Entries read per `block_on`, so interrupts are checked between chunks.
+const SCAN_CHUNK_KEYS: usize = 10_000;
+
+/// Fraction of keys sampled when `sample_rate` is not given.
+const DEFAULT_SAMPLE_RATE: f64 = 0.01;
+
+/// Prefixes reported when `top` is not given.
+const DEFAULT_TOP_PREFIXES: usize = 20;
+
+/// Sizes of the sampled keys or values.
+#[derive(Default)]
+struct SizeSample(Vec<usize>);
+
+impl SizeSample {
+    /// Summarize the sample: count, min, max, mean, percentiles and a
+    /// histogram of sizes rounded up to a power of two.
+    fn to_hash(&mut self, ruby: &Ruby) -> Result<RHash, Error> {
+        self.0.sort_unstable();
+        let sizes = &self.0;
+        let percentile = |p: f64| -> Option<usize> {
+            let rank = ((sizes.len() as f64) * p).ceil() as usize;
+            sizes.get(rank.saturating_sub(1)).copied()
+        };
+
+        let mut histogram: Vec<(usize, u64)> = Vec::new();
+        for &size in sizes {
+            let bucket = if size == 0 {
+                0
+            } else {
+                size.next_power_of_two()
+            };
+            match histogram.last_mut() {
+                Some((last, count)) if *last == bucket => *count += 1,
+                _ => histogram.push((bucket, 1)),
+            }
+        }
+        let buckets = ruby.hash_new();
+        for (bucket, count) in histogram {
+            buckets.aset(bucket, count)?;
+        }
+
+        let hash = ruby.hash_new();
+        let total: usize = sizes.iter().sum();
+        hash.aset(ruby.to_symbol("count"), sizes.len())?;
+        hash.aset(ruby.to_symbol("min"), sizes.first().copied())?;
+        hash.aset(ruby.to_symbol("max"), sizes.last().copied())?;
+        hash.aset(
+            ruby.to_symbol("mean"),
+            (!sizes.is_empty()).then(|| total as f64 / sizes.len() as f64),
+        )?;
+        hash.aset(ruby.to_symbol("p50"), percentile(0.5))?;
+        hash.aset(ruby.to_symbol("p90"), percentile(0.9))?;
+        hash.aset(ruby.to_symbol("p99"), percentile(0.99))?;
+        hash.aset(ruby.to_symbol("histogram"), buckets)?;
+        Ok(hash)
+    }
+}
+
+/// Sampled keys and bytes under one prefix.
+#[derive(Default)]
+struct PrefixSample {
+    keys: u64,
+    bytes: u64,
+}
+
+/// What a scan of the database collected.
+#[derive(Default)]
+struct Sample {
+    scanned: u64,
+    keys: SizeSample,
+    values: SizeSample,
+    prefixes: HashMap<Vec<u8>, PrefixSample>,
+}
+
+/// Options for `sample_stats`.
+struct SampleOptions {
+    rate: f64,
+    delimiter: Option<u8>,
+    top: usize,
+}
+
+impl SampleOptions {
+    fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let rate = sample_rate_from_kwargs(kwargs, DEFAULT_SAMPLE_RATE)?;
+        let delimiter = match get_optional::<String>(kwargs, "prefix_delimiter")? {
+            None => Some(b':'),
+            Some(d) if d.is_empty() => None,
+            Some(d) if d.len() == 1 => Some(d.as_bytes()[0]),
+            Some(d) => {
+                return Err(invalid_argument_error(&format!(
+                    "prefix_delimiter must be a single byte, got {:?}",
+                    d
+                )))
+            }
+        };
+        let top = get_positive(kwargs, "top")?.unwrap_or(DEFAULT_TOP_PREFIXES);
+        Ok(Self {
+            rate,
+            delimiter,
+            top,
+        })
+    }
+
+    fn sampled(&self, key: &[u8]) -> bool {
+        sampled(self.rate, key)
+    }
+
+    /// The prefix `key` is counted under: everything up to and including the
+    /// first delimiter, or the whole key if it has none.
+    fn prefix<'a>(&self, key: &'a [u8]) -> &'a [u8] {
+        self.delimiter
+            .and_then(|d| key.iter().position(|&b| b == d))
+            .map_or(key, |i| &key[..=i])
+    }
+}
+
+/// Parse the `sample_rate` option, a fraction in (0, 1].
+fn sample_rate_from_kwargs(kwargs: &RHash, default: f64) -> Result<f64, Error> {
+    let rate = get_optional::<f64>(kwargs, "sample_rate")?.unwrap_or(default);
+    if !(rate > 0.0 && rate <= 1.0) {
+        return Err(invalid_argument_error(
+            "sample_rate must be greater than 0 and at most 1",
+        ));
+    }
+    Ok(rate)
+}
+
+/// Whether `key` is in a `rate` sample. Sampling hashes the key rather than
+/// drawing at random, so repeated runs sample the same keys and their
+/// reports can be compared.
+fn sampled(rate: f64, key: &[u8]) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    let mut hasher = DefaultHasher::new();
+    hasher.write(key);
+    (hasher.finish() as f64) < rate * u64::MAX as f64
+}
+
+/// Sample the sizes of the database's keys and values.
+///
+/// Reads every entry with a read-only reader, and records the key and value
+/// sizes and the prefix of a deterministic `sample_rate` fraction of them.
+///
+/// # Returns
+/// Hash with scanned_entries, sampled_entries, sample_rate, duration, keys,
+/// values and prefixes
+pub fn sample_stats(
+    path: &Path,
+    object_store: &Arc<dyn ObjectStore>,
+    kwargs: RHash,
+) -> Result<RHash, Error> {
+    let options = SampleOptions::from_kwargs(&kwargs)?;
+    let started = Instant::now();
+    let reader = block_on_result(DbReader::open(
+        path.clone(),
+        object_store.clone(),
+        None,
+        DbReaderOptions::default(),
+    ))?;
+    let scanned = scan(&reader, &options);
+    let closed = block_on_result(reader.close());
+    let mut sample = scanned?;
+    closed?;
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let hash = ruby.hash_new();
+    hash.aset(ruby.to_symbol("scanned_entries"), sample.scanned)?;
+    hash.aset(ruby.to_symbol("sampled_entries"), sample.keys.0.len())?;
+    hash.aset(ruby.to_symbol("sample_rate"), options.rate)?;
+    hash.aset(ruby.to_symbol("duration"), started.elapsed().as_secs_f64())?;
+    hash.aset(ruby.to_symbol("keys"), sample.keys.to_hash(&ruby)?)?;
+    hash.aset(ruby.to_symbol("values"), sample.values.to_hash(&ruby)?)?;
+    hash.aset(
+        ruby.to_symbol("prefixes"),
+        prefixes_to_array(&ruby, sample.prefixes, &options)?,
+    )?;
+    Ok(hash)
+}
+
+/// Read every entry, in chunks of `SCAN_CHUNK_KEYS`, into a sample.
+fn scan(reader: &DbReader, options: &SampleOptions) -> Result<Sample, Error> {
+    let mut iter = block_on_result(reader.scan(..))?;
+    let mut sample = Sample::default();
+
+    loop {
+        let done = block_on_result(async {
+            for _ in 0..SCAN_CHUNK_KEYS {
+                let Some(kv) = iter.next().await? else {
+                    return Ok(true);
+                };
+                sample.scanned += 1;
+                if !options.sampled(&kv.key) {
+                    continue;
+                }
+                sample.keys.0.push(kv.key.len());
+                sample.values.0.push(kv.value.len());
+                let prefix = sample
+                    .prefixes
+                    .entry(options.prefix(&kv.key).to_vec())
+                    .or_default();
+                prefix.keys += 1;
+                prefix.bytes += (kv.key.len() + kv.value.len()) as u64;
+            }
+            Ok(false)
+        })?;
+        if done {
+            return Ok(sample);
+        }
+    }
+}
+
+/// The `top` prefixes by estimated bytes, with their sampled counts scaled
+/// up by the sample rate.
+fn prefixes_to_array(
+    ruby: &Ruby,
+    prefixes: HashMap<Vec<u8>, PrefixSample>,
+    options: &SampleOptions,
+) -> Result<RArray, Error> {
+    let mut prefixes: Vec<_> = prefixes.into_iter().collect();
+    prefixes.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then_with(|| a.0.cmp(&b.0)));
+
+    let array = ruby.ary_new();
+    for (prefix, counts) in prefixes.into_iter().take(options.top) {
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("prefix"), ruby.str_from_slice(&prefix))?;
+        hash.aset(
+            ruby.to_symbol("estimated_keys"),
+            (counts.keys as f64 / options.rate).round() as u64,
+        )?;
+        hash.aset(
+            ruby.to_symbol("estimated_bytes"),
+            (counts.bytes as f64 / options.rate).round() as u64,
+        )?;
+        array.push(hash)?;
+    }
+    Ok(array)
+}
+
+/// Entries and bytes counted under one prefix.
+#[derive(Default)]
+struct PrefixTotals {
+    entries: u64,
+    key_bytes: u64,
+    value_bytes: u64,
+}
+
+/// Count the entries and bytes under each of `prefixes`.
+///
+/// Scans each prefix with a read-only reader, so a running writer is not
+/// disturbed. With a `sample_rate` below 1 only that fraction of keys is
+/// counted and the totals are scaled up; every entry is still read.
+/// Overlapping prefixes are each counted in full.
+///
+/// # Returns
+/// Hash of prefix to entries, key_bytes, value_bytes and bytes
+pub fn prefix_stats(
+    path: &Path,
+    object_store: &Arc<dyn ObjectStore>,
+    prefixes: Vec<String>,
+    kwargs: RHash,
+) -> Result<RHash, Error> {
+    let rate = sample_rate_from_kwargs(&kwargs, 1.0)?;
+    let reader = block_on_result(DbReader::open(
+        path.clone(),
+        object_store.clone(),
+        None,
+        DbReaderOptions::default(),
+    ))?;
+    let counted: Result<Vec<_>, Error> = prefixes
+        .into_iter()
+        .map(|prefix| {
+            let totals = count_prefix(&reader, prefix.as_bytes(), rate)?;
+            Ok((prefix, totals))
+        })
+        .collect();
+    let closed = block_on_result(reader.close());
+    let counted = counted?;
+    closed?;
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let scale = |n: u64| (n as f64 / rate).round() as u64;
+    let hash = ruby.hash_new();
+    for (prefix, totals) in counted {
+        let counts = ruby.hash_new();
+        counts.aset(ruby.to_symbol("entries"), scale(totals.entries))?;
+        counts.aset(ruby.to_symbol("key_bytes"), scale(totals.key_bytes))?;
+        counts.aset(ruby.to_symbol("value_bytes"), scale(totals.value_bytes))?;
+        counts.aset(
+            ruby.to_symbol("bytes"),
+            scale(totals.key_bytes + totals.value_bytes),
+        )?;
+        hash.aset(prefix, counts)?;
+    }
+    Ok(hash)
+}
+
+/// Read every entry under `prefix`, in chunks of `SCAN_CHUNK_KEYS`, counting
+/// the sampled ones.
+fn count_prefix(reader: &DbReader, prefix: &[u8], rate: f64) -> Result<PrefixTotals, Error> {
+    let mut iter = block_on_result(reader.scan_prefix(prefix, ..))?;
+    let mut totals = PrefixTotals::default();
+
+    loop {
+        let done = block_on_result(async {
+            for _ in 0..SCAN_CHUNK_KEYS {
+                let Some(kv) = iter.next().await? else {
+                    return Ok(true);
+                };
+                if !sampled(rate, &kv.key) {
+                    continue;
+                }
+                totals.entries += 1;
+                totals.key_bytes += kv.key.len() as u64;
+                totals.value_bytes += kv.value.len() as u64;
+            }
+            Ok(false)
+        })?;
+        if done {
+            return Ok(totals);
+        }
+    }
+}
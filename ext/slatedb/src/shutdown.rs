@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use futures::future::join_all;
+use magnus::prelude::*;
+use magnus::{function, Error};
+use once_cell::sync::Lazy;
+use slatedb::{Db, DbReader};
+
+use crate::errors::closed_error;
+use crate::runtime::{block_on, is_forked};
+use crate::telemetry;
+
+/// Open databases and readers, with the id of the process that opened them,
+/// closed by `shutdown`.
+static DATABASES: Lazy<Mutex<Vec<(u32, Weak<Db>)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static READERS: Lazy<Mutex<Vec<(u32, Weak<DbReader>)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+fn register<T>(handles: &Mutex<Vec<(u32, Weak<T>)>>, handle: &Arc<T>) {
+    let mut handles = handles.lock().expect("shutdown registry poisoned");
+    handles.retain(|(_, h)| h.strong_count() > 0);
+    handles.push((std::process::id(), Arc::downgrade(handle)));
+}
+
+/// Take the open handles owned by this process. Handles inherited across
+/// fork are left to the parent, which closes them itself.
+fn live<T>(handles: &Mutex<Vec<(u32, Weak<T>)>>) -> Vec<Arc<T>> {
+    let mut handles = handles.lock().expect("shutdown registry poisoned");
+    handles
+        .drain(..)
+        .filter(|(pid, _)| !is_forked(*pid))
+        .filter_map(|(_, h)| h.upgrade())
+        .collect()
+}
+
+/// Track a newly opened database so `shutdown` can close it.
+pub fn register_database(db: &Arc<Db>) {
+    register(&DATABASES, db);
+}
+
+/// Track a newly opened reader so `shutdown` can close it.
+pub fn register_reader(reader: &Arc<DbReader>) {
+    register(&READERS, reader);
+}
+
+/// Raise ClosedError if SlateDB has been shut down in this process.
+pub fn ensure_running() -> Result<(), Error> {
+    if SHUT_DOWN.load(Ordering::SeqCst) {
+        return Err(closed_error("SlateDB has been shut down"));
+    }
+    Ok(())
+}
+
+/// Close every open database and reader, then flush exported spans.
+///
+/// Closing a database flushes its WAL buffer and stops its background
+/// flush, compaction and garbage collection tasks. Waits up to `timeout_ms`
+/// in total. Later attempts to open a database or reader raise ClosedError.
+///
+/// Returns the number of databases and readers that were closed.
+fn shutdown(timeout_ms: u64) -> Result<usize, Error> {
+    SHUT_DOWN.store(true, Ordering::SeqCst);
+    let databases = live(&DATABASES);
+    let readers = live(&READERS);
+    let exporter = telemetry::stop_exporter();
+    let closed = databases.len() + readers.len();
+
+    block_on(async move {
+        let close_all = async {
+            // Errors are ignored: a database that fails to close here has
+            // nothing left to retry with.
+            join_all(databases.iter().map(|db| db.close())).await;
+            join_all(readers.iter().map(|reader| reader.close())).await;
+            if let Some(exporter) = exporter {
+                let _ = exporter.await;
+            }
+        };
+        let _ = tokio::time::timeout(Duration::from_millis(timeout_ms), close_all).await;
+    })?;
+
+    Ok(closed)
+}
+
+/// Whether `shutdown` has been called in this process.
+fn is_shut_down() -> bool {
+    SHUT_DOWN.load(Ordering::SeqCst)
+}
+
+/// Define the shutdown functions on the SlateDb module.
+pub fn define_shutdown_functions(module: &magnus::RModule) -> Result<(), Error> {
+    module.define_module_function("_shutdown", function!(shutdown, 1))?;
+    module.define_module_function("shut_down?", function!(is_shut_down, 0))?;
+    Ok(())
+}
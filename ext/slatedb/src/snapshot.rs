@@ -1,56 +1,145 @@
-use std::cell::RefCell;
-use std::sync::Arc;
+use std::ops::Bound;
+use std::sync::{Arc, RwLock};
 
+use futures::future::try_join_all;
 use magnus::prelude::*;
-use magnus::{method, Error, RHash, Ruby};
-use slatedb::config::{DurabilityLevel, ReadOptions, ScanOptions};
+use magnus::{method, DataTypeFunctions, Error, RArray, RHash, Ruby, TypedData, Value};
+use slatedb::config::{DurabilityLevel, ScanOptions};
 use slatedb::DbSnapshot;
+use slatedb::Error as SlateError;
 use slatedb::IterationOrder;
 
+use crate::codec::Codec;
 use crate::errors::{closed_error, invalid_argument_error};
-use crate::iterator::Iterator;
-use crate::runtime::block_on_result;
-use crate::utils::{get_optional, prefix_subrange_from_kwargs};
+use crate::iterator::{
+    auto_read_ahead_from_kwargs, AdaptiveReadAhead, Iterator, ScanRange, ViewInfo,
+};
+use crate::read_defaults::ReadDefaults;
+use crate::runtime::{ensure_same_process, is_forked, RuntimeRef};
+use crate::utils::{get_optional, get_positive, prefix_subrange_from_kwargs};
 
 /// Ruby wrapper for SlateDB Snapshot.
 ///
 /// This struct is exposed to Ruby as `SlateDb::Snapshot`.
 /// Provides a consistent, read-only view of the database at a point in time.
-#[magnus::wrap(class = "SlateDb::Snapshot", free_immediately, size)]
+/// Snapshots are frozen and shareable, so Ractors can read from one
+/// concurrently.
+#[derive(TypedData)]
+#[magnus(class = "SlateDb::Snapshot", free_immediately, size, frozen_shareable)]
 pub struct Snapshot {
-    inner: RefCell<Option<Arc<DbSnapshot>>>,
+    inner: RwLock<Option<Arc<DbSnapshot>>>,
+    view: ViewInfo,
+    codec: Codec,
+    /// The runtime of the database it was taken from.
+    runtime: RuntimeRef,
+    /// The read tuning of the database it was taken from.
+    read_defaults: ReadDefaults,
+    /// The process that took the snapshot.
+    pid: u32,
+}
+
+impl DataTypeFunctions for Snapshot {
+    /// Report the handle plus the SlateDB snapshot it holds until closed.
+    fn size(&self) -> usize {
+        let open = match self.inner.try_read() {
+            Ok(inner) => inner.is_some(),
+            Err(_) => true,
+        };
+        std::mem::size_of::<Self>()
+            + if open {
+                std::mem::size_of::<DbSnapshot>()
+            } else {
+                0
+            }
+    }
 }
 
 impl Snapshot {
     /// Create a new Snapshot from a DbSnapshot.
-    pub fn new(snapshot: Arc<DbSnapshot>) -> Self {
+    pub fn new(snapshot: Arc<DbSnapshot>, view: ViewInfo) -> Self {
         Self {
-            inner: RefCell::new(Some(snapshot)),
+            inner: RwLock::new(Some(snapshot)),
+            view,
+            codec: Codec::Raw,
+            runtime: RuntimeRef::default(),
+            read_defaults: ReadDefaults::default(),
+            pid: std::process::id(),
         }
     }
 
+    /// Set the codec used to decode values.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Run operations on the database's runtime.
+    pub fn with_runtime(mut self, runtime: RuntimeRef) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Apply the database's `read_ahead_bytes`, `cache_blocks` and
+    /// `max_fetch_tasks` open options to reads that don't override them.
+    pub fn with_read_defaults(mut self, read_defaults: ReadDefaults) -> Self {
+        self.read_defaults = read_defaults;
+        self
+    }
+
+    /// The codec used to decode values.
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
+    /// The runtime its operations run on.
+    pub fn runtime(&self) -> RuntimeRef {
+        self.runtime.clone()
+    }
+
+    /// Raise ForkedProcessError if the snapshot is used after `fork`: it
+    /// belongs to the parent's database, whose background tasks do not run
+    /// in the child.
+    fn ensure_usable(&self) -> Result<(), Error> {
+        ensure_same_process(self.pid)
+    }
+
+    /// The open snapshot, or ClosedError once closed.
+    ///
+    /// The lock is released before the caller blocks, so `close` from
+    /// another thread never waits on an in-flight read.
+    pub fn snapshot(&self) -> Result<Arc<DbSnapshot>, Error> {
+        self.ensure_usable()?;
+        self.inner
+            .read()
+            .expect("snapshot lock poisoned")
+            .clone()
+            .ok_or_else(|| closed_error("snapshot is closed"))
+    }
+
     /// Get a value by key from the snapshot.
-    pub fn get(&self, key: String) -> Result<Option<String>, Error> {
+    pub fn get(&self, key: String) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let guard = self.inner.borrow();
-        let snapshot = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("snapshot is closed"))?;
+        let snapshot = self.snapshot()?;
+        let opts = self.read_defaults.read_options();
 
-        let result = block_on_result(async { snapshot.get(key.as_bytes()).await })?;
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        let result = self
+            .runtime
+            .block_on_result(async { snapshot.get_with_options(key.as_bytes(), &opts).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
     }
 
     /// Get a value by key with options from the snapshot.
-    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<String>, Error> {
+    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let mut opts = ReadOptions::default();
+        let mut opts = self.read_defaults.read_options();
 
         if let Some(df) = get_optional::<String>(&kwargs, "durability_filter")? {
             opts.durability_filter = match df.as_str() {
@@ -73,38 +162,37 @@ impl Snapshot {
             opts.cache_blocks = cb;
         }
 
-        let guard = self.inner.borrow();
-        let snapshot = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("snapshot is closed"))?;
+        let snapshot = self.snapshot()?;
 
-        let result =
-            block_on_result(async { snapshot.get_with_options(key.as_bytes(), &opts).await })?;
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        let result = self
+            .runtime
+            .block_on_result(async { snapshot.get_with_options(key.as_bytes(), &opts).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
     }
 
     /// Scan a range of keys from the snapshot.
     pub fn scan(&self, start: String, end_key: Option<String>) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if start.is_empty() {
             return Err(invalid_argument_error("start key cannot be empty"));
         }
 
-        let guard = self.inner.borrow();
-        let snapshot = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("snapshot is closed"))?;
+        let snapshot = self.snapshot()?;
+        let opts = self.read_defaults.scan_options();
 
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
+        let iter = self.runtime.block_on_result(async {
             match end_bytes {
-                Some(end) => snapshot.scan(start_bytes..end).await,
-                None => snapshot.scan(start_bytes..).await,
+                Some(end) => snapshot.scan_with_options(start_bytes..end, &opts).await,
+                None => snapshot.scan_with_options(start_bytes.., &opts).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Scan a range of keys with options from the snapshot.
@@ -114,11 +202,12 @@ impl Snapshot {
         end_key: Option<String>,
         kwargs: RHash,
     ) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if start.is_empty() {
             return Err(invalid_argument_error("start key cannot be empty"));
         }
 
-        let mut opts = ScanOptions::default();
+        let mut opts = self.read_defaults.scan_options();
 
         if let Some(df) = get_optional::<String>(&kwargs, "durability_filter")? {
             opts.durability_filter = match df.as_str() {
@@ -161,38 +250,50 @@ impl Snapshot {
             };
         }
 
-        let guard = self.inner.borrow();
-        let snapshot = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("snapshot is closed"))?;
+        let snapshot = self.snapshot()?;
 
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
+        if auto_read_ahead_from_kwargs(&kwargs)? {
+            let range = ScanRange::Range(
+                Bound::Included(start_bytes),
+                end_bytes.map_or(Bound::Unbounded, Bound::Excluded),
+            );
+            return self.scan_adaptive(snapshot, range, opts);
+        }
+
+        let iter = self.runtime.block_on_result(async {
             match end_bytes {
                 Some(end) => snapshot.scan_with_options(start_bytes..end, &opts).await,
                 None => snapshot.scan_with_options(start_bytes.., &opts).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Scan all keys with a given prefix from the snapshot.
     pub fn scan_prefix(&self, prefix: String) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if prefix.is_empty() {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
 
-        let guard = self.inner.borrow();
-        let snapshot = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("snapshot is closed"))?;
+        let snapshot = self.snapshot()?;
+        let opts = self.read_defaults.scan_options();
 
-        let iter = block_on_result(async { snapshot.scan_prefix(prefix.as_bytes(), ..).await })?;
+        let iter = self.runtime.block_on_result(async {
+            snapshot
+                .scan_prefix_with_options(prefix.as_bytes(), .., &opts)
+                .await
+        })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Scan all keys with a given prefix with options from the snapshot.
@@ -201,11 +302,12 @@ impl Snapshot {
         prefix: String,
         kwargs: RHash,
     ) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if prefix.is_empty() {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
 
-        let mut opts = ScanOptions::default();
+        let mut opts = self.read_defaults.scan_options();
 
         if let Some(df) = get_optional::<String>(&kwargs, "durability_filter")? {
             opts.durability_filter = match df.as_str() {
@@ -250,29 +352,121 @@ impl Snapshot {
 
         let subrange = prefix_subrange_from_kwargs(&kwargs)?;
 
-        let guard = self.inner.borrow();
-        let snapshot = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("snapshot is closed"))?;
+        let snapshot = self.snapshot()?;
+
+        if auto_read_ahead_from_kwargs(&kwargs)? {
+            let range = ScanRange::Prefix(prefix.into_bytes(), subrange);
+            return self.scan_adaptive(snapshot, range, opts);
+        }
 
-        let iter = block_on_result(async {
+        let iter = self.runtime.block_on_result(async {
             snapshot
                 .scan_prefix_with_options(prefix.as_bytes(), subrange, &opts)
                 .await
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
+    }
+
+    /// Open a `read_ahead: :auto` scan over `range` of this snapshot.
+    fn scan_adaptive(
+        &self,
+        snapshot: Arc<DbSnapshot>,
+        range: ScanRange,
+        opts: ScanOptions,
+    ) -> Result<Iterator, Error> {
+        let (adaptive, iter) = self
+            .runtime
+            .block_on_result(AdaptiveReadAhead::open(snapshot, range, opts))?;
+
+        let view = self.view;
+        Ok(Iterator::with_adaptive_read_ahead(iter, view, adaptive)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
+    }
+
+    /// Scan several ranges of the snapshot concurrently.
+    ///
+    /// # Arguments
+    /// * `ranges` - `[start, end, exclude_end]` for each range, where a nil
+    ///   start or end leaves that side unbounded
+    /// * `kwargs` - Options (limit, the most entries to return per range)
+    ///
+    /// # Returns
+    /// The `[key, value]` pairs in each range, in the order the ranges were
+    /// given
+    pub fn scan_many(&self, ranges: RArray, kwargs: RHash) -> Result<RArray, Error> {
+        self.ensure_usable()?;
+        let limit = get_positive(&kwargs, "limit")?;
+        let ranges = ranges
+            .to_vec::<(Option<String>, Option<String>, bool)>()?
+            .into_iter()
+            .map(|(start, end, exclude_end)| {
+                if start.as_deref() == Some("") {
+                    return Err(invalid_argument_error("start key cannot be empty"));
+                }
+                let start = start.map_or(Bound::Unbounded, |s| Bound::Included(s.into_bytes()));
+                let end = match end {
+                    None => Bound::Unbounded,
+                    Some(end) if exclude_end => Bound::Excluded(end.into_bytes()),
+                    Some(end) => Bound::Included(end.into_bytes()),
+                };
+                Ok((start, end))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let snapshot = self.snapshot()?;
+        let opts = self.read_defaults.scan_options();
+        let scans = self
+            .runtime
+            .block_on_result(try_join_all(ranges.into_iter().map(|range| {
+                let snapshot = &snapshot;
+                let opts = &opts;
+                async move {
+                    let mut iter = snapshot.scan_with_options(range, opts).await?;
+                    let mut entries = Vec::new();
+                    while limit.is_none_or(|limit| entries.len() < limit) {
+                        match iter.next().await? {
+                            Some(kv) => entries.push(kv),
+                            None => break,
+                        }
+                    }
+                    Ok::<_, SlateError>(entries)
+                }
+            })))?;
+
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let results = ruby.ary_new_capa(scans.len());
+        for entries in scans {
+            let pairs = ruby.ary_new_capa(entries.len());
+            for kv in entries {
+                let key = ruby.str_new(&String::from_utf8_lossy(&kv.key));
+                pairs.push(
+                    ruby.ary_new_from_values(&[key.as_value(), self.codec.decode(&kv.value)?]),
+                )?;
+            }
+            results.push(pairs)?;
+        }
+        Ok(results)
     }
 
     /// Close the snapshot and release resources.
+    ///
+    /// Does nothing in a forked child; the parent still owns the snapshot.
     pub fn close(&self) -> Result<(), Error> {
-        let _ = self.inner.borrow_mut().take();
+        if is_forked(self.pid) {
+            return Ok(());
+        }
+        let _ = self.inner.write().expect("snapshot lock poisoned").take();
         Ok(())
     }
 
     /// Check if the snapshot is closed.
-    pub fn is_closed(&self) -> bool {
-        self.inner.borrow().is_none()
+    pub fn is_closed(&self) -> Result<bool, Error> {
+        self.ensure_usable()?;
+        Ok(self.inner.read().expect("snapshot lock poisoned").is_none())
     }
 }
 
@@ -293,6 +487,7 @@ pub fn define_snapshot_class(ruby: &Ruby, module: &magnus::RModule) -> Result<()
         "_scan_prefix_with_options",
         method!(Snapshot::scan_prefix_with_options, 2),
     )?;
+    class.define_method("_scan_many", method!(Snapshot::scan_many, 2))?;
     class.define_method("close", method!(Snapshot::close, 0))?;
     class.define_method("closed?", method!(Snapshot::is_closed, 0))?;
 
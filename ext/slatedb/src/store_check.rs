@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+use magnus::{function, Error, RHash, Value};
+use slatedb::object_store::path::Path;
+
+use crate::errors::{invalid_argument_error, map_error, timeout_error};
+use crate::runtime::block_on;
+use crate::utils::{resolve_object_store, to_slate_error, StoreOptions};
+
+/// Check that the object store at `url` can be reached with the given
+/// settings, by listing `path` (the root when nil) one level deep.
+///
+/// Returns the request's latency in seconds and, if it failed, the exception
+/// describing why, which is returned rather than raised. The object store
+/// retries failed requests for up to three minutes by default, so the check
+/// gives up after `timeout` seconds with a TimeoutError. Settings the store
+/// rejects before any request is made raise InvalidArgumentError.
+fn check_store(
+    url: String,
+    path: Option<String>,
+    timeout: f64,
+    kwargs: RHash,
+) -> Result<(f64, Option<Value>), Error> {
+    let options = StoreOptions::from_kwargs(&kwargs)?;
+    let store = resolve_object_store(&url, &options).map_err(map_error)?;
+    let prefix = path.map(Path::from);
+    let limit = Duration::try_from_secs_f64(timeout)
+        .ok()
+        .filter(|limit| !limit.is_zero())
+        .ok_or_else(|| {
+            invalid_argument_error(&format!(
+                "timeout must be a positive number of seconds, got {}",
+                timeout
+            ))
+        })?;
+
+    let started = Instant::now();
+    let result = block_on(tokio::time::timeout(
+        limit,
+        store.list_with_delimiter(prefix.as_ref()),
+    ))?;
+    let latency = started.elapsed().as_secs_f64();
+
+    let error = match result {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => map_error(to_slate_error(e)).value(),
+        Err(_) => timeout_error(&format!(
+            "store did not respond within {}s",
+            limit.as_secs_f64()
+        ))
+        .value(),
+    };
+    Ok((latency, error))
+}
+
+/// Define the store check function on the SlateDb module.
+pub fn define_store_check_functions(module: &magnus::RModule) -> Result<(), Error> {
+    module.define_module_function("_check_store", function!(check_store, 4))?;
+    Ok(())
+}
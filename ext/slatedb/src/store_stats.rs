@@ -0,0 +1,284 @@
+use std::fmt;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use magnus::{Error, RHash, Ruby};
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions, Result, UploadPart,
+};
+
+/// The request classes object stores bill by.
+#[derive(Clone, Copy, Debug)]
+enum RequestClass {
+    Get,
+    Put,
+    List,
+    Delete,
+}
+
+impl RequestClass {
+    const ALL: [Self; 4] = [Self::Get, Self::Put, Self::List, Self::Delete];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Put => "put",
+            Self::List => "list",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Counters for one request class.
+#[derive(Debug, Default)]
+struct ClassStats {
+    requests: AtomicU64,
+    bytes: AtomicU64,
+    errors: AtomicU64,
+}
+
+/// Requests a database has made to its object store, by request class; see
+/// `Database#object_store_stats`.
+///
+/// Every attempt counts, including SlateDB's own retries and the requests of
+/// background flushes, compaction and garbage collection. Reads served from
+/// the block cache never reach the store and are not counted.
+#[derive(Debug, Default)]
+pub struct StoreStats {
+    classes: [ClassStats; 4],
+}
+
+impl StoreStats {
+    /// Wrap `store` so its requests are counted here.
+    pub fn wrap(self: &Arc<Self>, store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(CountingStore {
+            inner: store,
+            stats: self.clone(),
+        })
+    }
+
+    fn class(&self, class: RequestClass) -> &ClassStats {
+        &self.classes[class as usize]
+    }
+
+    /// Count a request, with the bytes it transferred if it succeeded.
+    fn record<T>(&self, class: RequestClass, result: &Result<T>, bytes: impl FnOnce(&T) -> u64) {
+        let stats = self.class(class);
+        stats.requests.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Ok(value) => {
+                stats.bytes.fetch_add(bytes(value), Ordering::Relaxed);
+            }
+            Err(_) => {
+                stats.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Return a hash of request class (`:get`, `:put`, `:list` and
+    /// `:delete`) to its `:requests`, `:bytes` and `:errors`.
+    pub fn to_hash(&self) -> Result<RHash, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let hash = ruby.hash_new();
+        for class in RequestClass::ALL {
+            let stats = self.class(class);
+            let counts = ruby.hash_new();
+            counts.aset(
+                ruby.to_symbol("requests"),
+                stats.requests.load(Ordering::Relaxed),
+            )?;
+            counts.aset(ruby.to_symbol("bytes"), stats.bytes.load(Ordering::Relaxed))?;
+            counts.aset(
+                ruby.to_symbol("errors"),
+                stats.errors.load(Ordering::Relaxed),
+            )?;
+            hash.aset(ruby.to_symbol(class.name()), counts)?;
+        }
+        Ok(hash)
+    }
+
+    /// Zero every counter.
+    pub fn reset(&self) {
+        for stats in &self.classes {
+            stats.requests.store(0, Ordering::Relaxed);
+            stats.bytes.store(0, Ordering::Relaxed);
+            stats.errors.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// An object store that counts the requests it passes to the store it wraps.
+///
+/// GET covers gets, ranged reads and heads, with the bytes read. PUT covers
+/// puts, multipart uploads (each part counts), copies and renames, with the
+/// bytes written. LIST counts each listing once, however many pages it
+/// fetches. DELETE counts each object deleted.
+#[derive(Debug)]
+struct CountingStore {
+    inner: Arc<dyn ObjectStore>,
+    stats: Arc<StoreStats>,
+}
+
+impl fmt::Display for CountingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CountingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CountingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let bytes = payload.content_length() as u64;
+        let result = self.inner.put_opts(location, payload, opts).await;
+        self.stats.record(RequestClass::Put, &result, |_| bytes);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let result = self.inner.put_multipart_opts(location, opts).await;
+        self.stats.record(RequestClass::Put, &result, |_| 0);
+        Ok(Box::new(CountingUpload {
+            upload: result?,
+            stats: self.stats.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let head = options.head;
+        let result = self.inner.get_opts(location, options).await;
+        self.stats.record(RequestClass::Get, &result, |result| {
+            if head {
+                0
+            } else {
+                result.range.end - result.range.start
+            }
+        });
+        result
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let result = self.inner.get_ranges(location, ranges).await;
+        self.stats.record(RequestClass::Get, &result, |parts| {
+            parts.iter().map(|part| part.len() as u64).sum()
+        });
+        result
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, Result<Path>>,
+    ) -> BoxStream<'static, Result<Path>> {
+        let stats = self.stats.clone();
+        self.inner
+            .delete_stream(locations)
+            .inspect(move |result| stats.record(RequestClass::Delete, result, |_| 0))
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.stats
+            .class(RequestClass::List)
+            .requests
+            .fetch_add(1, Ordering::Relaxed);
+        count_list_errors(self.stats.clone(), self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.stats
+            .class(RequestClass::List)
+            .requests
+            .fetch_add(1, Ordering::Relaxed);
+        count_list_errors(
+            self.stats.clone(),
+            self.inner.list_with_offset(prefix, offset),
+        )
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let result = self.inner.list_with_delimiter(prefix).await;
+        self.stats.record(RequestClass::List, &result, |_| 0);
+        result
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, options: CopyOptions) -> Result<()> {
+        let result = self.inner.copy_opts(from, to, options).await;
+        self.stats.record(RequestClass::Put, &result, |_| 0);
+        result
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, options: RenameOptions) -> Result<()> {
+        let result = self.inner.rename_opts(from, to, options).await;
+        self.stats.record(RequestClass::Put, &result, |_| 0);
+        result
+    }
+}
+
+/// Count the errors a listing yields against the LIST class.
+fn count_list_errors(
+    stats: Arc<StoreStats>,
+    listing: BoxStream<'static, Result<ObjectMeta>>,
+) -> BoxStream<'static, Result<ObjectMeta>> {
+    listing
+        .inspect(move |result| {
+            if result.is_err() {
+                stats
+                    .class(RequestClass::List)
+                    .errors
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .boxed()
+}
+
+/// A multipart upload whose parts and completion count as PUT requests.
+#[derive(Debug)]
+struct CountingUpload {
+    upload: Box<dyn MultipartUpload>,
+    stats: Arc<StoreStats>,
+}
+
+#[async_trait]
+impl MultipartUpload for CountingUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let stats = self.stats.clone();
+        let bytes = data.content_length() as u64;
+        let part = self.upload.put_part(data);
+        Box::pin(async move {
+            let result = part.await;
+            stats.record(RequestClass::Put, &result, |_| bytes);
+            result
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self.upload.complete().await;
+        self.stats.record(RequestClass::Put, &result, |_| 0);
+        result
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let result = self.upload.abort().await;
+        self.stats.record(RequestClass::Delete, &result, |_| 0);
+        result
+    }
+}
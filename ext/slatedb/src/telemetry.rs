@@ -0,0 +1,336 @@
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use magnus::prelude::*;
+use magnus::{function, Error, RHash, Ruby, TryConvert};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value as JsonValue};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::errors::invalid_argument_error;
+use crate::runtime::{is_forked, spawn};
+use crate::utils::get_optional;
+
+/// Number of finished spans queued for export before further spans are
+/// dropped.
+const EXPORT_QUEUE_SIZE: usize = 2048;
+
+/// Maximum number of spans sent in one export request.
+const EXPORT_BATCH_SIZE: usize = 512;
+
+/// How long finished spans wait to be batched before they are exported.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// OTLP span kind for calls to a remote service.
+const SPAN_KIND_CLIENT: u8 = 3;
+
+/// The exporter configured by `SlateDb.configure_tracing`.
+static EXPORTER: Lazy<RwLock<Option<Exporter>>> = Lazy::new(|| RwLock::new(None));
+
+/// A running exporter task and the queue feeding it.
+struct Exporter {
+    sender: mpsc::Sender<SpanData>,
+    task: JoinHandle<()>,
+    /// The process running the task, which a forked child must restart.
+    pid: u32,
+    config: ExportConfig,
+}
+
+impl Exporter {
+    fn start(config: ExportConfig) -> Self {
+        let (sender, spans) = mpsc::channel(EXPORT_QUEUE_SIZE);
+        let task = spawn(export_spans(spans, config.clone()));
+        Self {
+            sender,
+            task,
+            pid: std::process::id(),
+            config,
+        }
+    }
+}
+
+/// Where and how to export spans.
+#[derive(Clone)]
+struct ExportConfig {
+    url: String,
+    service_name: String,
+    headers: Vec<(String, String)>,
+}
+
+/// A finished span, ready for export.
+struct SpanData {
+    trace_id: String,
+    parent_span_id: String,
+    span_id: String,
+    operation: &'static str,
+    start_ns: u128,
+    end_ns: u128,
+}
+
+/// The caller's span, parsed from a W3C `traceparent` header.
+struct TraceParent {
+    trace_id: String,
+    span_id: String,
+    sampled: bool,
+}
+
+impl TraceParent {
+    /// Parse `version-trace_id-parent_id-flags`. Returns None for malformed
+    /// headers, which the trace context spec says to ignore.
+    fn parse(header: &str) -> Option<Self> {
+        let parts: Vec<&str> = header.trim().split('-').collect();
+        let [version, trace_id, span_id, flags, ..] = parts.as_slice() else {
+            return None;
+        };
+        let is_hex = |s: &str, len: usize| {
+            s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        };
+        let is_zero = |s: &str| s.bytes().all(|b| b == b'0');
+        if !is_hex(version, 2) || *version == "ff" || !is_hex(flags, 2) {
+            return None;
+        }
+        if !is_hex(trace_id, 32) || !is_hex(span_id, 16) || is_zero(trace_id) || is_zero(span_id) {
+            return None;
+        }
+
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            sampled: flags & 1 == 1,
+        })
+    }
+}
+
+/// A span covering one database operation. It is exported when dropped,
+/// whether or not the operation succeeded.
+pub struct Span {
+    sender: mpsc::Sender<SpanData>,
+    parent: TraceParent,
+    operation: &'static str,
+    start_ns: u128,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let span = SpanData {
+            trace_id: std::mem::take(&mut self.parent.trace_id),
+            parent_span_id: std::mem::take(&mut self.parent.span_id),
+            span_id: format!("{:016x}", uuid::Uuid::new_v4().as_u64_pair().1),
+            operation: self.operation,
+            start_ns: self.start_ns,
+            end_ns: unix_nanos(),
+        };
+        // Spans are dropped rather than blocking the operation when the
+        // exporter falls behind.
+        let _ = self.sender.try_send(span);
+    }
+}
+
+fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+/// Start a child span for `operation` if the options carry a sampled
+/// `trace_context` and tracing is configured.
+///
+/// `trace_context` is a hash carrying a W3C `traceparent` header under a
+/// String or Symbol key, as produced by OpenTelemetry's propagators.
+pub fn trace_span(operation: &'static str, kwargs: &RHash) -> Result<Option<Span>, Error> {
+    let Some(context) = get_optional::<RHash>(kwargs, "trace_context")? else {
+        return Ok(None);
+    };
+    let Some(sender) = exporter_sender() else {
+        return Ok(None);
+    };
+
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let header = context
+        .get("traceparent")
+        .or_else(|| context.get(ruby.to_symbol("traceparent")));
+    let Some(header) = header.and_then(|h| String::try_convert(h).ok()) else {
+        return Ok(None);
+    };
+
+    Ok(TraceParent::parse(&header)
+        .filter(|parent| parent.sampled)
+        .map(|parent| Span {
+            sender,
+            parent,
+            operation,
+            start_ns: unix_nanos(),
+        }))
+}
+
+/// The queue feeding the exporter, if tracing is configured.
+///
+/// A forked child inherits the exporter but not the runtime thread running
+/// it, so the exporter is restarted with the same configuration the first
+/// time a span is exported in a new process.
+fn exporter_sender() -> Option<mpsc::Sender<SpanData>> {
+    let exporter = EXPORTER.read().expect("tracing lock poisoned");
+    match exporter.as_ref() {
+        Some(e) if is_forked(e.pid) => {}
+        other => return other.map(|e| e.sender.clone()),
+    }
+    drop(exporter);
+
+    let mut exporter = EXPORTER.write().expect("tracing lock poisoned");
+    let current = exporter.take()?;
+    let restarted = if is_forked(current.pid) {
+        let config = current.config.clone();
+        // The parent still owns the inherited queue and task.
+        std::mem::forget(current);
+        Exporter::start(config)
+    } else {
+        current
+    };
+    let sender = restarted.sender.clone();
+    *exporter = Some(restarted);
+    Some(sender)
+}
+
+/// Encode spans as an OTLP/HTTP JSON export request.
+fn export_request(config: &ExportConfig, spans: &[SpanData]) -> JsonValue {
+    let spans: Vec<JsonValue> = spans
+        .iter()
+        .map(|span| {
+            json!({
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "parentSpanId": span.parent_span_id,
+                "name": format!("slatedb.{}", span.operation),
+                "kind": SPAN_KIND_CLIENT,
+                "startTimeUnixNano": span.start_ns.to_string(),
+                "endTimeUnixNano": span.end_ns.to_string(),
+                "attributes": [
+                    { "key": "db.system.name", "value": { "stringValue": "slatedb" } },
+                    { "key": "db.operation.name", "value": { "stringValue": span.operation } },
+                ],
+            })
+        })
+        .collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": config.service_name } },
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "slatedb-rb" },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+/// Export spans in batches until every sender for the queue is dropped.
+async fn export_spans(mut spans: mpsc::Receiver<SpanData>, config: ExportConfig) {
+    let client = reqwest::Client::new();
+    let mut batch = Vec::with_capacity(EXPORT_BATCH_SIZE);
+
+    loop {
+        let mut closed = false;
+        let deadline = Instant::now() + EXPORT_INTERVAL;
+        while batch.len() < EXPORT_BATCH_SIZE {
+            let limit = EXPORT_BATCH_SIZE - batch.len();
+            match tokio::time::timeout_at(deadline, spans.recv_many(&mut batch, limit)).await {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+
+        if !batch.is_empty() {
+            let mut request = client
+                .post(&config.url)
+                .header("content-type", "application/json")
+                .body(export_request(&config, &batch).to_string());
+            for (name, value) in &config.headers {
+                request = request.header(name, value);
+            }
+            match request.send().await {
+                Ok(response) if !response.status().is_success() => {
+                    log::warn!("span export failed: HTTP {}", response.status());
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("span export failed: {}", e),
+            }
+            batch.clear();
+        }
+
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Export spans for traced operations to an OTLP/HTTP collector, or stop
+/// exporting when `endpoint` is nil.
+///
+/// `endpoint` is the collector's base URL (e.g. `http://localhost:4318`);
+/// spans are posted to its `/v1/traces` path.
+fn configure_tracing(endpoint: Option<String>, kwargs: RHash) -> Result<(), Error> {
+    let mut exporter = EXPORTER.write().expect("tracing lock poisoned");
+    let Some(endpoint) = endpoint else {
+        *exporter = None;
+        return Ok(());
+    };
+
+    let parsed = url::Url::parse(&endpoint)
+        .map_err(|e| invalid_argument_error(&format!("invalid tracing endpoint: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid_argument_error(
+            "invalid tracing endpoint: expected an http or https URL",
+        ));
+    }
+    let base = endpoint.trim_end_matches('/');
+    let url = if base.ends_with("/v1/traces") {
+        base.to_string()
+    } else {
+        format!("{}/v1/traces", base)
+    };
+
+    let service_name =
+        get_optional::<String>(&kwargs, "service_name")?.unwrap_or_else(|| "slatedb".to_string());
+    let headers = get_optional::<RHash>(&kwargs, "headers")?
+        .map(|h| h.to_vec::<String, String>())
+        .transpose()?
+        .unwrap_or_default();
+
+    let config = ExportConfig {
+        url,
+        service_name,
+        headers,
+    };
+    *exporter = Some(Exporter::start(config));
+    Ok(())
+}
+
+/// Stop accepting spans. Returns the exporter task, which finishes once the
+/// spans already queued have been exported, or None if the task belongs to
+/// the parent of a forked process.
+pub fn stop_exporter() -> Option<JoinHandle<()>> {
+    let exporter = EXPORTER.write().expect("tracing lock poisoned").take()?;
+    if is_forked(exporter.pid) {
+        std::mem::forget(exporter);
+        return None;
+    }
+    Some(exporter.task)
+}
+
+/// Define the tracing functions on the SlateDb module.
+pub fn define_tracing_functions(module: &magnus::RModule) -> Result<(), Error> {
+    module.define_module_function("_configure_tracing", function!(configure_tracing, 2))?;
+    Ok(())
+}
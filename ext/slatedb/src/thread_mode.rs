@@ -0,0 +1,74 @@
+use magnus::prelude::*;
+use magnus::{Error, RHash, Ruby};
+
+use crate::errors::{invalid_argument_error, thread_ownership_error};
+use crate::utils::get_optional;
+
+/// Which threads may use a database handle; see the `thread_safe` open
+/// option.
+#[derive(Default)]
+pub enum ThreadMode {
+    /// Any thread may use the handle. The Db synchronizes internally, so
+    /// concurrent calls are safe.
+    #[default]
+    Shared,
+    /// Only the Ruby thread that opened the handle may use it.
+    Exclusive {
+        /// `object_id` of the owner thread, which Ruby never reuses.
+        owner: u64,
+        /// The owner's `inspect`, for error messages.
+        owner_name: String,
+    },
+}
+
+impl ThreadMode {
+    /// Parse the `thread_safe` option, making the calling thread the owner
+    /// of an exclusive handle.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        match get_optional::<String>(kwargs, "thread_safe")?.as_deref() {
+            None | Some("shared") => Ok(Self::Shared),
+            Some("exclusive") => {
+                let (owner, owner_name) = current_thread()?;
+                Ok(Self::Exclusive { owner, owner_name })
+            }
+            Some(other) => Err(invalid_argument_error(&format!(
+                "invalid thread_safe: {} (expected :shared or :exclusive)",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Shared => "shared",
+            Self::Exclusive { .. } => "exclusive",
+        }
+    }
+
+    /// Raise ThreadOwnershipError if an exclusive handle is used from a
+    /// thread other than its owner. Fibers of the owner thread may use it.
+    pub fn ensure_owner(&self) -> Result<(), Error> {
+        let Self::Exclusive { owner, owner_name } = self else {
+            return Ok(());
+        };
+        let (current, current_name) = current_thread()?;
+        if current != *owner {
+            return Err(thread_ownership_error(&format!(
+                "database was opened with thread_safe: :exclusive by {} and cannot be used from {}; \
+                 open it with thread_safe: :shared to share it between threads",
+                owner_name, current_name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The current Ruby thread's `object_id` and `inspect`.
+///
+/// Ruby threads are compared rather than native ones, since Ruby may run a
+/// thread on more than one native thread.
+fn current_thread() -> Result<(u64, String), Error> {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let thread = ruby.thread_current();
+    Ok((thread.funcall("object_id", ())?, thread.inspect()))
+}
@@ -1,37 +1,204 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use futures::future::try_join_all;
 use magnus::prelude::*;
-use magnus::{method, Error, RHash, Ruby};
+use magnus::{method, DataTypeFunctions, Error, RHash, Ruby, Symbol, TypedData, Value};
 use slatedb::config::{
     DurabilityLevel, MergeOptions, PutOptions, ReadOptions, ScanOptions, Ttl, WriteOptions,
 };
-use slatedb::DbTransaction;
-use slatedb::IterationOrder;
+use slatedb::{Db, DbTransaction};
+use slatedb::{IsolationLevel, IterationOrder, KeyValue};
+use slatedb_common::clock::SystemClock;
+use uuid::Uuid;
+
+use crate::clock;
+use crate::codec::Codec;
+use crate::errors::{closed_error, commit_timeout_error, invalid_argument_error, map_error};
+use crate::iterator::{entry_shape_from_kwargs, Iterator, ViewInfo};
+use crate::limits::SizeLimits;
+use crate::metrics::LatencyStats;
+use crate::runtime::{ensure_same_process, is_forked, RuntimeRef};
+use crate::telemetry::trace_span;
+use crate::utils::{get_optional, prefix_subrange_from_kwargs, ttl_from_kwargs};
+use crate::write_batch::WriteBatch;
+use crate::write_result::WriteReturn;
+
+/// Totals for the write operations buffered in a transaction.
+#[derive(Clone, Copy, Default)]
+struct WriteStats {
+    ops: usize,
+    bytes: usize,
+}
+
+impl WriteStats {
+    fn add(&mut self, ops: usize, bytes: usize) {
+        self.ops += ops;
+        self.bytes += bytes;
+    }
+}
 
-use crate::errors::{closed_error, invalid_argument_error, map_error};
-use crate::iterator::Iterator;
-use crate::runtime::block_on_result;
-use crate::utils::{get_optional, prefix_subrange_from_kwargs};
+/// A value recorded by a savepoint.
+#[derive(Clone)]
+struct SavedValue {
+    kv: KeyValue,
+    /// Whether the value was written earlier in the transaction. Such values
+    /// don't report their expiry.
+    buffered: bool,
+}
+
+/// State captured by a savepoint.
+#[derive(Default)]
+struct Savepoint {
+    /// The values keys had when the savepoint was taken, recorded the first
+    /// time each key is written after it. `None` means the key did not exist.
+    values: HashMap<Vec<u8>, Option<SavedValue>>,
+    /// The transaction's write totals when the savepoint was taken.
+    writes: WriteStats,
+}
 
 /// Ruby wrapper for SlateDB Transaction.
 ///
 /// This struct is exposed to Ruby as `SlateDb::Transaction`.
 /// After commit or rollback, the transaction is closed.
-#[magnus::wrap(class = "SlateDb::Transaction", free_immediately, size)]
+#[derive(TypedData)]
+#[magnus(class = "SlateDb::Transaction", free_immediately, size)]
 pub struct Transaction {
+    db: Arc<Db>,
+    latency: Arc<LatencyStats>,
     inner: RefCell<Option<DbTransaction>>,
+    view: ViewInfo,
+    id: Uuid,
+    isolation: IsolationLevel,
+    writes: Cell<WriteStats>,
+    savepoints: RefCell<Vec<Savepoint>>,
+    /// Keys put or merged in the transaction.
+    buffered: RefCell<HashSet<Vec<u8>>>,
+    codec: Codec,
+    limits: SizeLimits,
+    /// The runtime of the database it was taken from.
+    runtime: RuntimeRef,
+    /// The clock of the database it was taken from, if not the system clock.
+    clock: Option<Arc<dyn SystemClock>>,
+    /// Whether writes are rejected; see `with_read_only`.
+    read_only: bool,
+    /// The process that began the transaction.
+    pid: u32,
+}
+
+impl DataTypeFunctions for Transaction {
+    /// Report the handle plus the bytes written so far, which SlateDB
+    /// buffers until commit, and the values savepoints hold for rollback.
+    fn size(&self) -> usize {
+        let open = match self.inner.try_borrow() {
+            Ok(inner) => inner.is_some(),
+            Err(_) => true,
+        };
+        if !open {
+            return std::mem::size_of::<Self>();
+        }
+        let saved = self.savepoints.try_borrow().map_or(0, |savepoints| {
+            savepoints
+                .iter()
+                .flat_map(|savepoint| &savepoint.values)
+                .map(|(key, value)| {
+                    key.len() + value.as_ref().map_or(0, |saved| saved.kv.value.len())
+                })
+                .sum()
+        });
+        std::mem::size_of::<Self>()
+            + std::mem::size_of::<DbTransaction>()
+            + self.writes.get().bytes
+            + saved
+    }
 }
 
 impl Transaction {
     /// Create a new Transaction from a DbTransaction.
-    pub fn new(txn: DbTransaction) -> Self {
+    pub fn new(
+        db: Arc<Db>,
+        latency: Arc<LatencyStats>,
+        txn: DbTransaction,
+        view: ViewInfo,
+        isolation: IsolationLevel,
+    ) -> Self {
         Self {
+            db,
+            latency,
+            id: txn.id(),
             inner: RefCell::new(Some(txn)),
+            view,
+            isolation,
+            writes: Cell::new(WriteStats::default()),
+            savepoints: RefCell::new(Vec::new()),
+            buffered: RefCell::new(HashSet::new()),
+            codec: Codec::Raw,
+            limits: SizeLimits::default(),
+            runtime: RuntimeRef::default(),
+            clock: None,
+            read_only: false,
+            pid: std::process::id(),
         }
     }
 
+    /// Set the codec used to encode and decode values.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Set the key and value size limits checked by writes.
+    pub fn with_limits(mut self, limits: SizeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Run operations on the database's runtime.
+    pub fn with_runtime(mut self, runtime: RuntimeRef) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Read TTL expiry times from the database's clock.
+    pub fn with_clock(mut self, clock: Option<Arc<dyn SystemClock>>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Make the transaction read-only: writes raise InvalidArgumentError,
+    /// reads are never marked for conflict detection, and commit just
+    /// releases the transaction like rollback.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Raise ForkedProcessError if the transaction is used after `fork`: it
+    /// belongs to the parent's database, whose background tasks do not run
+    /// in the child.
+    fn ensure_usable(&self) -> Result<(), Error> {
+        ensure_same_process(self.pid)
+    }
+
+    /// The current time in milliseconds since the epoch, by the database's
+    /// clock.
+    fn now_millis(&self) -> i64 {
+        clock::now_millis(self.clock.as_ref())
+    }
+
+    /// Raise InvalidArgumentError if the transaction is read-only.
+    fn ensure_writable(&self) -> Result<(), Error> {
+        if self.read_only {
+            return Err(invalid_argument_error("transaction is read-only"));
+        }
+        Ok(())
+    }
+
     /// Get a value by key within the transaction.
-    pub fn get(&self, key: String) -> Result<Option<String>, Error> {
+    pub fn get(&self, key: String) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
@@ -41,19 +208,96 @@ impl Transaction {
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        let result = block_on_result(async { txn.get(key.as_bytes()).await })?;
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        let result = self
+            .runtime
+            .block_on_result(async { txn.get(key.as_bytes()).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
     }
 
     /// Get a value by key with options within the transaction.
-    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<String>, Error> {
+    pub fn get_with_options(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
+        let opts = Self::read_options_from_kwargs(&kwargs)?;
+
+        let guard = self.inner.borrow();
+        let txn = guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        let result = self
+            .runtime
+            .block_on_result(async { txn.get_with_options(key.as_bytes(), &opts).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
+    }
+
+    /// Get several values within the transaction.
+    ///
+    /// The reads run concurrently against the transaction's snapshot. Returns
+    /// the values in the same order as `keys`, with nil for missing keys.
+    pub fn get_many(&self, keys: Vec<String>, kwargs: RHash) -> Result<Vec<Option<Value>>, Error> {
+        self.ensure_usable()?;
+        if keys.iter().any(|k| k.is_empty()) {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+
+        let opts = Self::read_options_from_kwargs(&kwargs)?;
+
+        let guard = self.inner.borrow();
+        let txn = guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        let results = self.runtime.block_on_result(async {
+            try_join_all(
+                keys.iter()
+                    .map(|key| txn.get_with_options(key.as_bytes(), &opts)),
+            )
+            .await
+        })?;
+
+        results
+            .into_iter()
+            .map(|r| r.map(|b| self.codec.decode(&b)).transpose())
+            .collect()
+    }
+
+    /// Get a value by key and register the key for conflict detection.
+    ///
+    /// The key is tracked as read regardless of isolation level, so the
+    /// commit fails with a conflict if another writer modifies it after the
+    /// transaction started. This gives snapshot-isolation transactions
+    /// protection for read-modify-write patterns.
+    pub fn get_for_update(&self, key: String, kwargs: RHash) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
+        if key.is_empty() {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+
+        let opts = Self::read_options_from_kwargs(&kwargs)?;
+
+        let guard = self.inner.borrow();
+        let txn = guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        if !self.read_only {
+            txn.mark_read([key.as_bytes()]).map_err(map_error)?;
+        }
+        let result = self
+            .runtime
+            .block_on_result(async { txn.get_with_options(key.as_bytes(), &opts).await })?;
+        result.map(|b| self.codec.decode(&b)).transpose()
+    }
+
+    /// Build read options from keyword arguments.
+    fn read_options_from_kwargs(kwargs: &RHash) -> Result<ReadOptions, Error> {
         let mut opts = ReadOptions::default();
 
-        if let Some(df) = get_optional::<String>(&kwargs, "durability_filter")? {
+        if let Some(df) = get_optional::<String>(kwargs, "durability_filter")? {
             opts.durability_filter = match df.as_str() {
                 "remote" => DurabilityLevel::Remote,
                 "memory" => DurabilityLevel::Memory,
@@ -66,52 +310,52 @@ impl Transaction {
             };
         }
 
-        if let Some(dirty) = get_optional::<bool>(&kwargs, "dirty")? {
+        if let Some(dirty) = get_optional::<bool>(kwargs, "dirty")? {
             opts.dirty = dirty;
         }
 
-        if let Some(cb) = get_optional::<bool>(&kwargs, "cache_blocks")? {
+        if let Some(cb) = get_optional::<bool>(kwargs, "cache_blocks")? {
             opts.cache_blocks = cb;
         }
 
-        let guard = self.inner.borrow();
-        let txn = guard
-            .as_ref()
-            .ok_or_else(|| closed_error("transaction is closed"))?;
-
-        let result = block_on_result(async { txn.get_with_options(key.as_bytes(), &opts).await })?;
-        Ok(result.map(|b| String::from_utf8_lossy(&b).to_string()))
+        Ok(opts)
     }
 
     /// Put a key-value pair within the transaction.
-    pub fn put(&self, key: String, value: String) -> Result<(), Error> {
+    pub fn put(&self, key: String, value: Value) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
         let guard = self.inner.borrow();
         let txn = guard
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        txn.put(key.as_bytes(), value.as_bytes())
-            .map_err(map_error)?;
+        self.record_for_savepoints(txn, vec![key.as_bytes().to_vec()])?;
+        txn.put(key.as_bytes(), &value).map_err(map_error)?;
+        self.record_buffered(key.as_bytes());
+        self.record_writes(1, key.len() + value.len());
 
         Ok(())
     }
 
     /// Put a key-value pair with options within the transaction.
-    pub fn put_with_options(&self, key: String, value: String, kwargs: RHash) -> Result<(), Error> {
+    pub fn put_with_options(&self, key: String, value: Value, kwargs: RHash) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
-        let ttl = get_optional::<u64>(&kwargs, "ttl")?;
         let put_opts = PutOptions {
-            ttl: match ttl {
-                Some(ms) => Ttl::ExpireAfter(ms),
-                None => Ttl::Default,
-            },
+            ttl: ttl_from_kwargs(&kwargs)?,
         };
 
         let guard = self.inner.borrow();
@@ -119,41 +363,279 @@ impl Transaction {
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        txn.put_with_options(key.as_bytes(), value.as_bytes(), &put_opts)
+        self.record_for_savepoints(txn, vec![key.as_bytes().to_vec()])?;
+        txn.put_with_options(key.as_bytes(), &value, &put_opts)
             .map_err(map_error)?;
+        self.record_buffered(key.as_bytes());
+        self.record_writes(1, key.len() + value.len());
 
         Ok(())
     }
 
     /// Delete a key within the transaction.
     pub fn delete(&self, key: String) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        self.limits.check_key(key.as_bytes())?;
 
         let guard = self.inner.borrow();
         let txn = guard
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
+        self.record_for_savepoints(txn, vec![key.as_bytes().to_vec()])?;
         txn.delete(key.as_bytes()).map_err(map_error)?;
+        self.record_writes(1, key.len());
 
         Ok(())
     }
 
+    /// Delete several keys within the transaction.
+    ///
+    /// All keys are validated before any delete is applied.
+    pub fn delete_many(&self, keys: Vec<String>) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
+        if keys.iter().any(|k| k.is_empty()) {
+            return Err(invalid_argument_error("key cannot be empty"));
+        }
+        for key in &keys {
+            self.limits.check_key(key.as_bytes())?;
+        }
+
+        let guard = self.inner.borrow();
+        let txn = guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        self.record_for_savepoints(txn, keys.iter().map(|k| k.as_bytes().to_vec()).collect())?;
+        for key in &keys {
+            txn.delete(key.as_bytes()).map_err(map_error)?;
+            self.record_writes(1, key.len());
+        }
+
+        Ok(())
+    }
+
+    /// Apply every operation recorded in a WriteBatch to the transaction.
+    ///
+    /// The batch is emptied, as when writing it to the database. The writes
+    /// are buffered in the transaction and committed with it.
+    pub fn write(&self, batch: &WriteBatch) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
+        let guard = self.inner.borrow();
+        let txn = guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        let pending = batch.take(&self.limits, self.codec)?;
+        if !self.savepoints.borrow().is_empty() {
+            let keys = self.runtime.block_on_result(pending.keys(txn))?;
+            self.record_for_savepoints(txn, keys)?;
+        }
+        let (ops, bytes) = self.runtime.block_on_result(pending.apply_to(txn))?;
+        for key in pending.value_keys() {
+            self.record_buffered(key);
+        }
+        self.record_writes(ops, bytes);
+
+        Ok(())
+    }
+
+    /// Take a savepoint, returning its id.
+    ///
+    /// Savepoints nest: later savepoints get larger ids.
+    pub fn savepoint(&self) -> Result<usize, Error> {
+        self.ensure_usable()?;
+        let guard = self.inner.borrow();
+        guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        let mut savepoints = self.savepoints.borrow_mut();
+        savepoints.push(Savepoint {
+            values: HashMap::new(),
+            writes: self.writes.get(),
+        });
+        Ok(savepoints.len() - 1)
+    }
+
+    /// Undo every write made since a savepoint was taken.
+    ///
+    /// SlateDB cannot drop writes from a transaction, so each key written
+    /// since the savepoint is written again with the value (and remaining
+    /// TTL) it had at the savepoint, or deleted if it did not exist. Values
+    /// that were themselves written earlier in the transaction are restored
+    /// with the default TTL. The write totals go back to what they were at
+    /// the savepoint. The savepoint stays active; later savepoints are
+    /// released.
+    pub fn rollback_to(&self, savepoint: usize) -> Result<(), Error> {
+        self.ensure_usable()?;
+        let guard = self.inner.borrow();
+        let txn = guard
+            .as_ref()
+            .ok_or_else(|| closed_error("transaction is closed"))?;
+
+        let (journal, writes) = {
+            let mut savepoints = self.savepoints.borrow_mut();
+            if savepoint >= savepoints.len() {
+                return Err(invalid_argument_error(&format!(
+                    "unknown savepoint: {}",
+                    savepoint
+                )));
+            }
+            savepoints.truncate(savepoint + 1);
+            let sp = &mut savepoints[savepoint];
+            (std::mem::take(&mut sp.values), sp.writes)
+        };
+        self.writes.set(writes);
+
+        let now = self.now_millis();
+
+        for (key, previous) in journal {
+            let ttl = match &previous {
+                Some(SavedValue { kv, buffered }) => match kv.expire_ts {
+                    // Values buffered in the transaction don't report their
+                    // expiry, so they fall back to the default TTL.
+                    None if *buffered => Some(Ttl::Default),
+                    None => Some(Ttl::NoExpiry),
+                    Some(expire_ts) if expire_ts > now => {
+                        Some(Ttl::ExpireAfter((expire_ts - now) as u64))
+                    }
+                    Some(_) => None,
+                },
+                None => None,
+            };
+            match (previous, ttl) {
+                (Some(saved), Some(ttl)) => {
+                    txn.put_with_options(&key, &saved.kv.value, &PutOptions { ttl })
+                        .map_err(map_error)?;
+                    self.record_buffered(&key);
+                }
+                _ => txn.delete(&key).map_err(map_error)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Release a savepoint and every savepoint taken after it, keeping
+    /// their writes. Releasing an already released savepoint does nothing.
+    pub fn release_savepoint(&self, savepoint: usize) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.savepoints.borrow_mut().truncate(savepoint);
+        Ok(())
+    }
+
+    /// Record the current value of keys about to be written, for every
+    /// active savepoint that has not seen them yet.
+    fn record_for_savepoints(&self, txn: &DbTransaction, keys: Vec<Vec<u8>>) -> Result<(), Error> {
+        let missing: Vec<Vec<u8>> = {
+            let savepoints = self.savepoints.borrow();
+            keys.into_iter()
+                .filter(|key| savepoints.iter().any(|sp| !sp.values.contains_key(key)))
+                .collect()
+        };
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let values = self.runtime.block_on_result(try_join_all(
+            missing.iter().map(|key| txn.get_key_value(key)),
+        ))?;
+
+        let buffered = self.buffered.borrow();
+        let mut savepoints = self.savepoints.borrow_mut();
+        for (key, value) in missing.into_iter().zip(values) {
+            let value = value.map(|kv| SavedValue {
+                kv,
+                buffered: buffered.contains(&key),
+            });
+            for sp in savepoints.iter_mut() {
+                sp.values
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Note that a key's value is now buffered in the transaction.
+    fn record_buffered(&self, key: &[u8]) {
+        self.buffered.borrow_mut().insert(key.to_vec());
+    }
+
+    /// Add to the transaction's write totals.
+    fn record_writes(&self, ops: usize, bytes: usize) {
+        let mut writes = self.writes.get();
+        writes.add(ops, bytes);
+        self.writes.set(writes);
+    }
+
+    /// The transaction's unique id, as a UUID string.
+    pub fn id(&self) -> Result<String, Error> {
+        self.ensure_usable()?;
+        Ok(self.id.to_string())
+    }
+
+    /// The isolation level, as `:snapshot` or `:serializable`.
+    pub fn isolation_level(&self) -> Result<Symbol, Error> {
+        self.ensure_usable()?;
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        Ok(ruby.to_symbol(match self.isolation {
+            IsolationLevel::Snapshot => "snapshot",
+            IsolationLevel::SerializableSnapshot => "serializable",
+        }))
+    }
+
+    /// Whether the transaction rejects writes.
+    pub fn is_read_only(&self) -> Result<bool, Error> {
+        self.ensure_usable()?;
+        Ok(self.read_only)
+    }
+
+    /// The sequence number the transaction reads at.
+    pub fn seqnum(&self) -> Result<Option<u64>, Error> {
+        self.ensure_usable()?;
+        Ok(self.view.seq)
+    }
+
+    /// The number of write operations buffered in the transaction.
+    pub fn op_count(&self) -> Result<usize, Error> {
+        self.ensure_usable()?;
+        Ok(self.writes.get().ops)
+    }
+
+    /// The number of key and value bytes buffered in the transaction.
+    pub fn write_bytes(&self) -> Result<usize, Error> {
+        self.ensure_usable()?;
+        Ok(self.writes.get().bytes)
+    }
+
     /// Merge a value within the transaction.
-    pub fn merge(&self, key: String, value: String) -> Result<(), Error> {
+    pub fn merge(&self, key: String, value: Value) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
         let guard = self.inner.borrow();
         let txn = guard
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        txn.merge(key.as_bytes(), value.as_bytes())
-            .map_err(map_error)?;
+        self.record_for_savepoints(txn, vec![key.as_bytes().to_vec()])?;
+        txn.merge(key.as_bytes(), &value).map_err(map_error)?;
+        self.record_buffered(key.as_bytes());
+        self.record_writes(1, key.len() + value.len());
 
         Ok(())
     }
@@ -162,19 +644,19 @@ impl Transaction {
     pub fn merge_with_options(
         &self,
         key: String,
-        value: String,
+        value: Value,
         kwargs: RHash,
     ) -> Result<(), Error> {
+        self.ensure_usable()?;
+        self.ensure_writable()?;
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
+        let value = self.codec.encode(value)?;
+        self.limits.check(key.as_bytes(), &value)?;
 
-        let ttl = get_optional::<u64>(&kwargs, "ttl")?;
         let merge_opts = MergeOptions {
-            ttl: match ttl {
-                Some(ms) => Ttl::ExpireAfter(ms),
-                None => Ttl::Default,
-            },
+            ttl: ttl_from_kwargs(&kwargs)?,
         };
 
         let guard = self.inner.borrow();
@@ -182,14 +664,18 @@ impl Transaction {
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        txn.merge_with_options(key.as_bytes(), value.as_bytes(), &merge_opts)
+        self.record_for_savepoints(txn, vec![key.as_bytes().to_vec()])?;
+        txn.merge_with_options(key.as_bytes(), &value, &merge_opts)
             .map_err(map_error)?;
+        self.record_buffered(key.as_bytes());
+        self.record_writes(1, key.len() + value.len());
 
         Ok(())
     }
 
     /// Scan a range of keys within the transaction.
     pub fn scan(&self, start: String, end_key: Option<String>) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if start.is_empty() {
             return Err(invalid_argument_error("start key cannot be empty"));
         }
@@ -202,14 +688,16 @@ impl Transaction {
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
+        let iter = self.runtime.block_on_result(async {
             match end_bytes {
                 Some(end) => txn.scan(start_bytes..end).await,
                 None => txn.scan(start_bytes..).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Scan a range of keys with options within the transaction.
@@ -219,6 +707,7 @@ impl Transaction {
         end_key: Option<String>,
         kwargs: RHash,
     ) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if start.is_empty() {
             return Err(invalid_argument_error("start key cannot be empty"));
         }
@@ -266,6 +755,8 @@ impl Transaction {
             };
         }
 
+        let shape = entry_shape_from_kwargs(&kwargs)?;
+
         let guard = self.inner.borrow();
         let txn = guard
             .as_ref()
@@ -274,18 +765,22 @@ impl Transaction {
         let start_bytes = start.into_bytes();
         let end_bytes = end_key.map(|e| e.into_bytes());
 
-        let iter = block_on_result(async {
+        let iter = self.runtime.block_on_result(async {
             match end_bytes {
                 Some(end) => txn.scan_with_options(start_bytes..end, &opts).await,
                 None => txn.scan_with_options(start_bytes.., &opts).await,
             }
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_shape(shape)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Scan all keys with a given prefix within the transaction.
     pub fn scan_prefix(&self, prefix: String) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if prefix.is_empty() {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
@@ -295,9 +790,13 @@ impl Transaction {
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        let iter = block_on_result(async { txn.scan_prefix(prefix.as_bytes(), ..).await })?;
+        let iter = self
+            .runtime
+            .block_on_result(async { txn.scan_prefix(prefix.as_bytes(), ..).await })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Scan all keys with a given prefix with options within the transaction.
@@ -306,6 +805,7 @@ impl Transaction {
         prefix: String,
         kwargs: RHash,
     ) -> Result<Iterator, Error> {
+        self.ensure_usable()?;
         if prefix.is_empty() {
             return Err(invalid_argument_error("prefix cannot be empty"));
         }
@@ -354,18 +854,22 @@ impl Transaction {
         }
 
         let subrange = prefix_subrange_from_kwargs(&kwargs)?;
+        let shape = entry_shape_from_kwargs(&kwargs)?;
 
         let guard = self.inner.borrow();
         let txn = guard
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
-        let iter = block_on_result(async {
+        let iter = self.runtime.block_on_result(async {
             txn.scan_prefix_with_options(prefix.as_bytes(), subrange, &opts)
                 .await
         })?;
 
-        Ok(Iterator::new(iter))
+        Ok(Iterator::new(iter, self.view)
+            .with_shape(shape)
+            .with_codec(self.codec)
+            .with_runtime(self.runtime.clone()))
     }
 
     /// Mark keys as read for conflict detection.
@@ -376,11 +880,15 @@ impl Transaction {
     /// # Arguments
     /// * `keys` - Array of keys to mark as read
     pub fn mark_read(&self, keys: Vec<String>) -> Result<(), Error> {
+        self.ensure_usable()?;
         let guard = self.inner.borrow();
         let txn = guard
             .as_ref()
             .ok_or_else(|| closed_error("transaction is closed"))?;
 
+        if self.read_only {
+            return Ok(());
+        }
         let key_bytes: Vec<&[u8]> = keys.iter().map(|k| k.as_bytes()).collect();
         txn.mark_read(&key_bytes).map_err(map_error)?;
 
@@ -389,20 +897,37 @@ impl Transaction {
 
     /// Commit the transaction.
     pub fn commit(&self) -> Result<(), Error> {
+        self.ensure_usable()?;
         let txn = self
             .inner
             .borrow_mut()
             .take()
             .ok_or_else(|| closed_error("transaction is closed"))?;
+        if self.read_only {
+            return Ok(());
+        }
 
-        block_on_result(async { txn.commit().await })?;
+        let _timer = self.latency.start("commit");
+        self.runtime.block_on_result(async { txn.commit().await })?;
         Ok(())
     }
 
     /// Commit the transaction with options.
-    pub fn commit_with_options(&self, kwargs: RHash) -> Result<(), Error> {
+    ///
+    /// With `timeout_ms`, gives up waiting for the commit after that many
+    /// milliseconds and raises `CommitTimeoutError`. The transaction is
+    /// closed either way; a commit that timed out may still have been
+    /// applied.
+    ///
+    /// With `return_result`, `return_handle` or `return_token`, returns a
+    /// WriteResult, a DurabilityHandle or the commit's sequence number, as
+    /// for database writes, or nil if the transaction wrote nothing.
+    pub fn commit_with_options(&self, kwargs: RHash) -> Result<Option<Value>, Error> {
+        self.ensure_usable()?;
         let await_durable = get_optional::<bool>(&kwargs, "await_durable")?.unwrap_or(true);
         let seqnum = get_optional::<u64>(&kwargs, "seqnum")?.unwrap_or(0);
+        let timeout_ms = get_optional::<u64>(&kwargs, "timeout_ms")?;
+        let write_return = WriteReturn::from_kwargs(&kwargs)?;
         let write_opts = WriteOptions {
             await_durable,
             seqnum,
@@ -413,21 +938,55 @@ impl Transaction {
             .borrow_mut()
             .take()
             .ok_or_else(|| closed_error("transaction is closed"))?;
+        if self.read_only {
+            return Ok(None);
+        }
 
-        block_on_result(async { txn.commit_with_options(&write_opts).await })?;
-        Ok(())
+        let started = Instant::now();
+        let _timer = self.latency.start("commit");
+        let _span = trace_span("commit", &kwargs)?;
+        let handle = match timeout_ms {
+            None => self
+                .runtime
+                .block_on_result(async { txn.commit_with_options(&write_opts).await })?,
+            Some(timeout_ms) => {
+                let deadline = Duration::from_millis(timeout_ms);
+                let result = self.runtime.block_on(async {
+                    tokio::time::timeout(deadline, txn.commit_with_options(&write_opts)).await
+                })?;
+                match result {
+                    Ok(committed) => committed.map_err(map_error)?,
+                    Err(_) => {
+                        return Err(commit_timeout_error(&format!(
+                            "commit did not complete within {}ms",
+                            timeout_ms
+                        )))
+                    }
+                }
+            }
+        };
+
+        let bytes = self.writes.get().bytes;
+        Ok(handle
+            .and_then(|handle| write_return.build(&self.db, &handle, &write_opts, bytes, started)))
     }
 
     /// Rollback the transaction (discard all changes).
+    ///
+    /// Does nothing in a forked child; the parent still owns the transaction.
     pub fn rollback(&self) -> Result<(), Error> {
+        if is_forked(self.pid) {
+            return Ok(());
+        }
         // Simply drop the transaction - changes are not committed
         let _ = self.inner.borrow_mut().take();
         Ok(())
     }
 
     /// Check if the transaction is closed.
-    pub fn is_closed(&self) -> bool {
-        self.inner.borrow().is_none()
+    pub fn is_closed(&self) -> Result<bool, Error> {
+        self.ensure_usable()?;
+        Ok(self.inner.borrow().is_none())
     }
 }
 
@@ -446,12 +1005,22 @@ pub fn define_transaction_class(ruby: &Ruby, module: &magnus::RModule) -> Result
         "_put_with_options",
         method!(Transaction::put_with_options, 3),
     )?;
+    class.define_method("_get_many", method!(Transaction::get_many, 2))?;
+    class.define_method("_get_for_update", method!(Transaction::get_for_update, 2))?;
     class.define_method("_delete", method!(Transaction::delete, 1))?;
+    class.define_method("_delete_many", method!(Transaction::delete_many, 1))?;
     class.define_method("_merge", method!(Transaction::merge, 2))?;
     class.define_method(
         "_merge_with_options",
         method!(Transaction::merge_with_options, 3),
     )?;
+    class.define_method("_write", method!(Transaction::write, 1))?;
+    class.define_method("_savepoint", method!(Transaction::savepoint, 0))?;
+    class.define_method("_rollback_to", method!(Transaction::rollback_to, 1))?;
+    class.define_method(
+        "_release_savepoint",
+        method!(Transaction::release_savepoint, 1),
+    )?;
     class.define_method("_scan", method!(Transaction::scan, 2))?;
     class.define_method(
         "_scan_with_options",
@@ -468,6 +1037,12 @@ pub fn define_transaction_class(ruby: &Ruby, module: &magnus::RModule) -> Result
         "_commit_with_options",
         method!(Transaction::commit_with_options, 1),
     )?;
+    class.define_method("id", method!(Transaction::id, 0))?;
+    class.define_method("isolation_level", method!(Transaction::isolation_level, 0))?;
+    class.define_method("read_only?", method!(Transaction::is_read_only, 0))?;
+    class.define_method("seqnum", method!(Transaction::seqnum, 0))?;
+    class.define_method("op_count", method!(Transaction::op_count, 0))?;
+    class.define_method("write_bytes", method!(Transaction::write_bytes, 0))?;
     class.define_method("rollback", method!(Transaction::rollback, 0))?;
     class.define_method("closed?", method!(Transaction::is_closed, 0))?;
 
@@ -1,16 +1,26 @@
+use std::collections::BTreeMap;
 use std::ops::Bound;
 use std::sync::Arc;
+use std::time::Duration;
 
 use magnus::value::ReprValue;
-use magnus::{Error, RHash, Ruby, TryConvert};
-use slatedb::object_store::aws::AmazonS3Builder;
+use magnus::{Error, RHash, RString, Ruby, Symbol, TryConvert, Value};
+use slatedb::config::Ttl;
+use slatedb::object_store::aws::{AmazonS3Builder, AwsCredentialProvider};
+use slatedb::object_store::azure::MicrosoftAzureBuilder;
+use slatedb::object_store::gcp::GoogleCloudStorageBuilder;
+use slatedb::object_store::path::Path;
 use slatedb::object_store::prefix::PrefixStore;
 use slatedb::object_store::{
-    parse_url_opts, Error as ObjectStoreError, ObjectStore, ObjectStoreScheme,
+    parse_url_opts, Error as ObjectStoreError, ObjectStore, ObjectStoreScheme, RetryConfig,
 };
 use slatedb::Error as SlateError;
 use url::Url;
 
+use crate::credentials::{self, RubyCredentialProvider};
+use crate::errors::invalid_argument_error;
+use crate::runtime::ensure_main_ractor;
+
 /// Helper to extract an optional value from an RHash
 pub fn get_optional<T: TryConvert>(hash: &RHash, key: &str) -> Result<Option<T>, Error> {
     let ruby = Ruby::get().expect("Ruby runtime not available");
@@ -27,6 +37,88 @@ pub fn get_optional<T: TryConvert>(hash: &RHash, key: &str) -> Result<Option<T>,
     }
 }
 
+/// Extract an optional size or count that must be at least 1.
+pub fn get_positive(hash: &RHash, key: &str) -> Result<Option<usize>, Error> {
+    match get_optional::<usize>(hash, key)? {
+        Some(0) => Err(invalid_argument_error(&format!(
+            "{} must be at least 1",
+            key
+        ))),
+        value => Ok(value),
+    }
+}
+
+/// Parse the `ttl` and `expire_at` options shared by put and merge
+/// operations.
+///
+/// `ttl` accepts an Integer number of milliseconds, `:default` (use the
+/// database's default TTL, same as omitting the option) or `:none` (never
+/// expire, even when the database has a default TTL).
+///
+/// `expire_at` is an absolute deadline instead: a Time (or anything with
+/// `to_time`) or an Integer number of milliseconds since the Unix epoch. A
+/// deadline already passed writes a value that has expired.
+pub fn ttl_from_kwargs(hash: &RHash) -> Result<Ttl, Error> {
+    let ruby = Ruby::get().expect("Ruby runtime not available");
+    let expire_at = match hash.get(ruby.to_symbol("expire_at")) {
+        Some(val) if !val.is_nil() => Some(val),
+        _ => None,
+    };
+    let val = match hash.get(ruby.to_symbol("ttl")) {
+        Some(val) if !val.is_nil() => {
+            if expire_at.is_some() {
+                return Err(invalid_argument_error(
+                    "ttl and expire_at cannot both be given",
+                ));
+            }
+            val
+        }
+        _ => match expire_at {
+            Some(expire_at) => return expire_at_to_ttl(expire_at),
+            None => return Ok(Ttl::Default),
+        },
+    };
+
+    if let Some(sym) = Symbol::from_value(val) {
+        return match sym.name()?.as_ref() {
+            "default" => Ok(Ttl::Default),
+            "none" => Ok(Ttl::NoExpiry),
+            other => Err(invalid_argument_error(&format!(
+                "invalid ttl: :{} (expected :default, :none, or milliseconds)",
+                other
+            ))),
+        };
+    }
+
+    Ok(Ttl::ExpireAfter(u64::try_convert(val)?))
+}
+
+/// The TTL that expires a write at the `expire_at` deadline.
+fn expire_at_to_ttl(expire_at: Value) -> Result<Ttl, Error> {
+    if expire_at.respond_to("to_time", false)? {
+        let seconds: f64 = expire_at
+            .funcall::<_, _, Value>("to_time", ())?
+            .funcall("to_f", ())?;
+        return Ok(Ttl::ExpireAt((seconds * 1000.0).round() as i64));
+    }
+    let millis = i64::try_convert(expire_at).map_err(|_| {
+        invalid_argument_error(
+            "invalid expire_at (expected a Time or milliseconds since the epoch)",
+        )
+    })?;
+    Ok(Ttl::ExpireAt(millis))
+}
+
+/// Copy the raw bytes out of a Ruby String.
+///
+/// Unlike converting to a Rust `String`, this accepts binary strings that are
+/// not valid UTF-8 (such as an upper bound of `"user:\xff"`).
+pub fn rstring_to_bytes(s: RString) -> Vec<u8> {
+    // SAFETY: the slice is copied before any other Ruby code can run and
+    // mutate or free the string.
+    unsafe { s.as_slice() }.to_vec()
+}
+
 /// A key sub-range for a prefix scan, expressed as owned byte bounds. This
 /// implements `slatedb::bytes_range::ByteRangeBounds`, so it can be passed
 /// straight to `scan_prefix`/`scan_prefix_with_options`.
@@ -51,51 +143,455 @@ pub fn prefix_subrange_from_kwargs(hash: &RHash) -> Result<PrefixSubrange, Error
     Ok((start, end))
 }
 
-/// Convert an object_store error to a SlateDB error
-fn to_slate_error(e: ObjectStoreError) -> SlateError {
-    SlateError::unavailable(e.to_string())
+/// Convert an object_store error to a SlateDB error, keeping it as the
+/// source so the exception's `details` can report on it.
+pub fn to_slate_error(e: ObjectStoreError) -> SlateError {
+    SlateError::unavailable(e.to_string()).with_source(Box::new(e))
+}
+
+/// Settings for the object store behind a URL, from the `store_options` and
+/// `credentials_provider` open options. Keys are the object store's
+/// configuration keys for the URL's scheme, such as `service_account_key`
+/// for GCS; values are strings.
+#[derive(Debug, Default)]
+pub struct StoreOptions {
+    options: Vec<(String, String)>,
+    /// Id of the Ruby block supplying S3 credentials.
+    credentials_provider: Option<u64>,
+    client: ClientSettings,
+}
+
+impl StoreOptions {
+    /// Parse the `store_options` Hash, which the Ruby wrappers pass with
+    /// string keys and values, the `credentials_provider` id and the
+    /// `client_options` Hash.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let options =
+            get_optional::<std::collections::HashMap<String, String>>(kwargs, "store_options")?;
+        let mut options: Vec<_> = options.unwrap_or_default().into_iter().collect();
+        options.sort();
+        let credentials_provider = get_optional(kwargs, "credentials_provider")?;
+        if credentials_provider.is_some() {
+            ensure_main_ractor("credentials_provider")?;
+        }
+        Ok(Self {
+            options,
+            credentials_provider,
+            client: ClientSettings::from_kwargs(kwargs)?,
+        })
+    }
+
+    /// Apply each option, then the client settings, to a builder through the
+    /// config key type of its store. Unknown keys are rejected rather than
+    /// ignored, so a typo in a credential option fails loudly.
+    fn apply<B, K>(
+        &self,
+        mut builder: B,
+        store: &str,
+        with_config: fn(B, K, String) -> B,
+        with_retry: fn(B, RetryConfig) -> B,
+    ) -> Result<B, SlateError>
+    where
+        K: std::str::FromStr,
+    {
+        for (key, value) in self.options.iter().chain(&self.client.config) {
+            let config_key = key.parse::<K>().map_err(|_| {
+                SlateError::invalid(format!("unknown store option for {}: {}", store, key))
+            })?;
+            builder = with_config(builder, config_key, value.clone());
+        }
+        if let Some(retry) = &self.client.retry {
+            builder = with_retry(builder, retry.clone());
+        }
+        Ok(builder)
+    }
+
+    /// The options for an S3 store.
+    ///
+    /// Expands `preset` into the settings an S3-compatible service needs,
+    /// translates `force_path_style` (as the AWS SDKs call it) to object_store's
+    /// inverse `virtual_hosted_style_request`, and checks that a custom
+    /// `endpoint` can be reached the way it is configured.
+    fn for_s3(&self) -> Result<Self, SlateError> {
+        let mut options: BTreeMap<String, String> = self.options.iter().cloned().collect();
+
+        if let Some(preset) = options.remove("preset") {
+            apply_s3_preset(&preset, &mut options)?;
+        } else if options.contains_key("account_id") {
+            return Err(SlateError::invalid(
+                "store option account_id requires preset: :r2".to_string(),
+            ));
+        }
+
+        if let Some(value) = options.remove("force_path_style") {
+            let path_style: bool = value.parse().map_err(|_| {
+                SlateError::invalid(format!(
+                    "invalid force_path_style: {} (expected true or false)",
+                    value
+                ))
+            })?;
+            options.insert(
+                "virtual_hosted_style_request".to_string(),
+                (!path_style).to_string(),
+            );
+        }
+
+        if let Some(endpoint) = options.get("endpoint") {
+            validate_s3_endpoint(endpoint, &options)?;
+        }
+
+        Ok(Self {
+            options: options.into_iter().collect(),
+            credentials_provider: self.credentials_provider,
+            client: self.client.clone(),
+        })
+    }
+
+    /// Reject options for stores that take none.
+    fn ensure_empty(&self, store: &str) -> Result<(), SlateError> {
+        match self.options.first() {
+            Some((key, _)) => Err(SlateError::invalid(format!(
+                "store options are not supported for {} (got {})",
+                store, key
+            ))),
+            None if !self.client.is_empty() => Err(SlateError::invalid(format!(
+                "client options are not supported for {}",
+                store
+            ))),
+            None => self.ensure_no_credentials_provider(store),
+        }
+    }
+
+    /// Reject `credentials_provider` for stores other than S3.
+    fn ensure_no_credentials_provider(&self, store: &str) -> Result<(), SlateError> {
+        match self.credentials_provider {
+            Some(_) => Err(SlateError::invalid(format!(
+                "credentials_provider is only supported for S3, not {}",
+                store
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// The Ruby credentials provider for an S3 store: the one given when
+    /// opening, or else the default set with `SlateDb.credentials_provider`.
+    fn s3_credentials(&self) -> Option<AwsCredentialProvider> {
+        self.credentials_provider
+            .or_else(credentials::default_provider)
+            .map(RubyCredentialProvider::new)
+    }
+}
+
+/// HTTP client settings for S3, GCS and Azure stores, from the
+/// `client_options` open option: timeouts, retries, proxy and user agent.
+///
+/// Timeouts and durations are given in seconds. Client keys are applied
+/// through each store's config keys, on top of any set in the environment;
+/// retry settings replace object_store's defaults (10 retries within 3
+/// minutes, backing off from 100ms to 15s) only where given.
+#[derive(Debug, Default, Clone)]
+struct ClientSettings {
+    config: Vec<(String, String)>,
+    retry: Option<RetryConfig>,
+}
+
+impl ClientSettings {
+    const DURATION_KEYS: [&'static str; 4] = [
+        "timeout",
+        "connect_timeout",
+        "read_timeout",
+        "pool_idle_timeout",
+    ];
+    const STRING_KEYS: [&'static str; 4] = [
+        "proxy_url",
+        "proxy_excludes",
+        "proxy_ca_certificate",
+        "user_agent",
+    ];
+    const RETRY_KEYS: [&'static str; 5] = [
+        "max_retries",
+        "retry_timeout",
+        "init_backoff",
+        "max_backoff",
+        "backoff_base",
+    ];
+
+    fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let Some(hash) = get_optional::<RHash>(kwargs, "client_options")? else {
+            return Ok(Self::default());
+        };
+
+        let keys: Vec<Symbol> = hash.funcall("keys", ())?;
+        for key in keys {
+            let name = key.name()?;
+            let known = Self::DURATION_KEYS
+                .iter()
+                .chain(&Self::STRING_KEYS)
+                .chain(&Self::RETRY_KEYS)
+                .any(|k| *k == name);
+            if !known {
+                return Err(invalid_argument_error(&format!(
+                    "unknown client option: {}",
+                    name
+                )));
+            }
+        }
+
+        let mut config = Vec::new();
+        for key in Self::DURATION_KEYS {
+            if let Some(duration) = get_seconds(&hash, key)? {
+                config.push((key.to_string(), format!("{}ms", duration.as_millis())));
+            }
+        }
+        for key in Self::STRING_KEYS {
+            if let Some(value) = get_optional::<String>(&hash, key)? {
+                config.push((key.to_string(), value));
+            }
+        }
+
+        let mut retry = RetryConfig::default();
+        let mut retry_given = false;
+        if let Some(max_retries) = get_optional::<usize>(&hash, "max_retries")? {
+            retry.max_retries = max_retries;
+            retry_given = true;
+        }
+        for (key, field) in [
+            ("retry_timeout", &mut retry.retry_timeout),
+            ("init_backoff", &mut retry.backoff.init_backoff),
+            ("max_backoff", &mut retry.backoff.max_backoff),
+        ] {
+            if let Some(duration) = get_seconds(&hash, key)? {
+                *field = duration;
+                retry_given = true;
+            }
+        }
+        if let Some(base) = get_optional::<f64>(&hash, "backoff_base")? {
+            if !(base >= 1.0 && base.is_finite()) {
+                return Err(invalid_argument_error(&format!(
+                    "client option backoff_base must be at least 1, got {}",
+                    base
+                )));
+            }
+            retry.backoff.base = base;
+            retry_given = true;
+        }
+
+        Ok(Self {
+            config,
+            retry: retry_given.then_some(retry),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.config.is_empty() && self.retry.is_none()
+    }
+}
+
+/// Read a positive number of seconds from a client option.
+fn get_seconds(hash: &RHash, key: &str) -> Result<Option<Duration>, Error> {
+    let Some(seconds) = get_optional::<f64>(hash, key)? else {
+        return Ok(None);
+    };
+    if !(seconds > 0.0 && seconds.is_finite()) {
+        return Err(invalid_argument_error(&format!(
+            "client option {} must be a positive number of seconds, got {}",
+            key, seconds
+        )));
+    }
+    Ok(Some(Duration::from_secs_f64(seconds)))
+}
+
+/// Fill in the settings an S3-compatible service needs, leaving any option
+/// the caller set explicitly alone.
+///
+/// - `minio`: path-style requests to the given `endpoint`
+/// - `localstack`: path-style requests to `http://localhost:4566` with
+///   LocalStack's placeholder credentials
+/// - `r2`: Cloudflare R2's endpoint for `account_id`, in the `auto` region
+fn apply_s3_preset(preset: &str, options: &mut BTreeMap<String, String>) -> Result<(), SlateError> {
+    let mut default = |key: &str, value: String| {
+        options.entry(key.to_string()).or_insert(value);
+    };
+    match preset {
+        "minio" => {
+            default("region", "us-east-1".to_string());
+            default("force_path_style", "true".to_string());
+        }
+        "localstack" => {
+            default("endpoint", "http://localhost:4566".to_string());
+            default("region", "us-east-1".to_string());
+            default("force_path_style", "true".to_string());
+            default("access_key_id", "test".to_string());
+            default("secret_access_key", "test".to_string());
+        }
+        "r2" => {
+            default("region", "auto".to_string());
+        }
+        other => {
+            return Err(SlateError::invalid(format!(
+                "unknown store preset: {} (expected minio, localstack or r2)",
+                other
+            )))
+        }
+    }
+
+    if preset == "r2" {
+        if let Some(account_id) = options.remove("account_id") {
+            options
+                .entry("endpoint".to_string())
+                .or_insert_with(|| format!("https://{}.r2.cloudflarestorage.com", account_id));
+        }
+    }
+
+    let Some(endpoint) = options.get("endpoint") else {
+        return Err(SlateError::invalid(format!(
+            "store preset {} requires an endpoint{}",
+            preset,
+            if preset == "r2" { " or account_id" } else { "" }
+        )));
+    };
+    // Local services usually run without TLS; opting into a preset is
+    // taken as opting into that.
+    if endpoint.starts_with("http://") {
+        options
+            .entry("allow_http".to_string())
+            .or_insert_with(|| "true".to_string());
+    }
+    Ok(())
+}
+
+/// Check a custom S3 endpoint before any request is made, so a
+/// misconfiguration fails with an explanation rather than a connection
+/// error.
+fn validate_s3_endpoint(
+    endpoint: &str,
+    options: &BTreeMap<String, String>,
+) -> Result<(), SlateError> {
+    let url = Url::parse(endpoint)
+        .map_err(|e| SlateError::invalid(format!("invalid endpoint {}: {}", endpoint, e)))?;
+
+    match url.scheme() {
+        "https" => {}
+        "http" if options.get("allow_http").map(String::as_str) == Some("true") => {}
+        "http" => {
+            return Err(SlateError::invalid(format!(
+            "endpoint {} uses plain HTTP; pass allow_http: true to allow unencrypted connections",
+            endpoint
+        )))
+        }
+        other => {
+            return Err(SlateError::invalid(format!(
+                "invalid endpoint {}: scheme must be http or https, not {}",
+                endpoint, other
+            )))
+        }
+    }
+
+    // Virtual-hosted-style requests go to <bucket>.<host>, which cannot
+    // resolve when the host is an IP address or localhost, as it is for most
+    // MinIO and LocalStack setups.
+    let virtual_hosted = options
+        .get("virtual_hosted_style_request")
+        .is_some_and(|v| v == "true");
+    let bare_host = matches!(
+        url.host(),
+        Some(url::Host::Ipv4(_) | url::Host::Ipv6(_)) | Some(url::Host::Domain("localhost"))
+    );
+    if virtual_hosted && bare_host {
+        return Err(SlateError::invalid(format!(
+            "endpoint {} cannot serve virtual-hosted-style requests (<bucket>.{}); \
+             use force_path_style: true",
+            endpoint,
+            url.host_str().unwrap_or_default()
+        )));
+    }
+
+    Ok(())
 }
 
 /// Resolve an object store URL to an ObjectStore instance.
 ///
-/// This function handles S3 URLs specially to ensure environment variables
-/// like AWS_ACCESS_KEY_ID are properly recognized (the default object_store
-/// registry only recognizes lowercase variants like aws_access_key_id).
-pub fn resolve_object_store(url: &str) -> Result<Arc<dyn ObjectStore>, SlateError> {
+/// S3, GCS and Azure URLs are built from the environment, so variables like
+/// AWS_ACCESS_KEY_ID and GOOGLE_APPLICATION_CREDENTIALS are recognized (the
+/// default object_store registry only recognizes lowercase variants like
+/// aws_access_key_id), and then from `options`.
+pub fn resolve_object_store(
+    url: &str,
+    options: &StoreOptions,
+) -> Result<Arc<dyn ObjectStore>, SlateError> {
     let parsed_url: Url = url
         .try_into()
         .map_err(|e: url::ParseError| SlateError::invalid(format!("invalid URL: {}", e)))?;
 
-    let (scheme, _path) =
+    let (scheme, path) =
         ObjectStoreScheme::parse(&parsed_url).map_err(|e| to_slate_error(e.into()))?;
 
     match scheme {
         ObjectStoreScheme::AmazonS3 => {
             // Use from_env() to properly handle uppercase AWS_* environment variables
             // (the default object_store registry only recognizes lowercase variants)
-            let store = AmazonS3Builder::from_env()
-                .with_url(url)
-                .build()
-                .map_err(to_slate_error)?;
+            let builder = AmazonS3Builder::from_env().with_url(url);
+            let mut builder = options.for_s3()?.apply(
+                builder,
+                "S3",
+                AmazonS3Builder::with_config,
+                AmazonS3Builder::with_retry,
+            )?;
+            if let Some(credentials) = options.s3_credentials() {
+                builder = builder.with_credentials(credentials);
+            }
+            let store = builder.build().map_err(to_slate_error)?;
             Ok(Arc::new(store))
         }
+        ObjectStoreScheme::GoogleCloudStorage => {
+            options.ensure_no_credentials_provider("GCS")?;
+            let builder = GoogleCloudStorageBuilder::from_env().with_url(url);
+            let builder = options.apply(
+                builder,
+                "GCS",
+                GoogleCloudStorageBuilder::with_config,
+                GoogleCloudStorageBuilder::with_retry,
+            )?;
+            let store = builder.build().map_err(to_slate_error)?;
+            Ok(with_prefix(Arc::new(store), path))
+        }
+        ObjectStoreScheme::MicrosoftAzure => {
+            // Reads AZURE_STORAGE_ACCOUNT_NAME, AZURE_STORAGE_ACCOUNT_KEY,
+            // AZURE_STORAGE_SAS_TOKEN and friends. Without a key or token the
+            // store falls back to managed identity.
+            options.ensure_no_credentials_provider("Azure")?;
+            let builder = MicrosoftAzureBuilder::from_env().with_url(url);
+            let builder = options.apply(
+                builder,
+                "Azure",
+                MicrosoftAzureBuilder::with_config,
+                MicrosoftAzureBuilder::with_retry,
+            )?;
+            let store = builder.build().map_err(to_slate_error)?;
+            Ok(with_prefix(Arc::new(store), path))
+        }
         _ => {
+            options.ensure_empty(parsed_url.scheme())?;
             // SlateDB 0.14 changed `Db::resolve_object_store` to reject any URL
             // that carries a path component (returning `InvalidObjectStorePath`),
             // whereas 0.13 transparently wrapped it in a `PrefixStore`. Preserve
             // the old, more forgiving behavior here so callers can keep passing a
-            // full location such as "file:///data/mydb" or "gs://bucket/prefix".
+            // full location such as "file:///data/mydb".
             //
             // Env keys are lowercased because `parse_url_opts` only recognizes
             // lower-case option keys.
             let env_vars = std::env::vars().map(|(k, v)| (k.to_ascii_lowercase(), v));
             let (store, path) = parse_url_opts(&parsed_url, env_vars).map_err(to_slate_error)?;
-            let store: Arc<dyn ObjectStore> = Arc::from(store);
-            if path.as_ref().is_empty() {
-                Ok(store)
-            } else {
-                Ok(Arc::new(PrefixStore::new(store, path)))
-            }
+            Ok(with_prefix(Arc::from(store), path))
         }
     }
 }
+
+/// Scope a store to the path given in its URL, if any.
+fn with_prefix(store: Arc<dyn ObjectStore>, path: Path) -> Arc<dyn ObjectStore> {
+    if path.as_ref().is_empty() {
+        store
+    } else {
+        Arc::new(PrefixStore::new(store, path))
+    }
+}
@@ -0,0 +1,72 @@
+use std::ffi::c_void;
+
+use bytes::Bytes;
+use magnus::rb_sys::{protect, AsRawValue, FromRawValue};
+use magnus::{Error, Ruby, Value};
+use rb_sys::rb_io_buffer_flags::{RB_IO_BUFFER_EXTERNAL, RB_IO_BUFFER_READONLY};
+use rb_sys::special_consts::Qnil;
+use rb_sys::{rb_intern, rb_io_buffer_get_bytes_for_writing, rb_ivar_set, VALUE};
+
+use crate::errors::invalid_argument_error;
+
+extern "C" {
+    // Declared here rather than taken from rb_sys, whose binding types the
+    // flags as a single enum variant, while the buffer needs two of them.
+    #[link_name = "rb_io_buffer_new"]
+    fn io_buffer_new(base: *mut c_void, size: usize, flags: u32) -> VALUE;
+}
+
+/// Owns a value's bytes for as long as an `IO::Buffer` points at them.
+///
+/// This struct is exposed to Ruby as `SlateDb::ValueBuffer`, but is only
+/// ever held by the buffer itself, under an ivar Ruby code cannot see.
+#[magnus::wrap(class = "SlateDb::ValueBuffer", free_immediately, size)]
+pub struct ValueBuffer {
+    bytes: Bytes,
+}
+
+/// Return a read-only `IO::Buffer` over `bytes`, without copying them.
+///
+/// The buffer holds a reference to a `ValueBuffer` owning the bytes, so they
+/// are released when the buffer is garbage collected. Read-only because the
+/// bytes may be shared with SlateDB's block cache.
+pub fn io_buffer(ruby: &Ruby, bytes: Bytes) -> Value {
+    let (base, size) = (bytes.as_ptr() as *mut c_void, bytes.len());
+    let owner = ruby.obj_wrap(ValueBuffer { bytes });
+    let flags = RB_IO_BUFFER_EXTERNAL as u32 | RB_IO_BUFFER_READONLY as u32;
+    unsafe {
+        let buffer = io_buffer_new(base, size, flags);
+        rb_ivar_set(buffer, rb_intern(c"slatedb_value".as_ptr()), owner.as_raw());
+        Value::from_raw(buffer)
+    }
+}
+
+/// Copy `bytes` to the start of the writable `IO::Buffer` `buffer`.
+///
+/// Raises InvalidArgumentError if the buffer is too small, leaving it
+/// unchanged.
+pub fn copy_into(buffer: Value, bytes: &[u8]) -> Result<usize, Error> {
+    let mut base: *mut c_void = std::ptr::null_mut();
+    let mut size: usize = 0;
+    // Raises if `buffer` is not an IO::Buffer or is read-only.
+    protect(|| {
+        unsafe { rb_io_buffer_get_bytes_for_writing(buffer.as_raw(), &mut base, &mut size) };
+        Qnil as VALUE
+    })?;
+    if size < bytes.len() {
+        return Err(invalid_argument_error(&format!(
+            "value is {} bytes, but the buffer holds {}",
+            bytes.len(),
+            size
+        )));
+    }
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), base as *mut u8, bytes.len()) };
+    Ok(bytes.len())
+}
+
+/// Define the ValueBuffer class on the SlateDb module.
+pub fn define_value_buffer_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    module.define_class("ValueBuffer", ruby.class_object())?;
+
+    Ok(())
+}
@@ -1,38 +1,322 @@
 use std::cell::RefCell;
 
 use magnus::prelude::*;
-use magnus::{function, method, Error, RHash, Ruby};
-use slatedb::config::{MergeOptions, PutOptions, Ttl};
-use slatedb::WriteBatch as SlateWriteBatch;
+use magnus::value::Opaque;
+use magnus::{
+    function, gc, method, DataTypeFunctions, Error, RHash, RString, Ruby, TypedData, Value,
+};
+use slatedb::config::{MergeOptions, PutOptions, WriteOptions};
+use slatedb::{
+    Db, DbReadOps, DbTransaction, Error as SlateError, ErrorKind, IsolationLevel,
+    WriteBatch as SlateWriteBatch, WriteHandle,
+};
 
+use crate::codec::Codec;
+use crate::database::{
+    CONFLICT_MAX_RETRIES, TRANSACTION_RETRY_BASE_DELAY, TRANSACTION_RETRY_MAX_DELAY,
+};
 use crate::errors::invalid_argument_error;
-use crate::utils::get_optional;
+use crate::limits::SizeLimits;
+use crate::retry;
+use crate::utils::{rstring_to_bytes, ttl_from_kwargs};
+
+/// A single operation recorded by a WriteBatch, in the order it was added.
+///
+/// A WriteBatch records the Ruby values it is given and only encodes them,
+/// with the codec of the database or transaction it is written to, when it
+/// is taken; a taken batch holds the encoded bytes.
+enum BatchOp<V = Vec<u8>> {
+    Put(Vec<u8>, V, PutOptions),
+    Merge(Vec<u8>, V, MergeOptions),
+    Delete(Vec<u8>),
+    DeleteRange(Vec<u8>, Vec<u8>),
+}
+
+impl BatchOp<Opaque<Value>> {
+    /// Bytes allocated for the operation's keys. Recorded values are Ruby
+    /// objects, which Ruby accounts for itself.
+    fn heap_size(&self) -> usize {
+        match self {
+            BatchOp::Put(key, _, _) | BatchOp::Merge(key, _, _) | BatchOp::Delete(key) => {
+                key.capacity()
+            }
+            BatchOp::DeleteRange(start, end) => start.capacity() + end.capacity(),
+        }
+    }
+
+    /// The recorded value, if the operation has one.
+    fn value(&self) -> Option<Opaque<Value>> {
+        match self {
+            BatchOp::Put(_, value, _) | BatchOp::Merge(_, value, _) => Some(*value),
+            BatchOp::Delete(_) | BatchOp::DeleteRange(..) => None,
+        }
+    }
+
+    /// Copy the operation with its value encoded by `codec`.
+    fn encode(&self, ruby: &Ruby, codec: Codec) -> Result<BatchOp, Error> {
+        Ok(match self {
+            BatchOp::Put(key, value, opts) => BatchOp::Put(
+                key.clone(),
+                codec.encode(ruby.get_inner(*value))?,
+                opts.clone(),
+            ),
+            BatchOp::Merge(key, value, opts) => BatchOp::Merge(
+                key.clone(),
+                codec.encode(ruby.get_inner(*value))?,
+                opts.clone(),
+            ),
+            BatchOp::Delete(key) => BatchOp::Delete(key.clone()),
+            BatchOp::DeleteRange(start, end) => BatchOp::DeleteRange(start.clone(), end.clone()),
+        })
+    }
+}
+
+impl BatchOp {
+    /// The number of key and value bytes the operation records.
+    fn bytes(&self) -> usize {
+        match self {
+            BatchOp::Put(key, value, _) | BatchOp::Merge(key, value, _) => key.len() + value.len(),
+            BatchOp::Delete(key) => key.len(),
+            BatchOp::DeleteRange(start, end) => start.len() + end.len(),
+        }
+    }
+}
+
+/// The operations taken out of a WriteBatch, waiting to be written.
+///
+/// SlateDB has no range tombstones, so a `delete_range` is recorded as-is
+/// and only expanded into point deletes when the batch is written, inside a
+/// serializable transaction that conflicts with any concurrent write into
+/// the range; see [`PendingBatch::write`].
+pub struct PendingBatch {
+    ops: Vec<BatchOp>,
+}
+
+impl PendingBatch {
+    /// The number of key and value bytes recorded in the batch.
+    pub fn bytes(&self) -> usize {
+        self.ops.iter().map(BatchOp::bytes).sum()
+    }
+
+    /// The number of operations recorded in the batch, counting a
+    /// `delete_range` once.
+    pub fn op_count(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Split the batch, keeping its order, into consecutive batches of at
+    /// most `max_ops` operations and `max_bytes` key and value bytes. An
+    /// operation larger than `max_bytes` gets a batch to itself.
+    ///
+    /// Each part is written separately, so a `delete_range` only removes
+    /// keys from earlier parts once they have been written.
+    pub fn split(self, max_ops: usize, max_bytes: usize) -> Vec<PendingBatch> {
+        let mut parts = Vec::new();
+        let mut ops = Vec::new();
+        let mut bytes = 0;
+        for op in self.ops {
+            let size = op.bytes();
+            if !ops.is_empty() && (ops.len() >= max_ops || bytes + size > max_bytes) {
+                parts.push(PendingBatch {
+                    ops: std::mem::take(&mut ops),
+                });
+                bytes = 0;
+            }
+            bytes += size;
+            ops.push(op);
+        }
+        if !ops.is_empty() {
+            parts.push(PendingBatch { ops });
+        }
+        parts
+    }
+
+    /// Write the batch to `db` atomically.
+    ///
+    /// A batch without a `delete_range` is written as a single SlateDB
+    /// batch. One with a range delete is applied, in order, to a serializable
+    /// transaction instead: the scan of each range is tracked, so a write
+    /// another writer commits into the range first makes the commit conflict,
+    /// and the transaction is retried with backoff, up to
+    /// `CONFLICT_MAX_RETRIES` times, before TransactionError is raised.
+    pub async fn write(&self, db: &Db, options: &WriteOptions) -> Result<WriteHandle, SlateError> {
+        if let Some(batch) = self.to_write_batch() {
+            return db.write_with_options(batch, options).await;
+        }
+
+        let mut attempt = 0;
+        loop {
+            let txn = db.begin(IsolationLevel::SerializableSnapshot).await?;
+            self.apply_to(&txn).await?;
+            match txn.commit_with_options(options).await {
+                Ok(Some(handle)) => return Ok(handle),
+                // Nothing to delete and nothing else to write, but the
+                // caller still expects the write's handle.
+                Ok(None) => return db.write_with_options(SlateWriteBatch::new(), options).await,
+                Err(e)
+                    if matches!(e.kind(), ErrorKind::Transaction)
+                        && attempt < CONFLICT_MAX_RETRIES =>
+                {
+                    attempt += 1;
+                    tokio::time::sleep(retry::backoff(
+                        TRANSACTION_RETRY_BASE_DELAY,
+                        TRANSACTION_RETRY_MAX_DELAY,
+                        attempt as u32,
+                        true,
+                    ))
+                    .await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// The recorded operations as a SlateDB WriteBatch, or None if the batch
+    /// has a `delete_range`, which needs a transaction to resolve.
+    fn to_write_batch(&self) -> Option<SlateWriteBatch> {
+        let mut batch = SlateWriteBatch::new();
+        for op in &self.ops {
+            match op {
+                BatchOp::Put(key, value, opts) => batch.put_with_options(key, value, opts),
+                BatchOp::Merge(key, value, opts) => batch.merge_with_options(key, value, opts),
+                BatchOp::Delete(key) => batch.delete(key),
+                BatchOp::DeleteRange(..) => return None,
+            }
+        }
+        Some(batch)
+    }
+
+    /// The keys the batch puts or merges.
+    pub fn value_keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.ops.iter().filter_map(|op| match op {
+            BatchOp::Put(key, _, _) | BatchOp::Merge(key, _, _) => Some(key.as_slice()),
+            BatchOp::Delete(_) | BatchOp::DeleteRange(..) => None,
+        })
+    }
+
+    /// The keys the batch will write, with each `delete_range`
+    /// expanded to the keys visible to `reader`.
+    pub async fn keys<R>(&self, reader: &R) -> Result<Vec<Vec<u8>>, SlateError>
+    where
+        R: DbReadOps + Sync,
+    {
+        let mut keys = Vec::new();
+        for op in &self.ops {
+            match op {
+                BatchOp::Put(key, _, _) | BatchOp::Merge(key, _, _) | BatchOp::Delete(key) => {
+                    keys.push(key.clone())
+                }
+                BatchOp::DeleteRange(start, end) => {
+                    let mut iter = reader.scan(start.clone()..end.clone()).await?;
+                    while let Some(kv) = iter.next().await? {
+                        keys.push(kv.key.to_vec());
+                    }
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Replay the recorded operations, in order, into a transaction.
+    ///
+    /// A `delete_range` deletes every key in `[start, end)` visible to
+    /// the transaction, including keys it has already written. Operations are
+    /// applied one at a time, so on error the transaction may hold part of
+    /// the batch and should be rolled back.
+    ///
+    /// Returns the number of operations applied, counting each key removed
+    /// by a `delete_range`, and their key and value bytes.
+    pub async fn apply_to(&self, txn: &DbTransaction) -> Result<(usize, usize), SlateError> {
+        let mut ops = 0;
+        let mut bytes = 0;
+        for op in &self.ops {
+            match op {
+                BatchOp::Put(key, value, opts) => {
+                    txn.put_with_options(key, value, opts)?;
+                    ops += 1;
+                    bytes += key.len() + value.len();
+                }
+                BatchOp::Merge(key, value, opts) => {
+                    txn.merge_with_options(key, value, opts)?;
+                    ops += 1;
+                    bytes += key.len() + value.len();
+                }
+                BatchOp::Delete(key) => {
+                    txn.delete(key)?;
+                    ops += 1;
+                    bytes += key.len();
+                }
+                BatchOp::DeleteRange(start, end) => {
+                    let mut keys = Vec::new();
+                    let mut iter = txn.scan(start.clone()..end.clone()).await?;
+                    while let Some(kv) = iter.next().await? {
+                        keys.push(kv.key);
+                    }
+                    for key in keys {
+                        txn.delete(&key)?;
+                        ops += 1;
+                        bytes += key.len();
+                    }
+                }
+            }
+        }
+
+        Ok((ops, bytes))
+    }
+}
 
 /// Ruby wrapper for SlateDB WriteBatch.
 ///
 /// This struct is exposed to Ruby as `SlateDb::WriteBatch`.
-#[magnus::wrap(class = "SlateDb::WriteBatch", free_immediately, size)]
+#[derive(TypedData)]
+#[magnus(class = "SlateDb::WriteBatch", free_immediately, size, mark)]
 pub struct WriteBatch {
-    inner: RefCell<SlateWriteBatch>,
+    ops: RefCell<Vec<BatchOp<Opaque<Value>>>>,
+}
+
+impl DataTypeFunctions for WriteBatch {
+    /// Report the handle plus the keys of the queued operations.
+    fn size(&self) -> usize {
+        let ops = self.ops.try_borrow().map_or(0, |ops| {
+            ops.capacity() * std::mem::size_of::<BatchOp<Opaque<Value>>>()
+                + ops.iter().map(BatchOp::heap_size).sum::<usize>()
+        });
+        std::mem::size_of::<Self>() + ops
+    }
+
+    /// Keep the recorded values alive until the batch is taken.
+    fn mark(&self, marker: &gc::Marker) {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        if let Ok(ops) = self.ops.try_borrow() {
+            for value in ops.iter().filter_map(BatchOp::value) {
+                marker.mark(ruby.get_inner(value));
+            }
+        }
+    }
 }
 
 impl WriteBatch {
     /// Create a new empty WriteBatch.
     pub fn new() -> Self {
         Self {
-            inner: RefCell::new(SlateWriteBatch::new()),
+            ops: RefCell::new(Vec::new()),
         }
     }
 
     /// Add a put operation to the batch.
-    pub fn put(&self, key: String, value: String) -> Result<(), Error> {
+    ///
+    /// The value is encoded when the batch is written, by the codec of the
+    /// database or transaction it is written to.
+    pub fn put(&self, key: String, value: Value) -> Result<(), Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        self.inner
-            .borrow_mut()
-            .put(key.as_bytes(), value.as_bytes());
+        self.ops.borrow_mut().push(BatchOp::Put(
+            key.into_bytes(),
+            value.into(),
+            PutOptions::default(),
+        ));
 
         Ok(())
     }
@@ -40,23 +324,19 @@ impl WriteBatch {
     /// Add a put operation with options to the batch.
     ///
     /// Options:
-    /// - ttl: Time-to-live in milliseconds
-    pub fn put_with_options(&self, key: String, value: String, kwargs: RHash) -> Result<(), Error> {
+    /// - ttl: Time-to-live in milliseconds, :default, or :none
+    pub fn put_with_options(&self, key: String, value: Value, kwargs: RHash) -> Result<(), Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let ttl = get_optional::<u64>(&kwargs, "ttl")?;
         let put_opts = PutOptions {
-            ttl: match ttl {
-                Some(ms) => Ttl::ExpireAfter(ms),
-                None => Ttl::Default,
-            },
+            ttl: ttl_from_kwargs(&kwargs)?,
         };
 
-        self.inner
+        self.ops
             .borrow_mut()
-            .put_with_options(key.as_bytes(), value.as_bytes(), &put_opts);
+            .push(BatchOp::Put(key.into_bytes(), value.into(), put_opts));
 
         Ok(())
     }
@@ -67,20 +347,51 @@ impl WriteBatch {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        self.inner.borrow_mut().delete(key.as_bytes());
+        self.ops
+            .borrow_mut()
+            .push(BatchOp::Delete(key.into_bytes()));
+
+        Ok(())
+    }
+
+    /// Add a delete of every key in a range to the batch.
+    ///
+    /// When the batch is written, the keys in `[start, end)` are scanned and
+    /// each one is deleted, along with keys added earlier in the batch; later
+    /// operations are kept. The scan and the write commit together: a key
+    /// another writer adds to the range in between makes the write retry.
+    pub fn delete_range(&self, start: RString, end_key: RString) -> Result<(), Error> {
+        let start = rstring_to_bytes(start);
+        let end_key = rstring_to_bytes(end_key);
+
+        if start.is_empty() {
+            return Err(invalid_argument_error("start key cannot be empty"));
+        }
+        if start >= end_key {
+            return Err(invalid_argument_error(
+                "start key must be less than end key",
+            ));
+        }
+
+        self.ops
+            .borrow_mut()
+            .push(BatchOp::DeleteRange(start, end_key));
 
         Ok(())
     }
 
-    /// Add a merge operation to the batch.
-    pub fn merge(&self, key: String, value: String) -> Result<(), Error> {
+    /// Add a merge operation to the batch. The operand is encoded as for
+    /// `put`.
+    pub fn merge(&self, key: String, value: Value) -> Result<(), Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        self.inner
-            .borrow_mut()
-            .merge(key.as_bytes(), value.as_bytes());
+        self.ops.borrow_mut().push(BatchOp::Merge(
+            key.into_bytes(),
+            value.into(),
+            MergeOptions::default(),
+        ));
 
         Ok(())
     }
@@ -88,36 +399,55 @@ impl WriteBatch {
     /// Add a merge operation with options to the batch.
     ///
     /// Options:
-    /// - ttl: Time-to-live in milliseconds
+    /// - ttl: Time-to-live in milliseconds, :default, or :none
     pub fn merge_with_options(
         &self,
         key: String,
-        value: String,
+        value: Value,
         kwargs: RHash,
     ) -> Result<(), Error> {
         if key.is_empty() {
             return Err(invalid_argument_error("key cannot be empty"));
         }
 
-        let ttl = get_optional::<u64>(&kwargs, "ttl")?;
         let merge_opts = MergeOptions {
-            ttl: match ttl {
-                Some(ms) => Ttl::ExpireAfter(ms),
-                None => Ttl::Default,
-            },
+            ttl: ttl_from_kwargs(&kwargs)?,
         };
 
-        self.inner
+        self.ops
             .borrow_mut()
-            .merge_with_options(key.as_bytes(), value.as_bytes(), &merge_opts);
+            .push(BatchOp::Merge(key.into_bytes(), value.into(), merge_opts));
 
         Ok(())
     }
 
-    /// Take ownership of the inner WriteBatch (consumes it).
-    /// Used internally when writing the batch to the database.
-    pub fn take(&self) -> Result<SlateWriteBatch, Error> {
-        Ok(self.inner.replace(SlateWriteBatch::new()))
+    /// Take ownership of the recorded operations (leaving the batch empty),
+    /// encoding their values with `codec`. Used internally when writing the
+    /// batch to the database.
+    ///
+    /// Raises, leaving the batch as it was, if a value cannot be encoded or a
+    /// key or value exceeds the database's `limits`.
+    pub fn take(&self, limits: &SizeLimits, codec: Codec) -> Result<PendingBatch, Error> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        let ops = self
+            .ops
+            .borrow()
+            .iter()
+            .map(|op| op.encode(&ruby, codec))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        for op in &ops {
+            match op {
+                BatchOp::Put(key, value, _) | BatchOp::Merge(key, value, _) => {
+                    limits.check(key, value)?
+                }
+                BatchOp::Delete(key) => limits.check_key(key)?,
+                BatchOp::DeleteRange(..) => {}
+            }
+        }
+
+        self.ops.borrow_mut().clear();
+        Ok(PendingBatch { ops })
     }
 }
 
@@ -135,6 +465,7 @@ pub fn define_write_batch_class(ruby: &Ruby, module: &magnus::RModule) -> Result
         method!(WriteBatch::put_with_options, 3),
     )?;
     class.define_method("_delete", method!(WriteBatch::delete, 1))?;
+    class.define_method("_delete_range", method!(WriteBatch::delete_range, 2))?;
     class.define_method("_merge", method!(WriteBatch::merge, 2))?;
     class.define_method(
         "_merge_with_options",
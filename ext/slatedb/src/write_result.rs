@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use magnus::{method, Error, RHash, Ruby, Symbol, Value};
+use slatedb::config::WriteOptions;
+use slatedb::{Db, WriteHandle};
+
+use crate::durability_handle::DurabilityHandle;
+use crate::errors::invalid_argument_error;
+use crate::utils::get_optional;
+
+/// What a database write returns to Ruby, chosen by the `return_result`,
+/// `return_handle` and `return_token` options.
+#[derive(Clone, Copy)]
+pub enum WriteReturn {
+    Nothing,
+    Result,
+    Handle,
+    /// The write's sequence number, to pass as `min_seq` to a later read.
+    Token,
+}
+
+impl WriteReturn {
+    /// Parse the `return_result`, `return_handle` and `return_token`
+    /// options.
+    pub fn from_kwargs(kwargs: &RHash) -> Result<Self, Error> {
+        let result = get_optional::<bool>(kwargs, "return_result")?.unwrap_or(false);
+        let handle = get_optional::<bool>(kwargs, "return_handle")?.unwrap_or(false);
+        let token = get_optional::<bool>(kwargs, "return_token")?.unwrap_or(false);
+        match (result, handle, token) {
+            (false, false, false) => Ok(Self::Nothing),
+            (true, false, false) => Ok(Self::Result),
+            (false, true, false) => Ok(Self::Handle),
+            (false, false, true) => Ok(Self::Token),
+            (true, true, false) => Err(invalid_argument_error(
+                "return_result and return_handle cannot be combined",
+            )),
+            _ => Err(invalid_argument_error(
+                "return_token cannot be combined with return_result or return_handle",
+            )),
+        }
+    }
+
+    /// Build the return value for a completed write.
+    ///
+    /// `bytes` is the size of the keys and values submitted and `started`
+    /// when the write was issued.
+    pub fn build(
+        self,
+        db: &Arc<Db>,
+        handle: &WriteHandle,
+        opts: &WriteOptions,
+        bytes: usize,
+        started: Instant,
+    ) -> Option<Value> {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        match self {
+            Self::Nothing => None,
+            Self::Result => {
+                let result = WriteResult::new(handle, opts, bytes, started.elapsed());
+                Some(ruby.into_value(result))
+            }
+            Self::Handle => {
+                let durability = DurabilityHandle::new(db.clone(), handle.seqnum());
+                Some(ruby.into_value(durability))
+            }
+            Self::Token => Some(ruby.into_value(handle.seqnum())),
+        }
+    }
+}
+
+/// Ruby wrapper describing a completed write.
+///
+/// This struct is exposed to Ruby as `SlateDb::WriteResult` and is returned
+/// by database writes called with `return_result: true`.
+#[magnus::wrap(class = "SlateDb::WriteResult", free_immediately, size)]
+pub struct WriteResult {
+    seq: u64,
+    durable: bool,
+    bytes: usize,
+    duration: Duration,
+}
+
+impl WriteResult {
+    /// Describe a write from its handle and the options it was issued with.
+    ///
+    /// `bytes` is the size of the keys and values submitted and `duration`
+    /// the time spent waiting for the write to complete.
+    pub fn new(
+        handle: &WriteHandle,
+        opts: &WriteOptions,
+        bytes: usize,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            seq: handle.seqnum(),
+            durable: opts.await_durable,
+            bytes,
+            duration,
+        }
+    }
+
+    /// The sequence number assigned to the write.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// The durability level the write reached before returning: `:remote`
+    /// when it waited for object storage, `:memory` otherwise.
+    pub fn durability(&self) -> Symbol {
+        let ruby = Ruby::get().expect("Ruby runtime not available");
+        ruby.to_symbol(if self.durable { "remote" } else { "memory" })
+    }
+
+    /// Whether the write was durable in object storage when it returned.
+    pub fn is_durable(&self) -> bool {
+        self.durable
+    }
+
+    /// The number of key and value bytes submitted.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The time the write took, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.duration.as_secs_f64()
+    }
+}
+
+/// Define the WriteResult class on the SlateDb module.
+pub fn define_write_result_class(ruby: &Ruby, module: &magnus::RModule) -> Result<(), Error> {
+    let class = module.define_class("WriteResult", ruby.class_object())?;
+
+    class.define_method("seq", method!(WriteResult::seq, 0))?;
+    class.define_method("durability", method!(WriteResult::durability, 0))?;
+    class.define_method("durable?", method!(WriteResult::is_durable, 0))?;
+    class.define_method("bytes", method!(WriteResult::bytes, 0))?;
+    class.define_method("duration", method!(WriteResult::duration, 0))?;
+
+    Ok(())
+}
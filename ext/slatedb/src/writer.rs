@@ -0,0 +1,118 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use magnus::{Error, RHash, Ruby};
+use slatedb::admin::AdminBuilder;
+use slatedb::object_store::path::Path;
+use slatedb::object_store::{Error as ObjectStoreError, ObjectStore, ObjectStoreExt};
+use slatedb::Error as SlateError;
+
+use crate::utils::to_slate_error;
+
+/// Object, next to the manifests, naming the handle that last claimed the
+/// writer epoch.
+const WRITER_FILE: &str = "writer.json";
+
+/// Who is writing to a database and when they last changed its manifest.
+pub struct WriterInfo {
+    /// Writer epoch in the latest manifest. Opening a Database bumps it and
+    /// fences every writer with a lower epoch.
+    pub epoch: u64,
+    pub manifest_id: u64,
+    /// When the latest manifest was written, in seconds since the epoch.
+    pub last_manifest_write: f64,
+    /// Client id of the writer holding `epoch`, if it recorded one.
+    pub client_id: Option<String>,
+}
+
+impl WriterInfo {
+    pub fn to_hash(&self, ruby: &Ruby) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("epoch"), self.epoch)?;
+        hash.aset(ruby.to_symbol("manifest_id"), self.manifest_id)?;
+        hash.aset(
+            ruby.to_symbol("last_manifest_write"),
+            self.last_manifest_write,
+        )?;
+        hash.aset(ruby.to_symbol("client_id"), self.client_id.clone())?;
+        Ok(hash)
+    }
+}
+
+/// Record `client_id` as the writer of the database at `path`.
+///
+/// Called right after a Database opens, when its epoch is the latest one.
+///
+/// # Returns
+/// The writer epoch the database opened with
+pub async fn register_writer(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &str,
+    client_id: &str,
+) -> Result<u64, SlateError> {
+    let admin = AdminBuilder::new(path, object_store.clone()).build();
+    let epoch = admin
+        .read_manifest(None)
+        .await?
+        .map(|manifest| manifest.writer_epoch())
+        .ok_or_else(|| SlateError::internal("database has no manifest".to_string()))?;
+
+    let opened_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    let record = serde_json::json!({
+        "client_id": client_id,
+        "epoch": epoch,
+        "pid": std::process::id(),
+        "opened_at": opened_at,
+    });
+    object_store
+        .put(
+            &Path::from(path).join(WRITER_FILE),
+            record.to_string().into_bytes().into(),
+        )
+        .await
+        .map_err(to_slate_error)?;
+    Ok(epoch)
+}
+
+/// Read the writer of the database at `path`, or None if it has no manifest.
+pub async fn read_writer_info(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &str,
+) -> Result<Option<WriterInfo>, SlateError> {
+    let admin = AdminBuilder::new(path, object_store.clone()).build();
+    let Some(manifest) = admin.read_manifest(None).await? else {
+        return Ok(None);
+    };
+    let root = Path::from(path);
+
+    let location = root
+        .clone()
+        .join("manifest")
+        .join(format!("{:020}.manifest", manifest.id()));
+    let meta = object_store.head(&location).await.map_err(to_slate_error)?;
+
+    let recorded = match object_store.get(&root.join(WRITER_FILE)).await {
+        Ok(result) => {
+            let bytes = result.bytes().await.map_err(to_slate_error)?;
+            serde_json::from_slice::<serde_json::Value>(&bytes)
+                .map_err(|e| SlateError::data(format!("invalid writer record: {}", e)))?
+        }
+        Err(ObjectStoreError::NotFound { .. }) => serde_json::Value::Null,
+        Err(e) => return Err(to_slate_error(e)),
+    };
+    // A record from an older epoch names a writer that has since been fenced.
+    let client_id = recorded["client_id"]
+        .as_str()
+        .filter(|_| recorded["epoch"].as_u64() == Some(manifest.writer_epoch()))
+        .map(str::to_string);
+
+    Ok(Some(WriterInfo {
+        epoch: manifest.writer_epoch(),
+        manifest_id: manifest.id(),
+        last_manifest_write: meta.last_modified.timestamp_millis() as f64 / 1000.0,
+        client_id,
+    }))
+}
@@ -11,11 +11,33 @@ rescue LoadError
 end
 
 # Load Ruby class extensions
+require_relative "slatedb/errors"
 require_relative "slatedb/database"
 require_relative "slatedb/iterator"
+require_relative "slatedb/change_stream"
+require_relative "slatedb/watcher"
 require_relative "slatedb/write_batch"
+require_relative "slatedb/write_result"
+require_relative "slatedb/durability_handle"
+require_relative "slatedb/future"
 require_relative "slatedb/transaction"
 require_relative "slatedb/snapshot"
+require_relative "slatedb/snapshot_diff"
+require_relative "slatedb/namespace"
 require_relative "slatedb/reader"
 require_relative "slatedb/admin"
+require_relative "slatedb/checkpoint_lease"
+require_relative "slatedb/clock"
+require_relative "slatedb/testing"
 require_relative "slatedb/metrics"
+require_relative "slatedb/logging"
+require_relative "slatedb/credentials"
+require_relative "slatedb/encryption"
+require_relative "slatedb/store_check"
+require_relative "slatedb/tracing"
+require_relative "slatedb/instrumentation"
+require_relative "slatedb/shutdown"
+require_relative "slatedb/fork"
+
+# Loaded last: LazyDatabase delegates to the Database methods defined above
+require_relative "slatedb/lazy_database"
@@ -7,14 +7,22 @@ module SlateDb
       #
       # @param path [String] Database path
       # @param url [String, nil] Optional object store URL
+      # @param store_options [Hash, nil] Settings for the object store behind
+      #   `url`; see {Database.open}
+      # @param credentials_provider [#call, nil] Supplies S3 credentials; see
+      #   {Database.open}
+      # @param client_options [Hash, nil] HTTP client timeouts, retries and
+      #   proxy for the object store; see {Database.open}
       # @return [Admin] The admin handle
       #
       # @example
       #   admin = SlateDb::Admin.new("/tmp/mydb")
       #   checkpoints = admin.list_checkpoints
       #
-      def new(path, url: nil)
-        _new(path, url)
+      def new(path, url: nil, store_options: nil, credentials_provider: nil, client_options: nil)
+        opts = {}
+        opts.merge!(SlateDb.store_open_options(store_options, credentials_provider, client_options))
+        _new(path, url, opts)
       end
     end
 
@@ -65,6 +73,30 @@ module SlateDb
       _create_checkpoint(opts)
     end
 
+    # Create a named checkpoint of the latest manifest, replacing any
+    # checkpoints that already have the name.
+    #
+    # The old checkpoints are deleted only after the new one exists, so a
+    # "nightly" checkpoint is never missing in between. Two concurrent calls
+    # can each leave a checkpoint behind, but never delete each other's.
+    #
+    # @param name [String] Checkpoint name
+    # @param lifetime [Integer, nil] Checkpoint lifetime in milliseconds
+    # @param replace [Boolean] Replace existing checkpoints with the name
+    #   (default: true). When false, raise instead if the name is taken.
+    # @return [Hash] Hash with :id (UUID string), :manifest_id (Integer) and
+    #   :replaced (ids of the deleted checkpoints)
+    # @raise [InvalidArgumentError] If `replace` is false and a checkpoint
+    #   with the name exists
+    #
+    # @example
+    #   admin.create_checkpoint!(name: "nightly", lifetime: 2 * 86_400_000)
+    #   # => { id: "uuid-string", manifest_id: 42, replaced: ["old-uuid"] }
+    #
+    def create_checkpoint!(name:, lifetime: nil, replace: true)
+      _create_checkpoint_replacing({ name:, lifetime:, replace: }.compact)
+    end
+
     # List known checkpoints for the database.
     #
     # @param name [String, nil] Optional checkpoint name filter
@@ -93,6 +125,39 @@ module SlateDb
       _refresh_checkpoint(id, lifetime)
     end
 
+    # Keep a checkpoint alive until released.
+    #
+    # A runtime task refreshes the checkpoint every `refresh_every_ms`,
+    # extending it to expire `lifetime_ms` from the refresh. Use it for
+    # long-running jobs reading at a checkpoint. Once released, the
+    # checkpoint expires at the end of its current lifetime.
+    #
+    # With a block, yields the lease and releases it when the block returns.
+    #
+    # @param id [String] Checkpoint UUID string
+    # @param refresh_every_ms [Integer] Interval between refreshes
+    # @param lifetime_ms [Integer, nil] Lifetime set on each refresh.
+    #   Defaults to three intervals, so two refreshes can fail in a row.
+    # @return [CheckpointLease, Object] The lease, or the block's result
+    # @raise [InvalidArgumentError] If the id is not a UUID or the
+    #   lifetime is not longer than the interval
+    #
+    # @example
+    #   admin.hold_checkpoint(id, refresh_every_ms: 60_000) do
+    #     run_report(checkpoint: id)
+    #   end
+    #
+    def hold_checkpoint(id, refresh_every_ms:, lifetime_ms: nil)
+      lease = _hold_checkpoint(id, { refresh_every_ms:, lifetime_ms: }.compact)
+      return lease unless block_given?
+
+      begin
+        yield lease
+      ensure
+        lease.release
+      end
+    end
+
     # Delete a checkpoint.
     #
     # @param id [String] Checkpoint UUID string
@@ -107,16 +172,367 @@ module SlateDb
 
     # Run garbage collection once.
     #
+    # With a block, each directory (manifest, WAL, compacted SSTs, compaction
+    # state and clone detachment) is collected in its own pass and the block
+    # is called after each.
+    #
     # @param min_age [Integer, nil] Minimum age in milliseconds for objects to be collected
-    # @return [void]
+    # @param manifest_min_age [Integer, nil] Overrides `min_age` for manifests
+    # @param wal_min_age [Integer, nil] Overrides `min_age` for WAL SSTs
+    # @param compacted_min_age [Integer, nil] Overrides `min_age` for compacted SSTs
+    # @param report [Boolean] List the database's objects before and after
+    #   collecting to report what was deleted (default: true). Pass false to
+    #   skip the listings on very large databases.
+    # @yieldparam progress [Hash] :phase (the directory just collected),
+    #   :completed and :total passes, and :percent
+    # @return [Hash, nil] Hash with :deleted_objects, :deleted_bytes,
+    #   :duration (in seconds) and :directories, mapping each directory
+    #   GC deleted from ("manifest", "wal", "compacted", ...) to its
+    #   :objects and :bytes; nil with `report: false`
     #
     # @example
     #   admin.run_gc(min_age: 3600_000) # 1 hour
+    #   admin.run_gc { |p| puts "#{p[:phase]}: #{p[:percent].round}%" }
     #
-    def run_gc(min_age: nil)
-      opts = {}
-      opts[:min_age] = min_age if min_age
-      _run_gc(opts)
+    # @example Log what was collected
+    #   report = admin.run_gc
+    #   logger.info("gc deleted #{report[:deleted_objects]} objects (#{report[:deleted_bytes]} bytes)")
+    #
+    def run_gc(min_age: nil, manifest_min_age: nil, wal_min_age: nil, compacted_min_age: nil, report: true,
+               &progress)
+      opts = { min_age:, manifest_min_age:, wal_min_age:, compacted_min_age:, report: }.compact
+      _run_gc(opts, progress)
+    end
+
+    # Copy the SSTs added since a previous backup, plus the latest manifest,
+    # to another object store.
+    #
+    # The backup is pinned with a short-lived checkpoint while copying so that
+    # garbage collection cannot remove the files being copied. WAL files that
+    # have not reached L0 yet are copied too, and the manifest is written last.
+    # Each file whose copy fails because a store is unavailable or throttling
+    # is retried on its own, with backoff.
+    # Pass the returned `:manifest_id` as `since_manifest_id` on the next run;
+    # that manifest must not have been garbage collected in the meantime.
+    #
+    # @param since_manifest_id [Integer, nil] Manifest id returned by the
+    #   previous backup, or nil to copy everything
+    # @param dest_url [String] Object store URL to copy into
+    # @yieldparam progress [Hash] :phase (:ssts, then :manifest once the WAL
+    #   and manifest are copied), :copied_ssts, :total_ssts,
+    #   :copied_wal_ssts, :copied_bytes and :percent, after each SST
+    # @return [Hash] Hash with :manifest_id (the new high-water mark),
+    #   :copied_ssts, :copied_wal_ssts and :copied_bytes
+    #
+    # @example Nightly backups
+    #   result = admin.incremental_backup(last_manifest_id, "s3://backups/mydb")
+    #   last_manifest_id = result[:manifest_id]
+    #
+    # @example With progress
+    #   admin.incremental_backup(nil, "s3://backups/mydb") { |p| puts "#{p[:percent].round}%" }
+    #
+    def incremental_backup(since_manifest_id, dest_url, &progress)
+      _incremental_backup(since_manifest_id, dest_url, progress)
+    end
+
+    # Verify that a backup of this database can be restored.
+    #
+    # Checks that every SST referenced by the backup's manifest exists, then
+    # opens the backup with a read-only reader and reads every entry back,
+    # computing a fingerprint of the contents. Read failures are reported in
+    # the result rather than raised. Nothing is written to the backup: the
+    # checkpoint a reader registers without a checkpoint id is kept in
+    # memory.
+    #
+    # @param src_url [String] Object store URL holding the backup
+    # @param checkpoint_id [String, nil] Checkpoint UUID string to verify, or
+    #   nil to verify the latest manifest
+    # @yieldparam progress [Hash] :phase (:ssts once the SSTs are checked,
+    #   with :checked_ssts and :missing_ssts, then :entries every 10,000
+    #   keys read, with :keys and :bytes)
+    # @return [Hash] Report with :ok, :manifest_id, :checked_ssts,
+    #   :missing_ssts, :keys, :bytes, :fingerprint and :error
+    #
+    # @example
+    #   report = admin.verify_backup("s3://backups/mydb")
+    #   raise "backup is not restorable: #{report[:error]}" unless report[:ok]
+    #
+    def verify_backup(src_url, checkpoint_id = nil, &progress)
+      _verify_backup(src_url, checkpoint_id, progress)
+    end
+
+    # Export the database's entries to a JSON Lines or CSV file.
+    #
+    # Entries are read through a read-only reader, so the export sees a
+    # consistent view and a running writer is not disturbed. Each JSON line
+    # holds `key`, `value` and, for entries with a TTL, `expire_ts` in
+    # milliseconds since the Unix epoch; keys and values that are not valid
+    # UTF-8 are base64-encoded under `key_base64` and `value_base64`. CSV
+    # files have a `key,value,expire_ts` header and hold text only.
+    #
+    # @param dest [String, Pathname, IO] File path or IO to write to
+    # @param format [Symbol] :jsonl or :csv
+    # @param range [Range, nil] Key range to export, or nil for every key
+    # @param batch_size [Integer, nil] Entries read between progress reports
+    #   (default: 10,000)
+    # @yieldparam progress [Hash] :entries and :bytes written so far
+    # @return [Hash] Hash with :entries and :bytes
+    #
+    # @example
+    #   admin.export("backup.jsonl") { |p| puts "#{p[:entries]} entries" }
+    #   admin.export($stdout, format: :csv, range: "user:".."user;")
+    #
+    def export(dest, format: :jsonl, range: nil, batch_size: nil, &progress)
+      opts = { format: format.to_s, batch_size: }.compact
+      if range
+        opts[:range_start] = range.begin
+        opts[:range_end] = range.end
+        opts[:range_exclusive] = range.exclude_end?
+      end
+      _export(path_or_io(dest), opts.compact, progress)
+    end
+
+    # Import entries written by {#export}.
+    #
+    # Opens the database as its writer, which fences any other open writer,
+    # and writes entries in batches, then flushes. Entries whose `expire_ts`
+    # has passed are skipped. A CSV header line is optional.
+    #
+    # @param source [String, Pathname, IO] File path or IO to read from
+    # @param format [Symbol] :jsonl or :csv
+    # @param batch_size [Integer, nil] Entries per write batch (default: 10,000)
+    # @yieldparam progress [Hash] :entries, :skipped and :bytes read so far,
+    #   plus :percent when reading from a file
+    # @return [Hash] Hash with :entries, :skipped and :bytes
+    #
+    # @example
+    #   admin.import("backup.jsonl") { |p| puts "#{p[:percent].round}%" }
+    #
+    def import(source, format: :jsonl, batch_size: nil, &progress)
+      _import(path_or_io(source), { format: format.to_s, batch_size: }.compact, progress)
+    end
+
+    # Re-encrypt the database under a new key by compacting the whole tree.
+    #
+    # Submits one compaction merging every L0 SST and sorted run, as
+    # {#purge_expired} does, and runs a compaction worker that writes with
+    # `new_key` until it finishes. The worker neither opens the database as
+    # its writer nor replaces its compactor, so the running writer is not
+    # fenced. That writer schedules the compaction and its own worker may
+    # run it, so reopen it with `new_key` (and `old_key` in `previous_keys`)
+    # first; the database must be open for the compaction to make progress.
+    #
+    # The old key can be dropped from `previous_keys` once this returns,
+    # provided every writer and reader was already using the new key when
+    # it was called and no checkpoint (or clone) taken before then is still
+    # needed: checkpoints keep the old SSTs alive until they are deleted.
+    #
+    # @param old_key [String, Hash, #call] The key the data is encrypted
+    #   with, in any form the `encryption:` open option takes; its
+    #   `previous_keys` are used too
+    # @param new_key [String, Hash, #call] The key to encrypt it with, with
+    #   a different `key_id`
+    # @param merge_operator [String, nil] The database's built-in merge
+    #   operator, so merge operands are combined as the writer would
+    # @param timeout_ms [Integer, nil] How long to wait before raising
+    #   TimeoutError (the compaction is handed back to the writer's worker)
+    # @yieldparam progress [Hash] :bytes compacted so far
+    # @return [Hash, nil] Hash with :id, :status (:completed, or :finished if
+    #   a later compaction has already replaced its record), :l0_ssts,
+    #   :sorted_runs, :destination, :bytes and the new :key_id; or nil if
+    #   nothing has been flushed yet
+    # @raise [InvalidArgumentError] if both keys have the same key id
+    # @raise [SlateDb::InternalError] if the compaction fails
+    #
+    # @example
+    #   keys = { key_id: "2024-10", key: new, previous_keys: { "2024-01" => old } }
+    #   db = SlateDb::Database.open("mydb", url:, encryption: keys)
+    #   admin.rewrap_keys(old_key: { key_id: "2024-01", key: old },
+    #                     new_key: { key_id: "2024-10", key: new }) { |p| puts p[:bytes] }
+    #
+    def rewrap_keys(old_key:, new_key:, merge_operator: nil, timeout_ms: nil, &progress)
+      opts = { encryption: rewrap_encryption(old_key, new_key), merge_operator: merge_operator&.to_s, timeout_ms: }
+      _rewrap_keys(opts.compact, progress)
+    end
+
+    # Sample the sizes of the database's keys and values, to find the keys or
+    # prefixes taking up the most space.
+    #
+    # Every entry is read through a read-only reader, so a running writer is
+    # not disturbed, but only a `sample_rate` fraction of keys is recorded.
+    # Keys are picked by hash rather than at random, so repeated runs sample
+    # the same keys. A key's prefix is everything up to and including the
+    # first `prefix_delimiter`, or the whole key if it has none.
+    #
+    # @param sample_rate [Float] Fraction of keys to sample, in (0, 1]
+    # @param prefix_delimiter [String] Single-byte delimiter ending a prefix,
+    #   or "" to group by whole key
+    # @param top [Integer] Number of prefixes to report
+    # @return [Hash] Hash with :scanned_entries, :sampled_entries,
+    #   :sample_rate, :duration, :keys and :values (each with :count, :min,
+    #   :max, :mean, :p50, :p90, :p99 and a :histogram of sizes rounded up to
+    #   a power of two), and :prefixes, the `top` prefixes by estimated size,
+    #   each with :prefix, :estimated_keys and :estimated_bytes
+    #
+    # @example Find the tenant using the most space
+    #   stats = admin.sample_stats(sample_rate: 0.05)
+    #   stats[:prefixes].first # => { prefix: "tenant42:", estimated_keys: 1_200_000, ... }
+    #
+    def sample_stats(sample_rate: nil, prefix_delimiter: nil, top: nil)
+      _sample_stats({ sample_rate: sample_rate&.to_f, prefix_delimiter:, top: }.compact)
+    end
+
+    # Count the entries and bytes under each of the given key prefixes, for
+    # example to bill tenants by the space their keys take up.
+    #
+    # Each prefix is scanned through a read-only reader, so a running writer
+    # is not disturbed. With a `sample_rate` below 1 only that fraction of
+    # keys is counted and the totals are scaled up to estimates; every entry
+    # is still read. Overlapping prefixes are each counted in full.
+    #
+    # @param prefixes [Array<String>] Key prefixes to count
+    # @param sample_rate [Float] Fraction of keys to count, in (0, 1]
+    # @return [Hash{String => Hash}] Hash of prefix to its :entries,
+    #   :key_bytes, :value_bytes and :bytes (keys plus values)
+    #
+    # @example Bytes used per tenant
+    #   admin.prefix_stats(["tenant:a:", "tenant:b:"])
+    #   # => { "tenant:a:" => { entries: 1200, key_bytes: 14_400, value_bytes: 96_000, bytes: 110_400 }, ... }
+    #
+    def prefix_stats(prefixes, sample_rate: nil)
+      _prefix_stats(Array(prefixes).map(&:to_s), { sample_rate: sample_rate&.to_f }.compact)
+    end
+
+    # Count the rows in the compacted SSTs whose TTL has passed but that
+    # compaction hasn't removed yet, to check TTLs are reclaiming space.
+    #
+    # Every L0 and sorted run SST in the latest manifest is read, without
+    # disturbing a running writer. Rows still in the memtable or WAL are not
+    # counted. Compaction judges expiry by the time of the latest write
+    # flushed to L0 (`:compaction_clock`) rather than the wall clock, so only
+    # expired rows at or before it are `reclaimable`; the rest wait for a
+    # newer write to reach L0.
+    #
+    # @param now [Time, Integer, nil] Time to judge expiry at, as a Time or
+    #   milliseconds since the epoch (default: now)
+    # @return [Hash] Hash with :now and :compaction_clock (milliseconds since
+    #   the epoch), :duration, and :ssts, :entries, :bytes,
+    #   :expired_entries, :expired_bytes, :reclaimable_entries and
+    #   :reclaimable_bytes across the database, plus the same counts for :l0
+    #   and for each of :sorted_runs (with its :id)
+    #
+    # @example
+    #   stats = admin.expired_stats
+    #   puts "#{stats[:expired_bytes]} of #{stats[:bytes]} bytes have expired"
+    #
+    def expired_stats(now: nil)
+      now = (now.to_r * 1000).to_i if now.is_a?(Time)
+      _expired_stats({ now: }.compact)
+    end
+
+    # Rewrite the whole tree in one compaction, dropping the rows
+    # `expired_stats` reports as reclaimable.
+    #
+    # Every L0 SST and sorted run is merged into the lowest sorted run. The
+    # compaction is submitted to the database's compactor, which runs in
+    # the writer, so the database must be open for it to make progress.
+    # The space the old SSTs held is freed once garbage collection deletes
+    # them.
+    #
+    # @param wait [Boolean] Block until the compaction finishes
+    # @param timeout_ms [Integer, nil] With `wait`, how long to wait before
+    #   raising TimeoutError (the compaction carries on)
+    # @return [Hash, nil] Hash with :id, :status (:submitted, or after
+    #   waiting :completed, or :finished if a later compaction has already
+    #   replaced its record), :l0_ssts, :sorted_runs and :destination; or
+    #   nil if nothing has been flushed yet
+    # @raise [SlateDb::InternalError] if the compaction fails
+    #
+    # @example
+    #   admin.purge_expired(wait: true, timeout_ms: 60_000)
+    #   admin.run_gc(compacted_min_age: 0)
+    #
+    def purge_expired(wait: false, timeout_ms: nil)
+      _purge_expired({ wait:, timeout_ms: }.compact)
+    end
+
+    # Describe the database's current writer, to tell whether it is safe to
+    # promote a new one.
+    #
+    # The epoch comes from the latest manifest; the client id from the
+    # `writer.json` record the writer left when it opened, and is nil if
+    # that record belongs to an older epoch or is missing. A
+    # `:last_manifest_write` far in the past, on a database that is expected
+    # to be taking writes, suggests the writer has stalled.
+    #
+    # @return [Hash, nil] `:epoch`, `:manifest_id`, `:last_manifest_write`
+    #   (a Time) and `:client_id`, or nil if the database has no manifest
+    #
+    # @example
+    #   writer = admin.active_writer
+    #   promote! if writer.nil? || Time.now - writer[:last_manifest_write] > 300
+    #
+    def active_writer
+      info = _active_writer
+      info&.merge(last_manifest_write: Time.at(info[:last_manifest_write]))
+    end
+
+    # Read the labels stamped on the database.
+    #
+    # @return [Hash{String => String}] Label names to values, empty if none
+    #   have been set
+    #
+    # @example
+    #   admin.metadata # => { "environment" => "production", "owner" => "payments" }
+    #
+    def metadata
+      _metadata
+    end
+
+    # Set or remove labels on the database, such as its owner, environment or
+    # schema version.
+    #
+    # Labels are merged into the existing set; a nil value removes a label.
+    # They are stored as JSON in a `metadata.json` object under the database
+    # path, so tooling with access to the object store can discover them
+    # without opening the database.
+    #
+    # The object is replaced with a conditional write against the version
+    # that was read, so concurrent admins never drop each other's labels;
+    # a lost race re-reads and re-applies the update. The local filesystem
+    # store has no conditional writes, so there updates are last-writer-wins.
+    #
+    # @param labels [Hash] Label names to String values, or nil to remove
+    # @return [Hash{String => String}] All labels after the update
+    # @raise [SlateDb::TransactionError] if the metadata kept changing underneath
+    #
+    # @example
+    #   admin.set_metadata(owner: "payments", environment: "production", schema_version: 3)
+    #   admin.metadata["owner"] # => "payments"
+    #   admin.set_metadata(environment: nil)
+    #
+    def set_metadata(labels)
+      _set_metadata(labels.to_h { |name, value| [name.to_s, value&.to_s] })
+    end
+
+    private
+
+    def path_or_io(target)
+      defined?(Pathname) && target.is_a?(Pathname) ? target.to_s : target
+    end
+
+    # The `encryption:` option that decrypts with `old_key` and encrypts
+    # with `new_key`.
+    def rewrap_encryption(old_key, new_key)
+      old_key = SlateDb.encryption_options(old_key)
+      new_key = SlateDb.encryption_options(new_key)
+      old_id = old_key[:key_id] || "default"
+      new_id = new_key[:key_id] || "default"
+      raise InvalidArgumentError, "old_key and new_key must have different key_ids, got #{new_id.inspect}" if old_id == new_id
+
+      previous_keys = (old_key[:previous_keys] || {}).merge(new_key[:previous_keys] || {})
+      previous_keys[old_id] = old_key[:key]
+      { key: new_key[:key], key_id: new_id, previous_keys: previous_keys.except(new_id) }
     end
   end
 end
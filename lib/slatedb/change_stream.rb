@@ -0,0 +1,55 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class ChangeStream
+    include Enumerable
+
+    # Iterate over the changes written so far.
+    #
+    # Stops once the stream has caught up with the WAL. Calling it again
+    # continues from where the last call stopped.
+    #
+    # @yield [change] Yields each change as a hash with :key, :value, :op
+    #   (:put, :delete or :merge), :seq and :timestamp (milliseconds)
+    # @return [self, Enumerator] Returns self if block given, otherwise an Enumerator
+    #
+    # @example Invalidate cached entries
+    #   stream = db.changes(since_seq: checkpoint)
+    #   stream.each { |change| cache.delete(change[:key]) }
+    #   checkpoint = stream.last_seq
+    #
+    def each
+      return to_enum(:each) unless block_given?
+
+      while (change = next_change)
+        yield change
+      end
+
+      self
+    end
+
+    # Follow the stream, waiting for new changes once it has caught up.
+    #
+    # Runs until the block breaks or raises.
+    #
+    # @param interval [Numeric] Seconds to sleep between polls when caught up
+    # @yield [change] Yields each change, as for {#each}
+    # @return [void]
+    #
+    # @example Feed a search index
+    #   db.changes(since_seq: indexer.position).follow do |change|
+    #     indexer.apply(change)
+    #   end
+    #
+    def follow(interval: 0.1)
+      loop do
+        change = next_change
+        if change
+          yield change
+        else
+          sleep interval
+        end
+      end
+    end
+  end
+end
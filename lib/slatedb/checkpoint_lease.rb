@@ -0,0 +1,17 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class CheckpointLease
+    # Whether the lease has stopped refreshing, either because it was
+    # released or because the checkpoint no longer exists.
+    #
+    # @return [Boolean]
+    def released?
+      !active?
+    end
+
+    def inspect
+      "#<#{self.class.name} id=#{id} refreshes=#{refresh_count} active=#{active?}>"
+    end
+  end
+end
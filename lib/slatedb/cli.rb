@@ -0,0 +1,197 @@
+# frozen_string_literal: true
+
+require "json"
+require "optparse"
+require_relative "../slatedb"
+
+module SlateDb
+  # The `slatedb` command-line tool, for poking at a database without writing
+  # a script.
+  #
+  # Every command names the database explicitly with `--url` and `--path`,
+  # plus any `--store-option`s, and nothing is read from a config file. Reads
+  # go through a read-only {Reader}, so they never disturb a running writer;
+  # `put` opens the database as its writer, which fences any other writer.
+  #
+  # @example
+  #   slatedb --url s3://bucket/dbs --path orders get order:42
+  #   slatedb --url s3://bucket/dbs --path orders scan order: --limit 10
+  #   slatedb --url s3://bucket/dbs --path orders export orders.jsonl --progress
+  class CLI
+    # Exit status for bad arguments, as in sysexits.h.
+    EX_USAGE = 64
+
+    # The smallest possible key, since keys cannot be empty.
+    FIRST_KEY = "\0"
+
+    BANNER = <<~TEXT
+      Usage: slatedb --url URL --path PATH [options] COMMAND [ARGS]
+
+      Commands:
+          get KEY                      Print the value stored under KEY
+          put KEY VALUE                Store VALUE ("-" reads stdin) under KEY; fences the writer
+          scan [PREFIX]                Print keys and values, tab-separated
+          list-manifests               Print the manifests as JSON
+          create-checkpoint            Create a checkpoint and print it as JSON
+          gc                           Run garbage collection once
+          verify BACKUP_URL [ID]       Verify a backup of the database and print the report
+          export FILE                  Export entries to FILE ("-" writes stdout)
+
+      Options:
+    TEXT
+
+    # Command names mapped to the method that runs them and the number of
+    # arguments they take.
+    COMMANDS = {
+      "get" => [:get, 1..1],
+      "put" => [:put, 2..2],
+      "scan" => [:scan, 0..1],
+      "list-manifests" => [:list_manifests, 0..0],
+      "create-checkpoint" => [:create_checkpoint, 0..0],
+      "gc" => [:gc, 0..0],
+      "verify" => [:verify, 1..2],
+      "export" => [:export, 1..1]
+    }.freeze
+
+    class UsageError < StandardError; end
+
+    # Run the tool and exit with its status.
+    #
+    # @param argv [Array<String>] Command-line arguments
+    def self.start(argv = ARGV)
+      exit(new.run(argv))
+    end
+
+    # @param stdin [IO] Where `put -` reads values from
+    # @param stdout [IO] Where results are written
+    # @param stderr [IO] Where errors and progress are written
+    def initialize(stdin: $stdin, stdout: $stdout, stderr: $stderr)
+      @stdin = stdin
+      @stdout = stdout
+      @stderr = stderr
+      @options = { store_options: {} }
+    end
+
+    # Run a command.
+    #
+    # @param argv [Array<String>] Command-line arguments
+    # @return [Integer] Exit status: 0 on success, 1 if a key was not found,
+    #   a backup failed verification or the database raised, 64 for bad usage
+    def run(argv)
+      command, *args = parser.parse(argv, into: @options)
+      name, arity = COMMANDS.fetch(command) { raise UsageError, command ? "unknown command: #{command}" : "no command" }
+      raise UsageError, "wrong number of arguments for #{command}" unless arity.cover?(args.size)
+      raise UsageError, "--url and --path are required" unless @options[:url] && @options[:path]
+
+      send(name, *args) ? 0 : 1
+    rescue OptionParser::ParseError, UsageError => e
+      @stderr.puts("slatedb: #{e.message}", parser.help)
+      EX_USAGE
+    rescue SlateDb::Error => e
+      @stderr.puts("slatedb: #{e.message}")
+      1
+    end
+
+    private
+
+    def parser
+      @parser ||= OptionParser.new do |opts|
+        opts.banner = BANNER
+        opts.on("--url URL", "Object store URL, e.g. s3://bucket/prefix")
+        opts.on("--path PATH", "Database path within the object store")
+        opts.on("--store-option KEY=VALUE", "Object store setting; repeatable") { |v| add_store_option(v) }
+        opts.on("--limit N", Integer, "scan: stop after N entries")
+        opts.on("--json", "get, scan: print JSON")
+        opts.on("--start ID", Integer, "list-manifests: first manifest id")
+        opts.on("--end ID", Integer, "list-manifests: manifest id to stop before")
+        opts.on("--name NAME", "create-checkpoint: checkpoint name")
+        opts.on("--lifetime MS", Integer, "create-checkpoint: lifetime in milliseconds")
+        opts.on("--min-age MS", Integer, "gc: minimum age in milliseconds")
+        opts.on("--format FORMAT", %w[jsonl csv], "export: jsonl (default) or csv")
+        opts.on("--progress", "gc, verify, export: report progress on stderr")
+      end
+    end
+
+    def add_store_option(pair)
+      key, value = pair.split("=", 2)
+      raise OptionParser::InvalidArgument, pair if value.nil? || key.empty?
+
+      @options[:store_options][key.to_sym] = value
+    end
+
+    def store_args
+      { url: @options[:url], store_options: @options[:store_options] }
+    end
+
+    def reader(&)
+      Reader.open(@options[:path], **store_args, &)
+    end
+
+    def admin
+      @admin ||= Admin.new(@options[:path], **store_args)
+    end
+
+    def progress
+      return unless @options[:progress]
+
+      proc do |progress|
+        @stderr.puts(progress.map { |name, value| "#{name}=#{value.is_a?(Float) ? value.round(1) : value}" }.join(" "))
+      end
+    end
+
+    def get(key)
+      value = reader { |r| r.get(key) }
+      return false if value.nil?
+
+      @stdout.puts(@options[:json] ? JSON.generate(key => value) : value)
+      true
+    end
+
+    def put(key, value)
+      value = @stdin.read if value == "-"
+      Database.open(@options[:path], **store_args) { |db| db.put(key, value) }
+      true
+    end
+
+    def scan(prefix = nil)
+      reader do |r|
+        iter = prefix ? r.scan_prefix(prefix) : r.scan(FIRST_KEY)
+        iter = iter.take(@options[:limit]) if @options[:limit]
+        iter.each do |key, value|
+          @stdout.puts(@options[:json] ? JSON.generate(key => value) : "#{key}\t#{value}")
+        end
+      end
+      true
+    end
+
+    def list_manifests
+      @stdout.puts(admin.list_manifests(start: @options[:start], end_id: @options[:end]))
+      true
+    end
+
+    def create_checkpoint
+      checkpoint = admin.create_checkpoint(name: @options[:name], lifetime: @options[:lifetime])
+      @stdout.puts(JSON.generate(checkpoint))
+      true
+    end
+
+    def gc
+      report = admin.run_gc(min_age: @options[:"min-age"], &progress)
+      @stdout.puts(JSON.generate(report))
+      true
+    end
+
+    def verify(backup_url, checkpoint_id = nil)
+      report = admin.verify_backup(backup_url, checkpoint_id, &progress)
+      @stdout.puts(JSON.generate(report))
+      report[:ok]
+    end
+
+    def export(file)
+      format = (@options[:format] || "jsonl").to_sym
+      result = admin.export(file == "-" ? @stdout : file, format:, &progress)
+      @stdout.puts(JSON.generate(result)) unless file == "-"
+      true
+    end
+  end
+end
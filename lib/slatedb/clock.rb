@@ -0,0 +1,76 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # A clock that only moves when told to, for testing time-dependent
+  # behavior (TTL expiry, checkpoint lifetimes, garbage collection `min_age`)
+  # without sleeping. Install it with `SlateDb.test_clock=`.
+  #
+  # @example
+  #   clock = SlateDb::ManualClock.new
+  #   SlateDb.test_clock = clock
+  #   SlateDb::Database.open("/tmp/db") do |db|
+  #     db.put("session", "data", ttl: 60_000)
+  #     clock.advance(61)
+  #     db.get_key_value("session")[:ttl] # => 0
+  #   end
+  class ManualClock
+    class << self
+      # Create a clock stopped at the given time.
+      #
+      # @param now [Time, Integer] Start time, or milliseconds since the epoch
+      # @return [ManualClock]
+      def new(now = Time.now)
+        _new(now.is_a?(Integer) ? now : (now.to_r * 1000).to_i)
+      end
+    end
+
+    # The clock's current time.
+    #
+    # @return [Time]
+    def now
+      Time.at(now_ms, :millisecond)
+    end
+
+    # Move the clock forward.
+    #
+    # @param seconds [Numeric] Seconds to advance by
+    # @return [Time] The new time
+    # @raise [InvalidArgumentError] if seconds is negative
+    def advance(seconds)
+      Time.at(_advance_ms((seconds * 1000).round), :millisecond)
+    end
+
+    # Move the clock forward to the given time.
+    #
+    # @param time [Time, Integer] The new time, or milliseconds since the epoch
+    # @return [Time] The new time
+    # @raise [InvalidArgumentError] if time is earlier than the clock's time
+    def set(time)
+      _set_ms(time.is_a?(Integer) ? time : (time.to_r * 1000).to_i)
+      now
+    end
+
+    def inspect
+      "#<#{self.class.name} now_ms=#{now_ms}>"
+    end
+  end
+
+  class << self
+    # The clock installed with `test_clock=`, if any.
+    #
+    # @return [ManualClock, nil]
+    attr_reader :test_clock
+
+    # Use a ManualClock instead of the system clock.
+    #
+    # Applies to databases, readers and admins opened afterwards; handles
+    # already open keep their clock. Set to nil to go back to the system
+    # clock. Intended for tests only.
+    #
+    # @param clock [ManualClock, nil]
+    def test_clock=(clock)
+      _set_test_clock(clock)
+      @test_clock = clock
+    end
+  end
+end
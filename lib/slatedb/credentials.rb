@@ -0,0 +1,98 @@
+# frozen_string_literal: true
+
+module SlateDb
+  @credentials_mutex = Mutex.new
+
+  class << self
+    # Supply S3 credentials from Ruby, for short-lived credentials such as
+    # those from STS AssumeRole or Kubernetes IRSA.
+    #
+    # The block is called with no arguments from a background thread whenever
+    # a store needs credentials: when it is opened, and again shortly before
+    # the `expires_at` of the last credentials. It returns a Hash with
+    # `access_key_id`, `secret_access_key` and optionally `session_token` and
+    # `expires_at` (a Time or seconds since the epoch). Without `expires_at`
+    # the first credentials are used for the life of the store.
+    #
+    # The provider applies to every S3 store opened without
+    # `credentials_provider:`, including backups. Called without a block,
+    # returns the current provider.
+    #
+    # @yieldreturn [Hash] The credentials
+    # @return [Proc, nil] The provider
+    #
+    # @example
+    #   sts = Aws::STS::Client.new
+    #   SlateDb.credentials_provider do
+    #     creds = sts.assume_role(role_arn: ROLE_ARN, role_session_name: "slatedb").credentials
+    #     { access_key_id: creds.access_key_id, secret_access_key: creds.secret_access_key,
+    #       session_token: creds.session_token, expires_at: creds.expiration }
+    #   end
+    def credentials_provider(&block)
+      self.credentials_provider = block if block
+      @credentials_provider
+    end
+
+    # Set or clear (with nil) the default S3 credentials provider.
+    #
+    # @param provider [#call, nil]
+    def credentials_provider=(provider)
+      _set_default_credentials_provider(provider && credentials_provider_id(provider))
+      @credentials_provider = provider
+    end
+
+    # The store settings passed to the native open functions.
+    #
+    # @api private
+    def store_open_options(store_options, credentials_provider, client_options)
+      opts = {}
+      opts[:store_options] = store_options.to_h { |k, v| [k.to_s, v.to_s] } if store_options
+      opts[:client_options] = client_options.to_h { |k, v| [k.to_sym, v] } if client_options
+      opts[:credentials_provider] = credentials_provider_id(credentials_provider) if credentials_provider
+      opts
+    end
+
+    # Register a provider and return the id the native extension knows it by.
+    #
+    # @api private
+    def credentials_provider_id(provider)
+      raise InvalidArgumentError, "credentials_provider must respond to call" unless provider.respond_to?(:call)
+
+      @credentials_mutex.synchronize do
+        @credential_providers ||= {}.compare_by_identity
+        id = @credential_providers[provider] ||= @credential_providers.size + 1
+        start_credentials_thread
+        id
+      end
+    end
+
+    private
+
+    def start_credentials_thread
+      return if @credentials_thread&.alive?
+
+      @credentials_thread = Thread.new do
+        loop do
+          token, id = _next_credential_request
+          serve_credential_request(token, id) if token
+        end
+      end
+      @credentials_thread.name = "slatedb-credentials"
+    end
+
+    def serve_credential_request(token, id)
+      provider = @credentials_mutex.synchronize { @credential_providers.key(id) }
+      credentials = provider.call.to_h.transform_keys(&:to_sym)
+      expires_at = credentials[:expires_at]
+      _resolve_credential_request(
+        token,
+        credentials.fetch(:access_key_id).to_s,
+        credentials.fetch(:secret_access_key).to_s,
+        credentials[:session_token]&.to_s,
+        expires_at&.to_f
+      )
+    rescue StandardError => e
+      _reject_credential_request(token, "#{e.class}: #{e.message}")
+    end
+  end
+end
@@ -10,8 +10,96 @@ module SlateDb
       # @param path [String] The path identifier for the database
       # @param url [String, nil] Optional object store URL (e.g., "s3://bucket/path")
       # @param merge_operator [Symbol, String, Proc, nil] Optional merge operator.
-      #   Can be a symbol/string ("string_concat" or "concat") or a Proc/lambda
-      #   that takes (key, existing_value, new_value) and returns the merged value.
+      #   Can be a built-in operator (`:string_concat`/`:concat`/`:append`,
+      #   `:add_i64` for decimal integer counters or `:set_union` for JSON array
+      #   sets) or a Proc/lambda that takes (key, existing_value, new_value) and
+      #   returns the merged value.
+      # @param default_ttl_ms [Integer, nil] Default time-to-live in milliseconds
+      #   applied to puts and merges that don't pass `ttl:`. Use `ttl: :none` on
+      #   a write to opt out. Defaults to no expiry.
+      # @param retry [Hash, nil] Retry get, put and scan calls that fail because
+      #   object storage is unavailable or throttling requests. Keys:
+      #   `max_attempts` (default 3), `base_delay_ms` (default 50),
+      #   `max_delay_ms` (default 2000), `jitter` (default true) and `on`, the
+      #   error codes to retry (default `[:unavailable, :throttled]`).
+      #   Defaults to no retries.
+      # @param store_options [Hash, nil] Settings for the object store behind
+      #   `url`, overriding the environment. For `s3://` URLs: `access_key_id`,
+      #   `secret_access_key`, `session_token`, `region`, `endpoint`,
+      #   `allow_http` and `force_path_style`, plus `preset` (`:minio`,
+      #   `:localstack` or `:r2`, with `account_id` for R2) to fill in the
+      #   settings an S3-compatible service needs. For `gs://` URLs: `service_account`
+      #   (path to a service account file), `service_account_key` (its JSON),
+      #   `application_credentials` or `bearer_token`. For `az://` and
+      #   `abfss://` URLs: `account_name`, `account_key`, `sas_token`, or
+      #   `client_id`/`object_id`/`msi_resource_id` for a managed identity.
+      # @param credentials_provider [#call, nil] Supplies S3 credentials, called
+      #   again before they expire; see {SlateDb.credentials_provider}. Defaults
+      #   to the provider set there, if any.
+      # @param client_options [Hash, nil] HTTP client settings for S3, GCS and
+      #   Azure stores. Durations are in seconds: `timeout`, `connect_timeout`,
+      #   `read_timeout`, `pool_idle_timeout`. Retries of failed requests:
+      #   `max_retries` (default 10), `retry_timeout` (default 180),
+      #   `init_backoff` (default 0.1), `max_backoff` (default 15) and
+      #   `backoff_base` (default 2). Proxy: `proxy_url`, `proxy_excludes`
+      #   (comma-separated hosts) and `proxy_ca_certificate` (PEM). Also
+      #   `user_agent`.
+      # @param codec [Symbol, nil] How values are encoded: `:raw` (default)
+      #   stores strings as given, while `:json`, `:msgpack` and `:marshal`
+      #   serialize values on write, including merge operands and WriteBatch
+      #   values, and deserialize them on read, including in {#changes} and
+      #   {#watch}. {#append} requires `:raw`. Only use `:marshal` with data
+      #   you trust.
+      # @param encryption [String, Hash, #call, nil] Encrypt SST and WAL blocks
+      #   with AES-256-GCM before they reach the object store. Pass a 32-byte
+      #   key, or a Hash with `key`, `key_id` (stored with each block; default
+      #   "default") and `previous_keys` (`key_id => key`) to read blocks written
+      #   before a rotation. A callable is called once at open and returns
+      #   either. Must be set when the database is created.
+      # @param max_key_bytes [Integer, nil] Reject writes whose key is larger,
+      #   with InvalidArgumentError, before they reach SlateDB (default and
+      #   maximum 65535)
+      # @param max_value_bytes [Integer, nil] Reject writes whose value, after
+      #   the codec, is larger (default and maximum 4294967295). Also caps
+      #   {#append}'s `max_size`.
+      # @param max_requests_per_second [Numeric, nil] Start at most this many
+      #   object store requests per second, spacing them evenly, so a large
+      #   scan doesn't set off throttling on a shared bucket. Includes
+      #   background flushes and compaction. Defaults to no limit.
+      # @param max_concurrent_requests [Integer, nil] Allow at most this many
+      #   object store requests in flight at once. Defaults to no limit.
+      # @param read_ahead_bytes [Integer, nil] Default `read_ahead_bytes` for
+      #   {#scan}, {#scan_prefix} and {#scan_arrow}, and for scans of its
+      #   snapshots
+      # @param cache_blocks [Boolean, nil] Default `cache_blocks` for gets and
+      #   scans, including those of its snapshots
+      # @param max_fetch_tasks [Integer, nil] Default `max_fetch_tasks` for
+      #   scans and snapshot scans. Options passed to a call override these
+      #   three.
+      # @param l0_sst_size_bytes [Integer, nil] Size at which the memtable is
+      #   frozen and flushed to an L0 SST
+      # @param l0_max_ssts [Integer, nil] Stop flushing memtables while L0
+      #   holds this many SSTs, until compaction catches up
+      # @param max_unflushed_bytes [Integer, nil] Pause writers while this many
+      #   bytes are waiting to be flushed to object storage. Bounds memory use
+      #   and how much WAL a restart has to replay.
+      # @param wal_recovery [Symbol, nil] What to do when a WAL SST written
+      #   before an unclean shutdown can't be read: `:strict` (default) fails
+      #   to open with DataError, while `:tolerant` deletes that SST and every
+      #   later one and opens without their writes, once the open has fenced
+      #   the previous writer. See {#recovery_info}. `:tolerant` can't be
+      #   combined with `encryption`.
+      # @param thread_safe [Symbol, nil] Which threads may use the handle:
+      #   `:shared` (default) lets any thread use it concurrently, while
+      #   `:exclusive` makes the opening thread its owner and raises
+      #   ThreadOwnershipError when another thread uses it. Any thread may
+      #   still {#close} an exclusive handle. See {#thread_safe}.
+      # @param dedicated_runtime [Boolean] Run the database's operations and
+      #   background tasks (flushes, compaction, garbage collection) on a
+      #   Tokio runtime of its own, so heavy traffic on other databases can't
+      #   starve it. Defaults to the runtime shared by every database.
+      # @param worker_threads [Integer, nil] Worker threads for the dedicated
+      #   runtime (default: one per CPU). Requires `dedicated_runtime: true`.
       # @yield [db] If a block is given, yields the database and ensures it's closed
       # @return [Database] The opened database (or block result if block given)
       #
@@ -38,8 +126,71 @@ module SlateDb
       #   db.merge("counter", "3")
       #   db.get("counter") # => "8"
       #
-      def open(path, url: nil, merge_operator: nil)
-        opts = {}
+      # @example Open a cache where entries expire after an hour by default
+      #   db = SlateDb::Database.open("/tmp/cache", default_ttl_ms: 3_600_000)
+      #
+      # @example Open with credentials for a second AWS account
+      #   db = SlateDb::Database.open("mydb", url: "s3://other-account-bucket/path",
+      #                               store_options: { access_key_id: key_id, secret_access_key: secret,
+      #                                                region: "eu-west-1" })
+      #
+      # @example Open on GCS with an explicit service account
+      #   db = SlateDb::Database.open("mydb", url: "gs://mybucket/path",
+      #                               store_options: { service_account_key: ENV.fetch("GCS_KEY_JSON") })
+      #
+      # @example Open with short-lived credentials from STS
+      #   db = SlateDb::Database.open("mydb", url: "s3://mybucket/path",
+      #                               credentials_provider: -> { assume_role_credentials })
+      #
+      # @example Reach S3 through a corporate proxy
+      #   db = SlateDb::Database.open("mydb", url: "s3://mybucket/path",
+      #                               client_options: { proxy_url: "http://proxy.internal:3128", timeout: 30 })
+      #
+      # @example Store Hashes and Arrays as JSON
+      #   db = SlateDb::Database.open("/tmp/mydb", codec: :json)
+      #   db.put("user:1", { "name" => "Ada", "roles" => ["admin"] })
+      #   db.get("user:1") # => {"name"=>"Ada", "roles"=>["admin"]}
+      #
+      # @example Encrypt with a key fetched from a secrets manager at open
+      #   db = SlateDb::Database.open("mydb", url: "s3://mybucket/path",
+      #                               encryption: -> { { key_id: "2024-10", key: fetch_key("2024-10") } })
+      #
+      # @example Retry reads and writes while S3 is throttling
+      #   db = SlateDb::Database.open("mydb", url: "s3://mybucket/path",
+      #                               retry: { max_attempts: 5, on: [:throttled] })
+      #
+      # @example Tune every scan once, rather than at each call site
+      #   db = SlateDb::Database.open("mydb", url: "s3://mybucket/path",
+      #                               read_ahead_bytes: 4 * 1024 * 1024, max_fetch_tasks: 4)
+      #   db.scan("a")                        # reads ahead 4 MiB
+      #   db.scan("a", read_ahead_bytes: 1)   # except here
+      #
+      # @example Keep a batch job from flooding a shared bucket
+      #   db = SlateDb::Database.open("mydb", url: "s3://shared-bucket/path",
+      #                               max_requests_per_second: 200, max_concurrent_requests: 16)
+      #
+      # @example Keep a latency-sensitive database off the shared runtime
+      #   sessions = SlateDb::Database.open("sessions", url: "s3://mybucket/sessions",
+      #                                     dedicated_runtime: true, worker_threads: 2)
+      #
+      # @example Catch a per-thread handle leaking into other threads
+      #   db = SlateDb::Database.open("/tmp/mydb", thread_safe: :exclusive)
+      #   Thread.new { db.get("key") }.join # raises SlateDb::ThreadOwnershipError
+      #
+      def open(path, url: nil, merge_operator: nil, default_ttl_ms: nil, retry: nil, store_options: nil,
+               credentials_provider: nil, client_options: nil, codec: nil, encryption: nil,
+               max_key_bytes: nil, max_value_bytes: nil, max_requests_per_second: nil, max_concurrent_requests: nil,
+               read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, l0_sst_size_bytes: nil,
+               l0_max_ssts: nil, max_unflushed_bytes: nil, wal_recovery: nil, thread_safe: nil,
+               dedicated_runtime: nil, worker_threads: nil)
+        opts = { default_ttl_ms:, max_key_bytes:, max_value_bytes:, max_requests_per_second:,
+                 max_concurrent_requests:, read_ahead_bytes:, cache_blocks:, max_fetch_tasks:,
+                 l0_sst_size_bytes:, l0_max_ssts:, max_unflushed_bytes:, wal_recovery: wal_recovery&.to_s,
+                 thread_safe: thread_safe&.to_s, dedicated_runtime:, worker_threads: }.compact
+        opts.merge!(value_options(codec, encryption))
+        opts.merge!(SlateDb.store_open_options(store_options, credentials_provider, client_options))
+        retry_policy = binding.local_variable_get(:retry)
+        opts[:retry] = normalize_retry_policy(retry_policy) if retry_policy
 
         case merge_operator
         when Symbol, String
@@ -66,6 +217,80 @@ module SlateDb
           db
         end
       end
+
+      # Open a writable, copy-on-write clone of another database.
+      #
+      # The first call creates the clone at `path`: its manifest references
+      # the parent's SSTs instead of copying them (only the parent's WAL is
+      # copied), so it takes seconds regardless of the parent's size. Writes
+      # to the clone never reach the parent. Later calls with the same
+      # parent simply reopen the clone.
+      #
+      # Both databases must live in the same object store, and the parent's
+      # SSTs must outlive the clone: clone from a checkpoint (with a long or
+      # no lifetime) to keep GC from collecting them.
+      #
+      # @param path [String] The path identifier for the clone
+      # @param parent_path [String] The path of the database to clone
+      # @param url [String] Object store URL holding both databases
+      # @param parent_checkpoint [String, nil] Checkpoint UUID of the parent to
+      #   clone; its latest state if nil
+      # @param options [Hash] Any other options accepted by {.open}
+      # @yield [db] If a block is given, yields the clone and closes it
+      # @return [Database] The opened clone (or block result if block given)
+      #
+      # @example Fork production data for local development
+      #   checkpoint = SlateDb::Admin.new("orders", url: "s3://prod/db").create_checkpoint!(name: "dev-fork")
+      #   SlateDb::Database.open_clone("orders-dev", parent_path: "orders", url: "s3://prod/db",
+      #                                              parent_checkpoint: checkpoint[:id]) do |db|
+      #     db.put("order:1", "edited in dev only")
+      #   end
+      #
+      def open_clone(path, parent_path:, url:, parent_checkpoint: nil, **options, &)
+        store = SlateDb.store_open_options(options[:store_options], options[:credentials_provider],
+                                           options[:client_options])
+        _create_clone(path, url, { parent_path:, parent_checkpoint: }.compact.merge(store))
+        open(path, url:, **options, &)
+      end
+
+      # Create a database handle that opens on first use.
+      #
+      # Opening a database reads its manifest from object storage, which can
+      # be slow. `lazy_open` returns immediately so application boot isn't
+      # held up by databases that are rarely used. Errors from opening are
+      # raised by the first operation instead.
+      #
+      # @param path [String] The path identifier for the database
+      # @param options [Hash] Any options accepted by {.open}
+      # @return [LazyDatabase] A handle that opens the database on first use
+      #
+      # @example
+      #   ARCHIVE = SlateDb::Database.lazy_open("archive", url: "s3://archive-bucket/db")
+      #   # ... later, the first call opens the database
+      #   ARCHIVE.get("order:42")
+      #
+      def lazy_open(path, **options)
+        LazyDatabase.new(path, **options)
+      end
+
+      # The `codec:` and `encryption:` options, shared with {Reader.open}.
+      #
+      # @api private
+      def value_options(codec, encryption)
+        opts = {}
+        opts[:codec] = codec.to_s if codec
+        opts[:encryption] = SlateDb.encryption_options(encryption) if encryption
+        opts
+      end
+
+      private
+
+      # `retry:` error codes are passed to the extension as strings.
+      def normalize_retry_policy(policy)
+        policy = policy.to_h
+        policy[:on] = Array(policy[:on]).map(&:to_s) if policy.key?(:on)
+        policy
+      end
     end
 
     # Get a value by key.
@@ -74,6 +299,12 @@ module SlateDb
     # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
     # @param dirty [Boolean, nil] Whether to include uncommitted data
     # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @param min_seq [Integer, nil] Consistency token from a write made with
+    #   `return_token: true`; waits until that write is durable before reading
+    # @param min_seq_timeout_ms [Integer, nil] Longest to wait for `min_seq`
+    #   before raising TimeoutError (default: 10,000)
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
     # @return [String, nil] The value, or nil if not found
     #
     # @example Basic get
@@ -82,11 +313,15 @@ module SlateDb
     # @example Get with options
     #   value = db.get("mykey", durability_filter: "memory", dirty: true)
     #
-    def get(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+    def get(key, durability_filter: nil, dirty: nil, cache_blocks: nil, min_seq: nil, min_seq_timeout_ms: nil,
+            trace_context: nil)
       opts = {}
       opts[:durability_filter] = durability_filter.to_s if durability_filter
       opts[:dirty] = dirty unless dirty.nil?
       opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
+      opts[:min_seq] = min_seq if min_seq
+      opts[:min_seq_timeout_ms] = min_seq_timeout_ms if min_seq_timeout_ms
+      opts[:trace_context] = trace_context if trace_context
 
       if opts.empty?
         _get(key)
@@ -101,12 +336,14 @@ module SlateDb
     # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
     # @param dirty [Boolean, nil] Whether to include uncommitted data
     # @param cache_blocks [Boolean, nil] Whether to cache blocks
-    # @return [Hash, nil] A hash with :key, :value, :seq, :create_ts, and :expire_ts, or nil if not found
+    # @return [Hash, nil] A hash with :key, :value, :seq, :create_ts, :expire_ts
+    #   and :ttl (milliseconds until expiry, or nil), or nil if not found
     #
     # @example Inspect metadata
     #   entry = db.get_key_value("mykey")
     #   entry[:value] # => "myvalue"
     #   entry[:seq]   # => sequence number
+    #   entry[:ttl]   # => 59_874
     #
     def get_key_value(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
       opts = {}
@@ -123,17 +360,114 @@ module SlateDb
 
     alias get_entry get_key_value
 
+    # Get a value as a read-only IO::Buffer over the bytes SlateDB read,
+    # without copying them into a String.
+    #
+    # For multi-megabyte values the copy into a String can dominate a read.
+    # The buffer keeps the bytes alive until it is garbage collected. The
+    # codec is not applied, so the buffer holds the stored bytes.
+    #
+    # @param key [String] The key to look up
+    # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @return [IO::Buffer, nil] The value, or nil if not found
+    #
+    # @example Stream a large value to a socket
+    #   buffer = db.get_buffer("video:42")
+    #   buffer&.write(socket, buffer.size)
+    #
+    def get_buffer(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      _get_buffer(key, { durability_filter: durability_filter&.to_s, dirty:, cache_blocks: }.compact)
+    end
+
+    # Get a value, copying it into the start of a writable IO::Buffer.
+    #
+    # Reusing one buffer across reads avoids allocating a String per value.
+    # The codec is not applied.
+    #
+    # @param key [String] The key to look up
+    # @param buffer [IO::Buffer] Where to copy the value
+    # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @return [Integer, nil] The size of the value in bytes, or nil if not found
+    # @raise [InvalidArgumentError] If the value is larger than the buffer
+    #
+    # @example
+    #   buffer = IO::Buffer.new(16 * 1024 * 1024)
+    #   size = db.get_into("blob:1", buffer)
+    #   digest = Digest::SHA256.digest(buffer.get_string(0, size)) if size
+    #
+    def get_into(key, buffer, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      _get_into(key, buffer, { durability_filter: durability_filter&.to_s, dirty:, cache_blocks: }.compact)
+    end
+
+    # Get the size of a value in bytes, without reading it into Ruby.
+    #
+    # Distinguishes an empty value (0) from a missing key (nil).
+    #
+    # @param key [String] The key to look up
+    # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @return [Integer, nil] The size of the value, or nil if not found
+    #
+    def value_size(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      _value_size(key, { durability_filter: durability_filter&.to_s, dirty:, cache_blocks: }.compact)
+    end
+
+    # Check whether a key is present, whatever its value.
+    #
+    # Unlike `get(key).nil?`, this is true for keys holding an empty value.
+    #
+    # @param key [String] The key to look up
+    # @return [Boolean] true if the key has a value, including an empty one
+    #
+    def key?(key, **)
+      !value_size(key, **).nil?
+    end
+
+    # Check whether a key is present with an empty value, as used by
+    # protocols that write present-but-empty markers.
+    #
+    # @param key [String] The key to look up
+    # @return [Boolean] true if the key holds an empty value; false if it
+    #   holds a non-empty value or is not found
+    #
+    # @example
+    #   db.put("lock:job-1", "")
+    #   db.empty_value?("lock:job-1") # => true
+    #   db.get("lock:job-1")          # => ""
+    #   db.empty_value?("lock:job-2") # => false
+    #
+    def empty_value?(key, **)
+      value_size(key, **)&.zero? || false
+    end
+
     # Store a key-value pair.
     #
     # @param key [String] The key to store
     # @param value [String] The value to store
-    # @param ttl [Integer, nil] Time-to-live in milliseconds
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   to use the database's default TTL, or `:none` to never expire
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as a Time or
+    #   milliseconds since the epoch, instead of `ttl`
     # @param await_durable [Boolean] Whether to wait for durability (default: true)
     # @param seqnum [Integer, nil] User-supplied sequence number for this write.
     #   When provided (and non-zero), it is used instead of the internally
     #   generated sequence number. It must be strictly greater than the current
     #   maximum sequence number or the write fails. (Requires SlateDB >= 0.13.0)
-    # @return [void]
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   write instead of nil (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the write
+    #   instead of nil (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the write's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested
     #
     # @example Basic put
     #   db.put("mykey", "myvalue")
@@ -141,17 +475,37 @@ module SlateDb
     # @example Put with TTL
     #   db.put("mykey", "myvalue", ttl: 60_000) # expires in 60 seconds
     #
+    # @example Put that expires at a deadline
+    #   db.put("offer:42", "10% off", expire_at: Time.utc(2026, 12, 31))
+    #
+    # @example Put that ignores the database's default TTL
+    #   db.put("mykey", "myvalue", ttl: :none)
+    #
     # @example Put without waiting for durability
     #   db.put("mykey", "myvalue", await_durable: false)
     #
+    # @example Confirm durability of a fire-and-forget write later
+    #   handle = db.put("mykey", "myvalue", await_durable: false, return_handle: true)
+    #   handle.wait(timeout_ms: 1_000) # => true once durable
+    #
     # @example Put with an explicit sequence number
     #   db.put("mykey", "myvalue", seqnum: 42)
     #
-    def put(key, value, ttl: nil, await_durable: nil, seqnum: nil)
+    # @example Record the sequence number of a write
+    #   result = db.put("mykey", "myvalue", return_result: true)
+    #   result.seq # => 42
+    #
+    def put(key, value, ttl: nil, expire_at: nil, await_durable: nil, seqnum: nil, return_result: false,
+            return_handle: false, return_token: false, trace_context: nil)
       opts = {}
       opts[:ttl] = ttl if ttl
+      opts[:expire_at] = expire_at if expire_at
       opts[:await_durable] = await_durable unless await_durable.nil?
       opts[:seqnum] = seqnum if seqnum
+      opts[:return_result] = true if return_result
+      opts[:return_handle] = true if return_handle
+      opts[:return_token] = true if return_token
+      opts[:trace_context] = trace_context if trace_context
 
       if opts.empty?
         _put(key, value)
@@ -166,7 +520,16 @@ module SlateDb
     # @param await_durable [Boolean] Whether to wait for durability (default: true)
     # @param seqnum [Integer, nil] User-supplied sequence number for this write.
     #   See {#put} for semantics. (Requires SlateDB >= 0.13.0)
-    # @return [void]
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   write instead of nil (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the write
+    #   instead of nil (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the write's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested
     #
     # @example Basic delete
     #   db.delete("mykey")
@@ -174,10 +537,15 @@ module SlateDb
     # @example Delete without waiting for durability
     #   db.delete("mykey", await_durable: false)
     #
-    def delete(key, await_durable: nil, seqnum: nil)
+    def delete(key, await_durable: nil, seqnum: nil, return_result: false,
+               return_handle: false, return_token: false, trace_context: nil)
       opts = {}
       opts[:await_durable] = await_durable unless await_durable.nil?
       opts[:seqnum] = seqnum if seqnum
+      opts[:return_result] = true if return_result
+      opts[:return_handle] = true if return_handle
+      opts[:return_token] = true if return_token
+      opts[:trace_context] = trace_context if trace_context
 
       if opts.empty?
         _delete(key)
@@ -186,6 +554,35 @@ module SlateDb
       end
     end
 
+    # Delete several keys in one atomic write.
+    #
+    # The deletes go into a single WriteBatch, so cleanup jobs pay for one
+    # write instead of one per key.
+    #
+    # @param keys [Array<String>] The keys to delete
+    # @param await_durable [Boolean] Whether to wait for durability (default: true)
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   write instead of nil (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the write
+    #   instead of nil (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the write's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested; nil when
+    #   `keys` is empty
+    #
+    # @example
+    #   db.delete_many(expired_session_ids.map { |id| "session:#{id}" })
+    #
+    def delete_many(keys, await_durable: nil, return_result: false, return_handle: false, return_token: false)
+      keys = Array(keys)
+      return nil if keys.empty?
+
+      batch = WriteBatch.new
+      keys.each { |key| batch.delete(key) }
+      write(batch, await_durable:, return_result:, return_handle:, return_token:)
+    end
+
     # Scan a range of keys.
     #
     # @param start_key [String] The start key (inclusive)
@@ -193,9 +590,24 @@ module SlateDb
     # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
     # @param dirty [Boolean, nil] Whether to include uncommitted data
     # @param read_ahead_bytes [Integer, nil] Number of bytes to read ahead
+    # @param read_ahead [Symbol, nil] Pass :auto to start with a small
+    #   read-ahead and grow it as the scan is consumed. Auto scans read from
+    #   a snapshot taken when the scan is opened.
     # @param cache_blocks [Boolean, nil] Whether to cache blocks
     # @param max_fetch_tasks [Integer, nil] Maximum number of fetch tasks
     # @param order [Symbol, String, nil] Iteration order (:asc/:ascending or :desc/:descending)
+    # @param limit [Integer, nil] Stop after this many entries
+    # @param keys_only [Boolean] Yield only keys instead of [key, value] pairs
+    # @param binary [Boolean] Return binary (ASCII-8BIT) strings with the
+    #   exact stored bytes instead of UTF-8 strings
+    # @param frozen [Boolean] Return frozen strings, with keys interned so
+    #   repeated keys share one String across scans
+    # @param seq [Boolean] Yield each entry's sequence number after its value,
+    #   as [key, value, seq] (or [key, seq] with keys_only)
+    # @param min_seq [Integer, nil] Consistency token; see {#get}
+    # @param min_seq_timeout_ms [Integer, nil] Longest to wait for `min_seq`
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
     # @return [Iterator] An iterator over key-value pairs
     #
     # @example Basic scan
@@ -213,16 +625,16 @@ module SlateDb
     #     puts "#{key}: #{value}"
     #   end
     #
-    def scan(start_key, end_key = nil, durability_filter: nil, dirty: nil,
-             read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, order: nil, &)
-      opts = scan_options(
-        durability_filter: durability_filter,
-        dirty: dirty,
-        read_ahead_bytes: read_ahead_bytes,
-        cache_blocks: cache_blocks,
-        max_fetch_tasks: max_fetch_tasks,
-        order: order
-      )
+    # @example The last ten keys
+    #   db.scan("a", order: :desc, limit: 10, keys_only: true).to_a
+    #
+    def scan(start_key, end_key = nil, durability_filter: nil, dirty: nil, read_ahead_bytes: nil,
+             read_ahead: nil, cache_blocks: nil, max_fetch_tasks: nil, order: nil,
+             limit: nil, keys_only: false, binary: false, frozen: false, seq: false, min_seq: nil,
+             min_seq_timeout_ms: nil, trace_context: nil, &)
+      opts = scan_options(durability_filter:, dirty:, read_ahead_bytes:, read_ahead:, cache_blocks:,
+                          max_fetch_tasks:, order:, limit:, keys_only:, binary:, frozen:, seq:, trace_context:)
+      opts.merge!({ min_seq:, min_seq_timeout_ms: }.compact)
 
       iter = if opts.empty?
                _scan(start_key, end_key)
@@ -237,12 +649,52 @@ module SlateDb
       end
     end
 
+    # Scan a range of keys into an Arrow IPC stream, for loading into Arrow,
+    # Polars or DataFrame libraries without creating a Ruby object per row.
+    #
+    # @param start_key [String] The start key (inclusive)
+    # @param end_key [String, nil] The end key (exclusive)
+    # @param schema [Hash, nil] Columns to write, in order, mapping :key and
+    #   :value to :utf8 or :binary, :seq to :uint64, and :create_ts and
+    #   :expire_ts to :timestamp (milliseconds, UTC) or :int64. Defaults to
+    #   { key: :utf8, value: :binary }
+    # @param batch_size [Integer, nil] Rows per record batch (default: 65,536)
+    # @param limit [Integer, nil] Stop after this many entries
+    # @param order [Symbol, nil] :asc (default) or :desc
+    # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @return [String] A binary String holding the Arrow IPC stream
+    #
+    # @example Load into a red-arrow table
+    #   bytes = db.scan_arrow("event:", "event;", schema: { key: :utf8, value: :utf8, create_ts: :timestamp })
+    #   table = Arrow::Table.load(Arrow::Buffer.new(bytes), format: :arrow_streaming)
+    #
+    def scan_arrow(start_key, end_key = nil, schema: nil, batch_size: nil, limit: nil, order: nil,
+                   durability_filter: nil, dirty: nil, read_ahead_bytes: nil, cache_blocks: nil,
+                   max_fetch_tasks: nil)
+      opts = {
+        schema: schema&.map { |name, type| [name.to_s, type.to_s] },
+        batch_size:,
+        limit:,
+        order: order&.to_s,
+        durability_filter: durability_filter&.to_s,
+        dirty:,
+        read_ahead_bytes:,
+        cache_blocks:,
+        max_fetch_tasks:
+      }.compact
+      _scan_arrow(start_key, end_key, opts)
+    end
+
     # Scan all keys with a given prefix.
     #
     # @param prefix [String] The key prefix to scan
     # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
     # @param dirty [Boolean, nil] Whether to include uncommitted data
     # @param read_ahead_bytes [Integer, nil] Number of bytes to read ahead
+    # @param read_ahead [Symbol, nil] Pass :auto to start with a small
+    #   read-ahead and grow it as the scan is consumed. Auto scans read from
+    #   a snapshot taken when the scan is opened.
     # @param cache_blocks [Boolean, nil] Whether to cache blocks
     # @param max_fetch_tasks [Integer, nil] Maximum number of fetch tasks
     # @param order [Symbol, String, nil] Iteration order (:asc/:ascending or :desc/:descending)
@@ -251,6 +703,18 @@ module SlateDb
     #   starts at "user:100"). Defaults to the start of the prefix.
     # @param to [String, nil] Exclusive upper bound suffix, appended to the
     #   prefix, to stop scanning at. Defaults to the end of the prefix.
+    # @param limit [Integer, nil] Stop after this many entries
+    # @param keys_only [Boolean] Yield only keys instead of [key, value] pairs
+    # @param binary [Boolean] Return binary (ASCII-8BIT) strings with the
+    #   exact stored bytes instead of UTF-8 strings
+    # @param frozen [Boolean] Return frozen strings, with keys interned so
+    #   repeated keys share one String across scans
+    # @param seq [Boolean] Yield each entry's sequence number after its value,
+    #   as [key, value, seq] (or [key, seq] with keys_only)
+    # @param min_seq [Integer, nil] Consistency token; see {#get}
+    # @param min_seq_timeout_ms [Integer, nil] Longest to wait for `min_seq`
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
     # @return [Iterator] An iterator over key-value pairs
     #
     # @example Scan all user keys
@@ -262,19 +726,13 @@ module SlateDb
     #   # keys "user:100" (inclusive) up to "user:200" (exclusive)
     #   db.scan_prefix("user:", from: "100", to: "200")
     #
-    def scan_prefix(prefix, durability_filter: nil, dirty: nil,
-                    read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, order: nil,
-                    from: nil, to: nil, &)
-      opts = scan_options(
-        durability_filter: durability_filter,
-        dirty: dirty,
-        read_ahead_bytes: read_ahead_bytes,
-        cache_blocks: cache_blocks,
-        max_fetch_tasks: max_fetch_tasks,
-        order: order
-      )
-      opts[:subrange_from] = from if from
-      opts[:subrange_to] = to if to
+    def scan_prefix(prefix, durability_filter: nil, dirty: nil, read_ahead_bytes: nil,
+                    read_ahead: nil, cache_blocks: nil, max_fetch_tasks: nil, order: nil,
+                    from: nil, to: nil, limit: nil, keys_only: false, binary: false, frozen: false,
+                    seq: false, min_seq: nil, min_seq_timeout_ms: nil, trace_context: nil, &)
+      opts = scan_options(durability_filter:, dirty:, read_ahead_bytes:, read_ahead:, cache_blocks:,
+                          max_fetch_tasks:, order:, limit:, keys_only:, binary:, frozen:, seq:, trace_context:)
+      opts.merge!({ subrange_from: from, subrange_to: to, min_seq:, min_seq_timeout_ms: }.compact)
 
       iter = if opts.empty?
                _scan_prefix(prefix)
@@ -289,16 +747,100 @@ module SlateDb
       end
     end
 
-    def scan_options(durability_filter:, dirty:, read_ahead_bytes:, cache_blocks:,
-                     max_fetch_tasks:, order:)
-      opts = {}
-      opts[:durability_filter] = durability_filter.to_s if durability_filter
-      opts[:dirty] = dirty unless dirty.nil?
-      opts[:read_ahead_bytes] = read_ahead_bytes if read_ahead_bytes
-      opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
-      opts[:max_fetch_tasks] = max_fetch_tasks if max_fetch_tasks
-      opts[:order] = order.to_s if order
-      opts
+    # Resume a scan from a cursor returned by {Iterator#cursor}.
+    #
+    # The cursor holds the part of the original scan's range that had not
+    # been returned, and its order, so the resumed scan picks up just after
+    # the last entry the iterator returned. Cursors are plain strings that
+    # survive process restarts; they don't pin a snapshot, so the resumed
+    # scan sees writes made since. Only scans opened by a Database (including
+    # namespace scans) hand out cursors. Takes the options of {#scan}
+    # except `order`, which comes from the cursor.
+    #
+    # @param cursor [String] Token returned by {Iterator#cursor}
+    # @param limit [Integer, nil] Stop after this many entries
+    # @return [Iterator] An iterator over the rest of the range
+    # @raise [InvalidArgumentError] if the cursor is malformed
+    #
+    # @example Resumable batch job
+    #   iter = cursor ? db.scan_from_cursor(cursor, limit: 1_000) : db.scan("event:", "event;", limit: 1_000)
+    #   iter.each { |key, value| process(key, value) }
+    #   save_checkpoint(iter.cursor)
+    #
+    def scan_from_cursor(cursor, durability_filter: nil, dirty: nil, read_ahead_bytes: nil, read_ahead: nil,
+                         cache_blocks: nil, max_fetch_tasks: nil, limit: nil, keys_only: false, binary: false,
+                         frozen: false, seq: false, trace_context: nil, &)
+      opts = scan_options(durability_filter:, dirty:, read_ahead_bytes:, read_ahead:, cache_blocks:,
+                          max_fetch_tasks:, order: nil, limit:, keys_only:, binary:, frozen:, seq:, trace_context:)
+      iter = _scan_from_cursor(cursor, opts)
+
+      if block_given?
+        iter.each(&)
+      else
+        iter
+      end
+    end
+
+    # Read one page of keys, in the shape paginated APIs return.
+    #
+    # Pages run in a single native call. `:next_cursor` is an opaque
+    # {Iterator#cursor} token for the page after this one, or nil on the last
+    # page; pass it back as `cursor:` (or to {#scan_from_cursor}). The first
+    # page is chosen with `prefix:` and `start_after:`, an exclusive full key
+    # such as the last key a client has seen; later pages keep the first
+    # page's prefix and order.
+    #
+    # @param limit [Integer] Maximum entries per page (default: 100)
+    # @param prefix [String, nil] Only return keys starting with this prefix
+    # @param start_after [String, nil] Start after this key, in scan order
+    # @param cursor [String, nil] `:next_cursor` from the previous page. Can't
+    #   be combined with `prefix:` or `start_after:`
+    # @param order [Symbol, nil] :asc (default) or :desc, for the first page
+    # @param keys_only [Boolean] Return only keys instead of [key, value] pairs
+    # @param binary [Boolean] Return binary (ASCII-8BIT) strings
+    # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @return [Hash] `{ entries: [...], next_cursor: String or nil }`
+    # @raise [InvalidArgumentError] if the cursor is malformed or combined
+    #   with `prefix:` or `start_after:`
+    #
+    # @example A REST endpoint
+    #   get "/orders" do
+    #     json db.page(prefix: "order:", limit: 50, cursor: params[:cursor])
+    #   end
+    #
+    def page(limit: nil, prefix: nil, start_after: nil, cursor: nil, order: nil, keys_only: false,
+             binary: false, durability_filter: nil, dirty: nil)
+      _page({
+        limit:,
+        prefix:,
+        start_after:,
+        cursor:,
+        order: order&.to_s,
+        keys_only: keys_only || nil,
+        binary: binary || nil,
+        durability_filter: durability_filter&.to_s,
+        dirty:
+      }.compact)
+    end
+
+    def scan_options(durability_filter:, dirty:, read_ahead_bytes:, read_ahead:, cache_blocks:,
+                     max_fetch_tasks:, order:, limit:, keys_only:, binary:, frozen:, seq:, trace_context:)
+      {
+        durability_filter: durability_filter&.to_s,
+        dirty: dirty,
+        read_ahead_bytes: read_ahead_bytes,
+        read_ahead: read_ahead&.to_s,
+        cache_blocks: cache_blocks,
+        max_fetch_tasks: max_fetch_tasks,
+        order: order&.to_s,
+        limit: limit,
+        keys_only: keys_only || nil,
+        binary: binary || nil,
+        frozen: frozen || nil,
+        seq: seq || nil,
+        trace_context: trace_context
+      }.compact
     end
 
     private :scan_options
@@ -309,7 +851,16 @@ module SlateDb
     # @param await_durable [Boolean] Whether to wait for durability (default: true)
     # @param seqnum [Integer, nil] User-supplied sequence number applied to the
     #   batch. See {#put} for semantics. (Requires SlateDB >= 0.13.0)
-    # @return [void]
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   write instead of nil (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the write
+    #   instead of nil (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the write's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested
     #
     # @example Write a batch
     #   batch = SlateDb::WriteBatch.new
@@ -324,10 +875,15 @@ module SlateDb
     #     b.put("key2", "value2")
     #   end
     #
-    def write(batch, await_durable: nil, seqnum: nil)
+    def write(batch, await_durable: nil, seqnum: nil, return_result: false,
+              return_handle: false, return_token: false, trace_context: nil)
       opts = {}
       opts[:await_durable] = await_durable unless await_durable.nil?
       opts[:seqnum] = seqnum if seqnum
+      opts[:return_result] = true if return_result
+      opts[:return_handle] = true if return_handle
+      opts[:return_token] = true if return_token
+      opts[:trace_context] = trace_context if trace_context
 
       if opts.empty?
         _write(batch)
@@ -336,26 +892,75 @@ module SlateDb
       end
     end
 
+    # Write a batch too large to commit at once as a series of smaller
+    # atomic batches.
+    #
+    # Operations keep their order, and each chunk is written before the next
+    # is built, so memory stays bounded by one chunk. The batch as a whole is
+    # NOT atomic: readers can see the chunks written so far, and if a chunk
+    # fails the chunks before it stay written and the error is raised. A
+    # `delete_range` counts as one operation and removes keys written by
+    # earlier chunks.
+    #
+    # @param batch [WriteBatch] The batch to write; it is empty afterwards
+    # @param max_ops [Integer] Most operations per chunk (default: 10,000)
+    # @param max_bytes [Integer] Most key and value bytes per chunk (default:
+    #   4 MiB). An operation larger than this is written in a chunk of its own.
+    # @param await_durable [Boolean] Whether each chunk waits for durability
+    #   (default: true)
+    # @yield [info] Called after each chunk is written, with a Hash of
+    #   :chunk (1-based), :chunks, :ops, :bytes, :seq and :percent
+    # @return [Integer] The number of chunks written
+    #
+    # @example Load a large import in chunks
+    #   batch = SlateDb::WriteBatch.new
+    #   rows.each { |key, value| batch.put(key, value) }
+    #   db.write_chunked(batch, max_ops: 5_000) do |info|
+    #     puts "chunk #{info[:chunk]}/#{info[:chunks]} at seq #{info[:seq]}"
+    #   end
+    #
+    def write_chunked(batch, max_ops: nil, max_bytes: nil, await_durable: nil, &block)
+      _write_chunked(batch, { max_ops:, max_bytes:, await_durable: }.compact, block)
+    end
+
     # Merge a value into the database.
     #
     # @param key [String] The key to merge into
-    # @param value [String] The merge operand to apply
-    # @param ttl [Integer, nil] Time-to-live in milliseconds
+    # @param value [Object] The merge operand to apply, encoded by the database's codec
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   to use the database's default TTL, or `:none` to never expire
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as a Time or
+    #   milliseconds since the epoch, instead of `ttl`
     # @param await_durable [Boolean] Whether to wait for durability (default: true)
     # @param seqnum [Integer, nil] User-supplied sequence number for this write.
     #   See {#put} for semantics. (Requires SlateDB >= 0.13.0)
-    # @return [void]
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   write instead of nil (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the write
+    #   instead of nil (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the write's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested
     #
     # @example Merge with string concatenation operator
     #   db = SlateDb::Database.open("/tmp/mydb", merge_operator: :string_concat)
     #   db.merge("key", "part1")
     #   db.merge("key", "part2")
     #
-    def merge(key, value, ttl: nil, await_durable: nil, seqnum: nil)
+    def merge(key, value, ttl: nil, expire_at: nil, await_durable: nil, seqnum: nil, return_result: false,
+              return_handle: false, return_token: false, trace_context: nil)
       opts = {}
       opts[:ttl] = ttl if ttl
+      opts[:expire_at] = expire_at if expire_at
       opts[:await_durable] = await_durable unless await_durable.nil?
       opts[:seqnum] = seqnum if seqnum
+      opts[:return_result] = true if return_result
+      opts[:return_handle] = true if return_handle
+      opts[:return_token] = true if return_token
+      opts[:trace_context] = trace_context if trace_context
 
       if opts.empty?
         _merge(key, value)
@@ -364,13 +969,71 @@ module SlateDb
       end
     end
 
+    # Append bytes to the value stored at a key.
+    #
+    # The read-modify-write happens natively inside a serializable transaction
+    # that is retried on conflict, so concurrent appends are never lost. A
+    # missing key is treated as an empty value. Only databases opened with
+    # the `:raw` codec support appends; others raise InvalidArgumentError.
+    #
+    # @param key [String] The key to append to
+    # @param value [String] The bytes to append
+    # @param max_size [Integer, nil] Maximum size in bytes of the resulting value
+    #   (default: 16 MiB). Raises InvalidArgumentError if the append would exceed it.
+    # @param retries [Integer, nil] How many times to retry after a conflict
+    #   with a concurrent write to the key (default: 5). Raises TransactionError
+    #   once they are used up.
+    # @param await_durable [Boolean] Whether to wait for durability (default: true)
+    # @return [Integer] The length of the value after the append
+    #
+    # @example Build a per-entity event log
+    #   db.append("events:order-42", "created\n")
+    #   db.append("events:order-42", "paid\n")
+    #   db.get("events:order-42") # => "created\npaid\n"
+    #
+    def append(key, value, max_size: nil, retries: nil, await_durable: nil)
+      _append(key, value, { max_size:, retries:, await_durable: }.compact)
+    end
+
+    # Store a value only if the key has no live value.
+    #
+    # The check and the write happen natively inside a serializable
+    # transaction that is retried on conflict, so when several processes race
+    # to fill the same key exactly one wins. Expired values count as absent.
+    # Together with the `:ttl` from {#get_entry} this is enough to build a
+    # cache with `fetch` and race-condition TTL semantics.
+    #
+    # @param key [String] The key to store
+    # @param value [String] The value to store
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   or `:none`; see {#put}
+    # @param expire_at [Time, Integer, nil] Absolute expiry time; see {#put}
+    # @param await_durable [Boolean] Whether to wait for durability (default: true)
+    # @return [Boolean] true if the value was stored, false if the key already
+    #   had one
+    #
+    # @example Take a lock for 30 seconds
+    #   db.put_if_absent("lock:report", worker_id, ttl: 30_000) # => true
+    #   db.put_if_absent("lock:report", other_id, ttl: 30_000)  # => false
+    #
+    def put_if_absent(key, value, ttl: nil, expire_at: nil, await_durable: nil)
+      _put_if_absent(key, value, { ttl:, expire_at:, await_durable: }.compact)
+    end
+
     # Create and write a batch using a block.
     #
     # @param await_durable [Boolean] Whether to wait for durability (default: true)
     # @param seqnum [Integer, nil] User-supplied sequence number applied to the
     #   batch. See {#put} for semantics. (Requires SlateDB >= 0.13.0)
     # @yield [batch] Yields a WriteBatch to the block
-    # @return [void]
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   write instead of nil (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the write
+    #   instead of nil (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the write's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested
     #
     # @example
     #   db.batch do |b|
@@ -379,15 +1042,159 @@ module SlateDb
     #     b.delete("old_key")
     #   end
     #
-    def batch(await_durable: nil, seqnum: nil)
+    def batch(await_durable: nil, seqnum: nil, return_result: false,
+              return_handle: false, return_token: false)
       b = WriteBatch.new
       yield b
-      write(b, await_durable: await_durable, seqnum: seqnum)
+      write(b, await_durable: await_durable, seqnum: seqnum, return_result: return_result,
+            return_handle: return_handle, return_token: return_token)
+    end
+
+    # Start a get without blocking the calling thread.
+    #
+    # The lookup runs in the background; call {Future#await} for the value.
+    # Takes the same options as {#get}.
+    #
+    # @param key [String] The key to look up
+    # @return [Future] Resolves to the value, or nil if not found
+    #
+    # @example Look up many keys concurrently
+    #   futures = keys.map { |key| db.get_async(key) }
+    #   values = futures.map(&:await)
+    #
+    def get_async(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      opts = {}
+      opts[:durability_filter] = durability_filter.to_s if durability_filter
+      opts[:dirty] = dirty unless dirty.nil?
+      opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
+      _get_async(key, opts)
+    end
+
+    # Start a put without blocking the calling thread.
+    #
+    # @param key [String] The key to store
+    # @param value [String] The value to store
+    # @param ttl [Integer, Symbol, nil] Time-to-live, as for {#put}
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as for {#put}
+    # @param await_durable [Boolean] Whether the future waits for durability (default: true)
+    # @param seqnum [Integer, nil] User-supplied sequence number, as for {#put}
+    # @return [Future] Resolves to nil once the write completes
+    #
+    # @example
+    #   db.put_async("key", "value").on_complete do |_, error|
+    #     warn("write failed: #{error.message}") if error
+    #   end
+    #
+    def put_async(key, value, ttl: nil, expire_at: nil, await_durable: nil, seqnum: nil)
+      opts = {}
+      opts[:ttl] = ttl if ttl
+      opts[:expire_at] = expire_at if expire_at
+      opts[:await_durable] = await_durable unless await_durable.nil?
+      opts[:seqnum] = seqnum if seqnum
+      _put_async(key, value, opts)
+    end
+
+    # Start writing a batch without blocking the calling thread.
+    #
+    # @param batch [WriteBatch] The batch to write
+    # @param await_durable [Boolean] Whether the future waits for durability (default: true)
+    # @param seqnum [Integer, nil] User-supplied sequence number, as for {#write}
+    # @return [Future] Resolves to nil once the write completes
+    def write_async(batch, await_durable: nil, seqnum: nil)
+      opts = {}
+      opts[:await_durable] = await_durable unless await_durable.nil?
+      opts[:seqnum] = seqnum if seqnum
+      _write_async(batch, opts)
+    end
+
+    # Flush buffered writes to object storage.
+    #
+    # Use it as a durability barrier after writes made with
+    # `await_durable: false`: once it returns, those writes survive a crash.
+    #
+    # @param target [Symbol, nil] `:wal` writes the WAL buffer to object
+    #   storage; `:remote` also writes the memtable to an L0 SST and records
+    #   it in the manifest. Defaults to `:wal`, or `:remote` when the WAL is
+    #   disabled.
+    # @param timeout_ms [Integer, nil] Stop waiting after this many
+    #   milliseconds. The flush carries on in the background.
+    # @return [Hash] :target, :durable_seq (the latest write durable in the
+    #   WAL), :flushed_seq (the latest write in L0) and :bytes written to
+    #   object storage by flushes while this one ran
+    # @raise [TimeoutError] If the flush did not finish within `timeout_ms`
+    #
+    # @example Acknowledge a batch of fast writes once they are durable
+    #   events.each { |event| db.put(event.id, event.body, await_durable: false) }
+    #   db.flush(timeout_ms: 5_000)
+    #   ack(events)
+    #
+    def flush(target: nil, timeout_ms: nil)
+      _flush({ target: target&.to_s, timeout_ms: }.compact)
+    end
+
+    # Freeze the active memtable and flush it to an L0 SST now, rather than
+    # when it reaches `l0_sst_size_bytes`.
+    #
+    # Makes tests that depend on data reaching L0 deterministic, and bounds
+    # the WAL a restart has to replay. The same as `flush(target: :remote)`.
+    #
+    # @param timeout_ms [Integer, nil] Stop waiting after this many milliseconds
+    # @return [Hash] What was flushed, as for {#flush}
+    #
+    # @example
+    #   db.put("key", "value")
+    #   db.freeze_memtable
+    #   db.stats.dig(:db, :l0_sst_count) # => 1
+    #
+    def freeze_memtable(timeout_ms: nil)
+      flush(target: :remote, timeout_ms:)
+    end
+
+    # Put many key-value pairs as a series of batch writes.
+    #
+    # Pairs are written `batch_size` at a time, each chunk as one batch that
+    # doesn't wait for durability, then flushed once at the end. This saves
+    # the per-call overhead of {#put}, but every pair still goes through the
+    # WAL and memtable like any other write. Each chunk is atomic on its own;
+    # the pairs as a whole are not.
+    #
+    # @param pairs [Enumerable] Yields [key, value] pairs, e.g. a Hash or an
+    #   Enumerator reading a file
+    # @param sorted [Boolean] Raise InvalidArgumentError unless keys arrive
+    #   in strictly ascending order. Only a check on the input; writes are
+    #   no faster for sorted keys
+    # @param batch_size [Integer] Number of pairs written per batch
+    # @param ttl [Integer, nil] Time-to-live for every pair, in milliseconds
+    # @return [Integer] The number of pairs written
+    #
+    # @example Load a TSV file
+    #   rows = File.foreach("export.tsv").lazy.map { |line| line.chomp.split("\t", 2) }
+    #   db.put_chunked(rows)
+    #
+    def put_chunked(pairs, sorted: true, batch_size: 10_000, ttl: nil)
+      raise InvalidArgumentError, "batch_size must be positive" unless batch_size.is_a?(Integer) && batch_size.positive?
+
+      opts = { sorted:, ttl: }.compact
+      count = 0
+      last_key = nil
+      pairs.each_slice(batch_size) do |chunk|
+        last_key = _put_chunk(chunk, last_key, opts)
+        count += chunk.size
+      end
+      flush
+      count
     end
 
     # Begin a new transaction.
     #
+    # A `read_only` transaction raises InvalidArgumentError on writes, never
+    # tracks its reads for conflicts, and commits without touching the
+    # database, so it can't fail with TransactionError. It reads like a
+    # {Snapshot}, for code written against the Transaction interface.
+    #
     # @param isolation [Symbol, String] Isolation level (:snapshot or :serializable)
+    # @param read_only [Boolean] Reject writes (default: false). Cannot be
+    #   combined with `isolation: :serializable`.
     # @yield [txn] If a block is given, yields the transaction and auto-commits/rollbacks
     # @return [Transaction, Object] The transaction (or block result if block given)
     #
@@ -408,19 +1215,31 @@ module SlateDb
     #     txn.put("counter", (val.to_i + 1).to_s)
     #   end
     #
-    def begin_transaction(isolation: nil)
+    # @example Read-only transaction
+    #   txn = db.begin_transaction(read_only: true)
+    #   txn.get("key")
+    #   txn.commit
+    #
+    def begin_transaction(isolation: nil, read_only: false)
       isolation_str = isolation&.to_s
-      _begin_transaction(isolation_str)
+      _begin_transaction(isolation_str, read_only)
     end
 
     # Execute a block within a transaction.
     #
     # The transaction is automatically committed if the block succeeds,
-    # or rolled back if an exception is raised.
+    # or rolled back if an exception is raised. When `retries` is positive,
+    # the whole block is re-run in a fresh transaction after a conflict
+    # (`TransactionError`), waiting with exponential backoff and jitter
+    # between attempts, so the block should be safe to run more than once.
     #
     # @param isolation [Symbol, String] Isolation level (:snapshot or :serializable)
+    # @param retries [Integer] Number of times to retry after a conflict (default: 0)
+    # @param read_only [Boolean] Run the block in a read-only transaction; see
+    #   {#begin_transaction}
     # @yield [txn] Yields the transaction to the block
     # @return [Object] The result of the block
+    # @raise [TransactionError] If the transaction still conflicts after all retries
     #
     # @example
     #   result = db.transaction do |txn|
@@ -430,20 +1249,15 @@ module SlateDb
     #     new_val
     #   end
     #
-    def transaction(isolation: nil)
-      txn = begin_transaction(isolation: isolation)
-      begin
-        result = yield txn
-        txn.commit
-        result
-      rescue StandardError
-        begin
-          txn.rollback
-        rescue StandardError
-          nil
-        end
-        raise
-      end
+    # @example Retry on conflicts
+    #   db.transaction(isolation: :serializable, retries: 3) do |txn|
+    #     txn.put("counter", (txn.get("counter").to_i + 1).to_s)
+    #   end
+    #
+    def transaction(isolation: nil, retries: 0, read_only: false, &block)
+      raise InvalidArgumentError, "a block is required" unless block
+
+      _transaction(isolation&.to_s, read_only, retries, block)
     end
 
     # Create a snapshot for consistent reads.
@@ -480,6 +1294,49 @@ module SlateDb
       end
     end
 
+    # Open a read-only view of the database as it was at a past time.
+    #
+    # Reads at the newest checkpoint created at or before `time`, so the
+    # view is only as fine-grained as the database's checkpoint history:
+    # create checkpoints regularly (e.g. nightly with
+    # {Admin#create_checkpoint!}) to be able to look back. The view is a
+    # {Reader} on the database's object store, decoded with its codec and
+    # encryption.
+    #
+    # @param time [Time, Numeric] A Time or seconds since the epoch
+    # @yield [reader] If a block is given, yields the reader and closes it
+    # @return [Reader, Object] The reader (or block result if block given)
+    # @raise [InvalidArgumentError] If no checkpoint is that old
+    #
+    # @example What did this key look like yesterday?
+    #   db.snapshot_at(Time.now - 86_400) { |past| past.get("user:42") }
+    #
+    def snapshot_at(time)
+      reader = _snapshot_at(time.to_f)
+      return reader unless block_given?
+
+      begin
+        yield reader
+      ensure
+        reader.close
+      end
+    end
+
+    # A handle on the keys under `prefix`, which adds the prefix to keys it
+    # is given and strips it from keys it returns.
+    #
+    # @param prefix [String] The namespace prefix, e.g. "tenant:42:"
+    # @return [Namespace]
+    #
+    # @example
+    #   tenant = db.namespace("tenant:42:")
+    #   tenant.put("key", "value")
+    #   db.get("tenant:42:key") # => "value"
+    #
+    def namespace(prefix)
+      Namespace.new(self, prefix)
+    end
+
     # Create a checkpoint of the database.
     #
     # @param lifetime [Integer, nil] Checkpoint lifetime in milliseconds
@@ -500,11 +1357,257 @@ module SlateDb
       _create_checkpoint(opts)
     end
 
+    # Stream committed changes, for cache invalidation, search indexing and
+    # similar pipelines.
+    #
+    # Changes are read from the WAL in object storage, so a write appears
+    # once it is durable. Only changes still in retained WAL files are
+    # available; WAL files are removed by garbage collection after they have
+    # been compacted. Changes written in one batch or transaction share a
+    # sequence number.
+    #
+    # @param since_seq [Integer, nil] Only return changes with a greater
+    #   sequence number. Defaults to every change still in the WAL.
+    # @return [ChangeStream] The stream of changes
+    # @raise [InvalidArgumentError] If the database is encrypted, as its WAL
+    #   cannot be read directly
+    #
+    # @example Process changes and remember the position
+    #   stream = db.changes(since_seq: last_position)
+    #   stream.each do |change|
+    #     puts "#{change[:op]} #{change[:key]} at #{change[:seq]}"
+    #   end
+    #   last_position = stream.last_seq
+    #
+    def changes(since_seq: nil)
+      opts = {}
+      opts[:since_seq] = since_seq if since_seq
+      _changes(opts)
+    end
+
+    # Watch keys beginning with a prefix, calling the block for each change.
+    #
+    # Changes are read from the WAL by a background task and the block runs
+    # on a dedicated Ruby thread, in write order. Changes appear once they
+    # are durable. If the block raises, the watcher stops and the error is
+    # raised from {Watcher#join}.
+    #
+    # @param prefix [String] Key prefix to watch ("" for every key)
+    # @param since_seq [Integer, nil] Report changes after this sequence
+    #   number. Defaults to changes made from now on.
+    # @param interval [Numeric] Seconds between WAL checks once caught up
+    # @yield [key, value, op] The changed key, its new value (nil for
+    #   deletes) and :put, :delete or :merge
+    # @return [Watcher] Call {Watcher#stop} to stop watching
+    # @raise [InvalidArgumentError] If the database is encrypted, as for {#changes}
+    #
+    # @example Reload configuration when it changes
+    #   watcher = db.watch("config:") do |key, value, op|
+    #     op == :delete ? settings.delete(key) : settings[key] = value
+    #   end
+    #   # ...
+    #   watcher.stop
+    #
+    def watch(prefix, since_seq: nil, interval: 0.1, &block)
+      raise InvalidArgumentError, "a block is required" unless block
+
+      opts = { interval_ms: (interval * 1000).to_i }
+      opts[:since_seq] = since_seq if since_seq
+      _watch(prefix, opts).start(&block)
+    end
+
+    # Call a block when too many operations are waiting on this database.
+    #
+    # Each operation counts as pending while it waits on the database (see
+    # `pending_ops`). When starting an operation brings the count above
+    # `threshold`, the block is called with the count before the operation
+    # runs. If the block raises, the operation is abandoned and the error
+    # propagates to its caller, so a web app can fail fast with a 503 rather
+    # than queue behind a degraded object store. The block runs on the
+    # calling thread for every operation started while above the threshold,
+    # so keep it cheap.
+    #
+    # Call without a block to remove the hook.
+    #
+    # @param threshold [Integer] Pending operation count above which the block is called
+    # @yield [pending] The current number of pending operations
+    # @return [void]
+    #
+    # @example Shed load when the object store is slow
+    #   db.on_overload(64) do |pending|
+    #     raise ServiceUnavailable, "#{pending} database operations queued"
+    #   end
+    #
+    def on_overload(threshold = nil, &block)
+      raise InvalidArgumentError, "threshold is required" if block && threshold.nil?
+
+      # Store the block to prevent GC while the hook is installed
+      @_overload_hook = block
+      _set_overload_hook(threshold.to_i, block)
+    end
+
+    # Describe this handle as the database's writer.
+    #
+    # Opening a Database claims a new writer epoch, fencing any writer that
+    # opened before it, and records this handle's client id in a
+    # `writer.json` object under the database path. The latest manifest's
+    # write time shows when the writer last flushed or compacted; an idle
+    # writer doesn't update it. See {Admin#active_writer} for the view from
+    # outside the process.
+    #
+    # @return [Hash] `:client_id` and `:epoch` of this handle, `:manifest_id`
+    #   and `:last_manifest_write` (a Time) of the latest manifest, and
+    #   `:fenced`, true once another writer has opened the database
+    #
+    # @example
+    #   info = db.writer_info
+    #   info[:fenced] && abort("another writer took over at epoch > #{info[:epoch]}")
+    #
+    def writer_info
+      info = _writer_info
+      info.merge(last_manifest_write: Time.at(info[:last_manifest_write]))
+    end
+
+    # Probe the object store behind the database, for readiness checks.
+    #
+    # Lists the manifests newer than the one this handle is using (or heads
+    # that manifest if there are none), so it costs one or two metadata
+    # requests and doesn't show up as a read. A failed or timed-out request,
+    # or a closed handle, makes the result unhealthy; the error is reported
+    # rather than raised.
+    #
+    # @param timeout_ms [Integer, nil] Give up on the object store after this
+    #   many milliseconds (default: 5,000)
+    # @return [Hash] `:healthy`, `:latency` of the probe in seconds,
+    #   `:manifest_id` this handle is using, `:latest_manifest_id` in the
+    #   store, `:manifests_behind`, `:last_manifest_write` (a Time),
+    #   `:staleness` in seconds since then, and `:error`
+    #
+    # @example
+    #   db.health
+    #   # => { healthy: true, latency: 0.012, manifest_id: 87, latest_manifest_id: 87,
+    #   #      manifests_behind: 0, last_manifest_write: 2026-10-15 09:12:44 UTC, staleness: 3.2, error: nil }
+    #
+    def health(timeout_ms: nil)
+      report = _health({ timeout_ms: }.compact)
+      written = report[:last_manifest_write]
+      report.merge(last_manifest_write: written && Time.at(written))
+    end
+
+    # Whether {#health} finds the object store reachable and the handle open.
+    #
+    # @param timeout_ms [Integer, nil] Give up on the object store after this
+    #   many milliseconds (default: 5,000)
+    # @return [Boolean]
+    #
+    # @example A readiness endpoint
+    #   get("/ready") { db.healthy?(timeout_ms: 1_000) ? 200 : 503 }
+    #
+    def healthy?(timeout_ms: nil)
+      health(timeout_ms:)[:healthy]
+    end
+
+    # The error that closed the database in the background, if any.
+    #
+    # Failed WAL or memtable flushes and compactions (expired credentials,
+    # a deleted bucket) close the database, as does being fenced by a newer
+    # writer. Until then the failure only shows up as an error from whichever
+    # call comes next; this returns it without raising.
+    #
+    # @return [SlateDb::Error, nil] The error, or nil while the database is
+    #   open or after a clean {#close}
+    #
+    # @example
+    #   raise db.background_error if db.background_error
+    #
+    def background_error
+      _background_error
+    end
+
+    # Call a block as soon as a background error closes the database.
+    #
+    # The block runs once, on a dedicated thread, with the error that
+    # {#background_error} returns. It isn't called after a clean {#close}.
+    # Registering another block replaces this one.
+    #
+    # @yieldparam error [SlateDb::Error] The error that closed the database
+    # @return [self]
+    #
+    # @example
+    #   db.on_background_error { |e| logger.error("write path down: #{e.message}"); exit!(1) }
+    #
+    def on_background_error(&block)
+      raise InvalidArgumentError, "on_background_error requires a block" unless block
+
+      @background_error_block = block
+      previous = @background_error_thread
+      @background_error_thread = Thread.new do
+        error = _wait_for_background_error
+        block.call(error) if error
+      end
+      @background_error_thread.name = "slatedb-background-error"
+      # The previous block may be the caller, reopening the database
+      previous&.kill unless previous == Thread.current
+      self
+    end
+
     # Get database metrics registry.
     #
     # @return [Metrics] Metrics registry
     def metrics
       _metrics
     end
+
+    # Close the database.
+    #
+    # Flushes the memtable to object storage, so the next open doesn't replay
+    # the WAL, and stops the background tasks. Against an unreachable object
+    # store this can take as long as the store's retries; pass `timeout_ms`
+    # to bound it, so a deploy doesn't hang on shutdown. If the close doesn't
+    # finish in time it raises {SlateDb::TimeoutError} and carries on in the
+    # background, or with `force: true` it is abandoned: writes not yet
+    # durable in the WAL are dropped, and the rest are recovered from the WAL
+    # on the next open. An abandoned database raises {SlateDb::ClosedError}
+    # until {#reopen}.
+    #
+    # @param timeout_ms [Integer, nil] How long to wait for the close
+    # @param force [Boolean] Abandon the close after `timeout_ms` instead of
+    #   raising (requires `timeout_ms`)
+    # @return [Hash, nil] nil without `timeout_ms`; otherwise `:clean`,
+    #   false if the close was abandoned, `:last_seq` and `:durable_seq`,
+    #   the latest write and the latest durable one, and `:dropped_seqs`,
+    #   the number of sequence numbers lost. Each put, batch or commit takes
+    #   one, so a dropped batch counts once however many keys it wrote
+    # @raise [SlateDb::TimeoutError] if the close times out without `force`
+    #
+    # @example Shutting down within a deploy's grace period
+    #   result = db.close(timeout_ms: 10_000, force: true)
+    #   warn "dropped #{result[:dropped_seqs]} writes" unless result[:clean]
+    #
+    def close(timeout_ms: nil, force: false)
+      _close({ timeout_ms:, force: }.compact)
+    end
+
+    # Close the database and open it again in place, with the options it was
+    # first opened with.
+    #
+    # Use this to recover after a prolonged object store outage or a
+    # background error, without rewiring everything that holds the handle.
+    # The reopened database reads the latest manifest, replays the WAL and
+    # starts with empty caches; this handle claims a new writer epoch,
+    # fencing any other writer. Snapshots, transactions and iterators taken
+    # before the reopen stay closed. A block registered with
+    # {#on_background_error} carries over.
+    #
+    # @return [self]
+    #
+    # @example
+    #   db.reopen if db.background_error
+    #
+    def reopen
+      _reopen
+      on_background_error(&@background_error_block) if @background_error_block
+      self
+    end
   end
 end
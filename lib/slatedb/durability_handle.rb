@@ -0,0 +1,26 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class DurabilityHandle
+    # Wait for the write to become durable in object storage.
+    #
+    # @param timeout_ms [Integer, nil] Give up after this many milliseconds.
+    #   Waits indefinitely when nil.
+    # @return [Boolean] true once the write is durable, false on timeout
+    # @raise [ClosedError] If the database closes before the write is durable
+    #
+    # @example
+    #   handle = db.put("key", "value", await_durable: false, return_handle: true)
+    #   handle.wait(timeout_ms: 1_000) || warn("write not yet durable")
+    #
+    def wait(timeout_ms: nil)
+      opts = {}
+      opts[:timeout_ms] = timeout_ms if timeout_ms
+      _wait(opts)
+    end
+
+    def inspect
+      "#<#{self.class.name} seq=#{seq} durable=#{durable?}>"
+    end
+  end
+end
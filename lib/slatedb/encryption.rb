@@ -0,0 +1,35 @@
+# frozen_string_literal: true
+
+require "securerandom"
+
+module SlateDb
+  class << self
+    # Generate a random 256-bit key for the `encryption:` open option.
+    #
+    # @return [String] 32 random bytes (a binary String)
+    #
+    # @example Create a key to store in your secrets manager
+    #   Base64.strict_encode64(SlateDb.generate_encryption_key)
+    def generate_encryption_key
+      SecureRandom.bytes(32)
+    end
+
+    # Normalize the `encryption:` open option to the Hash the native
+    # extension expects, calling it first if it is a key provider.
+    #
+    # @api private
+    # @param encryption [String, Hash, #call] A key, a Hash with `key`,
+    #   `key_id` and `previous_keys`, or a callable returning either
+    # @return [Hash]
+    def encryption_options(encryption)
+      encryption = encryption.call if encryption.respond_to?(:call)
+      encryption = { key: encryption } if encryption.is_a?(String)
+      raise InvalidArgumentError, "encryption must be a key, a Hash or respond to call" unless encryption.is_a?(Hash)
+
+      options = encryption.transform_keys(&:to_sym)
+      options[:key_id] = options[:key_id].to_s if options[:key_id]
+      options[:previous_keys] &&= options[:previous_keys].transform_keys(&:to_s)
+      options
+    end
+  end
+end
@@ -0,0 +1,82 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class Error
+    # The slatedb error kind behind each code.
+    KINDS = {
+      transaction_conflict: :transaction,
+      closed: :closed,
+      fenced: :closed,
+      background_panic: :closed,
+      forked_process: :closed,
+      unavailable: :unavailable,
+      throttled: :unavailable,
+      invalid_argument: :invalid,
+      data: :data,
+      internal: :internal,
+      commit_timeout: :timeout,
+      timeout: :timeout,
+      thread_ownership: :invalid
+    }.freeze
+
+    # Codes for failures that may succeed if the operation is tried again.
+    RETRYABLE_CODES = %i[transaction_conflict unavailable throttled].freeze
+
+    # The code of errors raised from Ruby rather than by the extension.
+    CODE = :internal
+
+    # The specific condition that caused the error, such as +:throttled+,
+    # +:fenced+ or +:transaction_conflict+.
+    #
+    # @return [Symbol]
+    def code
+      @code || self.class::CODE
+    end
+
+    # The broad category of the error, following slatedb's error kinds:
+    # +:transaction+, +:closed+, +:unavailable+, +:invalid+, +:data+,
+    # +:internal+ or +:timeout+.
+    #
+    # @return [Symbol]
+    def kind
+      KINDS.fetch(code, :internal)
+    end
+
+    # What the object store reported about the failure, for errors caused
+    # by a storage request. Keys are present when known:
+    #
+    # - +:store+ - the object store implementation, such as "S3"
+    # - +:path+ - the object the request was for
+    # - +:method+, +:uri+ - the HTTP request that failed
+    # - +:status+ - the HTTP status code, e.g. 403 or 404
+    # - +:request_id+ - the store's request id, for support cases
+    # - +:causes+ - messages of the underlying errors, outermost first
+    #
+    # @return [Hash{Symbol => Object}] frozen; empty for other errors
+    def details
+      @details || {}
+    end
+
+    # Whether the operation may succeed if retried: transaction conflicts,
+    # throttling and unavailable storage. A commit that timed out is not
+    # retryable, because it may still have been applied.
+    #
+    # @return [Boolean]
+    def retryable?
+      RETRYABLE_CODES.include?(code)
+    end
+  end
+
+  TransactionError::CODE = :transaction_conflict
+  ClosedError::CODE = :closed
+  FencedError::CODE = :fenced
+  UnavailableError::CODE = :unavailable
+  ThrottledError::CODE = :throttled
+  InvalidArgumentError::CODE = :invalid_argument
+  DataError::CODE = :data
+  InternalError::CODE = :internal
+  CommitTimeoutError::CODE = :commit_timeout
+  ForkedProcessError::CODE = :forked_process
+  TimeoutError::CODE = :timeout
+  ThreadOwnershipError::CODE = :thread_ownership
+end
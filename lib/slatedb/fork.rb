@@ -0,0 +1,29 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # Restarts SlateDB's background Ruby threads in forked children.
+  #
+  # The native extension detects fork itself: a child builds its own Tokio
+  # runtime, and databases and readers inherited from the parent raise
+  # ForkedProcessError. Ruby threads, however, do not survive fork.
+  #
+  # @api private
+  module ForkHook
+    def _fork
+      pid = super
+      SlateDb.send(:after_fork) if pid.zero?
+      pid
+    end
+  end
+
+  class << self
+    private
+
+    def after_fork
+      start_log_thread if logger
+      start_credentials_thread if @credential_providers
+    end
+  end
+
+  Process.singleton_class.prepend(ForkHook)
+end
@@ -0,0 +1,110 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # An operation running in the background, returned by
+  # {Database#get_async}, {Database#put_async} and {Database#write_async}.
+  #
+  # Operations run on SlateDB's own threads, so many can be in flight from a
+  # single Ruby thread.
+  class Future
+    # Guards callbacks and the futures waiting on the dispatcher.
+    LOCK = Mutex.new
+    private_constant :LOCK
+
+    class << self
+      private
+
+      # Run callbacks for a future once it finishes.
+      def watch(future)
+        LOCK.synchronize do
+          watched[future.id] = future
+          start_dispatcher
+        end
+        future._watch
+      end
+
+      def watched
+        @watched ||= {}
+      end
+
+      # Start the thread that runs callbacks, unless it is running. It exits
+      # once no future is watched; the next watch starts a new one.
+      def start_dispatcher
+        return if @dispatcher&.alive?
+
+        @dispatcher = Thread.new do
+          loop do
+            id = _next_completed
+            future = id && LOCK.synchronize { watched.delete(id) }
+            future&.send(:run_callbacks)
+            break if LOCK.synchronize { watched.empty? && stop_dispatcher }
+          end
+        end
+        @dispatcher.name = "slatedb-futures"
+      end
+
+      # Forget the dispatcher thread, which is about to exit.
+      def stop_dispatcher
+        @dispatcher = nil
+        true
+      end
+    end
+
+    # Wait for the operation to finish.
+    #
+    # @param timeout [Numeric, nil] Seconds to wait, or nil to wait forever
+    # @return [String, nil] The operation's result: the value for a get,
+    #   nil for writes
+    # @raise [TimeoutError] If the timeout elapses first. The operation keeps
+    #   running and can be awaited again.
+    # @raise [Error] The error the operation failed with
+    def await(timeout: nil)
+      _await(timeout && (timeout * 1000).to_i)
+    end
+
+    # Call a block when the operation finishes.
+    #
+    # The block receives the result and nil, or nil and the error the
+    # operation failed with. It runs on a shared background thread, so keep
+    # it short; if the operation already finished it runs soon after. An
+    # exception raised by the block is reported with +warn+, like a thread's
+    # with Thread#report_on_exception, and does not stop other blocks.
+    #
+    # @yield [value, error]
+    # @return [self]
+    #
+    # @example
+    #   db.get_async("key").on_complete do |value, error|
+    #     error ? warn(error.message) : puts(value)
+    #   end
+    def on_complete(&block)
+      raise InvalidArgumentError, "a block is required" unless block
+
+      LOCK.synchronize { (@callbacks ||= []) << block }
+      Future.send(:watch, self)
+      self
+    end
+
+    def inspect
+      "#<#{self.class.name} fulfilled=#{fulfilled?}>"
+    end
+
+    private
+
+    def run_callbacks
+      callbacks = LOCK.synchronize { @callbacks.tap { @callbacks = [] } }
+      return if callbacks.nil? || callbacks.empty?
+
+      value, error = begin
+        [await, nil]
+      rescue Error => e
+        [nil, e]
+      end
+      callbacks.each do |callback|
+        callback.call(value, error)
+      rescue StandardError => e
+        warn("#{self.class.name}#on_complete callback raised:\n#{e.full_message(highlight: false)}")
+      end
+    end
+  end
+end
@@ -0,0 +1,119 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class << self
+    # Call a block after every database operation.
+    #
+    # The block receives the event name (e.g. "get.slatedb") and a payload
+    # hash with:
+    #
+    # - `:operation` - the operation, e.g. `:get`
+    # - `:key_size` - the key size in bytes (the start key or prefix for scans),
+    #   or nil for batch writes
+    # - `:value_size` - the value size in bytes, or nil when there is none or
+    #   the value is not a String (such as with the `codec` open option)
+    # - `:duration` - the time taken in milliseconds
+    # - `:error` - the exception raised by the operation, or nil
+    #
+    # Subscribers run on the calling thread once the operation finishes,
+    # including when it raised.
+    #
+    # @yield [event, payload] Called for each operation
+    # @return [Proc] The subscriber, for passing to {.unsubscribe}
+    #
+    # @example Forward to ActiveSupport::Notifications
+    #   SlateDb.subscribe do |event, payload|
+    #     ActiveSupport::Notifications.instrument(event, payload)
+    #   end
+    def subscribe(&block)
+      raise InvalidArgumentError, "a block is required" unless block
+
+      @subscribers = (subscribers + [block]).freeze
+      block
+    end
+
+    # Remove a subscriber added with {.subscribe}.
+    #
+    # @param subscriber [Proc] The subscriber returned by {.subscribe}
+    # @return [void]
+    def unsubscribe(subscriber)
+      @subscribers = (subscribers - [subscriber]).freeze
+      nil
+    end
+
+    # Run an operation and publish an event for it to subscribers.
+    #
+    # @api private
+    # @param operation [Symbol] The operation name
+    # @param key [String] The key (or start key) the operation acts on
+    # @yield [payload] Runs the operation; may set `:value_size` on the payload
+    # @return [Object] The block's result
+    def instrument(operation, key)
+      return yield({}) if subscribers.empty?
+
+      payload = { operation: operation, key_size: key&.bytesize, value_size: nil, error: nil }
+      started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+      begin
+        yield payload
+      rescue StandardError => e
+        payload[:error] = e
+        raise
+      ensure
+        payload[:duration] = (Process.clock_gettime(Process::CLOCK_MONOTONIC) - started) * 1000
+        event = "#{operation}.slatedb"
+        subscribers.each { |subscriber| subscriber.call(event, payload) }
+      end
+    end
+
+    private
+
+    def subscribers
+      @subscribers ||= [].freeze
+    end
+  end
+
+  # Publishes an event to {SlateDb.subscribe} subscribers for each operation.
+  #
+  # @api private
+  module Instrumentation
+    def get(key, **)
+      SlateDb.instrument(:get, key) do |payload|
+        value = super
+        payload[:value_size] = value.bytesize if value.is_a?(String)
+        value
+      end
+    end
+
+    def put(key, value, **)
+      SlateDb.instrument(:put, key) do |payload|
+        payload[:value_size] = value.bytesize if value.is_a?(String)
+        super
+      end
+    end
+
+    def merge(key, value, **)
+      SlateDb.instrument(:merge, key) do |payload|
+        payload[:value_size] = value.to_s.bytesize
+        super
+      end
+    end
+
+    def delete(key, **)
+      SlateDb.instrument(:delete, key) { super }
+    end
+
+    def write(batch, **)
+      SlateDb.instrument(:write, nil) { super }
+    end
+
+    def scan(start_key, *, **, &)
+      SlateDb.instrument(:scan, start_key) { super }
+    end
+
+    def scan_prefix(prefix, **, &)
+      SlateDb.instrument(:scan_prefix, prefix) { super }
+    end
+  end
+
+  Database.prepend(Instrumentation)
+end
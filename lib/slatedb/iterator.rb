@@ -27,5 +27,34 @@ module SlateDb
 
       self
     end
+
+    # Enumerate the entries, reading them from the scan `batch_size` at a
+    # time.
+    #
+    # `each` crosses into the native extension, and releases the GVL, once
+    # per entry. This Enumerator does so once per batch and hands entries out
+    # of the buffered batch, which keeps long lazy chains and external
+    # iteration with `next` cheap. The iterator reads up to `batch_size`
+    # entries ahead of the caller.
+    #
+    # @param batch_size [Integer] Entries to read per batch (default: 256)
+    # @return [Enumerator] Yields entries as for {#each}
+    #
+    # @example
+    #   iter.lazy_each(batch_size: 500).lazy.map { |k, v| [k, JSON.parse(v)] }
+    #       .select { |_, doc| doc["active"] }.first(10)
+    #
+    def lazy_each(batch_size: 256)
+      raise InvalidArgumentError, "batch_size must be positive" unless batch_size.is_a?(Integer) && batch_size.positive?
+
+      Enumerator.new do |yielder|
+        loop do
+          batch = _next_batch(batch_size)
+          break if batch.empty?
+
+          batch.each { |entry| yielder << entry }
+        end
+      end
+    end
   end
 end
@@ -0,0 +1,68 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # A database handle that defers opening until it is first used.
+  #
+  # Returned by {Database.lazy_open}. It responds to the same methods as
+  # {Database}; the first call opens the database and later calls reuse it.
+  # Concurrent first calls share a single open. If opening fails, the error
+  # is raised to the caller and the next call tries again.
+  class LazyDatabase
+    # @param path [String] The path identifier for the database
+    # @param options [Hash] Options passed to {Database.open}
+    def initialize(path, **options)
+      @path = path
+      @options = options
+      @mutex = Mutex.new
+      @database = nil
+      @closed = false
+    end
+
+    # Whether the database has been opened yet.
+    #
+    # @return [Boolean]
+    def opened?
+      !@database.nil?
+    end
+
+    # Whether {#close} has been called.
+    #
+    # @return [Boolean]
+    def closed?
+      @closed
+    end
+
+    # Open the database now, if it isn't open already.
+    #
+    # @return [Database] The underlying database
+    # @raise [ClosedError] If the handle has been closed
+    def database
+      @database || @mutex.synchronize do
+        raise ClosedError, "database is closed" if @closed
+
+        @database ||= Database.open(@path, **@options)
+      end
+    end
+
+    # Close the database. Does nothing if it was never opened.
+    #
+    # @param timeout_ms [Integer, nil] See {Database#close}
+    # @param force [Boolean] See {Database#close}
+    # @return [Hash, nil] What {Database#close} returns, or nil if the
+    #   database was never opened
+    def close(timeout_ms: nil, force: false)
+      @mutex.synchronize do
+        @closed = true
+        @database&.close(timeout_ms:, force:)
+      end
+    end
+
+    Database.public_instance_methods(false).each do |name|
+      next if name.start_with?("_") || method_defined?(name)
+
+      define_method(name) do |*args, **kwargs, &block|
+        database.public_send(name, *args, **kwargs, &block)
+      end
+    end
+  end
+end
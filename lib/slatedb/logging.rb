@@ -0,0 +1,81 @@
+# frozen_string_literal: true
+
+require "logger"
+
+module SlateDb
+  # Ruby Logger severity for each Rust log level.
+  LOG_SEVERITIES = {
+    error: Logger::ERROR,
+    warn: Logger::WARN,
+    info: Logger::INFO,
+    debug: Logger::DEBUG,
+    trace: Logger::DEBUG
+  }.freeze
+
+  class << self
+    # The logger receiving SlateDB's diagnostics, if any.
+    #
+    # @return [Logger, nil]
+    attr_reader :logger
+
+    # Send SlateDB and object store diagnostics to a Ruby logger.
+    #
+    # Log records from the Rust core are queued and passed to the logger from
+    # a background thread, with the target module (e.g. `slatedb::db`) as the
+    # progname. Records are dropped while the queue is full; see
+    # `dropped_log_records`. Set to nil to stop logging.
+    #
+    # @param logger [Logger, nil] Any object responding to `add`
+    #
+    # @example
+    #   SlateDb.logger = Logger.new($stderr)
+    #   SlateDb.log_level = :debug
+    def logger=(logger)
+      @logger = logger
+      if logger
+        _install_logger
+        _set_log_level(log_level.to_s)
+        start_log_thread
+      else
+        _set_log_level("off")
+      end
+    end
+
+    # The most verbose level sent to the logger.
+    #
+    # @return [Symbol] One of :error, :warn, :info, :debug or :trace
+    def log_level
+      @log_level || :info
+    end
+
+    # Set the most verbose level sent to the logger (default :info).
+    #
+    # @param level [Symbol, String] :off, :error, :warn, :info, :debug or :trace
+    def log_level=(level)
+      level = level.to_sym
+      _set_log_level(level.to_s)
+      _set_log_level("off") unless @logger
+      @log_level = level
+    end
+
+    private
+
+    def start_log_thread
+      return if @log_thread&.alive?
+
+      @log_thread = Thread.new do
+        loop do
+          level, target, message = _next_log_record
+          next unless level
+
+          begin
+            @logger&.add(LOG_SEVERITIES.fetch(level), message, target)
+          rescue StandardError
+            # A failing logger must not stop SlateDB logging
+          end
+        end
+      end
+      @log_thread.name = "slatedb-log"
+    end
+  end
+end
@@ -0,0 +1,100 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # A view of the keys under a prefix, returned by {Database#namespace}.
+  #
+  # Keys passed in are prefixed and keys returned are stripped natively, so
+  # tenants or models sharing a database cannot see each other's keys.
+  #
+  # @example
+  #   tenant = db.namespace("tenant:42:")
+  #   tenant.put("user:1", "Ada")   # stores "tenant:42:user:1"
+  #   tenant.get("user:1")          # => "Ada"
+  #   tenant.scan.to_a              # => [["user:1", "Ada"]]
+  class Namespace
+    # @return [Database] The database the namespace belongs to
+    attr_reader :database
+
+    # @return [String] The prefix added to every key
+    attr_reader :prefix
+
+    # @param database [Database] The database to read and write
+    # @param prefix [String] The prefix added to every key
+    def initialize(database, prefix)
+      raise InvalidArgumentError, "namespace prefix cannot be empty" if prefix.to_s.empty?
+
+      @database = database
+      @prefix = prefix.to_s.dup.freeze
+    end
+
+    # Get a value by key within the namespace.
+    #
+    # @param key [String] The key, without the prefix
+    # @return [String, nil] The value, or nil if not found
+    def get(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      opts = { durability_filter: durability_filter&.to_s, dirty:, cache_blocks: }.compact
+      database._namespace_get(prefix, key, opts)
+    end
+
+    # Store a key-value pair within the namespace.
+    #
+    # @param key [String] The key, without the prefix
+    # @param value [String] The value to store
+    # @return [nil]
+    def put(key, value, ttl: nil, expire_at: nil, await_durable: nil)
+      database._namespace_put(prefix, key, value, { ttl:, expire_at:, await_durable: }.compact)
+      nil
+    end
+
+    # Delete a key within the namespace.
+    #
+    # @param key [String] The key, without the prefix
+    # @return [nil]
+    def delete(key, await_durable: nil)
+      database._namespace_delete(prefix, key, { await_durable: }.compact)
+      nil
+    end
+
+    # Scan the namespace, yielding keys without the prefix.
+    #
+    # Seeking on the returned iterator is relative to the namespace too.
+    #
+    # @param from [String, nil] Inclusive lower bound, without the prefix
+    # @param to [String, nil] Exclusive upper bound, without the prefix
+    # @return [Iterator] An iterator over key-value pairs
+    #
+    # @example
+    #   tenant.scan("user:", "user;") { |key, value| puts key }
+    def scan(from = nil, to = nil, order: nil, limit: nil, keys_only: false, durability_filter: nil, dirty: nil,
+             &)
+      opts = {
+        subrange_from: from,
+        subrange_to: to,
+        order: order&.to_s,
+        limit:,
+        keys_only: keys_only || nil,
+        durability_filter: durability_filter&.to_s,
+        dirty:
+      }.compact
+      iter = database._namespace_scan(prefix, opts)
+
+      if block_given?
+        iter.each(&)
+      else
+        iter
+      end
+    end
+
+    # A namespace nested inside this one.
+    #
+    # @param prefix [String] Appended to this namespace's prefix
+    # @return [Namespace]
+    def namespace(prefix)
+      Namespace.new(database, self.prefix + prefix.to_s)
+    end
+
+    def inspect
+      "#<#{self.class.name} prefix=#{prefix.inspect}>"
+    end
+  end
+end
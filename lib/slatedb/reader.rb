@@ -8,6 +8,9 @@ module SlateDb
       # @param path [String] The path identifier for the database
       # @param url [String, nil] Optional object store URL
       # @param checkpoint_id [String, nil] Optional checkpoint UUID to read at
+      # @param as_of [Time, Numeric, nil] Read at the newest checkpoint created
+      #   at or before this time (a Time or seconds since the epoch). Cannot be
+      #   combined with `checkpoint_id`.
       # @param manifest_poll_interval [Integer, nil] Poll interval in milliseconds
       # @param checkpoint_lifetime [Integer, nil] Checkpoint lifetime in milliseconds
       # @param max_memtable_bytes [Integer, nil] Maximum memtable size in bytes
@@ -19,6 +22,16 @@ module SlateDb
       #   recently used handle is closed (default: 1000). Only takes effect when
       #   `cache_root` is set. (Requires SlateDB >= 0.13.0)
       # @param merge_operator [Symbol, String, nil] Optional merge operator ("string_concat" or "concat")
+      # @param store_options [Hash, nil] Settings for the object store behind
+      #   `url`; see {Database.open}
+      # @param credentials_provider [#call, nil] Supplies S3 credentials; see
+      #   {Database.open}
+      # @param client_options [Hash, nil] HTTP client timeouts, retries and
+      #   proxy for the object store; see {Database.open}
+      # @param codec [Symbol, nil] How values were encoded by the writer
+      #   (`:raw`, `:json`, `:msgpack` or `:marshal`); see {Database.open}
+      # @param encryption [String, Hash, #call, nil] The writer's encryption
+      #   keys; see {Database.open}
       # @yield [reader] If a block is given, yields the reader and ensures it's closed
       # @return [Reader] The opened reader (or block result if block given)
       #
@@ -35,22 +48,24 @@ module SlateDb
       # @example Open at a specific checkpoint
       #   reader = SlateDb::Reader.open("/tmp/mydb", checkpoint_id: "uuid-here")
       #
+      # @example Read the database as it was a day ago
+      #   reader = SlateDb::Reader.open("/tmp/mydb", as_of: Time.now - 86_400)
+      #
       # @example Enable the on-disk cache and cap its open file handles
       #   reader = SlateDb::Reader.open("/tmp/mydb",
       #                                 cache_root: "/var/cache/slatedb",
       #                                 max_open_file_handles: 256)
       #
-      def open(path, url: nil, checkpoint_id: nil,
+      def open(path, url: nil, checkpoint_id: nil, as_of: nil,
                manifest_poll_interval: nil, checkpoint_lifetime: nil,
                max_memtable_bytes: nil, cache_root: nil, max_open_file_handles: nil,
-               merge_operator: nil)
-        opts = {}
-        opts[:manifest_poll_interval] = manifest_poll_interval if manifest_poll_interval
-        opts[:checkpoint_lifetime] = checkpoint_lifetime if checkpoint_lifetime
-        opts[:max_memtable_bytes] = max_memtable_bytes if max_memtable_bytes
-        opts[:cache_root] = cache_root if cache_root
-        opts[:max_open_file_handles] = max_open_file_handles if max_open_file_handles
+               merge_operator: nil, store_options: nil, credentials_provider: nil,
+               client_options: nil, codec: nil, encryption: nil)
+        opts = Database.value_options(codec, encryption)
+        opts.merge!({ as_of: as_of&.to_f, manifest_poll_interval:, checkpoint_lifetime:, max_memtable_bytes:,
+                      cache_root:, max_open_file_handles: }.compact)
         opts[:merge_operator] = merge_operator.to_s if merge_operator
+        opts.merge!(SlateDb.store_open_options(store_options, credentials_provider, client_options))
 
         reader = _open(path, url, checkpoint_id, opts)
 
@@ -76,13 +91,24 @@ module SlateDb
     # @param durability_filter [String, nil] Filter by durability level ("remote" or "memory")
     # @param dirty [Boolean, nil] Whether to include uncommitted data
     # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @param min_seq [Integer, nil] Consistency token from a write made with
+    #   `return_token: true`; waits until the reader has seen that write
+    #   before reading, for read-your-writes across processes
+    # @param min_seq_timeout_ms [Integer, nil] Longest to wait for `min_seq`
+    #   before raising TimeoutError (default: 10,000)
     # @return [String, nil] The value, or nil if not found
     #
-    def get(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+    # @example Read a write made by another process
+    #   token = db.put("user:42", "alice", return_token: true) # in the writer
+    #   reader.get("user:42", min_seq: token)                  # in a replica
+    #
+    def get(key, durability_filter: nil, dirty: nil, cache_blocks: nil, min_seq: nil, min_seq_timeout_ms: nil)
       opts = {}
       opts[:durability_filter] = durability_filter.to_s if durability_filter
       opts[:dirty] = dirty unless dirty.nil?
       opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
+      opts[:min_seq] = min_seq if min_seq
+      opts[:min_seq_timeout_ms] = min_seq_timeout_ms if min_seq_timeout_ms
 
       if opts.empty?
         _get(key)
@@ -95,16 +121,21 @@ module SlateDb
     #
     # @param start_key [String] The start key (inclusive)
     # @param end_key [String, nil] The end key (exclusive)
+    # @param min_seq [Integer, nil] Consistency token; see {#get}
+    # @param min_seq_timeout_ms [Integer, nil] Longest to wait for `min_seq`
     # @return [Iterator] An iterator over key-value pairs
     #
     def scan(start_key, end_key = nil, durability_filter: nil, dirty: nil,
-             read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, &)
+             read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, min_seq: nil,
+             min_seq_timeout_ms: nil, &)
       opts = {}
       opts[:durability_filter] = durability_filter.to_s if durability_filter
       opts[:dirty] = dirty unless dirty.nil?
       opts[:read_ahead_bytes] = read_ahead_bytes if read_ahead_bytes
       opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
       opts[:max_fetch_tasks] = max_fetch_tasks if max_fetch_tasks
+      opts[:min_seq] = min_seq if min_seq
+      opts[:min_seq_timeout_ms] = min_seq_timeout_ms if min_seq_timeout_ms
 
       iter = if opts.empty?
                _scan(start_key, end_key)
@@ -132,11 +163,13 @@ module SlateDb
     #   starts at "user:100"). Defaults to the start of the prefix.
     # @param to [String, nil] Exclusive upper bound suffix, appended to the
     #   prefix, to stop scanning at. Defaults to the end of the prefix.
+    # @param min_seq [Integer, nil] Consistency token; see {#get}
+    # @param min_seq_timeout_ms [Integer, nil] Longest to wait for `min_seq`
     # @return [Iterator] An iterator over key-value pairs
     #
     def scan_prefix(prefix, durability_filter: nil, dirty: nil,
                     read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil,
-                    from: nil, to: nil, &)
+                    from: nil, to: nil, min_seq: nil, min_seq_timeout_ms: nil, &)
       opts = {
         durability_filter: durability_filter&.to_s,
         dirty: dirty,
@@ -144,7 +177,9 @@ module SlateDb
         cache_blocks: cache_blocks,
         max_fetch_tasks: max_fetch_tasks,
         subrange_from: from,
-        subrange_to: to
+        subrange_to: to,
+        min_seq: min_seq,
+        min_seq_timeout_ms: min_seq_timeout_ms
       }.compact
 
       iter = if opts.empty?
@@ -159,5 +194,44 @@ module SlateDb
         iter
       end
     end
+
+    # Probe the object store behind the reader, for readiness checks.
+    #
+    # Lists the manifests newer than the one this handle is using (or heads
+    # that manifest if there are none), so it costs one or two metadata
+    # requests and doesn't show up as a read. A failed or timed-out request,
+    # or a closed handle, makes the result unhealthy; the error is reported
+    # rather than raised.
+    #
+    # @param timeout_ms [Integer, nil] Give up on the object store after this
+    #   many milliseconds (default: 5,000)
+    # @return [Hash] `:healthy`, `:latency` of the probe in seconds,
+    #   `:manifest_id` this handle is using, `:latest_manifest_id` in the
+    #   store, `:manifests_behind`, `:last_manifest_write` (a Time),
+    #   `:staleness` in seconds since then, and `:error`
+    #
+    # @example
+    #   reader.health
+    #   # => { healthy: true, latency: 0.012, manifest_id: 85, latest_manifest_id: 87,
+    #   #      manifests_behind: 2, last_manifest_write: 2026-10-15 09:12:44 UTC, staleness: 3.2, error: nil }
+    #
+    def health(timeout_ms: nil)
+      report = _health({ timeout_ms: }.compact)
+      written = report[:last_manifest_write]
+      report.merge(last_manifest_write: written && Time.at(written))
+    end
+
+    # Whether {#health} finds the object store reachable and the handle open.
+    #
+    # @param timeout_ms [Integer, nil] Give up on the object store after this
+    #   many milliseconds (default: 5,000)
+    # @return [Boolean]
+    #
+    # @example A readiness endpoint
+    #   get("/ready") { reader.healthy?(timeout_ms: 1_000) ? 200 : 503 }
+    #
+    def healthy?(timeout_ms: nil)
+      health(timeout_ms:)[:healthy]
+    end
   end
 end
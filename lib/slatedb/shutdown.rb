@@ -0,0 +1,25 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # Seconds {SlateDb.shutdown!} waits for databases to close by default.
+  SHUTDOWN_TIMEOUT = 10
+
+  class << self
+    # Close every open database and reader in this process.
+    #
+    # Closing flushes each database's WAL buffer, so writes made with
+    # `await_durable: false` are not lost, and stops SlateDB's background
+    # tasks cleanly. Queued trace spans are exported too. Called
+    # automatically at exit; call it yourself to tear down earlier, e.g. at
+    # the end of a worker's shutdown sequence. Afterwards, opening a
+    # database or reader raises ClosedError.
+    #
+    # @param timeout [Numeric] Seconds to wait for everything to close
+    # @return [Integer] The number of databases and readers closed
+    def shutdown!(timeout: SHUTDOWN_TIMEOUT)
+      _shutdown((timeout * 1000).to_i)
+    end
+  end
+end
+
+at_exit { SlateDb.shutdown! unless SlateDb.shut_down? }
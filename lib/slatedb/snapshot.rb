@@ -27,14 +27,17 @@ module SlateDb
     #
     # @param start_key [String] The start key (inclusive)
     # @param end_key [String, nil] The end key (exclusive)
+    # @param read_ahead [Symbol, nil] Pass :auto to grow the read-ahead as the
+    #   scan is consumed
     # @return [Iterator] An iterator over key-value pairs
     #
-    def scan(start_key, end_key = nil, durability_filter: nil, dirty: nil,
-             read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, &)
+    def scan(start_key, end_key = nil, durability_filter: nil, dirty: nil, read_ahead_bytes: nil,
+             read_ahead: nil, cache_blocks: nil, max_fetch_tasks: nil, &)
       opts = {}
       opts[:durability_filter] = durability_filter.to_s if durability_filter
       opts[:dirty] = dirty unless dirty.nil?
       opts[:read_ahead_bytes] = read_ahead_bytes if read_ahead_bytes
+      opts[:read_ahead] = read_ahead.to_s if read_ahead
       opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
       opts[:max_fetch_tasks] = max_fetch_tasks if max_fetch_tasks
 
@@ -57,6 +60,8 @@ module SlateDb
     # @param durability_filter [String, nil] Filter by durability level
     # @param dirty [Boolean, nil] Whether to include uncommitted data
     # @param read_ahead_bytes [Integer, nil] Number of bytes to read ahead
+    # @param read_ahead [Symbol, nil] Pass :auto to grow the read-ahead as the
+    #   scan is consumed
     # @param cache_blocks [Boolean, nil] Whether to cache blocks
     # @param max_fetch_tasks [Integer, nil] Maximum number of fetch tasks
     # @param from [String, nil] Inclusive lower bound suffix, appended to the
@@ -65,13 +70,14 @@ module SlateDb
     #   prefix, to stop scanning at. Defaults to the end of the prefix.
     # @return [Iterator] An iterator over key-value pairs
     #
-    def scan_prefix(prefix, durability_filter: nil, dirty: nil,
-                    read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil,
+    def scan_prefix(prefix, durability_filter: nil, dirty: nil, read_ahead_bytes: nil,
+                    read_ahead: nil, cache_blocks: nil, max_fetch_tasks: nil,
                     from: nil, to: nil, &)
       opts = {
         durability_filter: durability_filter&.to_s,
         dirty: dirty,
         read_ahead_bytes: read_ahead_bytes,
+        read_ahead: read_ahead&.to_s,
         cache_blocks: cache_blocks,
         max_fetch_tasks: max_fetch_tasks,
         subrange_from: from,
@@ -90,5 +96,25 @@ module SlateDb
         iter
       end
     end
+
+    # Read several ranges of keys from the snapshot in one call.
+    #
+    # The ranges are scanned concurrently in the extension, and every result
+    # comes from this snapshot's version of the database.
+    #
+    # @param ranges [Array<Range>] Ranges of string keys; either end may be nil
+    # @param limit [Integer, nil] The most entries to return for each range
+    # @return [Hash{Range => Array<Array(String, String)>}] The `[key, value]`
+    #   pairs in each range, in key order
+    #
+    # @example
+    #   snap.scan_many(["user:1:"..."user:1;", "user:2:"..."user:2;"], limit: 100)
+    #   # => { "user:1:"..."user:1;" => [["user:1:name", "Ada"]], "user:2:"..."user:2;" => [] }
+    #
+    def scan_many(ranges, limit: nil)
+      ranges = ranges.to_a
+      bounds = ranges.map { |range| [range.begin, range.end, range.exclude_end?] }
+      ranges.zip(_scan_many(bounds, { limit: }.compact)).to_h
+    end
   end
 end
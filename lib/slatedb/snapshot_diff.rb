@@ -0,0 +1,51 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class SnapshotDiff
+    include Enumerable
+
+    # Iterate over the differences, in key order.
+    #
+    # @yield [change] Yields each difference as `[op, key, old_value, new_value]`
+    # @return [self, Enumerator] Returns self if block given, otherwise an Enumerator
+    def each
+      return to_enum(:each) unless block_given?
+
+      while (change = next_change)
+        yield change
+      end
+
+      self
+    end
+  end
+
+  class << self
+    # Compare two snapshots key by key.
+    #
+    # Both snapshots are scanned in key order and merged natively, so only
+    # the keys that differ reach Ruby. `op` is `:added` for a key only in
+    # `new_snapshot` (`old_value` is nil), `:removed` for a key only in
+    # `old_snapshot` (`new_value` is nil) and `:changed` for a key whose
+    # value differs; each value is decoded by its snapshot's codec. The
+    # snapshots may come from different databases.
+    #
+    # @param old_snapshot [Snapshot] The earlier state
+    # @param new_snapshot [Snapshot] The later state
+    # @param range [Range, nil] Only compare keys in this range of strings;
+    #   either end may be nil. Defaults to every key.
+    # @yield [op, key, old_value, new_value] Each difference, if a block is given
+    # @return [SnapshotDiff] The differences, as an Enumerable
+    #
+    # @example Assert exactly what a job changed
+    #   before = db.snapshot
+    #   job.run
+    #   SlateDb.diff(before, db.snapshot).to_a
+    #   # => [[:changed, "user:1", "pending", "active"], [:added, "audit:42", nil, "activated user:1"]]
+    #
+    def diff(old_snapshot, new_snapshot, range: nil, &)
+      opts = range ? { start: range.begin, end: range.end, exclude_end: range.exclude_end? }.compact : {}
+      changes = SnapshotDiff._new(old_snapshot, new_snapshot, opts)
+      block_given? ? changes.each(&) : changes
+    end
+  end
+end
@@ -0,0 +1,93 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # The result of {SlateDb.check_store}.
+  class StoreCheck
+    # @return [String] The URL that was checked
+    attr_reader :url
+
+    # @return [Float] Seconds taken by the request, including object store retries
+    attr_reader :latency
+
+    # @return [SlateDb::Error, nil] Why the check failed, if it did
+    attr_reader :error
+
+    def initialize(url, latency, error)
+      @url = url
+      @latency = latency
+      @error = error
+    end
+
+    # Whether the store could be listed.
+    def ok?
+      error.nil?
+    end
+
+    # The likely cause of a failure, from the error and the HTTP status the
+    # store returned:
+    #
+    # - `:unauthenticated` (401): missing, invalid or expired credentials
+    # - `:permission_denied` (403): the credentials lack list access
+    # - `:not_found` (404): the bucket or container does not exist
+    # - `:throttled`: the store is rate limiting requests
+    # - `:timeout`: no response within the check's timeout
+    # - `:network`: no HTTP response, e.g. DNS, TLS, proxy or connection errors
+    # - `:error`: any other failure
+    #
+    # @return [Symbol, nil] nil when the check passed
+    def problem
+      return if ok?
+      return error.code if %i[throttled timeout].include?(error.code)
+
+      case error.details[:status]
+      when 401 then :unauthenticated
+      when 403 then :permission_denied
+      when 404 then :not_found
+      when nil then :network
+      else :error
+      end
+    end
+
+    # Convert the result to a hash.
+    #
+    # @return [Hash] Hash with :url, :ok, :latency, :problem and :error (the message)
+    def to_h
+      { url: url, ok: ok?, latency: latency, problem: problem, error: error&.message }
+    end
+
+    def inspect
+      "#<#{self.class.name} url=#{url} ok=#{ok?} latency=#{latency.round(3)} problem=#{problem.inspect}>"
+    end
+  end
+
+  class << self
+    # Check that an object store can be reached with the given settings,
+    # without opening a database, so deploy scripts can validate
+    # configuration before booting the app.
+    #
+    # Makes a single list request for `path` and reports how long it took and
+    # why it failed, if it did. Failures are returned rather than raised;
+    # settings rejected before any request is made (such as an unknown store
+    # option) still raise InvalidArgumentError.
+    #
+    # @param url [String] Object store URL (e.g., "s3://bucket")
+    # @param path [String, nil] Path to list, such as the database path.
+    #   Defaults to the root of the store.
+    # @param timeout [Numeric] Seconds to wait, including the object store's
+    #   retries, before reporting a timeout (default 10)
+    # @param store_options [Hash, nil] See {Database.open}
+    # @param credentials_provider [#call, nil] See {Database.open}
+    # @param client_options [Hash, nil] See {Database.open}
+    # @return [StoreCheck]
+    #
+    # @example Fail a deploy when the bucket is unreachable
+    #   check = SlateDb.check_store("s3://mybucket", "mydb")
+    #   abort "S3 #{check.problem}: #{check.error.message}" unless check.ok?
+    def check_store(url, path = nil, timeout: 10, store_options: nil, credentials_provider: nil,
+                    client_options: nil)
+      opts = store_open_options(store_options, credentials_provider, client_options)
+      latency, error = _check_store(url, path, timeout.to_f, opts)
+      StoreCheck.new(url, latency, error)
+    end
+  end
+end
@@ -0,0 +1,74 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # Test-only hooks for exercising error handling and retry logic without a
+  # flaky network.
+  module Testing
+    class << self
+      # Fail or delay object store requests.
+      #
+      # Faults apply to every database, reader and admin in the process,
+      # including ones opened before the fault was injected. The first
+      # registered fault that matches a request and fires is applied.
+      #
+      # SlateDB retries transient object store errors itself, with backoff, so
+      # :unavailable and :throttled faults show up as latency and retries
+      # rather than exceptions. :not_found faults are not retried and raise
+      # DataError.
+      #
+      # @param op [Symbol] The request to target: :put, :get, :head, :delete,
+      #   :list, :copy, :rename or :any
+      # @param error [Symbol, nil] :unavailable, :throttled or :not_found; nil
+      #   to only delay requests
+      # @param probability [Float] Chance that a matching request is affected,
+      #   greater than 0 and at most 1
+      # @param delay_ms [Integer, nil] Milliseconds to wait before failing or
+      #   forwarding an affected request
+      # @param path [String, nil] Only affect requests for object paths
+      #   containing this string, e.g. "wal/" or ".manifest"
+      # @param times [Integer, nil] Stop after affecting this many requests
+      # @yield If a block is given, the fault is removed when it returns
+      # @return [Fault, Object] The fault, or the block's result
+      # @raise [InvalidArgumentError] if an option is invalid
+      #
+      # @example Fail one in ten writes
+      #   SlateDb::Testing.inject_fault(:put, error: :unavailable, probability: 0.1)
+      #
+      # @example Slow down SST reads for the duration of a block
+      #   SlateDb::Testing.inject_fault(:get, error: nil, delay_ms: 500, path: ".sst") do
+      #     expect(report.generate).to be_complete
+      #   end
+      def inject_fault(op, error: :unavailable, probability: 1.0, delay_ms: nil, path: nil, times: nil)
+        opts = { error: error&.to_s, probability:, delay_ms:, path:, times: }.compact
+        fault = _inject_fault(op.to_s, opts)
+        return fault unless block_given?
+
+        begin
+          yield fault
+        ensure
+          fault.remove
+        end
+      end
+    end
+
+    class Fault
+      # The request the fault targets.
+      #
+      # @return [Symbol]
+      def op
+        _op.to_sym
+      end
+
+      # The error the fault fails requests with, or nil if it only delays them.
+      #
+      # @return [Symbol, nil]
+      def error
+        _error&.to_sym
+      end
+
+      def inspect
+        "#<#{self.class.name} op=#{op} error=#{error.inspect} hits=#{hits} active=#{active?}>"
+      end
+    end
+  end
+end
@@ -0,0 +1,30 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class << self
+    # Export spans for traced operations to an OpenTelemetry collector.
+    #
+    # Operations called with a `trace_context:` carrying a sampled W3C
+    # `traceparent` header are recorded as child spans of the caller's span
+    # and sent to the collector over OTLP/HTTP (JSON) in the background.
+    # Operations without a trace context are not traced. Pass `endpoint: nil`
+    # to stop exporting.
+    #
+    # @param endpoint [String, nil] Collector base URL, e.g. "http://localhost:4318".
+    #   Spans are posted to its `/v1/traces` path.
+    # @param service_name [String] The `service.name` resource attribute
+    # @param headers [Hash] Extra HTTP headers sent with each export, e.g. for authentication
+    # @return [void]
+    #
+    # @example Trace database calls within the current OpenTelemetry span
+    #   SlateDb.configure_tracing(endpoint: "http://localhost:4318", service_name: "checkout")
+    #
+    #   carrier = {}
+    #   OpenTelemetry.propagation.inject(carrier)
+    #   db.get("cart:42", trace_context: carrier)
+    def configure_tracing(endpoint:, service_name: "slatedb", headers: {})
+      opts = { service_name: service_name, headers: headers.to_h { |name, value| [name.to_s, value.to_s] } }
+      _configure_tracing(endpoint, opts)
+    end
+  end
+end
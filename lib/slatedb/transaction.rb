@@ -23,16 +23,76 @@ module SlateDb
       end
     end
 
+    # Get several values within the transaction in one call.
+    #
+    # The reads run concurrently against the transaction's snapshot and are
+    # tracked for conflict detection just like {#get}.
+    #
+    # @param keys [Array<String>] The keys to look up
+    # @param durability_filter [String, nil] Filter by durability level
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @return [Array<String, nil>] Values in the same order as keys, nil for missing keys
+    #
+    # @example Move a balance between accounts
+    #   db.transaction(isolation: :serializable) do |txn|
+    #     from, to = txn.get_many(%w[acct:1 acct:2]).map(&:to_i)
+    #     txn.put("acct:1", (from - 100).to_s)
+    #     txn.put("acct:2", (to + 100).to_s)
+    #   end
+    #
+    def get_many(keys, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      opts = {}
+      opts[:durability_filter] = durability_filter.to_s if durability_filter
+      opts[:dirty] = dirty unless dirty.nil?
+      opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
+
+      _get_many(Array(keys), opts)
+    end
+
+    # Get a value by key and lock it against concurrent writers.
+    #
+    # The key is registered for conflict detection even under snapshot
+    # isolation, so the commit raises TransactionError if another writer
+    # modifies the key after this transaction started. Use it for
+    # read-modify-write updates without switching the whole transaction to
+    # `isolation: :serializable`. To protect keys without reading them, use
+    # {#mark_read}. In a read-only transaction it is a plain {#get}.
+    #
+    # @param key [String] The key to look up
+    # @param durability_filter [String, nil] Filter by durability level
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @return [String, nil] The value, or nil if not found
+    #
+    # @example Increment a counter safely under snapshot isolation
+    #   db.transaction(retries: 3) do |txn|
+    #     count = txn.get_for_update("counter").to_i
+    #     txn.put("counter", (count + 1).to_s)
+    #   end
+    #
+    def get_for_update(key, durability_filter: nil, dirty: nil, cache_blocks: nil)
+      opts = {}
+      opts[:durability_filter] = durability_filter.to_s if durability_filter
+      opts[:dirty] = dirty unless dirty.nil?
+      opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
+
+      _get_for_update(key, opts)
+    end
+
     # Store a key-value pair within the transaction.
     #
     # @param key [String] The key to store
     # @param value [String] The value to store
-    # @param ttl [Integer, nil] Time-to-live in milliseconds
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   to use the database's default TTL, or `:none` to never expire
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as a Time or
+    #   milliseconds since the epoch, instead of `ttl`
     # @return [void]
     #
-    def put(key, value, ttl: nil)
-      if ttl
-        _put_with_options(key, value, { ttl: ttl })
+    def put(key, value, ttl: nil, expire_at: nil)
+      if ttl || expire_at
+        _put_with_options(key, value, { ttl:, expire_at: }.compact)
       else
         _put(key, value)
       end
@@ -47,16 +107,51 @@ module SlateDb
       _delete(key)
     end
 
+    # Delete several keys within the transaction.
+    #
+    # @param keys [Array<String>] The keys to delete
+    # @return [void]
+    #
+    def delete_many(keys)
+      _delete_many(Array(keys))
+    end
+
+    # Apply every operation from a WriteBatch within the transaction.
+    #
+    # The operations are buffered in the transaction in the order they were
+    # added to the batch and committed along with the transaction's other
+    # writes. {WriteBatch#delete_range} deletes every key in the range that
+    # the transaction can see, including keys it has written. The batch is
+    # emptied, as with {Database#write}.
+    #
+    # @param batch [WriteBatch] The batch to apply
+    # @return [void]
+    #
+    # @example Run existing batch-building code transactionally
+    #   db.transaction do |txn|
+    #     next unless txn.get_for_update("import:state") == "pending"
+    #
+    #     txn.write(build_import_batch)
+    #     txn.put("import:state", "done")
+    #   end
+    #
+    def write(batch)
+      _write(batch)
+    end
+
     # Merge a value within the transaction.
     #
     # @param key [String] The key to merge into
-    # @param value [String] The merge operand to apply
-    # @param ttl [Integer, nil] Time-to-live in milliseconds
+    # @param value [Object] The merge operand to apply, encoded by the database's codec
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   to use the database's default TTL, or `:none` to never expire
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as a Time or
+    #   milliseconds since the epoch, instead of `ttl`
     # @return [void]
     #
-    def merge(key, value, ttl: nil)
-      if ttl
-        _merge_with_options(key, value, { ttl: ttl })
+    def merge(key, value, ttl: nil, expire_at: nil)
+      if ttl || expire_at
+        _merge_with_options(key, value, { ttl:, expire_at: }.compact)
       else
         _merge(key, value)
       end
@@ -64,18 +159,46 @@ module SlateDb
 
     # Scan a range of keys within the transaction.
     #
+    # The scan sees the transaction's own uncommitted writes.
+    #
     # @param start_key [String] The start key (inclusive)
     # @param end_key [String, nil] The end key (exclusive)
+    # @param durability_filter [String, nil] Filter by durability level
+    # @param dirty [Boolean, nil] Whether to include uncommitted data
+    # @param read_ahead_bytes [Integer, nil] Number of bytes to read ahead
+    # @param cache_blocks [Boolean, nil] Whether to cache blocks
+    # @param max_fetch_tasks [Integer, nil] Maximum number of fetch tasks
+    # @param order [Symbol, String, nil] Iteration order (:asc/:ascending or :desc/:descending)
+    # @param limit [Integer, nil] Stop after this many entries
+    # @param keys_only [Boolean] Yield only keys instead of [key, value] pairs
+    # @param binary [Boolean] Return binary (ASCII-8BIT) strings with the
+    #   exact stored bytes instead of UTF-8 strings
+    # @param frozen [Boolean] Return frozen strings, with keys interned so
+    #   repeated keys share one String across scans
+    # @param seq [Boolean] Yield each entry's sequence number after its value,
+    #   as [key, value, seq] (or [key, seq] with keys_only)
     # @return [Iterator] An iterator over key-value pairs
     #
+    # @example Newest five keys, including this transaction's writes
+    #   txn.scan("event:", "event;", order: :desc, limit: 5, keys_only: true).to_a
+    #
     def scan(start_key, end_key = nil, durability_filter: nil, dirty: nil,
-             read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil, &)
-      opts = {}
-      opts[:durability_filter] = durability_filter.to_s if durability_filter
-      opts[:dirty] = dirty unless dirty.nil?
-      opts[:read_ahead_bytes] = read_ahead_bytes if read_ahead_bytes
-      opts[:cache_blocks] = cache_blocks unless cache_blocks.nil?
-      opts[:max_fetch_tasks] = max_fetch_tasks if max_fetch_tasks
+             read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil,
+             order: nil, limit: nil, keys_only: false, binary: false, frozen: false,
+             seq: false, &)
+      opts = {
+        durability_filter: durability_filter&.to_s,
+        dirty: dirty,
+        read_ahead_bytes: read_ahead_bytes,
+        cache_blocks: cache_blocks,
+        max_fetch_tasks: max_fetch_tasks,
+        order: order&.to_s,
+        limit: limit,
+        keys_only: keys_only || nil,
+        binary: binary || nil,
+        frozen: frozen || nil,
+        seq: seq || nil
+      }.compact
 
       iter = if opts.empty?
                _scan(start_key, end_key)
@@ -102,11 +225,21 @@ module SlateDb
     #   prefix, to start scanning from. Defaults to the start of the prefix.
     # @param to [String, nil] Exclusive upper bound suffix, appended to the
     #   prefix, to stop scanning at. Defaults to the end of the prefix.
+    # @param order [Symbol, String, nil] Iteration order (:asc/:ascending or :desc/:descending)
+    # @param limit [Integer, nil] Stop after this many entries
+    # @param keys_only [Boolean] Yield only keys instead of [key, value] pairs
+    # @param binary [Boolean] Return binary (ASCII-8BIT) strings with the
+    #   exact stored bytes instead of UTF-8 strings
+    # @param frozen [Boolean] Return frozen strings, with keys interned so
+    #   repeated keys share one String across scans
+    # @param seq [Boolean] Yield each entry's sequence number after its value,
+    #   as [key, value, seq] (or [key, seq] with keys_only)
     # @return [Iterator] An iterator over key-value pairs
     #
     def scan_prefix(prefix, durability_filter: nil, dirty: nil,
                     read_ahead_bytes: nil, cache_blocks: nil, max_fetch_tasks: nil,
-                    from: nil, to: nil, &)
+                    from: nil, to: nil, order: nil, limit: nil, keys_only: false, binary: false, frozen: false,
+                    seq: false, &)
       opts = {
         durability_filter: durability_filter&.to_s,
         dirty: dirty,
@@ -114,7 +247,13 @@ module SlateDb
         cache_blocks: cache_blocks,
         max_fetch_tasks: max_fetch_tasks,
         subrange_from: from,
-        subrange_to: to
+        subrange_to: to,
+        order: order&.to_s,
+        limit: limit,
+        keys_only: keys_only || nil,
+        binary: binary || nil,
+        frozen: frozen || nil,
+        seq: seq || nil
       }.compact
 
       iter = if opts.empty?
@@ -134,7 +273,7 @@ module SlateDb
     #
     # This explicitly tracks reads for conflict checking in serializable isolation,
     # allowing selective read-write conflict detection even when keys weren't
-    # actually read via get().
+    # actually read via get(). Does nothing in a read-only transaction.
     #
     # @param keys [Array<String>] The keys to mark as read
     # @return [void]
@@ -150,6 +289,96 @@ module SlateDb
       _mark_read(Array(keys))
     end
 
+    # Take a savepoint that later writes can be rolled back to.
+    #
+    # Without a block, returns the savepoint for use with {#rollback_to} and
+    # {#release_savepoint}. With a block, yields and releases the savepoint
+    # afterwards; if the block raises, its writes are rolled back and the
+    # error is re-raised, leaving the rest of the transaction intact.
+    #
+    # While a savepoint is active, each key is read once before it is first
+    # written so its previous value can be restored. Under serializable
+    # isolation those keys are therefore also tracked as read.
+    #
+    # @yield Optional block whose writes are undone if it raises
+    # @return [Integer, Object] The savepoint, or the block's result
+    #
+    # @example Undo part of a transaction
+    #   db.transaction do |txn|
+    #     txn.put("order:1", "placed")
+    #     sp = txn.savepoint
+    #     txn.put("inventory:42", "0")
+    #     txn.rollback_to(sp) if backordered?
+    #   end
+    #
+    # @example Block form
+    #   db.transaction do |txn|
+    #     txn.put("order:1", "placed")
+    #     begin
+    #       txn.savepoint { apply_discount(txn) }
+    #     rescue DiscountError
+    #       # the discount's writes were rolled back; the order is still placed
+    #     end
+    #   end
+    #
+    def savepoint
+      id = _savepoint
+      return id unless block_given?
+
+      begin
+        yield
+      rescue StandardError
+        _rollback_to(id)
+        raise
+      ensure
+        _release_savepoint(id)
+      end
+    end
+
+    # Undo every write made since a savepoint was taken.
+    #
+    # SlateDB cannot remove writes from a transaction, so each key written
+    # since the savepoint is written again with the value and remaining TTL
+    # it had at the savepoint, or deleted if it did not exist. Those keys
+    # still take part in conflict detection on commit. The savepoint remains
+    # active; savepoints taken after it are released.
+    #
+    # @param savepoint [Integer] A savepoint returned by {#savepoint}
+    # @return [void]
+    # @raise [InvalidArgumentError] If the savepoint has been released
+    #
+    def rollback_to(savepoint)
+      _rollback_to(savepoint)
+    end
+
+    # Release a savepoint, and any taken after it, keeping their writes.
+    #
+    # @param savepoint [Integer] A savepoint returned by {#savepoint}
+    # @return [void]
+    #
+    def release_savepoint(savepoint)
+      _release_savepoint(savepoint)
+    end
+
+    # Describe the transaction's state, e.g. for logging.
+    #
+    # @return [Hash] Hash with :id, :isolation_level, :seqnum, :op_count and :write_bytes
+    #
+    # @example Log in-flight transactions
+    #   db.transaction do |txn|
+    #     txn.put("key", "value")
+    #     logger.debug(txn.to_h)
+    #   end
+    #
+    def to_h
+      { id: id, isolation_level: isolation_level, seqnum: seqnum, op_count: op_count, write_bytes: write_bytes }
+    end
+
+    def inspect
+      "#<#{self.class.name} id=#{id} isolation_level=#{isolation_level} seqnum=#{seqnum.inspect} " \
+        "op_count=#{op_count} write_bytes=#{write_bytes}>"
+    end
+
     # Commit the transaction.
     #
     # @param await_durable [Boolean, nil] Whether to wait for durability (default: true)
@@ -157,7 +386,21 @@ module SlateDb
     #   When provided (and non-zero), it is used instead of the internally
     #   generated sequence number and must be strictly greater than the current
     #   maximum sequence number. (Requires SlateDB >= 0.13.0)
-    # @return [void]
+    # @param timeout_ms [Integer, nil] Stop waiting for the commit after this
+    #   many milliseconds. The transaction is closed either way, and a commit
+    #   that timed out may still have been applied.
+    # @param return_result [Boolean] Return a {WriteResult} describing the
+    #   commit (default: false)
+    # @param return_handle [Boolean] Return a {DurabilityHandle} for the
+    #   commit (default: false). Cannot be combined with `return_result`.
+    # @param return_token [Boolean] Return the commit's sequence number as a
+    #   consistency token, to pass as `min_seq:` to a later read (default: false)
+    # @param trace_context [Hash, nil] Trace context with a W3C "traceparent"
+    #   header, to record the operation as a child span (see {SlateDb.configure_tracing})
+    # @return [WriteResult, DurabilityHandle, Integer, nil] The write result,
+    #   durability handle or consistency token, when requested and the
+    #   transaction wrote something. A read-only transaction is just closed, and returns nil.
+    # @raise [CommitTimeoutError] If the commit did not finish within `timeout_ms`
     #
     # @example Commit a transaction
     #   txn = db.begin_transaction
@@ -167,10 +410,19 @@ module SlateDb
     # @example Commit with an explicit sequence number
     #   txn.commit(seqnum: 99)
     #
-    def commit(await_durable: nil, seqnum: nil)
+    # @example Bound how long a commit can block
+    #   txn.commit(timeout_ms: 5_000)
+    #
+    def commit(await_durable: nil, seqnum: nil, timeout_ms: nil, return_result: false, return_handle: false,
+               return_token: false, trace_context: nil)
       opts = {}
       opts[:await_durable] = await_durable unless await_durable.nil?
       opts[:seqnum] = seqnum if seqnum
+      opts[:timeout_ms] = timeout_ms if timeout_ms
+      opts[:return_result] = true if return_result
+      opts[:return_handle] = true if return_handle
+      opts[:return_token] = true if return_token
+      opts[:trace_context] = trace_context if trace_context
 
       if opts.empty?
         _commit
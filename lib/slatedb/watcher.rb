@@ -0,0 +1,38 @@
+# frozen_string_literal: true
+
+module SlateDb
+  # A background watch on a key prefix, returned by {Database#watch}.
+  class Watcher
+    # Run the block for each change on a new Ruby thread.
+    #
+    # @api private
+    # @return [self]
+    def start(&block)
+      @thread = Thread.new do
+        while (event = next_event)
+          block.call(*event)
+        end
+      end
+      self
+    end
+
+    # Stop watching and wait for the current block call to finish.
+    #
+    # Safe to call from inside the block.
+    #
+    # @return [void]
+    def stop
+      _stop
+      @thread.join unless @thread.nil? || @thread == Thread.current
+    end
+
+    # Wait for the watcher to stop, e.g. after {#stop} or when the block
+    # raises.
+    #
+    # @param timeout [Numeric, nil] Seconds to wait, or nil to wait forever
+    # @return [self, nil] self once stopped, nil on timeout
+    def join(timeout = nil)
+      @thread.join(timeout) && self
+    end
+  end
+end
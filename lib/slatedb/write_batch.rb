@@ -5,17 +5,22 @@ module SlateDb
     # Add a put operation to the batch.
     #
     # @param key [String] The key to store
-    # @param value [String] The value to store
-    # @param ttl [Integer, nil] Time-to-live in milliseconds
+    # @param value [Object] The value to store, encoded when the batch is
+    #   written by the codec of the database or transaction it is written to
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   to use the database's default TTL, or `:none` to never expire
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as a Time or
+    #   milliseconds since the epoch, instead of `ttl`
     # @return [self] Returns self for method chaining
     #
     # @example
     #   batch.put("key", "value")
     #   batch.put("key2", "value2", ttl: 60_000)
+    #   batch.put("key3", "value3", expire_at: Time.now + 3600)
     #
-    def put(key, value, ttl: nil)
-      if ttl
-        _put_with_options(key, value, { ttl: ttl })
+    def put(key, value, ttl: nil, expire_at: nil)
+      if ttl || expire_at
+        _put_with_options(key, value, { ttl:, expire_at: }.compact)
       else
         _put(key, value)
       end
@@ -35,20 +40,45 @@ module SlateDb
       self
     end
 
+    # Add a delete of every key in [start_key, end_key) to the batch.
+    #
+    # SlateDB has no range tombstones, so when the batch is written the range
+    # is scanned and one delete is added per key found, and the cost grows
+    # with the number of keys in the range. The scan and the batch commit
+    # together in a serializable transaction: if another writer adds a key to
+    # the range first, the write is retried, and raises {TransactionError}
+    # after 5 retries. Puts or merges added to the batch before this call are
+    # deleted as well, while operations added afterwards are kept.
+    #
+    # @param start_key [String] The first key to delete (inclusive)
+    # @param end_key [String] The end of the range (exclusive)
+    # @return [self] Returns self for method chaining
+    #
+    # @example Delete every key under a prefix
+    #   batch.delete_range("user:42:", "user:42:\xff")
+    #
+    def delete_range(start_key, end_key)
+      _delete_range(start_key, end_key)
+      self
+    end
+
     # Add a merge operation to the batch.
     #
     # @param key [String] The key to merge into
-    # @param value [String] The merge operand to apply
-    # @param ttl [Integer, nil] Time-to-live in milliseconds
+    # @param value [Object] The merge operand to apply, encoded as for {#put}
+    # @param ttl [Integer, Symbol, nil] Time-to-live in milliseconds, `:default`
+    #   to use the database's default TTL, or `:none` to never expire
+    # @param expire_at [Time, Integer, nil] Absolute expiry time, as a Time or
+    #   milliseconds since the epoch, instead of `ttl`
     # @return [self] Returns self for method chaining
     #
     # @example
     #   batch.merge("key", "part1")
     #   batch.merge("key", "part2", ttl: 30_000)
     #
-    def merge(key, value, ttl: nil)
-      if ttl
-        _merge_with_options(key, value, { ttl: ttl })
+    def merge(key, value, ttl: nil, expire_at: nil)
+      if ttl || expire_at
+        _merge_with_options(key, value, { ttl:, expire_at: }.compact)
       else
         _merge(key, value)
       end
@@ -0,0 +1,16 @@
+# frozen_string_literal: true
+
+module SlateDb
+  class WriteResult
+    # Convert the result to a hash.
+    #
+    # @return [Hash] Hash with :seq, :durability, :bytes and :duration
+    def to_h
+      { seq: seq, durability: durability, bytes: bytes, duration: duration }
+    end
+
+    def inspect
+      "#<#{self.class.name} seq=#{seq} durability=#{durability} bytes=#{bytes} duration=#{duration}>"
+    end
+  end
+end
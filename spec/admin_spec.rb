@@ -70,6 +70,40 @@ RSpec.describe SlateDb::Admin do
     end
   end
 
+  describe "#create_checkpoint!" do
+    let(:dir) { Dir.mktmpdir("slatedb-checkpoint") }
+    let(:admin) { SlateDb::Admin.new("db", url: "file://#{dir}") }
+
+    before do
+      SlateDb::Database.open("db", url: "file://#{dir}") { |db| db.put("key", "value") }
+    end
+
+    after do
+      FileUtils.rm_rf(dir)
+    end
+
+    it "replaces checkpoints with the same name" do
+      first = admin.create_checkpoint!(name: "nightly")
+      expect(first[:replaced]).to eq([])
+
+      second = admin.create_checkpoint!(name: "nightly")
+      expect(second[:replaced]).to eq([first[:id]])
+      expect(admin.list_checkpoints(name: "nightly").map { |cp| cp[:id] }).to eq([second[:id]])
+    end
+
+    it "raises when the name is taken and replace is false" do
+      admin.create_checkpoint!(name: "nightly")
+
+      expect { admin.create_checkpoint!(name: "nightly", replace: false) }
+        .to raise_error(SlateDb::InvalidArgumentError, /already exists/)
+      expect(admin.list_checkpoints(name: "nightly").size).to eq(1)
+    end
+
+    it "requires a name" do
+      expect { admin.create_checkpoint!(name: "") }.to raise_error(SlateDb::InvalidArgumentError, /name/)
+    end
+  end
+
   describe "#refresh_checkpoint" do
     it "raises error for invalid UUID" do
       admin = SlateDb::Admin.new(db_path)
@@ -84,6 +118,84 @@ RSpec.describe SlateDb::Admin do
     end
   end
 
+  describe "#hold_checkpoint" do
+    let(:dir) { Dir.mktmpdir("slatedb-lease") }
+    let(:admin) { SlateDb::Admin.new("db", url: "file://#{dir}") }
+    let(:checkpoint_id) { admin.create_checkpoint(lifetime: 1_000)[:id] }
+
+    before do
+      SlateDb::Database.open("db", url: "file://#{dir}") { |db| db.put("key", "value") }
+    end
+
+    after do
+      FileUtils.rm_rf(dir)
+    end
+
+    it "keeps refreshing the checkpoint until released" do
+      lease = admin.hold_checkpoint(checkpoint_id, refresh_every_ms: 20)
+      sleep 0.2
+
+      expect(lease.id).to eq(checkpoint_id)
+      expect(lease.refresh_count).to be > 1
+      expect(lease.last_error).to be_nil
+      expect(lease).to be_active
+
+      lease.release
+      count = lease.refresh_count
+      sleep 0.1
+      expect(lease).to be_released
+      expect(lease.refresh_count).to eq(count)
+    end
+
+    it "releases the lease after the block" do
+      result = admin.hold_checkpoint(checkpoint_id, refresh_every_ms: 50) do |lease|
+        expect(lease).to be_active
+        lease
+      end
+
+      expect(result).to be_released
+    end
+
+    it "stops once the checkpoint is deleted" do
+      lease = admin.hold_checkpoint(checkpoint_id, refresh_every_ms: 20)
+      admin.delete_checkpoint(checkpoint_id)
+      sleep 0.2
+
+      expect(lease).not_to be_active
+      expect(lease.last_error).to be_a(String)
+    end
+
+    it "validates its arguments" do
+      expect { admin.hold_checkpoint("invalid-uuid", refresh_every_ms: 20) }
+        .to raise_error(SlateDb::InvalidArgumentError)
+      expect { admin.hold_checkpoint(checkpoint_id, refresh_every_ms: 0) }
+        .to raise_error(SlateDb::InvalidArgumentError)
+      expect { admin.hold_checkpoint(checkpoint_id, refresh_every_ms: 100, lifetime_ms: 100) }
+        .to raise_error(SlateDb::InvalidArgumentError, /lifetime_ms/)
+      expect { admin.hold_checkpoint("00000000-0000-0000-0000-000000000000", refresh_every_ms: 20) }
+        .to raise_error(SlateDb::Error)
+    end
+  end
+
+  describe "#active_writer" do
+    it "returns nil before the database is created" do
+      expect(SlateDb::Admin.new(db_path).active_writer).to be_nil
+    end
+
+    it "names the writer holding the latest epoch" do
+      Dir.mktmpdir("slatedb-writer") do |dir|
+        SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+          writer = SlateDb::Admin.new("db", url: "file://#{dir}").active_writer
+          info = db.writer_info
+
+          expect(writer[:client_id]).to eq(info[:client_id])
+          expect(writer[:epoch]).to eq(info[:epoch])
+          expect(writer[:last_manifest_write]).to be_within(60).of(Time.now)
+        end
+      end
+    end
+  end
+
   describe "#delete_checkpoint" do
     it "raises error for invalid UUID" do
       admin = SlateDb::Admin.new(db_path)
@@ -104,6 +216,394 @@ RSpec.describe SlateDb::Admin do
       # GC on empty/new database should work
       expect { admin.run_gc }.not_to raise_error
     end
+
+    it "yields progress after each directory" do
+      admin = SlateDb::Admin.new(db_path)
+      reports = []
+      admin.run_gc { |progress| reports << progress }
+
+      expect(reports.map { |r| r[:phase] }).to eq(%i[manifest wal compacted compactions detach])
+      expect(reports.last).to include(completed: 5, total: 5, percent: 100.0)
+    end
+
+    it "reports what was deleted" do
+      Dir.mktmpdir("slatedb-gc") do |dir|
+        SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+          3.times do |i|
+            db.put("key#{i}", "value")
+            db.flush(target: :remote)
+          end
+        end
+
+        report = SlateDb::Admin.new("db", url: "file://#{dir}").run_gc(min_age: 0)
+
+        expect(report).to include(:deleted_objects, :deleted_bytes, :duration, :directories)
+        expect(report[:deleted_objects]).to eq(report[:directories].values.sum { |d| d[:objects] })
+        expect(report[:deleted_bytes]).to eq(report[:directories].values.sum { |d| d[:bytes] })
+        expect(report[:deleted_objects]).to be > 0
+      end
+    end
+
+    it "returns nil with report: false" do
+      admin = SlateDb::Admin.new(db_path)
+      expect(admin.run_gc(report: false)).to be_nil
+    end
+  end
+
+  describe "#incremental_backup" do
+    let(:source_dir) { Dir.mktmpdir("slatedb-source") }
+    let(:backup_dir) { Dir.mktmpdir("slatedb-backup") }
+
+    after do
+      FileUtils.rm_rf(source_dir)
+      FileUtils.rm_rf(backup_dir)
+    end
+
+    it "copies only new SSTs and produces a restorable backup" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      backup_url = "file://#{backup_dir}"
+
+      SlateDb::Database.open("db", url: "file://#{source_dir}") do |db|
+        db.put("a", "1")
+        db.flush
+      end
+      first = admin.incremental_backup(nil, backup_url)
+      expect(first[:manifest_id]).to be_a(Integer)
+
+      SlateDb::Database.open("db", url: "file://#{source_dir}") do |db|
+        db.put("b", "2")
+        db.flush
+      end
+      second = admin.incremental_backup(first[:manifest_id], backup_url)
+      expect(second[:manifest_id]).to be > first[:manifest_id]
+
+      third = admin.incremental_backup(second[:manifest_id], backup_url)
+      expect(third[:copied_ssts]).to eq(0)
+
+      SlateDb::Database.open("db", url: backup_url) do |db|
+        expect(db.get("a")).to eq("1")
+        expect(db.get("b")).to eq("2")
+      end
+    end
+
+    it "raises InvalidArgumentError for an unknown since manifest" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") { |db| db.put("a", "1") }
+
+      expect do
+        admin.incremental_backup(999_999, "file://#{backup_dir}")
+      end.to raise_error(SlateDb::InvalidArgumentError)
+    end
+
+    it "reads the since manifest from the destination" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") { |db| db.put("a", "1") }
+      first = admin.incremental_backup(nil, "file://#{backup_dir}")
+
+      Dir.mktmpdir("slatedb-other") do |other_dir|
+        expect do
+          admin.incremental_backup(first[:manifest_id], "file://#{other_dir}")
+        end.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+
+    it "yields progress after each SST" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") do |db|
+        db.put("a", "1")
+        db.flush
+      end
+
+      reports = []
+      result = admin.incremental_backup(nil, "file://#{backup_dir}") { |progress| reports << progress }
+
+      expect(reports.count { |r| r[:phase] == :ssts }).to eq(result[:copied_ssts])
+      expect(reports.last).to include(phase: :manifest, copied_bytes: result[:copied_bytes], percent: 100.0)
+    end
+
+    it "leaves its temporary checkpoint out of both databases" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") do |db|
+        db.put("a", "1")
+        db.flush
+      end
+
+      admin.incremental_backup(nil, "file://#{backup_dir}")
+
+      backup = SlateDb::Admin.new("db", url: "file://#{backup_dir}")
+      expect(backup.list_checkpoints(name: "incremental-backup")).to be_empty
+      expect(admin.list_checkpoints(name: "incremental-backup")).to be_empty
+    end
+  end
+
+  describe "#verify_backup" do
+    let(:source_dir) { Dir.mktmpdir("slatedb-source") }
+    let(:backup_dir) { Dir.mktmpdir("slatedb-backup") }
+
+    after do
+      FileUtils.rm_rf(source_dir)
+      FileUtils.rm_rf(backup_dir)
+    end
+
+    it "reports a restorable backup with a stable fingerprint" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+      end
+      admin.incremental_backup(nil, "file://#{backup_dir}")
+
+      report = admin.verify_backup("file://#{backup_dir}")
+      expect(report).to include(ok: true, keys: 2, missing_ssts: [], error: nil)
+      expect(report[:fingerprint]).to match(/\A\h{16}\z/)
+      expect(admin.verify_backup("file://#{source_dir}")[:fingerprint]).to eq(report[:fingerprint])
+    end
+
+    it "leaves the backup untouched" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") { |db| db.put("a", "1") }
+      admin.incremental_backup(nil, "file://#{backup_dir}")
+      files = Dir.glob(File.join(backup_dir, "**", "*")).sort
+
+      expect(admin.verify_backup("file://#{backup_dir}")[:ok]).to be(true)
+      expect(Dir.glob(File.join(backup_dir, "**", "*")).sort).to eq(files)
+    end
+
+    it "reports missing SSTs" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") { |db| db.put("a", "1") }
+      admin.incremental_backup(nil, "file://#{backup_dir}")
+      FileUtils.rm_rf(Dir.glob(File.join(backup_dir, "db", "compacted", "*")))
+
+      report = admin.verify_backup("file://#{backup_dir}")
+      expect(report[:ok]).to be(false)
+      expect(report[:missing_ssts]).not_to be_empty
+    end
+
+    it "yields progress as SSTs and entries are checked" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      SlateDb::Database.open("db", url: "file://#{source_dir}") { |db| db.put("a", "1") }
+      admin.incremental_backup(nil, "file://#{backup_dir}")
+
+      reports = []
+      admin.verify_backup("file://#{backup_dir}") { |progress| reports << progress }
+
+      expect(reports.first).to include(phase: :ssts, missing_ssts: 0)
+      expect(reports.last).to include(phase: :entries, keys: 1)
+    end
+
+    it "raises InvalidArgumentError for an invalid checkpoint UUID" do
+      admin = SlateDb::Admin.new(db_path)
+      expect { admin.verify_backup("file://#{backup_dir}", "invalid-uuid") }
+        .to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
+  describe "#sample_stats" do
+    let(:dir) { Dir.mktmpdir("slatedb-sample") }
+    let(:admin) { SlateDb::Admin.new("db", url: "file://#{dir}") }
+
+    after do
+      FileUtils.rm_rf(dir)
+    end
+
+    before do
+      SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+        10.times { |i| db.put("big:#{i}", "x" * 100) }
+        5.times { |i| db.put("small:#{i}", "y") }
+        db.put("loose", "zz")
+      end
+    end
+
+    it "reports key and value size distributions" do
+      stats = admin.sample_stats(sample_rate: 1.0)
+
+      expect(stats).to include(scanned_entries: 16, sampled_entries: 16, sample_rate: 1.0)
+      expect(stats[:values]).to include(count: 16, min: 1, max: 100, p50: 100)
+      expect(stats[:values][:histogram]).to eq(1 => 5, 2 => 1, 128 => 10)
+      expect(stats[:keys]).to include(count: 16, min: 5, max: 7)
+    end
+
+    it "ranks prefixes by estimated bytes" do
+      prefixes = admin.sample_stats(sample_rate: 1.0)[:prefixes]
+
+      expect(prefixes.map { |p| p[:prefix] }).to eq(["big:", "small:", "loose"])
+      expect(prefixes.first).to eq(prefix: "big:", estimated_keys: 10, estimated_bytes: 10 * (5 + 100))
+    end
+
+    it "honors prefix_delimiter and top" do
+      prefixes = admin.sample_stats(sample_rate: 1.0, prefix_delimiter: "i", top: 1)[:prefixes]
+      expect(prefixes).to eq([{ prefix: "bi", estimated_keys: 10, estimated_bytes: 1_050 }])
+    end
+
+    it "samples the same keys on every run" do
+      first = admin.sample_stats(sample_rate: 0.5)
+      expect(admin.sample_stats(sample_rate: 0.5)[:sampled_entries]).to eq(first[:sampled_entries])
+      expect(first[:scanned_entries]).to eq(16)
+    end
+
+    it "raises InvalidArgumentError for a sample_rate outside (0, 1]" do
+      expect { admin.sample_stats(sample_rate: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+      expect { admin.sample_stats(sample_rate: 1.5) }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+
+    it "raises InvalidArgumentError for a multi-byte prefix_delimiter" do
+      expect { admin.sample_stats(prefix_delimiter: "::") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
+  describe "#prefix_stats" do
+    let(:dir) { Dir.mktmpdir("slatedb-prefix") }
+    let(:admin) { SlateDb::Admin.new("db", url: "file://#{dir}") }
+
+    after do
+      FileUtils.rm_rf(dir)
+    end
+
+    before do
+      SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+        4.times { |i| db.put("tenant:a:#{i}", "x" * 10) }
+        2.times { |i| db.put("tenant:b:#{i}", "y" * 100) }
+      end
+    end
+
+    it "counts entries and bytes under each prefix" do
+      stats = admin.prefix_stats(["tenant:a:", "tenant:b:"])
+
+      expect(stats["tenant:a:"]).to eq(entries: 4, key_bytes: 4 * 10, value_bytes: 40, bytes: 80)
+      expect(stats["tenant:b:"]).to eq(entries: 2, key_bytes: 2 * 10, value_bytes: 200, bytes: 220)
+    end
+
+    it "counts overlapping prefixes in full" do
+      stats = admin.prefix_stats(%w[tenant: tenant:b:])
+      expect(stats["tenant:"][:entries]).to eq(6)
+      expect(stats["tenant:b:"][:entries]).to eq(2)
+    end
+
+    it "reports zeros for a prefix with no keys" do
+      expect(admin.prefix_stats("tenant:c:")).to eq(
+        "tenant:c:" => { entries: 0, key_bytes: 0, value_bytes: 0, bytes: 0 }
+      )
+    end
+
+    it "raises InvalidArgumentError for a sample_rate outside (0, 1]" do
+      expect { admin.prefix_stats(["tenant:"], sample_rate: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
+  describe "#expired_stats and #purge_expired" do
+    let(:dir) { Dir.mktmpdir("slatedb-expired") }
+    let(:admin) { SlateDb::Admin.new("db", url: "file://#{dir}") }
+
+    after do
+      FileUtils.rm_rf(dir)
+    end
+
+    def write_expiring(db, ttl: 500)
+      5.times { |i| db.put("ttl:#{i}", "x" * 10, ttl:, await_durable: false) }
+      5.times { |i| db.put("keep:#{i}", "y" * 10, await_durable: false) }
+      db.freeze_memtable
+    end
+
+    it "counts rows whose TTL has passed" do
+      SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+        write_expiring(db, ttl: 60_000)
+        expect(admin.expired_stats).to include(entries: 10, expired_entries: 0, ssts: 1)
+
+        later = admin.expired_stats(now: Time.now + 120)
+        expect(later).to include(entries: 10, expired_entries: 5, expired_bytes: 5 * (5 + 10))
+        expect(later[:l0]).to include(ssts: 1, expired_entries: 5)
+        expect(later[:sorted_runs]).to eq([])
+      end
+    end
+
+    it "reports rows as reclaimable once a later write reaches L0" do
+      SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+        write_expiring(db)
+        expect(admin.expired_stats(now: Time.now + 60)[:reclaimable_entries]).to eq(0)
+
+        sleep 0.6
+        db.put("later", "z")
+        db.freeze_memtable
+        expect(admin.expired_stats).to include(expired_entries: 5, reclaimable_entries: 5)
+      end
+    end
+
+    it "drops reclaimable rows with purge_expired" do
+      SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+        write_expiring(db)
+        sleep 0.6
+        db.put("later", "z")
+        db.freeze_memtable
+
+        result = admin.purge_expired(wait: true, timeout_ms: 30_000)
+        expect(result).to include(status: :completed, l0_ssts: 2, sorted_runs: 0, destination: 0)
+
+        stats = admin.expired_stats
+        expect(stats).to include(entries: 6, expired_entries: 0)
+        expect(stats[:sorted_runs].map { |run| run[:id] }).to eq([0])
+        expect(db.get("keep:0")).to eq("y" * 10)
+      end
+    end
+
+    it "returns nil when nothing has been flushed" do
+      SlateDb::Database.open("db", url: "file://#{dir}") do |db|
+        db.put("k", "v")
+        expect(admin.purge_expired).to be_nil
+        expect(admin.expired_stats).to include(ssts: 0, entries: 0)
+      end
+    end
+
+    it "raises InvalidArgumentError for a zero timeout_ms" do
+      expect { admin.purge_expired(wait: true, timeout_ms: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
+  describe "#set_metadata and #metadata" do
+    let(:source_dir) { Dir.mktmpdir("slatedb-source") }
+
+    after do
+      FileUtils.rm_rf(source_dir)
+    end
+
+    it "returns an empty hash when no labels have been set" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      expect(admin.metadata).to eq({})
+    end
+
+    it "merges and persists labels" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      admin.set_metadata(owner: "payments", environment: "staging")
+      result = admin.set_metadata(environment: "production", schema_version: 3)
+
+      expected = { "environment" => "production", "owner" => "payments", "schema_version" => "3" }
+      expect(result).to eq(expected)
+      expect(SlateDb::Admin.new("db", url: "file://#{source_dir}").metadata).to eq(expected)
+      expect(JSON.parse(File.read(File.join(source_dir, "db", "metadata.json")))).to eq(expected)
+    end
+
+    it "removes labels set to nil" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      admin.set_metadata(owner: "payments", environment: "staging")
+
+      expect(admin.set_metadata(environment: nil)).to eq("owner" => "payments")
+    end
+
+    it "does not interfere with the database" do
+      admin = SlateDb::Admin.new("db", url: "file://#{source_dir}")
+      admin.set_metadata(owner: "payments")
+
+      SlateDb::Database.open("db", url: "file://#{source_dir}") do |db|
+        db.put("a", "1")
+        expect(db.get("a")).to eq("1")
+      end
+      expect(admin.metadata).to eq("owner" => "payments")
+    end
+
+    it "raises InvalidArgumentError for an empty label name" do
+      admin = SlateDb::Admin.new(db_path)
+      expect { admin.set_metadata("" => "x") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
   end
 
   describe "API structure" do
@@ -111,10 +611,19 @@ RSpec.describe SlateDb::Admin do
       expect(SlateDb::Admin.instance_methods).to include(:read_manifest)
       expect(SlateDb::Admin.instance_methods).to include(:list_manifests)
       expect(SlateDb::Admin.instance_methods).to include(:create_checkpoint)
+      expect(SlateDb::Admin.instance_methods).to include(:expired_stats)
+      expect(SlateDb::Admin.instance_methods).to include(:purge_expired)
       expect(SlateDb::Admin.instance_methods).to include(:list_checkpoints)
       expect(SlateDb::Admin.instance_methods).to include(:refresh_checkpoint)
+      expect(SlateDb::Admin.instance_methods).to include(:hold_checkpoint)
+      expect(SlateDb::Admin.instance_methods).to include(:active_writer)
       expect(SlateDb::Admin.instance_methods).to include(:delete_checkpoint)
       expect(SlateDb::Admin.instance_methods).to include(:run_gc)
+      expect(SlateDb::Admin.instance_methods).to include(:incremental_backup)
+      expect(SlateDb::Admin.instance_methods).to include(:verify_backup)
+      expect(SlateDb::Admin.instance_methods).to include(:sample_stats)
+      expect(SlateDb::Admin.instance_methods).to include(:metadata)
+      expect(SlateDb::Admin.instance_methods).to include(:set_metadata)
     end
   end
 end
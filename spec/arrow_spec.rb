@@ -0,0 +1,76 @@
+# frozen_string_literal: true
+
+RSpec.describe "Database#scan_arrow" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:continuation) { "\xFF\xFF\xFF\xFF".b }
+  let(:end_of_stream) { "#{continuation}\0\0\0\0".b }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "returns an Arrow IPC stream of the range" do
+    SlateDb::Database.open(tmpdir) do |db|
+      %w[a b c].each { |k| db.put("key:#{k}", "value:#{k}") }
+      db.put("other", "skipped")
+
+      stream = db.scan_arrow("key:", "key;")
+      expect(stream.encoding).to eq(Encoding::BINARY)
+      expect(stream).to start_with(continuation)
+      expect(stream).to end_with(end_of_stream)
+      expect(stream).to include("key:akey:bkey:c", "value:avalue:bvalue:c")
+      expect(stream).not_to include("skipped")
+    end
+  end
+
+  it "writes one record batch per batch_size rows" do
+    SlateDb::Database.open(tmpdir) do |db|
+      10.times { |i| db.put("key:#{i}", "value") }
+
+      batches = ->(stream) { stream.scan(continuation).size - 2 }
+      expect(batches.call(db.scan_arrow("key:"))).to eq(1)
+      expect(batches.call(db.scan_arrow("key:", batch_size: 3))).to eq(4)
+      expect(batches.call(db.scan_arrow("key:", batch_size: 3, limit: 3))).to eq(1)
+    end
+  end
+
+  it "writes only the requested columns" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("key", "value")
+
+      stream = db.scan_arrow("key", schema: { key: :binary, seq: :uint64, expire_ts: :timestamp })
+      expect(stream).to include("seq", "expire_ts")
+      expect(stream).not_to include("value")
+    end
+  end
+
+  it "rejects invalid schemas" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect do
+        db.scan_arrow("a", schema: { ttl: :int64 })
+      end.to raise_error(SlateDb::InvalidArgumentError, /unknown schema column: ttl/)
+      expect do
+        db.scan_arrow("a", schema: { seq: :utf8 })
+      end.to raise_error(SlateDb::InvalidArgumentError, /invalid type for schema column seq: utf8/)
+    end
+  end
+
+  it "can be read by red-arrow" do
+    begin
+      require "arrow"
+    rescue LoadError
+      skip "red-arrow is not installed"
+    end
+
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("a", "1", ttl: 60_000)
+      db.put("b", "2")
+
+      stream = db.scan_arrow("a", schema: { key: :utf8, value: :utf8, expire_ts: :timestamp })
+      table = Arrow::Table.load(Arrow::Buffer.new(stream), format: :arrow_streaming)
+      expect(table.n_rows).to eq(2)
+      expect(table["key"].to_a).to eq(%w[a b])
+      expect(table["expire_ts"].to_a.last).to be_nil
+    end
+  end
+end
@@ -0,0 +1,109 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+require "json"
+require "stringio"
+require "tmpdir"
+
+RSpec.describe "SlateDb::Admin export and import" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+  let(:admin) { SlateDb::Admin.new("source", url:) }
+  let(:copy) { SlateDb::Admin.new("copy", url:) }
+
+  after { FileUtils.rm_rf(tmpdir) }
+
+  before do
+    SlateDb::Database.open("source", url:) do |db|
+      db.put("a", "1")
+      db.put("b", "two, \"quoted\"\nlines")
+      db.put("c", "3", ttl: 3_600_000)
+      db.put("d", "")
+    end
+  end
+
+  def copied_entries
+    SlateDb::Database.open("copy", url:) { |db| db.scan("a").to_a }
+  end
+
+  it "round-trips entries through JSON Lines" do
+    path = File.join(tmpdir, "export.jsonl")
+    result = admin.export(path)
+
+    expect(result[:entries]).to eq(4)
+    expect(result[:bytes]).to eq(File.size(path))
+
+    lines = File.readlines(path).map { |line| JSON.parse(line) }
+    expect(lines.first).to eq("key" => "a", "value" => "1")
+    expect(lines[2]["expire_ts"]).to be > (Time.now.to_f * 1000)
+
+    expect(copy.import(path)).to eq(entries: 4, skipped: 0, bytes: File.size(path))
+    expect(copied_entries).to eq([["a", "1"], ["b", "two, \"quoted\"\nlines"], %w[c 3], ["d", ""]])
+  end
+
+  it "round-trips entries through CSV" do
+    path = File.join(tmpdir, "export.csv")
+    admin.export(path, format: :csv)
+
+    expect(File.readlines(path).first).to eq("key,value,expire_ts\n")
+
+    copy.import(path, format: :csv)
+    expect(copied_entries).to eq([["a", "1"], ["b", "two, \"quoted\"\nlines"], %w[c 3], ["d", ""]])
+  end
+
+  it "base64-encodes binary values in JSON Lines" do
+    SlateDb::Database.open("source", url:) { |db| db.put("bin", "\xFF\x00".b) }
+    io = StringIO.new
+    admin.export(io, range: "bin".."bin")
+
+    expect(JSON.parse(io.string)).to eq("key" => "bin", "value_base64" => "/wA=")
+
+    copy.import(StringIO.new(io.string))
+    SlateDb::Database.open("copy", url:) { |db| expect(db.get("bin").b).to eq("\xFF\x00".b) }
+  end
+
+  it "rejects binary values in CSV" do
+    SlateDb::Database.open("source", url:) { |db| db.put("bin", "\xFF".b) }
+
+    expect { admin.export(StringIO.new, format: :csv) }
+      .to raise_error(SlateDb::InvalidArgumentError, /format: :jsonl/)
+  end
+
+  it "exports a key range" do
+    io = StringIO.new
+    expect(admin.export(io, range: "b"..."d")[:entries]).to eq(2)
+    expect(io.string.lines.map { |line| JSON.parse(line)["key"] }).to eq(%w[b c])
+  end
+
+  it "skips expired entries on import" do
+    io = StringIO.new(<<~JSONL)
+      {"key":"live","value":"1"}
+      {"key":"dead","value":"2","expire_ts":1000}
+    JSONL
+
+    expect(copy.import(io)).to include(entries: 1, skipped: 1)
+    SlateDb::Database.open("copy", url:) { |db| expect(db.get("dead")).to be_nil }
+  end
+
+  it "reports progress to a block" do
+    path = File.join(tmpdir, "export.jsonl")
+    exported = []
+    admin.export(path, batch_size: 2) { |progress| exported << progress[:entries] }
+    expect(exported).to eq([2, 4, 4])
+
+    imported = []
+    copy.import(path, batch_size: 3) { |progress| imported << progress }
+    expect(imported.map { |p| p[:entries] }).to eq([3, 4])
+    expect(imported.last[:percent]).to eq(100.0)
+  end
+
+  it "raises on malformed input with the line number" do
+    io = StringIO.new(%({"key":"a","value":"1"}\nnot json\n))
+    expect { copy.import(io) }.to raise_error(SlateDb::InvalidArgumentError, /line 2/)
+  end
+
+  it "rejects an unknown format" do
+    expect { admin.export(StringIO.new, format: :xml) }
+      .to raise_error(SlateDb::InvalidArgumentError, /invalid format/)
+  end
+end
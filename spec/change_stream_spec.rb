@@ -0,0 +1,72 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::ChangeStream do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "streams committed changes in write order" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("b", "1")
+      db.put("a", "2")
+      db.delete("b")
+
+      changes = db.changes.to_a
+      expect(changes.map { |c| [c[:op], c[:key], c[:value]] }).to eq(
+        [[:put, "b", "1"], [:put, "a", "2"], [:delete, "b", nil]]
+      )
+      expect(changes.map { |c| c[:seq] }).to eq(changes.map { |c| c[:seq] }.sort)
+      expect(changes.first[:timestamp]).to be_a(Integer)
+    end
+  end
+
+  it "gives a batch's changes the same sequence number" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.batch do |b|
+        b.put("x", "1")
+        b.put("y", "2")
+      end
+
+      expect(db.changes.map { |c| c[:seq] }.uniq.size).to eq(1)
+    end
+  end
+
+  it "includes merge operands" do
+    SlateDb::Database.open(tmpdir, merge_operator: :string_concat) do |db|
+      db.merge("log", "a")
+
+      expect(db.changes.first).to include(op: :merge, key: "log", value: "a")
+    end
+  end
+
+  it "only returns changes after since_seq" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("old", "1")
+      seq = db.get_key_value("old")[:seq]
+      db.put("new", "2")
+
+      expect(db.changes(since_seq: seq).map { |c| c[:key] }).to eq(%w[new])
+    end
+  end
+
+  it "picks up new changes after catching up" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("a", "1")
+      stream = db.changes
+      expect(stream.to_a.size).to eq(1)
+      expect(stream.next_change).to be_nil
+
+      db.put("b", "2")
+      expect(stream.next_change).to include(key: "b")
+      expect(stream.last_seq).to eq(db.get_key_value("b")[:seq])
+    end
+  end
+
+  it "rejects encrypted databases" do
+    SlateDb::Database.open(tmpdir, encryption: "k" * 32) do |db|
+      expect { db.changes }.to raise_error(SlateDb::InvalidArgumentError, /cannot be combined with encryption/)
+    end
+  end
+end
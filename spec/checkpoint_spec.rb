@@ -41,4 +41,45 @@ RSpec.describe "create_checkpoint" do
       end
     end
   end
+
+  describe "Database#snapshot_at" do
+    it "reads at the newest checkpoint created at or before the time" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "v1")
+        db.flush
+        db.create_checkpoint
+        sleep 0.01
+        before_update = Time.now
+        sleep 0.01
+
+        db.put("key", "v2")
+        db.flush
+        db.create_checkpoint
+
+        expect(db.snapshot_at(before_update) { |past| past.get("key") }).to eq("v1")
+        expect(db.snapshot_at(Time.now.to_f) { |past| past.get("key") }).to eq("v2")
+      end
+    end
+
+    it "returns an open reader without a block" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        db.flush
+        db.create_checkpoint
+
+        reader = db.snapshot_at(Time.now)
+        expect(reader).to be_a(SlateDb::Reader)
+        expect(reader.get("key")).to eq("value")
+        reader.close
+      end
+    end
+
+    it "raises InvalidArgumentError when no checkpoint is that old" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.create_checkpoint
+
+        expect { db.snapshot_at(Time.now - 3600) }.to raise_error(SlateDb::InvalidArgumentError, /no checkpoint/)
+      end
+    end
+  end
 end
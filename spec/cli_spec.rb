@@ -0,0 +1,121 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+require "json"
+require "stringio"
+require "tmpdir"
+require "slatedb/cli"
+
+RSpec.describe SlateDb::CLI do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+  let(:stdin) { StringIO.new }
+  let(:stdout) { StringIO.new }
+  let(:stderr) { StringIO.new }
+
+  after { FileUtils.rm_rf(tmpdir) }
+
+  def slatedb(*args)
+    described_class.new(stdin:, stdout:, stderr:).run(["--url", url, "--path", "db", *args])
+  end
+
+  before do
+    SlateDb::Database.open("db", url:) do |db|
+      db.put("user:1", "Ada")
+      db.put("user:2", "Grace")
+      db.put("zone", "eu")
+    end
+  end
+
+  describe "get" do
+    it "prints the value" do
+      expect(slatedb("get", "user:1")).to eq(0)
+      expect(stdout.string).to eq("Ada\n")
+    end
+
+    it "exits 1 for a missing key" do
+      expect(slatedb("get", "missing")).to eq(1)
+      expect(stdout.string).to be_empty
+    end
+  end
+
+  describe "put" do
+    it "stores a value" do
+      expect(slatedb("put", "user:3", "Barbara")).to eq(0)
+      SlateDb::Database.open("db", url:) { |db| expect(db.get("user:3")).to eq("Barbara") }
+    end
+
+    it "reads the value from stdin for -" do
+      stdin.string = "from stdin"
+      slatedb("put", "note", "-")
+      SlateDb::Database.open("db", url:) { |db| expect(db.get("note")).to eq("from stdin") }
+    end
+  end
+
+  describe "scan" do
+    it "prints every entry, tab-separated" do
+      slatedb("scan")
+      expect(stdout.string).to eq("user:1\tAda\nuser:2\tGrace\nzone\teu\n")
+    end
+
+    it "scans a prefix with a limit as JSON" do
+      slatedb("scan", "user:", "--limit", "1", "--json")
+      expect(stdout.string.lines.map { |line| JSON.parse(line) }).to eq([{ "user:1" => "Ada" }])
+    end
+  end
+
+  it "lists manifests as JSON" do
+    expect(slatedb("list-manifests")).to eq(0)
+    expect(JSON.parse(stdout.string)).not_to be_empty
+  end
+
+  it "creates a checkpoint" do
+    slatedb("create-checkpoint", "--name", "cli")
+    expect(JSON.parse(stdout.string)).to include("id", "manifest_id")
+  end
+
+  it "runs GC with progress on stderr" do
+    expect(slatedb("gc", "--progress")).to eq(0)
+    expect(stderr.string).to include("phase=manifest")
+    expect(JSON.parse(stdout.string)).to include("deleted_objects", "deleted_bytes")
+  end
+
+  it "exports entries" do
+    path = File.join(tmpdir, "export.csv")
+    slatedb("export", path, "--format", "csv")
+
+    expect(JSON.parse(stdout.string)).to include("entries" => 3)
+    expect(File.read(path)).to start_with("key,value,expire_ts\nuser:1,Ada,\n")
+  end
+
+  it "verifies a backup and exits 1 when it fails" do
+    SlateDb::Admin.new("db", url:).incremental_backup(nil, "file://#{tmpdir}/backup")
+    expect(slatedb("verify", "file://#{tmpdir}/backup")).to eq(0)
+    expect(JSON.parse(stdout.string)).to include("ok" => true, "keys" => 3)
+
+    FileUtils.rm_rf(Dir.glob(File.join(tmpdir, "backup", "db", "compacted", "*")))
+    expect(slatedb("verify", "file://#{tmpdir}/backup")).to eq(1)
+  end
+
+  describe "usage errors" do
+    it "exits 64 for an unknown command" do
+      expect(slatedb("frobnicate")).to eq(described_class::EX_USAGE)
+      expect(stderr.string).to include("unknown command: frobnicate", "Usage: slatedb")
+    end
+
+    it "exits 64 for the wrong number of arguments" do
+      expect(slatedb("get")).to eq(described_class::EX_USAGE)
+    end
+
+    it "requires --url and --path" do
+      cli = described_class.new(stdin:, stdout:, stderr:)
+      expect(cli.run(%w[get key])).to eq(described_class::EX_USAGE)
+      expect(stderr.string).to include("--url and --path are required")
+    end
+  end
+
+  it "reports database errors without a backtrace" do
+    expect(slatedb("verify", "file://#{tmpdir}/backup", "not-a-uuid")).to eq(1)
+    expect(stderr.string).to start_with("slatedb: ")
+  end
+end
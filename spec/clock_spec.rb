@@ -0,0 +1,81 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::ManualClock do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:start) { Time.utc(2026, 1, 1) }
+  let(:clock) { SlateDb::ManualClock.new(start) }
+
+  after do
+    SlateDb.test_clock = nil
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "starts at the given time and moves forward when advanced" do
+    expect(clock.now).to eq(start)
+    expect(clock.advance(1.5)).to eq(start + 1.5)
+    expect(clock.now_ms).to eq((start.to_i * 1000) + 1500)
+    expect(clock.set(start + 60)).to eq(start + 60)
+  end
+
+  it "refuses to move backwards" do
+    expect { clock.set(start - 1) }.to raise_error(SlateDb::InvalidArgumentError, /backwards/)
+    expect { clock.advance(-1) }.to raise_error(SlateDb::InvalidArgumentError)
+    expect(clock.now).to eq(start)
+  end
+
+  it "stamps writes and computes remaining TTLs with the test clock" do
+    SlateDb.test_clock = clock
+    expect(SlateDb.test_clock).to be(clock)
+
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("session", "data", ttl: 60_000)
+      kv = db.get_key_value("session")
+      expect(kv).to include(create_ts: start.to_i * 1000, expire_ts: (start.to_i * 1000) + 60_000, ttl: 60_000)
+
+      clock.advance(61)
+      expect(db.get_key_value("session")[:ttl]).to eq(0)
+    end
+  end
+
+  it "restores remaining TTLs on rollback_to by the database's clock" do
+    SlateDb.test_clock = clock
+
+    SlateDb::Database.open(tmpdir) do |db|
+      SlateDb.test_clock = nil
+      db.put("session", "data", ttl: 60_000)
+      clock.advance(30)
+
+      db.transaction do |txn|
+        savepoint = txn.savepoint
+        txn.put("session", "changed")
+        txn.rollback_to(savepoint)
+      end
+      expect(db.get_key_value("session")).to include(value: "data", ttl: 30_000)
+    end
+  end
+
+  it "makes rows reclaimable once the clock passes their expiry" do
+    SlateDb.test_clock = clock
+    admin = SlateDb::Admin.new("db", url: "file://#{tmpdir}")
+
+    SlateDb::Database.open("db", url: "file://#{tmpdir}") do |db|
+      db.put("ttl", "x", ttl: 500, await_durable: false)
+      db.freeze_memtable
+      expect(admin.expired_stats).to include(expired_entries: 0)
+
+      clock.advance(1)
+      db.put("later", "z")
+      db.freeze_memtable
+      expect(admin.expired_stats).to include(expired_entries: 1, reclaimable_entries: 1)
+    end
+  end
+
+  it "does not affect databases opened before it was set" do
+    SlateDb::Database.open(tmpdir) do |db|
+      SlateDb.test_clock = clock
+      clock.advance(3600)
+      db.put("session", "data", ttl: 60_000)
+      expect(db.get_key_value("session")[:ttl]).to be > 50_000
+    end
+  end
+end
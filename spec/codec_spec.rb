@@ -0,0 +1,150 @@
+# frozen_string_literal: true
+
+RSpec.describe "value codecs" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:record) { { "name" => "Ada", "roles" => ["admin", nil], "age" => 36, "score" => 1.5, "active" => true } }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  %i[json msgpack marshal].each do |codec|
+    context "with #{codec}" do
+      it "round-trips Hashes and Arrays" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          db.put("record", record)
+          db.put("list", [1, "two", [3]])
+
+          expect(db.get("record")).to eq(record)
+          expect(db.get("list")).to eq([1, "two", [3]])
+        end
+      end
+
+      it "decodes values in scans, snapshots and transactions" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          db.put("a", { "n" => 1 })
+          db.put("b", { "n" => 2 })
+
+          expect(db.scan("a").to_a).to eq([["a", { "n" => 1 }], ["b", { "n" => 2 }]])
+          expect(db.snapshot.get("a")).to eq({ "n" => 1 })
+          db.transaction do |txn|
+            txn.put("c", txn.get("a").merge("n" => 3))
+          end
+          expect(db.get("c")).to eq({ "n" => 3 })
+        end
+      end
+
+      it "encodes WriteBatch values" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          db.batch { |b| b.put("record", record) }
+
+          expect(db.get("record")).to eq(record)
+        end
+      end
+
+      it "encodes WriteBatch values written in a transaction" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          db.transaction do |txn|
+            txn.write(SlateDb::WriteBatch.new.put("record", record))
+          end
+
+          expect(db.get("record")).to eq(record)
+        end
+      end
+
+      it "decodes values in change streams" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          db.put("record", record)
+
+          expect(db.changes.first).to include(op: :put, key: "record", value: record)
+        end
+      end
+
+      it "decodes values in watchers" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          events = Queue.new
+          watcher = db.watch("record", interval: 0.01) { |*event| events << event }
+
+          db.put("record", record)
+          deadline = Time.now + 5
+          sleep 0.01 while events.empty? && Time.now < deadline
+          watcher.stop
+
+          expect(events.pop).to eq(["record", record, :put])
+        end
+      end
+
+      it "rejects appends" do
+        SlateDb::Database.open(tmpdir, codec: codec) do |db|
+          expect { db.append("key", "a") }.to raise_error(SlateDb::InvalidArgumentError, /requires the :raw codec/)
+        end
+      end
+    end
+  end
+
+  it "encodes merge operands" do
+    SlateDb::Database.open(tmpdir, codec: :json, merge_operator: :add_i64) do |db|
+      db.merge("n", 5)
+      db.batch { |b| b.merge("n", 2) }
+      db.transaction { |txn| txn.merge("n", 3) }
+
+      expect(db.get("n")).to eq(10)
+    end
+  end
+
+  it "stores JSON as text" do
+    SlateDb::Database.open("db", url: "file://#{tmpdir}", codec: :json) do |db|
+      db.put("key", { name: "Ada" })
+      expect(db.get("key")).to eq({ "name" => "Ada" })
+    end
+
+    SlateDb::Database.open("db", url: "file://#{tmpdir}") do |db|
+      expect(db.get("key")).to eq('{"name":"Ada"}')
+    end
+  end
+
+  it "round-trips binary strings with msgpack" do
+    SlateDb::Database.open(tmpdir, codec: :msgpack) do |db|
+      db.put("key", "\xFF\x00".b)
+      expect(db.get("key")).to eq("\xFF\x00".b)
+      expect(db.get("key").encoding).to eq(Encoding::BINARY)
+    end
+  end
+
+  it "leaves values unchanged with raw" do
+    SlateDb::Database.open(tmpdir, codec: :raw) do |db|
+      db.put("key", "value")
+      expect(db.get("key")).to eq("value")
+    end
+  end
+
+  it "rejects unknown codecs" do
+    expect do
+      SlateDb::Database.open(tmpdir, codec: :yaml)
+    end.to raise_error(SlateDb::InvalidArgumentError, /invalid codec: yaml/)
+  end
+
+  it "rejects values the codec cannot encode" do
+    SlateDb::Database.open(tmpdir, codec: :json) do |db|
+      expect { db.put("key", Object.new) }.to raise_error(SlateDb::InvalidArgumentError, /cannot encode Object/)
+    end
+  end
+
+  it "raises DataError for values the codec cannot decode" do
+    SlateDb::Database.open("db", url: "file://#{tmpdir}") { |db| db.put("key", "not json") }
+
+    SlateDb::Database.open("db", url: "file://#{tmpdir}", codec: :json) do |db|
+      expect { db.get("key") }.to raise_error(SlateDb::DataError, /cannot decode JSON/)
+    end
+  end
+
+  it "reads empty values as empty strings under every codec" do
+    SlateDb::Database.open("db", url: "file://#{tmpdir}") { |db| db.put("marker", "") }
+
+    %i[json msgpack marshal].each do |codec|
+      SlateDb::Database.open("db", url: "file://#{tmpdir}", codec:) do |db|
+        expect(db.get("marker")).to eq("")
+      end
+    end
+  end
+end
@@ -0,0 +1,82 @@
+# frozen_string_literal: true
+
+RSpec.describe "credentials providers" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    SlateDb.credentials_provider = nil
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "sets the default provider from a block" do
+    SlateDb.credentials_provider { { access_key_id: "key", secret_access_key: "secret" } }
+
+    expect(SlateDb.credentials_provider.call).to eq(access_key_id: "key", secret_access_key: "secret")
+  end
+
+  it "clears the default provider" do
+    SlateDb.credentials_provider { {} }
+    SlateDb.credentials_provider = nil
+
+    expect(SlateDb.credentials_provider).to be_nil
+  end
+
+  it "does not use the default provider for local databases" do
+    calls = 0
+    SlateDb.credentials_provider { calls += 1 }
+
+    SlateDb::Database.open(tmpdir) { |db| db.put("key", "value") }
+    expect(calls).to eq(0)
+  end
+
+  it "rejects a provider for stores other than S3" do
+    expect do
+      SlateDb::Database.open("db", url: "file://#{tmpdir}", credentials_provider: -> { {} })
+    end.to raise_error(SlateDb::InvalidArgumentError, /credentials_provider is only supported for S3/)
+  end
+
+  it "requires the provider to be callable" do
+    expect do
+      SlateDb::Database.open("db", url: "s3://bucket/path", credentials_provider: "static")
+    end.to raise_error(SlateDb::InvalidArgumentError, /must respond to call/)
+  end
+
+  it "raises InvalidArgumentError for an expiry that is not a time" do
+    expect do
+      SlateDb._resolve_credential_request(0, "key", "secret", nil, Float::INFINITY)
+    end.to raise_error(SlateDb::InvalidArgumentError, /expires_at/)
+  end
+
+  context "with MinIO" do
+    let(:endpoint) { ENV.fetch("SLATEDB_MINIO_ENDPOINT", nil) }
+    let(:url) { "s3://#{ENV.fetch("SLATEDB_MINIO_BUCKET", "slatedb-test")}/#{File.basename(tmpdir)}" }
+
+    before { skip "set SLATEDB_MINIO_ENDPOINT to run against MinIO" unless endpoint }
+
+    it "signs requests with the provider's credentials" do
+      calls = 0
+      provider = lambda do
+        calls += 1
+        {
+          access_key_id: ENV.fetch("SLATEDB_MINIO_ACCESS_KEY_ID", "minioadmin"),
+          secret_access_key: ENV.fetch("SLATEDB_MINIO_SECRET_ACCESS_KEY", "minioadmin"),
+          expires_at: Time.now + 3600
+        }
+      end
+
+      SlateDb::Database.open("db", url: url, store_options: { preset: :minio, endpoint: endpoint },
+                                   credentials_provider: provider) do |db|
+        db.put("key", "value")
+        expect(db.get("key")).to eq("value")
+      end
+      expect(calls).to eq(1)
+    end
+
+    it "surfaces errors raised by the provider" do
+      expect do
+        SlateDb::Database.open("db", url: url, store_options: { preset: :minio, endpoint: endpoint },
+                                     credentials_provider: -> { raise "token service down" })
+      end.to raise_error(SlateDb::Error, /token service down/)
+    end
+  end
+end
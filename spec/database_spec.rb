@@ -1,5 +1,7 @@
 # frozen_string_literal: true
 
+require "objspace"
+
 RSpec.describe SlateDb::Database do
   let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
 
@@ -38,6 +40,264 @@ RSpec.describe SlateDb::Database do
     end
   end
 
+  describe "default_ttl_ms" do
+    it "applies the default TTL to writes without their own ttl" do
+      SlateDb::Database.open(tmpdir, default_ttl_ms: 60_000) do |db|
+        db.put("defaulted", "value")
+        db.put("pinned", "value", ttl: :none)
+
+        expect(db.get_key_value("defaulted")[:expire_ts]).to be_a(Integer)
+        expect(db.get_key_value("pinned")[:expire_ts]).to be_nil
+      end
+    end
+
+    it "leaves writes without expiry when not set" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        expect(db.get_key_value("key")[:expire_ts]).to be_nil
+      end
+    end
+  end
+
+  describe "max_key_bytes and max_value_bytes" do
+    it "rejects oversized keys and values with their size" do
+      SlateDb::Database.open(tmpdir, max_key_bytes: 8, max_value_bytes: 16) do |db|
+        expect { db.put("k" * 9, "value") }
+          .to raise_error(SlateDb::InvalidArgumentError, "key is 9 bytes, exceeding max_key_bytes of 8")
+        expect { db.put("key", "v" * 17) }
+          .to raise_error(SlateDb::InvalidArgumentError, "value is 17 bytes, exceeding max_value_bytes of 16")
+        expect { db.merge("key", "v" * 17) }.to raise_error(SlateDb::InvalidArgumentError, /max_value_bytes/)
+        expect { db.delete("k" * 9) }.to raise_error(SlateDb::InvalidArgumentError, /max_key_bytes/)
+        expect(db.get("key")).to be_nil
+      end
+    end
+
+    it "checks batches and transactions" do
+      SlateDb::Database.open(tmpdir, max_value_bytes: 16) do |db|
+        expect { db.batch { |b| b.put("key", "v" * 17) } }.to raise_error(SlateDb::InvalidArgumentError)
+        expect { db.transaction { |txn| txn.put("key", "v" * 17) } }.to raise_error(SlateDb::InvalidArgumentError)
+        expect(db.get("key")).to be_nil
+      end
+    end
+
+    it "caps appends" do
+      SlateDb::Database.open(tmpdir, max_value_bytes: 4) do |db|
+        db.append("log", "abc")
+        expect { db.append("log", "de") }.to raise_error(SlateDb::InvalidArgumentError, /exceeding max_size of 4/)
+      end
+    end
+
+    it "defaults to SlateDB's key limit" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put("k" * 65_536, "value") }
+          .to raise_error(SlateDb::InvalidArgumentError, /exceeding max_key_bytes of 65535/)
+      end
+    end
+
+    it "rejects limits above SlateDB's" do
+      expect { SlateDb::Database.open(tmpdir, max_key_bytes: 70_000) }
+        .to raise_error(SlateDb::InvalidArgumentError, /max_key_bytes must be between 1 and 65535/)
+    end
+  end
+
+  describe "read defaults" do
+    it "applies read_ahead_bytes, cache_blocks and max_fetch_tasks to every read" do
+      SlateDb::Database.open(tmpdir, read_ahead_bytes: 1_048_576, cache_blocks: true, max_fetch_tasks: 4) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+        db.flush
+
+        expect(db.get("a")).to eq("1")
+        expect(db.scan("a").to_a).to eq([%w[a 1], %w[b 2]])
+        expect(db.scan_prefix("b", read_ahead_bytes: 1, max_fetch_tasks: 1).to_a).to eq([%w[b 2]])
+        expect(db.scan("a", read_ahead: :auto).to_a.size).to eq(2)
+      end
+    end
+
+    it "applies them to snapshot reads" do
+      SlateDb::Database.open(tmpdir, read_ahead_bytes: 1_048_576, cache_blocks: false, max_fetch_tasks: 2) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+        db.flush
+
+        snapshot = db.snapshot
+        expect(snapshot.get("a")).to eq("1")
+        expect(snapshot.scan("a").to_a).to eq([%w[a 1], %w[b 2]])
+        expect(snapshot.scan_prefix("b").to_a).to eq([%w[b 2]])
+        expect(snapshot.scan("a", read_ahead_bytes: 1, cache_blocks: true).to_a.size).to eq(2)
+        snapshot.close
+      end
+    end
+
+    it "rejects zero" do
+      expect { SlateDb::Database.open(tmpdir, max_fetch_tasks: 0) }
+        .to raise_error(SlateDb::InvalidArgumentError, "max_fetch_tasks must be at least 1")
+    end
+  end
+
+  describe "max_requests_per_second and max_concurrent_requests" do
+    it "spaces out object store requests" do
+      url = "file://#{tmpdir}"
+      SlateDb::Database.open("db", url:) { |db| db.put("key", "value") }
+
+      started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+      SlateDb::Database.open("db", url:, max_requests_per_second: 20, max_concurrent_requests: 2) do |db|
+        expect(db.get("key")).to eq("value")
+        5.times { |i| db.put("key#{i}", "value") }
+      end
+      # Opening, five durable puts and closing take well over ten requests.
+      expect(Process.clock_gettime(Process::CLOCK_MONOTONIC) - started).to be >= 0.5
+    end
+
+    it "rejects limits that are not positive" do
+      expect { SlateDb::Database.open(tmpdir, max_requests_per_second: 0) }
+        .to raise_error(SlateDb::InvalidArgumentError, /max_requests_per_second must be positive/)
+      expect { SlateDb::Database.open(tmpdir, max_concurrent_requests: 0) }
+        .to raise_error(SlateDb::InvalidArgumentError, /max_concurrent_requests must be at least 1/)
+    end
+  end
+
+  describe "retry" do
+    it "serves reads, writes and scans under a retry policy" do
+      policy = { max_attempts: 4, base_delay_ms: 1, max_delay_ms: 10, jitter: false, on: [:throttled] }
+      SlateDb::Database.open(tmpdir, retry: policy) do |db|
+        db.put("key", "value")
+        expect(db.get("key")).to eq("value")
+        expect(db.scan("k").to_a).to eq([%w[key value]])
+      end
+    end
+
+    it "rejects invalid policies" do
+      expect { SlateDb::Database.open(tmpdir, retry: { max_attempts: 0 }) }
+        .to raise_error(SlateDb::InvalidArgumentError, /max_attempts/)
+      expect { SlateDb::Database.open(tmpdir, retry: { on: [:data] }) }
+        .to raise_error(SlateDb::InvalidArgumentError, /retry code/)
+    end
+  end
+
+  describe "store_options" do
+    it "rejects options the store does not understand" do
+      expect do
+        SlateDb::Database.open("db", url: "gs://bucket/path", store_options: { not_an_option: "x" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /unknown store option for GCS: not_an_option/)
+    end
+
+    it "rejects unknown Azure options" do
+      expect do
+        SlateDb::Database.open("db", url: "az://container/path", store_options: { account_secret: "x" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /unknown store option for Azure: account_secret/)
+    end
+
+    it "rejects unknown S3 options" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { secret_key: "x" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /unknown store option for S3: secret_key/)
+    end
+
+    it "requires force_path_style to be a boolean" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { force_path_style: "sometimes" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /force_path_style/)
+    end
+
+    it "rejects unknown presets" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { preset: :ceph })
+      end.to raise_error(SlateDb::InvalidArgumentError, /unknown store preset: ceph/)
+    end
+
+    it "requires an endpoint for the minio preset" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { preset: :minio })
+      end.to raise_error(SlateDb::InvalidArgumentError, /preset minio requires an endpoint/)
+    end
+
+    it "requires an endpoint or account_id for the r2 preset" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { preset: :r2 })
+      end.to raise_error(SlateDb::InvalidArgumentError, /preset r2 requires an endpoint or account_id/)
+    end
+
+    it "only accepts account_id with the r2 preset" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { account_id: "abc" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /account_id requires preset: :r2/)
+    end
+
+    it "requires allow_http for plain HTTP endpoints" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { endpoint: "http://minio:9000" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /allow_http: true/)
+    end
+
+    it "rejects endpoints that are not http or https" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: { endpoint: "ftp://minio:21" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /scheme must be http or https/)
+    end
+
+    it "rejects virtual-hosted-style requests to an IP endpoint" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", store_options: {
+                                 endpoint: "http://127.0.0.1:9000", allow_http: true, force_path_style: false
+                               })
+      end.to raise_error(SlateDb::InvalidArgumentError, /use force_path_style: true/)
+    end
+
+    it "rejects options for stores that take none" do
+      expect do
+        SlateDb::Database.open("db", url: "file://#{tmpdir}", store_options: { region: "us-east-1" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /not supported/)
+    end
+  end
+
+  describe "client_options" do
+    it "rejects unknown options" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", client_options: { proxy: "http://proxy:3128" })
+      end.to raise_error(SlateDb::InvalidArgumentError, /unknown client option: proxy/)
+    end
+
+    it "requires timeouts to be positive" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", client_options: { timeout: 0 })
+      end.to raise_error(SlateDb::InvalidArgumentError, /timeout must be a positive number of seconds/)
+    end
+
+    it "requires backoff_base to be at least 1" do
+      expect do
+        SlateDb::Database.open("db", url: "s3://bucket/path", client_options: { backoff_base: 0.5 })
+      end.to raise_error(SlateDb::InvalidArgumentError, /backoff_base must be at least 1/)
+    end
+
+    it "rejects client options for local stores" do
+      expect do
+        SlateDb::Database.open("db", url: "file://#{tmpdir}", client_options: { timeout: 30 })
+      end.to raise_error(SlateDb::InvalidArgumentError, /client options are not supported for file/)
+    end
+  end
+
+  describe "S3-compatible endpoints" do
+    let(:endpoint) { ENV.fetch("SLATEDB_MINIO_ENDPOINT", nil) }
+
+    before { skip "set SLATEDB_MINIO_ENDPOINT to run against MinIO" unless endpoint }
+
+    it "reads and writes through the minio preset" do
+      url = "s3://#{ENV.fetch("SLATEDB_MINIO_BUCKET", "slatedb-test")}/#{File.basename(tmpdir)}"
+      options = {
+        preset: :minio,
+        endpoint: endpoint,
+        access_key_id: ENV.fetch("SLATEDB_MINIO_ACCESS_KEY_ID", "minioadmin"),
+        secret_access_key: ENV.fetch("SLATEDB_MINIO_SECRET_ACCESS_KEY", "minioadmin")
+      }
+
+      SlateDb::Database.open("db", url: url, store_options: options) { |db| db.put("key", "value") }
+      SlateDb::Database.open("db", url: url, store_options: options) do |db|
+        expect(db.get("key")).to eq("value")
+      end
+    end
+  end
+
   describe "#put and #get" do
     it "stores and retrieves string values" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -81,6 +341,43 @@ RSpec.describe SlateDb::Database do
       end
     end
 
+    it "sets an expiry for an integer ttl and none for ttl: :none" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("expiring", "value", ttl: 60_000)
+        db.put("forever", "value", ttl: :none)
+
+        expect(db.get_key_value("expiring")[:expire_ts]).to be_a(Integer)
+        expect(db.get_key_value("forever")[:expire_ts]).to be_nil
+      end
+    end
+
+    it "expires a put at an absolute expire_at" do
+      SlateDb::Database.open(tmpdir) do |db|
+        deadline = Time.at(Time.now.to_i + 3600)
+        db.put("deadline", "value", expire_at: deadline)
+        db.put("millis", "value", expire_at: 4_102_444_800_000)
+
+        expect(db.get_key_value("deadline")[:expire_ts]).to eq(deadline.to_i * 1000)
+        expect(db.get_key_value("millis")[:expire_ts]).to eq(4_102_444_800_000)
+      end
+    end
+
+    it "treats a put with a past expire_at as already expired" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "old")
+        db.put("key", "new", expire_at: Time.now - 60)
+        expect(db.get("key")).to be_nil
+      end
+    end
+
+    it "raises InvalidArgumentError for ttl together with expire_at" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put("key", "value", ttl: 1_000, expire_at: Time.now + 60) }
+          .to raise_error(SlateDb::InvalidArgumentError)
+        expect { db.put("key", "value", expire_at: "tomorrow") }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+
     it "raises InvalidArgumentError for empty keys on put" do
       SlateDb::Database.open(tmpdir) do |db|
         expect { db.put("", "value") }.to raise_error(SlateDb::InvalidArgumentError)
@@ -94,6 +391,70 @@ RSpec.describe SlateDb::Database do
     end
   end
 
+  describe "#get_buffer and #get_into" do
+    let(:value) { "\x00\xFF".b * 1024 }
+
+    it "returns a read-only IO::Buffer over the value" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("blob", value)
+        buffer = db.get_buffer("blob")
+
+        GC.start
+        expect(buffer).to be_a(IO::Buffer)
+        expect(buffer).to be_readonly
+        expect(buffer.get_string).to eq(value)
+        expect(db.get_buffer("missing")).to be_nil
+      end
+    end
+
+    it "copies the value into a writable IO::Buffer" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("blob", value)
+        buffer = IO::Buffer.new(4096)
+
+        expect(db.get_into("blob", buffer)).to eq(2048)
+        expect(buffer.get_string(0, 2048)).to eq(value)
+        expect(db.get_into("missing", buffer)).to be_nil
+      end
+    end
+
+    it "raises when the value does not fit" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("blob", value)
+        expect { db.get_into("blob", IO::Buffer.new(16)) }
+          .to raise_error(SlateDb::InvalidArgumentError, "value is 2048 bytes, but the buffer holds 16")
+      end
+    end
+  end
+
+  describe "empty values" do
+    it "stores and returns empty values distinctly from missing keys" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("marker", "")
+
+        expect(db.get("marker")).to eq("")
+        expect(db.get("missing")).to be_nil
+        expect(db.scan("marker").to_a).to eq([["marker", ""]])
+      end
+    end
+
+    it "checks presence and emptiness without reading the value" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("marker", "")
+        db.put("full", "value")
+
+        expect(db.value_size("marker")).to eq(0)
+        expect(db.value_size("full")).to eq(5)
+        expect(db.value_size("missing")).to be_nil
+        expect(db.key?("marker")).to be(true)
+        expect(db.key?("missing")).to be(false)
+        expect(db.empty_value?("marker")).to be(true)
+        expect(db.empty_value?("full")).to be(false)
+        expect(db.empty_value?("missing")).to be(false)
+      end
+    end
+  end
+
   describe "#get_key_value" do
     it "returns value metadata for an existing key" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -134,6 +495,42 @@ RSpec.describe SlateDb::Database do
         expect(db.get_entry("metadata-key")[:value]).to eq("metadata-value")
       end
     end
+
+    it "reports the remaining ttl" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("expiring", "v", ttl: 60_000)
+        db.put("forever", "v")
+
+        expect(db.get_entry("expiring")[:ttl]).to be_between(1, 60_000)
+        expect(db.get_entry("forever")[:ttl]).to be_nil
+      end
+    end
+  end
+
+  describe "#put_if_absent" do
+    it "stores a value only when the key is absent" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.put_if_absent("lock", "first")).to be(true)
+        expect(db.put_if_absent("lock", "second")).to be(false)
+        expect(db.get("lock")).to eq("first")
+      end
+    end
+
+    it "applies a ttl" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put_if_absent("lock", "first", ttl: 60_000)
+        expect(db.get_entry("lock")[:expire_ts]).to be_a(Integer)
+      end
+    end
+
+    it "lets exactly one of several racing threads win" do
+      SlateDb::Database.open(tmpdir) do |db|
+        winners = Array.new(8) { |i| Thread.new { db.put_if_absent("race", "t#{i}") } }.map(&:value)
+
+        expect(winners.count(true)).to eq(1)
+        expect(db.get("race")).to eq("t#{winners.index(true)}")
+      end
+    end
   end
 
   describe "#delete" do
@@ -160,6 +557,97 @@ RSpec.describe SlateDb::Database do
     end
   end
 
+  describe "#delete_many" do
+    it "removes every listed key in one write" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[a b c].each { |key| db.put(key, "1") }
+
+        expect(db.delete_many(%w[a b missing])).to be_nil
+        expect([db.get("a"), db.get("b"), db.get("c")]).to eq([nil, nil, "1"])
+      end
+    end
+
+    it "returns a write result covering all the deletes" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        result = db.delete_many(%w[a b], await_durable: false, return_result: true)
+        expect(result).to be_a(SlateDb::WriteResult)
+        expect(db.get("a")).to be_nil
+      end
+    end
+
+    it "does nothing for an empty list" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.delete_many([])).to be_nil
+      end
+    end
+
+    it "raises InvalidArgumentError for an empty key without deleting anything" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        expect { db.delete_many(["a", ""]) }.to raise_error(SlateDb::InvalidArgumentError)
+        expect(db.get("a")).to eq("1")
+      end
+    end
+  end
+
+  describe "#append" do
+    it "creates the key when missing and appends afterwards" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.append("log", "a")).to eq(1)
+        expect(db.append("log", "bc")).to eq(3)
+        expect(db.get("log")).to eq("abc")
+      end
+    end
+
+    it "does not lose appends from concurrent threads" do
+      SlateDb::Database.open(tmpdir) do |db|
+        threads = 4.times.map do |i|
+          Thread.new { 5.times { db.append("log", i.to_s) } }
+        end
+        threads.each(&:join)
+
+        expect(db.get("log").length).to eq(20)
+      end
+    end
+
+    it "retries conflicts as many times as asked" do
+      SlateDb::Database.open(tmpdir) do |db|
+        threads = 8.times.map do |i|
+          Thread.new { 5.times { db.append("log", i.to_s, retries: 100) } }
+        end
+        threads.each(&:join)
+
+        expect(db.get("log").length).to eq(40)
+        expect(db.append("log", "!", retries: 0)).to eq(41)
+      end
+    end
+
+    it "keeps the existing value's expiry" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("log", "a", ttl: 60_000)
+        expire_ts = db.get_key_value("log")[:expire_ts]
+
+        db.append("log", "b")
+        expect(db.get_key_value("log")).to include(value: "ab", expire_ts: expire_ts)
+      end
+    end
+
+    it "raises InvalidArgumentError when max_size would be exceeded" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.append("log", "12345")
+        expect { db.append("log", "678", max_size: 6) }.to raise_error(SlateDb::InvalidArgumentError)
+        expect(db.get("log")).to eq("12345")
+      end
+    end
+
+    it "raises InvalidArgumentError for empty keys" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.append("", "value") }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
   describe "#flush" do
     it "flushes without error" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -167,6 +655,45 @@ RSpec.describe SlateDb::Database do
         expect { db.flush }.not_to raise_error
       end
     end
+
+    it "reports what was flushed to the WAL" do
+      SlateDb::Database.open(tmpdir) do |db|
+        seq = db.put("key", "value", await_durable: false, return_result: true).seq
+        result = db.flush
+
+        expect(result).to include(target: :wal, durable_seq: seq)
+        expect(result[:bytes]).to be_positive
+      end
+    end
+
+    it "flushes the memtable to L0 with target: :remote" do
+      SlateDb::Database.open(tmpdir) do |db|
+        seq = db.put("key", "value", await_durable: false, return_result: true).seq
+        expect(db.flush(target: :remote, timeout_ms: 10_000)).to include(target: :remote, flushed_seq: seq)
+      end
+    end
+
+    it "freezes the memtable into L0 with #freeze_memtable" do
+      SlateDb::Database.open(tmpdir, l0_sst_size_bytes: 1_048_576, l0_max_ssts: 16,
+                                     max_unflushed_bytes: 8_388_608) do |db|
+        seq = db.put("key", "value", return_result: true).seq
+        expect(db.durability_status[:flushed_seq]).to be < seq
+
+        expect(db.freeze_memtable).to include(target: :remote, flushed_seq: seq)
+        expect(db.durability_status[:flushed_seq]).to eq(seq)
+      end
+    end
+
+    it "rejects a zero flush threshold" do
+      expect { SlateDb::Database.open(tmpdir, max_unflushed_bytes: 0) }
+        .to raise_error(SlateDb::InvalidArgumentError, "max_unflushed_bytes must be at least 1")
+    end
+
+    it "rejects an unknown target" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.flush(target: :disk) }.to raise_error(SlateDb::InvalidArgumentError, /invalid target/)
+      end
+    end
   end
 
   describe "#close" do
@@ -177,6 +704,418 @@ RSpec.describe SlateDb::Database do
     end
   end
 
+  describe "memory reporting" do
+    it "reports open and closed databases to ObjectSpace.memsize_of" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "v" * 100_000, await_durable: false)
+      expect(ObjectSpace.memsize_of(db)).to be > 0
+
+      db.close
+      expect(ObjectSpace.memsize_of(db)).to be > 0
+    end
+
+    it "counts a transaction's buffered writes" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        empty = ObjectSpace.memsize_of(txn)
+        txn.put("key", "v" * 100_000)
+
+        expect(ObjectSpace.memsize_of(txn)).to be >= empty + 100_000
+        txn.rollback
+      end
+    end
+
+    it "counts the blocks a scan buffers" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+
+        expect(ObjectSpace.memsize_of(db.scan("a"))).to be >= 4096
+        expect(ObjectSpace.memsize_of(db.snapshot)).to be > 0
+      end
+    end
+  end
+
+  describe "#put_chunked" do
+    it "writes pairs in batches and returns the count" do
+      SlateDb::Database.open(tmpdir) do |db|
+        pairs = (1..25).map { |i| [format("key%03d", i), "value#{i}"] }
+
+        expect(db.put_chunked(pairs, batch_size: 10)).to eq(25)
+        expect(db.get("key001")).to eq("value1")
+        expect(db.scan("key").count).to eq(25)
+      end
+    end
+
+    it "writes a Hash" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.put_chunked({ "a" => "1", "b" => "2" })).to eq(2)
+        expect(db.get("b")).to eq("2")
+      end
+    end
+
+    it "rejects unsorted keys, including across batches" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put_chunked([%w[b 1], %w[a 2]]) }.to raise_error(SlateDb::InvalidArgumentError, /ascending order/)
+        expect do
+          db.put_chunked([%w[a 1], %w[c 2], %w[b 3]], batch_size: 2)
+        end.to raise_error(SlateDb::InvalidArgumentError, /"b" after "c"/)
+      end
+    end
+
+    it "accepts unsorted keys with sorted: false" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.put_chunked([%w[b 1], %w[a 2]], sorted: false)).to eq(2)
+        expect(db.get("a")).to eq("2")
+      end
+    end
+
+    it "raises InvalidArgumentError for a non-positive batch_size" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put_chunked([%w[a 1]], batch_size: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
+  describe "#durability_status" do
+    it "reports sequence numbers as writes become durable and flushed" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1", await_durable: false)
+        db.put("b", "2", await_durable: false)
+        status = db.durability_status
+
+        expect(status.keys).to eq(%i[last_seq durable_seq flushed_seq manifest_id])
+        expect(status[:last_seq]).to eq(db.get_key_value("b")[:seq])
+        expect(status[:durable_seq]).to be <= status[:last_seq]
+
+        db.flush
+        expect(db.durability_status[:durable_seq]).to eq(status[:last_seq])
+      end
+    end
+  end
+
+  describe "#health" do
+    it "probes the manifest without reading any keys" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        gets = db.stats[:db][:request_count]["op=get"]
+        health = db.health
+
+        expect(health).to include(healthy: true, manifests_behind: 0, error: nil)
+        expect(health[:latency]).to be_a(Float)
+        expect(health[:latest_manifest_id]).to eq(db.durability_status[:manifest_id])
+        expect(health[:staleness]).to be >= 0
+        expect(db).to be_healthy
+        expect(db.stats[:db][:request_count]["op=get"]).to eq(gets)
+      end
+    end
+
+    it "is unhealthy once closed" do
+      db = SlateDb::Database.open(tmpdir)
+      db.close
+
+      expect(db.healthy?).to be(false)
+      expect(db.health[:error]).to eq("handle is closed")
+    end
+  end
+
+  describe "#writer_info" do
+    it "reports this handle's epoch and notices when it is fenced" do
+      url = "file://#{tmpdir}"
+      first = SlateDb::Database.open("db", url:)
+      info = first.writer_info
+
+      expect(info[:client_id]).to match(/\A[0-9a-f-]{36}\z/)
+      expect(info[:epoch]).to be >= 1
+      expect(info[:manifest_id]).to be_a(Integer)
+      expect(info[:last_manifest_write]).to be_a(Time)
+      expect(info[:fenced]).to be(false)
+
+      SlateDb::Database.open("db", url:) do |second|
+        expect(second.writer_info[:epoch]).to be > info[:epoch]
+        expect(second.writer_info[:client_id]).not_to eq(info[:client_id])
+        expect(first.writer_info[:fenced]).to be(true)
+      end
+    ensure
+      begin
+        first&.close
+      rescue SlateDb::Error
+        nil
+      end
+    end
+  end
+
+  describe "#close" do
+    it "returns nil without a timeout" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "value")
+
+      expect(db.close).to be_nil
+    end
+
+    it "reports a clean close that finishes within the timeout" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "value")
+      result = db.close(timeout_ms: 10_000)
+
+      expect(result).to include(clean: true, dropped_seqs: 0)
+      expect(result[:durable_seq]).to eq(result[:last_seq])
+      SlateDb::Database.open(tmpdir) { |reopened| expect(reopened.get("key")).to eq("value") }
+    end
+
+    it "abandons a close that times out when forced" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "value", await_durable: false)
+      result = db.close(timeout_ms: 0, force: true)
+
+      expect(result).to include(:clean, :last_seq, :durable_seq, :dropped_seqs)
+      expect(result[:dropped_seqs]).to eq(result[:clean] ? 0 : result[:last_seq] - result[:durable_seq])
+      expect { db.put("key", "value") }.to raise_error(SlateDb::ClosedError)
+      expect { db.get("key") }.to raise_error(SlateDb::ClosedError)
+    end
+
+    it "reopens a database whose close was abandoned" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "value")
+      db.close(timeout_ms: 0, force: true)
+
+      db.reopen
+      expect(db.get("key")).to eq("value")
+      db.close
+    end
+
+    it "requires a timeout to force" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.close(force: true) }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
+  describe "#recovery_info" do
+    # Leave writes in the WAL by abandoning a database without closing it,
+    # then overwrite its newest WAL SST.
+    def corrupt_wal_tail(url)
+      crashed = SlateDb::Database.open("db", url:)
+      crashed.put("a", "1")
+      crashed.put("b", "2")
+      newest = Dir[File.join(tmpdir, "db", "wal", "*.sst")].max
+      File.binwrite(newest, "not an sst")
+      File.basename(newest, ".sst").to_i
+    end
+
+    it "reports a new database" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.recovery_info).to include(mode: :strict, new_database: true, wal_ssts_replayed: 0,
+                                            writes_replayed: 0, truncated_wal_ssts: [])
+      end
+    end
+
+    it "reports the writes replayed from the WAL after an unclean shutdown" do
+      url = "file://#{tmpdir}"
+      crashed = SlateDb::Database.open("db", url:)
+      crashed.put("a", "1")
+      crashed.put("b", "2")
+
+      SlateDb::Database.open("db", url:) do |db|
+        info = db.recovery_info
+        expect(info).to include(new_database: false, writes_replayed: 2, truncated_wal_ssts: [])
+        expect(info[:wal_ssts_replayed]).to be >= 2
+        expect(db.get("b")).to eq("2")
+      end
+    end
+
+    it "fails to open over an unreadable WAL SST by default" do
+      url = "file://#{tmpdir}"
+      corrupt_wal_tail(url)
+
+      expect { SlateDb::Database.open("db", url:) }.to raise_error(SlateDb::DataError)
+    end
+
+    it "truncates an unreadable WAL tail when tolerant" do
+      url = "file://#{tmpdir}"
+      corrupt_id = corrupt_wal_tail(url)
+
+      SlateDb::Database.open("db", url:, wal_recovery: :tolerant) do |db|
+        expect(db.recovery_info).to include(mode: :tolerant, truncated_wal_ssts: [corrupt_id])
+        expect(db.get("a")).to eq("1")
+        expect(db.get("b")).to be_nil
+      end
+    end
+
+    it "rejects tolerant recovery with encryption" do
+      expect { SlateDb::Database.open(tmpdir, wal_recovery: :tolerant, encryption: "k" * 32) }
+        .to raise_error(SlateDb::InvalidArgumentError, /cannot be combined with encryption/)
+    end
+  end
+
+  describe "#reopen" do
+    it "reopens the database in place, keeping its data and options" do
+      SlateDb::Database.open(tmpdir, codec: :json) do |db|
+        db.put("key", { "a" => 1 })
+        epoch = db.writer_info[:epoch]
+
+        expect(db.reopen).to be(db)
+        expect(db.get("key")).to eq({ "a" => 1 })
+        expect(db.writer_info[:epoch]).to be > epoch
+        db.put("other", [1, 2])
+        expect(db.get("other")).to eq([1, 2])
+      end
+    end
+
+    it "reopens a closed database" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "value")
+      db.close
+      db.reopen
+
+      expect(db.get("key")).to eq("value")
+    ensure
+      db&.close
+    end
+
+    it "recovers a handle closed by being fenced" do
+      url = "file://#{tmpdir}"
+      first = SlateDb::Database.open("db", url:)
+      second = SlateDb::Database.open("db", url:)
+      expect { first.put("key", "value") }.to raise_error(SlateDb::FencedError)
+
+      first.reopen
+      first.put("key", "value")
+      expect(first.background_error).to be_nil
+      expect(first.writer_info[:fenced]).to be(false)
+    ensure
+      [first, second].each do |db|
+        db&.close
+      rescue SlateDb::Error
+        nil
+      end
+    end
+  end
+
+  describe "#background_error and #on_background_error" do
+    it "is nil while open and after a clean close" do
+      db = SlateDb::Database.open(tmpdir)
+      db.put("key", "value")
+      expect(db.background_error).to be_nil
+
+      db.close
+      expect(db.background_error).to be_nil
+    end
+
+    it "surfaces the error that closed a fenced database" do
+      url = "file://#{tmpdir}"
+      first = SlateDb::Database.open("db", url:)
+      errors = Queue.new
+      first.on_background_error { |e| errors << e }
+
+      SlateDb::Database.open("db", url:) do
+        expect { first.put("key", "value") }.to raise_error(SlateDb::FencedError)
+        expect(first.background_error).to be_a(SlateDb::FencedError)
+        expect(errors.pop(timeout: 5)).to be_a(SlateDb::FencedError)
+      end
+    ensure
+      begin
+        first&.close
+      rescue SlateDb::Error
+        nil
+      end
+    end
+
+    it "requires a block" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.on_background_error }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
+  describe "#pending_ops and #on_overload" do
+    it "reports no pending operations when idle" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        expect(db.pending_ops).to eq(0)
+      end
+    end
+
+    it "calls the hook with the pending count above the threshold" do
+      SlateDb::Database.open(tmpdir) do |db|
+        counts = []
+        db.on_overload(0) { |pending| counts << pending }
+        db.put("key", "value")
+
+        expect(counts).to eq([1])
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "abandons the operation when the hook raises" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.on_overload(0) { raise "overloaded" }
+
+        expect { db.put("key", "value") }.to raise_error(RuntimeError, "overloaded")
+        expect(db.pending_ops).to eq(0)
+
+        db.on_overload
+        expect(db.get("key")).to be_nil
+      end
+    end
+
+    it "does not call the hook at or below the threshold" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.on_overload(1) { raise "overloaded" }
+        expect { db.put("key", "value") }.not_to raise_error
+      end
+    end
+
+    it "requires a threshold with a block" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.on_overload { nil } }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
+  describe ".open_clone" do
+    let(:url) { "file://#{tmpdir}" }
+
+    before do
+      SlateDb::Database.open("parent", url:) do |db|
+        db.put("shared", "from parent")
+        db.flush
+      end
+    end
+
+    it "forks the parent without writing back to it" do
+      SlateDb::Database.open_clone("child", parent_path: "parent", url:) do |db|
+        expect(db.get("shared")).to eq("from parent")
+        db.put("shared", "from child")
+        db.put("only", "child")
+      end
+
+      SlateDb::Database.open("parent", url:) do |db|
+        expect(db.get("shared")).to eq("from parent")
+        expect(db.get("only")).to be_nil
+      end
+      SlateDb::Database.open_clone("child", parent_path: "parent", url:) do |db|
+        expect(db.get("shared")).to eq("from child")
+      end
+    end
+
+    it "clones from a parent checkpoint" do
+      checkpoint = SlateDb::Admin.new("parent", url:).create_checkpoint
+      SlateDb::Database.open("parent", url:) { |db| db.put("shared", "after checkpoint") }
+
+      SlateDb::Database.open_clone("child", parent_path: "parent", url:,
+                                            parent_checkpoint: checkpoint[:id]) do |db|
+        expect(db.get("shared")).to eq("from parent")
+      end
+    end
+
+    it "validates the parent" do
+      expect { SlateDb::Database.open_clone("parent", parent_path: "parent", url:) }
+        .to raise_error(SlateDb::InvalidArgumentError, /own path/)
+      expect { SlateDb::Database.open_clone("child", parent_path: "parent", url:, parent_checkpoint: "nope") }
+        .to raise_error(SlateDb::InvalidArgumentError, /parent_checkpoint/)
+    end
+  end
+
   describe "persistence with local file URL" do
     it "persists data across database reopens using file:// URL" do
       file_url = "file://#{tmpdir}"
@@ -248,8 +1187,43 @@ RSpec.describe SlateDb::Database do
       expect(SlateDb::InternalError).to be < SlateDb::Error
     end
 
+    it "defines CommitTimeoutError" do
+      expect(SlateDb::CommitTimeoutError).to be < SlateDb::Error
+    end
+
     it "all errors inherit from SlateDb::Error" do
       expect(SlateDb::Error).to be < StandardError
     end
+
+    it "defines ThrottledError and FencedError under their broader classes" do
+      expect(SlateDb::ThrottledError).to be < SlateDb::UnavailableError
+      expect(SlateDb::FencedError).to be < SlateDb::ClosedError
+    end
+
+    it "classifies errors raised by operations" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put("", "value") }.to raise_error(SlateDb::InvalidArgumentError) do |e|
+          expect(e.code).to eq(:invalid_argument)
+          expect(e.kind).to eq(:invalid)
+          expect(e).not_to be_retryable
+        end
+      end
+    end
+
+    it "reports no storage details for errors that did not reach the store" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.get("") }.to raise_error(SlateDb::InvalidArgumentError) do |e|
+          expect(e.details).to eq({})
+        end
+      end
+    end
+
+    it "marks throttling, unavailability and conflicts as retryable" do
+      expect(SlateDb::ThrottledError.new("slow down")).to be_retryable
+      expect(SlateDb::UnavailableError.new("timed out").kind).to eq(:unavailable)
+      expect(SlateDb::TransactionError.new("conflict").code).to eq(:transaction_conflict)
+      expect(SlateDb::FencedError.new("fenced")).not_to be_retryable
+      expect(SlateDb::CommitTimeoutError.new("timed out")).not_to be_retryable
+    end
   end
 end
@@ -0,0 +1,67 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::DurabilityHandle do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "is returned by writes with return_handle: true" do
+    SlateDb::Database.open(tmpdir) do |db|
+      handle = db.put("key", "value", await_durable: false, return_handle: true)
+
+      expect(handle).to be_a(described_class)
+      expect(handle.seq).to eq(db.get_key_value("key")[:seq])
+    end
+  end
+
+  it "becomes durable once the write is flushed" do
+    SlateDb::Database.open(tmpdir) do |db|
+      handle = db.put("key", "value", await_durable: false, return_handle: true)
+
+      expect(handle.wait(timeout_ms: 10_000)).to be true
+      expect(handle).to be_durable
+    end
+  end
+
+  it "is already durable for writes that awaited durability" do
+    SlateDb::Database.open(tmpdir) do |db|
+      handle = db.delete("key", return_handle: true)
+
+      expect(handle).to be_durable
+      expect(handle.wait(timeout_ms: 0)).to be true
+    end
+  end
+
+  it "is returned by write, merge and batch" do
+    SlateDb::Database.open(tmpdir, merge_operator: :string_concat) do |db|
+      expect(db.merge("key", "a", await_durable: false, return_handle: true)).to be_a(described_class)
+      expect(db.batch(await_durable: false, return_handle: true) { |b| b.put("k", "v") }).to be_a(described_class)
+    end
+  end
+
+  it "is returned by Transaction#commit" do
+    SlateDb::Database.open(tmpdir) do |db|
+      txn = db.begin_transaction
+      txn.put("key", "value")
+      handle = txn.commit(await_durable: false, return_handle: true)
+
+      expect(handle.wait(timeout_ms: 10_000)).to be true
+      expect(handle.seq).to eq(db.get_key_value("key")[:seq])
+    end
+  end
+
+  it "is nil for an empty commit" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect(db.begin_transaction.commit(return_handle: true)).to be_nil
+    end
+  end
+
+  it "cannot be combined with return_result" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect { db.put("key", "value", return_result: true, return_handle: true) }
+        .to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+end
@@ -0,0 +1,135 @@
+# frozen_string_literal: true
+
+RSpec.describe "encryption" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+  let(:old_key) { SlateDb.generate_encryption_key }
+  let(:new_key) { SlateDb.generate_encryption_key }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  def stored_bytes
+    Dir.glob("#{tmpdir}/**/*").select { |f| File.file?(f) }.map { |f| File.binread(f) }.join
+  end
+
+  it "generates 256-bit keys" do
+    expect(old_key.bytesize).to eq(32)
+    expect(old_key).not_to eq(new_key)
+  end
+
+  it "stores no plaintext and reads values back" do
+    SlateDb::Database.open("db", url: url, encryption: old_key) do |db|
+      db.put("secret-key", "secret-value")
+      db.flush
+    end
+
+    expect(stored_bytes).not_to include("secret-value")
+    SlateDb::Database.open("db", url: url, encryption: old_key) do |db|
+      expect(db.get("secret-key")).to eq("secret-value")
+    end
+  end
+
+  it "reads blocks written before a key rotation" do
+    SlateDb::Database.open("db", url: url, encryption: { key_id: "v1", key: old_key }) do |db|
+      db.put("old", "value")
+    end
+
+    rotated = { key_id: "v2", key: new_key, previous_keys: { v1: old_key } }
+    SlateDb::Database.open("db", url: url, encryption: rotated) do |db|
+      db.put("new", "value")
+      expect(db.get("old")).to eq("value")
+      expect(db.get("new")).to eq("value")
+    end
+  end
+
+  it "calls a key provider at open" do
+    calls = 0
+    provider = lambda do
+      calls += 1
+      old_key
+    end
+
+    SlateDb::Database.open("db", url: url, encryption: provider) { |db| db.put("key", "value") }
+    expect(calls).to eq(1)
+  end
+
+  it "raises DataError when the key is wrong" do
+    SlateDb::Database.open("db", url: url, encryption: old_key) { |db| db.put("key", "value") }
+
+    expect do
+      SlateDb::Database.open("db", url: url, encryption: new_key) { |db| db.get("key") }
+    end.to raise_error(SlateDb::DataError)
+  end
+
+  it "rejects keys that are not 32 bytes" do
+    expect do
+      SlateDb::Database.open("db", url: url, encryption: "short")
+    end.to raise_error(SlateDb::InvalidArgumentError, /must be 32 bytes, got 5/)
+  end
+
+  it "rejects options that are not a key, a Hash or a provider" do
+    expect do
+      SlateDb::Database.open("db", url: url, encryption: 42)
+    end.to raise_error(SlateDb::InvalidArgumentError, /encryption must be a key/)
+  end
+
+  it "rejects unknown options" do
+    expect do
+      SlateDb::Database.open("db", url: url, encryption: { key: old_key, cipher: "aes" })
+    end.to raise_error(SlateDb::InvalidArgumentError, /unknown encryption option: cipher/)
+  end
+
+  it "rejects the current key_id in previous_keys" do
+    expect do
+      SlateDb::Database.open("db", url: url,
+                                   encryption: { key_id: "v1", key: new_key, previous_keys: { "v1" => old_key } })
+    end.to raise_error(SlateDb::InvalidArgumentError, /must not contain the current key_id/)
+  end
+
+  describe "Admin#rewrap_keys" do
+    let(:admin) { SlateDb::Admin.new("db", url: url) }
+    let(:old_encryption) { { key_id: "v1", key: old_key } }
+    let(:new_encryption) { { key_id: "v2", key: new_key } }
+
+    before do
+      SlateDb::Database.open("db", url: url, encryption: old_encryption) do |db|
+        3.times { |i| db.put("user:#{i}", "value #{i}") }
+        db.put("order:1", "value")
+        db.flush(target: :remote)
+      end
+    end
+
+    it "compacts every SST under the new key without fencing the writer" do
+      reports = []
+      rotated = new_encryption.merge(previous_keys: { "v1" => old_key })
+      SlateDb::Database.open("db", url: url, encryption: rotated) do |db|
+        result = admin.rewrap_keys(old_key: old_encryption, new_key: new_encryption,
+                                   timeout_ms: 30_000) { |progress| reports << progress }
+
+        expect(result).to include(l0_ssts: 1, key_id: "v2")
+        expect(%i[completed finished]).to include(result[:status])
+        db.put("user:3", "value 3")
+        expect(db.get("user:0")).to eq("value 0")
+      end
+      expect(reports).to all(include(:bytes))
+
+      SlateDb::Database.open("db", url: url, encryption: new_encryption) do |db|
+        expect(db.get("user:2")).to eq("value 2")
+        expect(db.get("order:1")).to eq("value")
+      end
+    end
+
+    it "returns nil when nothing has been flushed" do
+      empty = SlateDb::Admin.new("empty", url: url)
+      expect(empty.rewrap_keys(old_key: old_encryption, new_key: new_encryption)).to be_nil
+    end
+
+    it "rejects keys with the same key_id" do
+      expect do
+        admin.rewrap_keys(old_key: old_key, new_key: new_key)
+      end.to raise_error(SlateDb::InvalidArgumentError, /different key_ids/)
+    end
+  end
+end
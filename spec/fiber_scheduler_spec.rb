@@ -0,0 +1,60 @@
+# frozen_string_literal: true
+
+require "async"
+
+RSpec.describe "fiber scheduler" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "lets other fibers run while an operation waits" do
+    SlateDb::Database.open(tmpdir) do |db|
+      ticks = 0
+      Sync do |task|
+        ticker = task.async do
+          loop do
+            ticks += 1
+            sleep 0.005
+          end
+        end
+        # Each durable put waits for the next WAL flush
+        5.times { |i| db.put("key#{i}", "value") }
+        ticker.stop
+      end
+
+      expect(ticks).to be > 5
+    end
+  end
+
+  it "runs operations from many fibers" do
+    SlateDb::Database.open(tmpdir) do |db|
+      values = Sync do |task|
+        10.times.map do |i|
+          task.async do
+            db.put("key#{i}", "value#{i}")
+            db.get("key#{i}")
+          end
+        end.map(&:wait)
+      end
+
+      expect(values).to eq(10.times.map { |i| "value#{i}" })
+    end
+  end
+
+  it "raises operation errors in the fiber" do
+    db = SlateDb::Database.open(tmpdir)
+    db.close
+
+    expect { Sync { db.get("key") } }.to raise_error(SlateDb::ClosedError)
+  end
+
+  it "awaits futures inside a fiber" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("key", "value")
+
+      expect(Sync { db.get_async("key").await }).to eq("value")
+    end
+  end
+end
@@ -0,0 +1,142 @@
+# frozen_string_literal: true
+
+RSpec.describe "fork" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+
+  before do
+    skip "fork is not supported on this platform" unless Process.respond_to?(:fork)
+  end
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "raises ForkedProcessError for databases inherited by a child" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      db.put("key", "value")
+      pid = fork do
+        begin
+          db.get("key")
+        rescue SlateDb::ForkedProcessError => e
+          puts e.class
+        end
+      end
+      Process.wait(pid)
+      puts db.get("key")
+      db.close
+    RUBY
+
+    expect(output.lines.map(&:chomp)).to eq(["SlateDb::ForkedProcessError", "value"])
+  end
+
+  it "raises ForkedProcessError for transactions inherited by a child" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      txn = db.begin_transaction
+      txn.put("key", "value")
+      pid = fork do
+        begin
+          txn.get("key")
+        rescue SlateDb::ForkedProcessError => e
+          puts e.class
+        end
+      end
+      Process.wait(pid)
+      txn.commit
+      puts db.get("key")
+      db.close
+    RUBY
+
+    expect(output.lines.map(&:chomp)).to eq(["SlateDb::ForkedProcessError", "value"])
+  end
+
+  it "raises ForkedProcessError for snapshots inherited by a child" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      db.put("key", "value")
+      snap = db.snapshot
+      pid = fork do
+        begin
+          snap.get("key")
+        rescue SlateDb::ForkedProcessError => e
+          puts e.class
+        end
+      end
+      Process.wait(pid)
+      puts snap.get("key")
+      snap.close
+      db.close
+    RUBY
+
+    expect(output.lines.map(&:chomp)).to eq(["SlateDb::ForkedProcessError", "value"])
+  end
+
+  it "raises ForkedProcessError for iterators inherited by a child" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      db.put("key", "value")
+      iter = db.scan("k")
+      pid = fork do
+        begin
+          iter.next_entry
+        rescue SlateDb::ForkedProcessError => e
+          puts e.class
+        end
+      end
+      Process.wait(pid)
+      puts iter.next_entry.join("=")
+      iter.close
+      db.close
+    RUBY
+
+    expect(output.lines.map(&:chomp)).to eq(["SlateDb::ForkedProcessError", "key=value"])
+  end
+
+  it "raises ForkedProcessError for durability handles inherited by a child" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      handle = db.put("key", "value", await_durable: false, return_handle: true)
+      pid = fork do
+        begin
+          handle.durable?
+        rescue SlateDb::ForkedProcessError => e
+          puts e.class
+        end
+      end
+      Process.wait(pid)
+      puts handle.wait
+      db.close
+    RUBY
+
+    expect(output.lines.map(&:chomp)).to eq(["SlateDb::ForkedProcessError", "true"])
+  end
+
+  it "lets a child open its own databases" do
+    output = run_ruby(<<~RUBY)
+      SlateDb::Database.open("parent") { |db| db.put("key", "parent") }
+      pid = fork do
+        SlateDb::Database.open("db", url: #{url.inspect}) do |db|
+          db.put("key", "child")
+          puts db.get("key")
+        end
+      end
+      Process.wait(pid)
+      exit($?.exitstatus)
+    RUBY
+
+    expect(output.chomp).to eq("child")
+  end
+
+  it "leaves inherited databases to the parent when a child exits" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      Process.wait(fork { db.close })
+      db.put("key", "value")
+      puts db.get("key")
+    RUBY
+
+    expect(output.chomp).to eq("value")
+  end
+end
@@ -0,0 +1,133 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::Future do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "resolves get_async to the value" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("key", "value")
+
+      expect(db.get_async("key").await).to eq("value")
+      expect(db.get_async("missing").await).to be_nil
+    end
+  end
+
+  it "runs many operations concurrently from one thread" do
+    SlateDb::Database.open(tmpdir) do |db|
+      writes = 20.times.map { |i| db.put_async("key#{i}", "value#{i}") }
+      expect(writes.map(&:await)).to all(be_nil)
+
+      reads = 20.times.map { |i| db.get_async("key#{i}") }
+      expect(reads.map(&:await)).to eq(20.times.map { |i| "value#{i}" })
+    end
+  end
+
+  it "writes batches with write_async" do
+    SlateDb::Database.open(tmpdir) do |db|
+      batch = SlateDb::WriteBatch.new
+      batch.put("a", "1")
+      batch.put("b", "2")
+
+      db.write_async(batch).await
+      expect([db.get("a"), db.get("b")]).to eq(%w[1 2])
+    end
+  end
+
+  it "reports whether the operation has finished" do
+    SlateDb::Database.open(tmpdir) do |db|
+      future = db.put_async("key", "value")
+      future.await
+
+      expect(future).to be_fulfilled
+    end
+  end
+
+  it "raises the operation's error from await, every time" do
+    db = SlateDb::Database.open(tmpdir)
+    db.close
+    future = db.get_async("key")
+
+    expect { future.await }.to raise_error(SlateDb::ClosedError)
+    expect { future.await }.to raise_error(SlateDb::ClosedError)
+  end
+
+  it "raises TimeoutError when the operation is still running" do
+    SlateDb::Database.open(tmpdir) do |db|
+      future = db.put_async("key", "value")
+
+      expect { future.await(timeout: 0) }.to raise_error(SlateDb::TimeoutError)
+      expect(future.await(timeout: 10)).to be_nil
+    end
+  end
+
+  describe "#on_complete" do
+    it "calls the block with the result" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        results = Queue.new
+        db.get_async("key").on_complete { |value, error| results << [value, error] }
+
+        expect(results.pop(timeout: 10)).to eq(["value", nil])
+      end
+    end
+
+    it "calls the block with the error" do
+      db = SlateDb::Database.open(tmpdir)
+      db.close
+      results = Queue.new
+      db.get_async("key").on_complete { |value, error| results << [value, error] }
+
+      value, error = results.pop(timeout: 10)
+      expect(value).to be_nil
+      expect(error).to be_a(SlateDb::ClosedError)
+    end
+
+    it "calls blocks added after the operation finished" do
+      SlateDb::Database.open(tmpdir) do |db|
+        future = db.put_async("key", "value")
+        future.await
+        results = Queue.new
+        future.on_complete { |value, error| results << [value, error] }
+
+        expect(results.pop(timeout: 10)).to eq([nil, nil])
+      end
+    end
+
+    it "raises InvalidArgumentError without a block" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put_async("key", "value").on_complete }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+
+    it "warns about a failing block and still calls the others" do
+      SlateDb::Database.open(tmpdir) do |db|
+        results = Queue.new
+        future = db.put_async("key", "value")
+
+        expect do
+          future.on_complete { raise ArgumentError, "boom" }
+          future.on_complete { |value, error| results << [value, error] }
+          expect(results.pop(timeout: 10)).to eq([nil, nil])
+        end.to output(/on_complete callback raised:.*boom/m).to_stderr
+      end
+    end
+
+    it "stops the dispatcher thread once every callback has run" do
+      SlateDb::Database.open(tmpdir) do |db|
+        results = Queue.new
+        db.put_async("key", "value").on_complete { results << :done }
+        expect(results.pop(timeout: 10)).to eq(:done)
+
+        dispatcher = Thread.list.find { |thread| thread.name == "slatedb-futures" }
+        expect(dispatcher.nil? || dispatcher.join(10)).to be_truthy
+
+        db.put_async("key", "again").on_complete { results << :again }
+        expect(results.pop(timeout: 10)).to eq(:again)
+      end
+    end
+  end
+end
@@ -0,0 +1,62 @@
+# frozen_string_literal: true
+
+RSpec.describe "instrumentation" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:events) { [] }
+  let!(:subscriber) { SlateDb.subscribe { |event, payload| events << [event, payload] } }
+
+  after do
+    SlateDb.unsubscribe(subscriber)
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "publishes an event for each operation" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("key", "value")
+      db.get("key")
+      db.get("missing")
+      db.delete("key")
+    end
+
+    expect(events.map(&:first)).to eq(%w[put.slatedb get.slatedb get.slatedb delete.slatedb])
+
+    _, put = events[0]
+    expect(put).to include(operation: :put, key_size: 3, value_size: 5, error: nil)
+    expect(put[:duration]).to be_a(Float)
+
+    expect(events[1].last).to include(operation: :get, value_size: 5)
+    expect(events[2].last).to include(operation: :get, value_size: nil)
+  end
+
+  it "includes the error when an operation fails" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect { db.get("") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+
+    _, payload = events.last
+    expect(payload[:error]).to be_a(SlateDb::InvalidArgumentError)
+  end
+
+  it "instruments scans and batch writes" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.batch { |b| b.put("a", "1") }
+      db.scan("a").to_a
+      db.scan_prefix("a") { |_key, _value| nil }
+    end
+
+    expect(events.map { |_, payload| payload[:operation] }).to eq(%i[write scan scan_prefix])
+    expect(events.first.last[:key_size]).to be_nil
+  end
+
+  it "stops publishing after unsubscribe" do
+    SlateDb.unsubscribe(subscriber)
+
+    SlateDb::Database.open(tmpdir) { |db| db.put("key", "value") }
+
+    expect(events).to be_empty
+  end
+
+  it "raises InvalidArgumentError when subscribing without a block" do
+    expect { SlateDb.subscribe }.to raise_error(SlateDb::InvalidArgumentError)
+  end
+end
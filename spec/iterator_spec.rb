@@ -61,6 +61,135 @@ RSpec.describe SlateDb::Iterator do
     end
   end
 
+  describe "read_ahead: :auto" do
+    let(:value) { "x" * 4096 }
+
+    it "returns every entry in order while the read-ahead grows" do
+      SlateDb::Database.open(tmpdir) do |db|
+        keys = (0...200).map { |i| format("k:%04d", i) }
+        keys.each { |key| db.put(key, value) }
+
+        expect(db.scan("k:", nil, read_ahead: :auto).map(&:first)).to eq(keys)
+        expect(db.scan_prefix("k:", read_ahead: :auto, order: :desc).map(&:first)).to eq(keys.reverse)
+      end
+    end
+
+    it "does not see writes made after the scan was opened" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        iter = db.scan("a", nil, read_ahead: :auto)
+        db.put("b", "2")
+
+        expect(iter.to_a).to eq([%w[a 1]])
+      end
+    end
+
+    it "supports seeking" do
+      SlateDb::Database.open(tmpdir) do |db|
+        (0...200).each { |i| db.put(format("k:%04d", i), value) }
+
+        iter = db.scan("k:", nil, read_ahead: :auto)
+        iter.seek("k:0150")
+
+        expect(iter.map(&:first).first(2)).to eq(%w[k:0150 k:0151])
+      end
+    end
+
+    it "works on snapshots" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        snapshot = db.snapshot
+
+        expect(snapshot.scan("a", nil, read_ahead: :auto).to_a).to eq([%w[a 1]])
+        snapshot.close
+      end
+    end
+
+    it "raises InvalidArgumentError for other values" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.scan("a", nil, read_ahead: :fast) }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
+  describe "limit, keys_only and binary" do
+    it "stops after limit entries" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[a b c].each { |k| db.put(k, k) }
+
+        expect(db.scan("a", limit: 2).to_a).to eq([%w[a a], %w[b b]])
+        expect(db.scan_prefix("b", limit: 0).to_a).to eq([])
+      end
+    end
+
+    it "yields keys alone with keys_only" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+
+        iter = db.scan("a", keys_only: true)
+        expect(iter.next_entry).to eq("a")
+        expect(iter.to_a).to eq(%w[b])
+      end
+    end
+
+    it "returns binary strings with binary" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+
+        key, value = db.scan("key", binary: true).first
+        expect(key).to eq("key".b)
+        expect(value.encoding).to eq(Encoding::BINARY)
+      end
+    end
+
+    it "returns frozen strings and interned keys with frozen" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+
+        key, value = db.scan("key", frozen: true).first
+        expect(key).to be_frozen
+        expect(value).to be_frozen
+        expect(db.scan_prefix("k", frozen: true, keys_only: true).first).to equal(key)
+
+        binary_key, = db.scan("key", frozen: true, binary: true).first
+        expect(binary_key).to be_frozen
+        expect(binary_key.encoding).to eq(Encoding::BINARY)
+      end
+    end
+
+    it "appends sequence numbers with seq" do
+      SlateDb::Database.open(tmpdir) do |db|
+        first = db.put("a", "1", return_result: true).seq
+        second = db.put("b", "2", return_result: true).seq
+
+        expect(db.scan("a", seq: true).to_a).to eq([["a", "1", first], ["b", "2", second]])
+        expect(db.scan_prefix("b", seq: true, keys_only: true).to_a).to eq([["b", second]])
+
+        db.put("a", "1", return_result: true)
+        expect(db.scan("a", seq: true).first.last).to be > first
+      end
+    end
+
+    it "returns unfrozen strings by default" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+
+        key, value = db.scan("key").first
+        expect(key).not_to be_frozen
+        expect(value).not_to be_frozen
+      end
+    end
+
+    it "applies to auto read-ahead scans" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[a b c].each { |k| db.put(k, k) }
+
+        expect(db.scan("a", read_ahead: :auto, limit: 1, keys_only: true).to_a).to eq(%w[a])
+      end
+    end
+  end
+
   describe "#next_entry_bytes" do
     it "returns key-value pairs as byte arrays" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -129,6 +258,54 @@ RSpec.describe SlateDb::Iterator do
     end
   end
 
+  describe "#lazy_each" do
+    it "enumerates every entry across batches" do
+      SlateDb::Database.open(tmpdir) do |db|
+        5.times { |i| db.put("k#{i}", i.to_s) }
+
+        enum = db.scan("k").lazy_each(batch_size: 2)
+        expect(enum).to be_a(Enumerator)
+        expect(enum.to_a).to eq((0...5).map { |i| ["k#{i}", i.to_s] })
+      end
+    end
+
+    it "supports lazy chains and external iteration" do
+      SlateDb::Database.open(tmpdir) do |db|
+        10.times { |i| db.put("k#{i}", i.to_s) }
+
+        odd = db.scan("k").lazy_each(batch_size: 3).lazy.map { |_, v| v.to_i }.select(&:odd?).first(2)
+        expect(odd).to eq([1, 3])
+
+        enum = db.scan("k").lazy_each(batch_size: 4)
+        expect([enum.next, enum.next]).to eq([%w[k0 0], %w[k1 1]])
+      end
+    end
+
+    it "respects the iterator's limit and shape" do
+      SlateDb::Database.open(tmpdir) do |db|
+        5.times { |i| db.put("k#{i}", i.to_s) }
+
+        expect(db.scan("k", limit: 3, keys_only: true).lazy_each(batch_size: 2).to_a).to eq(%w[k0 k1 k2])
+      end
+    end
+
+    it "continues from entries already read with next_entry" do
+      SlateDb::Database.open(tmpdir) do |db|
+        3.times { |i| db.put("k#{i}", i.to_s) }
+
+        iter = db.scan("k")
+        iter.next_entry
+        expect(iter.lazy_each.map(&:first)).to eq(%w[k1 k2])
+      end
+    end
+
+    it "raises ArgumentError for a non-positive batch_size" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.scan("k").lazy_each(batch_size: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
   describe "Enumerable methods" do
     it "supports map" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -177,6 +354,114 @@ RSpec.describe SlateDb::Iterator do
     end
   end
 
+  describe "#view_info" do
+    it "reports the manifest and durable sequence for database scans" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        info = db.scan("key").view_info
+
+        expect(info[:manifest_id]).to be_a(Integer)
+        expect(info[:durable_seq]).to be_a(Integer)
+        expect(info[:seq]).to be_nil
+      end
+    end
+
+    it "reports the pinned sequence for snapshot scans" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        snapshot = db.snapshot
+        info = snapshot.scan("key").view_info
+
+        expect(info[:seq]).to be_a(Integer)
+        expect(info[:manifest_id]).to be_a(Integer)
+
+        db.put("key2", "value2")
+        expect(snapshot.scan("key").view_info[:seq]).to eq(info[:seq])
+        snapshot.close
+      end
+    end
+  end
+
+  describe "#cursor" do
+    it "resumes a scan after the last returned entry" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[a b c d e].each { |key| db.put(key, key.upcase) }
+        iter = db.scan("a", "e", limit: 2)
+        expect(iter.to_a).to eq([%w[a A], %w[b B]])
+
+        cursor = iter.cursor
+        expect(cursor).to be_a(String)
+        expect(db.scan_from_cursor(cursor).to_a).to eq([%w[c C], %w[d D]])
+      end
+    end
+
+    it "keeps the order and prefix of the original scan" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[user:1 user:2 user:3 other:1].each { |key| db.put(key, "v") }
+        iter = db.scan_prefix("user:", order: :desc, keys_only: true)
+        expect(iter.next_entry).to eq("user:3")
+
+        expect(db.scan_from_cursor(iter.cursor, keys_only: true).to_a).to eq(%w[user:2 user:1])
+      end
+    end
+
+    it "survives reopening the database" do
+      SlateDb::Database.open(tmpdir) do |db|
+        (1..4).each { |i| db.put("k#{i}", i.to_s) }
+      end
+      cursor = SlateDb::Database.open(tmpdir) { |db| db.scan("k").tap(&:next_entry).cursor }
+
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.scan_from_cursor(cursor).map(&:first)).to eq(%w[k2 k3 k4])
+      end
+    end
+
+    it "resumes at the last seek when nothing has been returned since" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[a b c].each { |key| db.put(key, "v") }
+        iter = db.scan("a")
+        iter.seek("b")
+
+        expect(db.scan_from_cursor(iter.cursor, keys_only: true).to_a).to eq(%w[b c])
+      end
+    end
+
+    it "returns keys relative to the namespace" do
+      SlateDb::Database.open(tmpdir) do |db|
+        %w[a b c].each { |key| db.put("tenant:1:#{key}", key) }
+        iter = db.namespace("tenant:1:").scan(keys_only: true)
+        iter.next_entry
+
+        expect(db.scan_from_cursor(iter.cursor, keys_only: true).to_a).to eq(%w[b c])
+      end
+    end
+
+    it "is empty once the scan is exhausted" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        iter = db.scan("a")
+        iter.to_a
+
+        expect(db.scan_from_cursor(iter.cursor).to_a).to eq([])
+      end
+    end
+
+    it "is not available for snapshot scans" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        snapshot = db.snapshot
+        expect { snapshot.scan("a").cursor }.to raise_error(SlateDb::InvalidArgumentError)
+        snapshot.close
+      end
+    end
+
+    it "raises InvalidArgumentError for a malformed cursor" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.scan_from_cursor("not a cursor") }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
   describe "#close" do
     it "closes the iterator" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -256,3 +541,68 @@ RSpec.describe "Database#scan" do
     end
   end
 end
+
+RSpec.describe "Database#page" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  def all_pages(db, **options)
+    pages = [db.page(**options)]
+    pages << db.page(cursor: pages.last[:next_cursor], limit: options[:limit]) while pages.last[:next_cursor]
+    pages
+  end
+
+  it "returns entries with a cursor for the next page" do
+    SlateDb::Database.open(tmpdir) do |db|
+      (1..5).each { |i| db.put("order:#{i}", i.to_s) }
+      db.put("user:1", "ignored")
+
+      pages = all_pages(db, prefix: "order:", limit: 2)
+
+      expect(pages.map { |page| page[:entries].map(&:first) })
+        .to eq([%w[order:1 order:2], %w[order:3 order:4], %w[order:5]])
+      expect(pages.last[:next_cursor]).to be_nil
+    end
+  end
+
+  it "has no next cursor when the last page is exactly full" do
+    SlateDb::Database.open(tmpdir) do |db|
+      %w[a b].each { |key| db.put(key, "v") }
+
+      expect(db.page(limit: 2)).to eq(entries: [%w[a v], %w[b v]], next_cursor: nil)
+    end
+  end
+
+  it "starts after the given key" do
+    SlateDb::Database.open(tmpdir) do |db|
+      %w[a b c d].each { |key| db.put("k:#{key}", key) }
+
+      expect(db.page(prefix: "k:", start_after: "k:b", keys_only: true)[:entries]).to eq(%w[k:c k:d])
+      expect(db.page(prefix: "k:", start_after: "a", keys_only: true)[:entries].size).to eq(4)
+      expect(db.page(prefix: "k:", start_after: "z", keys_only: true)[:entries]).to eq([])
+    end
+  end
+
+  it "pages in descending order" do
+    SlateDb::Database.open(tmpdir) do |db|
+      %w[a b c d].each { |key| db.put(key, "v") }
+
+      pages = all_pages(db, order: :desc, start_after: "d", limit: 2, keys_only: true)
+
+      expect(pages.first[:entries]).to eq(%w[c b])
+      expect(pages.last[:entries].map(&:first)).to eq(%w[a])
+    end
+  end
+
+  it "rejects a cursor combined with prefix or start_after" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("a", "1")
+      cursor = db.scan("a").tap(&:next_entry).cursor
+
+      expect { db.page(cursor:, prefix: "a") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+end
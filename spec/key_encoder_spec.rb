@@ -0,0 +1,59 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::KeyEncoder do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  def sorts_like(values, &encode)
+    keys = values.map(&encode)
+    expect(keys.sort).to eq(keys)
+  end
+
+  it "encodes integers in order and decodes them" do
+    ints = [-(2**63), -1000, -1, 0, 1, 9, 10, 2**63 - 1]
+    sorts_like(ints) { |i| described_class.encode_int(i) }
+    ints.each { |i| expect(described_class.decode_int(described_class.encode_int(i))).to eq(i) }
+  end
+
+  it "encodes floats in order and decodes them" do
+    floats = [-Float::INFINITY, -1e300, -2.5, -1e-300, 0.0, 1e-300, 1.5, 1e300, Float::INFINITY]
+    sorts_like(floats) { |f| described_class.encode_float(f) }
+    floats.each { |f| expect(described_class.decode_float(described_class.encode_float(f))).to eq(f) }
+    expect(described_class.encode_float(-0.0)).to eq(described_class.encode_float(0.0))
+  end
+
+  it "encodes times with nanosecond precision" do
+    times = [Time.at(-1, 5, :nsec), Time.at(0), Time.at(1_700_000_000, 123_456_789, :nsec)]
+    sorts_like(times) { |t| described_class.encode_time(t) }
+    times.each { |t| expect(described_class.decode_time(described_class.encode_time(t))).to eq(t) }
+  end
+
+  it "round-trips tuples" do
+    tuple = [nil, true, false, -3, 2.5, "a\0b", Time.at(1, 2, :nsec)]
+    expect(described_class.decode_tuple(described_class.encode_tuple(tuple))).to eq(tuple)
+  end
+
+  it "sorts tuples element by element" do
+    tuples = [["a"], ["a", -1], ["a", 2], ["a", 10], ["a\0"], ["ab"], ["b", 1]]
+    sorts_like(tuples) { |t| described_class.encode_tuple(t) }
+  end
+
+  it "scans a tuple prefix" do
+    SlateDb::Database.open(tmpdir) do |db|
+      [["user", 2], ["user", 10], ["users", 1]].each { |t| db.put(described_class.encode_tuple(t), t.last.to_s) }
+
+      keys = db.scan_prefix(described_class.encode_tuple(["user"]), keys_only: true).to_a
+      expect(keys.map { |k| described_class.decode_tuple(k) }).to eq([["user", 2], ["user", 10]])
+    end
+  end
+
+  it "rejects values it cannot encode or decode" do
+    error = SlateDb::InvalidArgumentError
+    expect { described_class.encode_tuple([:sym]) }.to raise_error(error, /cannot encode Symbol/)
+    expect { described_class.decode_int("xyz") }.to raise_error(error, /invalid encoded integer/)
+    expect { described_class.decode_tuple("s\0") }.to raise_error(error, /invalid encoded tuple/)
+  end
+end
@@ -0,0 +1,54 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::LazyDatabase do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "opens the database on first use" do
+    db = SlateDb::Database.lazy_open(tmpdir)
+    expect(db).not_to be_opened
+
+    db.put("key", "value")
+    expect(db).to be_opened
+    expect(db.get("key")).to eq("value")
+    expect(db.database).to be_a(SlateDb::Database)
+    db.close
+  end
+
+  it "delegates blocks and keyword arguments" do
+    db = SlateDb::Database.lazy_open(tmpdir)
+    db.put("a", "1", ttl: :none)
+    db.transaction { |txn| txn.put("b", "2") }
+
+    expect(db.scan("a").to_a).to eq([%w[a 1], %w[b 2]])
+    db.close
+  end
+
+  it "opens only once when used from several threads" do
+    db = SlateDb::Database.lazy_open(tmpdir)
+    databases = Array.new(4) { Thread.new { db.database } }.map(&:value)
+
+    expect(databases.uniq.size).to eq(1)
+    db.close
+  end
+
+  it "raises open errors from the first operation and retries on the next" do
+    db = SlateDb::Database.lazy_open("/tmp/test", url: "invalid://not/a/real/scheme")
+
+    expect { db.get("key") }.to raise_error(SlateDb::Error)
+    expect(db).not_to be_opened
+    expect { db.get("key") }.to raise_error(SlateDb::Error)
+  end
+
+  it "does not open after being closed" do
+    db = SlateDb::Database.lazy_open(tmpdir)
+    db.close
+
+    expect(db).to be_closed
+    expect(db).not_to be_opened
+    expect { db.get("key") }.to raise_error(SlateDb::ClosedError)
+  end
+end
@@ -0,0 +1,53 @@
+# frozen_string_literal: true
+
+require "logger"
+require "stringio"
+
+RSpec.describe "logging" do
+  let(:output) { StringIO.new }
+
+  after do
+    SlateDb.logger = nil
+    SlateDb.log_level = :info
+  end
+
+  def wait_for_log(pattern)
+    deadline = Time.now + 5
+    sleep 0.05 until output.string.match?(pattern) || Time.now > deadline
+  end
+
+  it "defaults to the info level" do
+    expect(SlateDb.log_level).to eq(:info)
+  end
+
+  it "forwards SlateDB log records to the Ruby logger" do
+    SlateDb.logger = Logger.new(output)
+    SlateDb::Database.open("logging-test") { |db| db.put("key", "value") }
+
+    wait_for_log(/slatedb::/)
+    expect(output.string).to match(/INFO -- slatedb::/)
+    expect(output.string).not_to match(/DEBUG -- /)
+  end
+
+  it "forwards more detail at the debug level" do
+    SlateDb.logger = Logger.new(output)
+    SlateDb.log_level = :debug
+    SlateDb::Database.open("logging-test") { |db| db.put("key", "value") }
+
+    wait_for_log(/DEBUG -- slatedb::/)
+    expect(output.string).to match(/DEBUG -- slatedb::/)
+  end
+
+  it "stops forwarding when the logger is removed" do
+    SlateDb.logger = Logger.new(output)
+    SlateDb.logger = nil
+    SlateDb::Database.open("logging-test") { |db| db.put("key", "value") }
+
+    sleep 0.3
+    expect(output.string).to be_empty
+  end
+
+  it "rejects unknown levels" do
+    expect { SlateDb.log_level = :loud }.to raise_error(SlateDb::InvalidArgumentError)
+  end
+end
@@ -248,6 +248,67 @@ RSpec.describe "Merge Operations" do
     end
   end
 
+  describe "built-in operators" do
+    it "appends with append" do
+      SlateDb::Database.open(tmpdir, merge_operator: :append) do |db|
+        db.merge("log", "a")
+        db.merge("log", "b")
+
+        expect(db.get("log")).to eq("ab")
+      end
+    end
+
+    it "adds integers with add_i64" do
+      SlateDb::Database.open(tmpdir, merge_operator: :add_i64) do |db|
+        db.merge("visits", "1")
+        db.merge("visits", "41")
+        db.merge("visits", "-2")
+        db.put("reset", "10")
+        db.merge("reset", "5")
+
+        expect(db.get("visits")).to eq("40")
+        expect(db.get("reset")).to eq("15")
+      end
+    end
+
+    it "adds Integers with the JSON codec" do
+      SlateDb::Database.open(tmpdir, merge_operator: :add_i64, codec: :json) do |db|
+        3.times { db.merge("visits", 2) }
+
+        expect(db.get("visits")).to eq(6)
+      end
+    end
+
+    it "wraps around on overflow" do
+      SlateDb::Database.open(tmpdir, merge_operator: :add_i64) do |db|
+        db.merge("n", (2**63 - 1).to_s)
+        db.merge("n", "1")
+
+        expect(db.get("n")).to eq((-2**63).to_s)
+      end
+    end
+
+    it "unions JSON arrays with set_union" do
+      SlateDb::Database.open(tmpdir, merge_operator: :set_union, codec: :json) do |db|
+        db.merge("tags", %w[ruby db])
+        db.merge("tags", %w[db kv])
+        db.merge("tags", [1, "1"])
+
+        expect(db.get("tags")).to eq(["ruby", "db", "kv", 1, "1"])
+      end
+    end
+
+    it "raises InvalidArgumentError for invalid operands" do
+      SlateDb::Database.open(tmpdir, merge_operator: :add_i64) do |db|
+        expect { db.merge("visits", "many") }.to raise_error(SlateDb::InvalidArgumentError, /add_i64/)
+      end
+
+      SlateDb::Database.open(tmpdir, merge_operator: :set_union) do |db|
+        expect { db.merge("tags", "ruby") }.to raise_error(SlateDb::InvalidArgumentError, /set_union/)
+      end
+    end
+  end
+
   describe "with custom Proc merge operator" do
     context "basic operations" do
       it "uses a custom proc to merge values" do
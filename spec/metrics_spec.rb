@@ -28,4 +28,86 @@ RSpec.describe "metrics" do
       expect(metrics_hash).to include(first_name => metrics.get(first_name))
     end
   end
+
+  describe "Database#stats" do
+    it "returns SlateDB's internal metrics as a nested hash" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        db.get("key")
+        db.flush
+
+        stats = db.stats
+        expect(stats[:db][:write_ops]).to eq(1)
+        expect(stats[:db][:l0_sst_count]).to be_a(Integer)
+        expect(stats[:db][:request_count]["op=get"]).to eq(1)
+        expect(stats[:compactor]).to be_a(Hash)
+        expect(stats[:gc][:count]).to be_a(Integer)
+      end
+    end
+
+    it "reports histograms as hashes" do
+      SlateDb::Database.open(tmpdir) do |db|
+        histograms = db.stats[:object_store][:request_duration_seconds].values
+        expect(histograms).not_to be_empty
+        expect(histograms.first.keys).to contain_exactly(:count, :sum, :min, :max)
+      end
+    end
+  end
+
+  describe "Database#latency_stats" do
+    it "summarises latency per operation" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+        db.get("a")
+        db.scan("a").to_a
+        db.transaction { |txn| txn.put("c", "3") }
+
+        stats = db.latency_stats
+        expect(stats.keys).to contain_exactly(:put, :get, :scan, :commit)
+        expect(stats[:put][:count]).to eq(2)
+        expect(stats[:put].keys).to contain_exactly(:count, :avg, :p50, :p95, :p99, :max)
+        expect(stats[:put][:p50]).to be <= stats[:put][:max]
+        expect(stats[:commit][:count]).to eq(1)
+      end
+    end
+
+    it "is cleared by reset_stats" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        db.reset_stats
+        expect(db.latency_stats).to eq({})
+
+        db.get("a")
+        expect(db.latency_stats.keys).to eq([:get])
+      end
+    end
+  end
+
+  describe "Database#object_store_stats" do
+    it "counts requests and bytes per request class" do
+      SlateDb::Database.open(tmpdir) do |db|
+        stats = db.object_store_stats
+        expect(stats.keys).to eq(%i[get put list delete])
+        expect(stats[:get].keys).to eq(%i[requests bytes errors])
+        expect(stats[:put][:requests]).to be > 0
+
+        db.reset_stats
+        db.put("key", "x" * 1000)
+        db.flush(target: :remote)
+
+        put = db.object_store_stats[:put]
+        expect(put[:requests]).to be >= 2
+        expect(put[:bytes]).to be >= 2000
+      end
+    end
+
+    it "is cleared by reset_stats" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        db.reset_stats
+        expect(db.object_store_stats.values).to all(eq(requests: 0, bytes: 0, errors: 0))
+      end
+    end
+  end
 end
@@ -0,0 +1,60 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::Namespace do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "stores keys under the prefix" do
+    SlateDb::Database.open(tmpdir) do |db|
+      tenant = db.namespace("tenant:42:")
+      tenant.put("key", "value")
+
+      expect(tenant.get("key")).to eq("value")
+      expect(db.get("tenant:42:key")).to eq("value")
+
+      tenant.delete("key")
+      expect(db.get("tenant:42:key")).to be_nil
+    end
+  end
+
+  it "scans only its own keys, without the prefix" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.namespace("tenant:1:").put("a", "1")
+      db.namespace("tenant:2:").put("a", "2")
+      db.namespace("tenant:2:").put("b", "3")
+
+      expect(db.namespace("tenant:1:").scan.to_a).to eq([%w[a 1]])
+      expect(db.namespace("tenant:2:").scan.to_a).to eq([%w[a 2], %w[b 3]])
+      expect(db.namespace("tenant:2:").scan("b").to_a).to eq([%w[b 3]])
+      expect(db.namespace("tenant:2:").scan(nil, "b", keys_only: true).to_a).to eq(["a"])
+    end
+  end
+
+  it "seeks relative to the namespace" do
+    SlateDb::Database.open(tmpdir) do |db|
+      ns = db.namespace("ns:")
+      %w[a b c].each { |k| ns.put(k, k) }
+
+      iter = ns.scan
+      iter.seek("b")
+      expect(iter.next_entry).to eq(%w[b b])
+    end
+  end
+
+  it "nests" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.namespace("tenant:42:").namespace("user:").put("1", "Ada")
+      expect(db.get("tenant:42:user:1")).to eq("Ada")
+    end
+  end
+
+  it "rejects empty prefixes and keys" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect { db.namespace("") }.to raise_error(SlateDb::InvalidArgumentError, /prefix cannot be empty/)
+      expect { db.namespace("ns:").get("") }.to raise_error(SlateDb::InvalidArgumentError, /key cannot be empty/)
+    end
+  end
+end
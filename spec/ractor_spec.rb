@@ -0,0 +1,88 @@
+# frozen_string_literal: true
+
+RSpec.describe "Ractor" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  # Ractors print an experimental warning and renamed #take to #value in 3.5,
+  # so each script runs in its own process with a small helper.
+  def run_ractors(script)
+    run_ruby(<<~RUBY)
+      Warning[:experimental] = false
+      def result(ractor) = ractor.respond_to?(:value) ? ractor.value : ractor.take
+      #{script}
+    RUBY
+  end
+
+  it "reads from a shared reader in parallel Ractors" do
+    output = run_ractors(<<~RUBY)
+      SlateDb::Database.open("db", url: #{url.inspect}) do |db|
+        4.times { |i| db.put("key\#{i}", "value\#{i}") }
+        db.flush
+      end
+
+      reader = Ractor.make_shareable(SlateDb::Reader.open("db", url: #{url.inspect}))
+      ractors = 4.times.map do |i|
+        Ractor.new(reader, i) { |r, n| r.get("key\#{n}") }
+      end
+      puts ractors.map { |ractor| result(ractor) }.join(",")
+      reader.close
+    RUBY
+
+    expect(output.lines.last.chomp).to eq("value0,value1,value2,value3")
+  end
+
+  it "reads from a shared snapshot in parallel Ractors" do
+    output = run_ractors(<<~RUBY)
+      SlateDb::Database.open("db", url: #{url.inspect}) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+        snapshot = Ractor.make_shareable(db.snapshot)
+        db.put("a", "changed")
+
+        ractors = 2.times.map do
+          Ractor.new(snapshot) { |s| s.scan("a").map { |key, value| "\#{key}=\#{value}" }.join(" ") }
+        end
+        puts ractors.map { |ractor| result(ractor) }.uniq
+        snapshot.close
+      end
+    RUBY
+
+    expect(output.lines.last.chomp).to eq("a=1 b=2")
+  end
+
+  it "raises SlateDb errors inside Ractors" do
+    output = run_ractors(<<~RUBY)
+      SlateDb::Database.open("db", url: #{url.inspect}) { |db| db.put("key", "value") }
+      reader = Ractor.make_shareable(SlateDb::Reader.open("db", url: #{url.inspect}))
+      ractor = Ractor.new(reader) do |r|
+        r.get("")
+      rescue SlateDb::InvalidArgumentError => e
+        e.class.name
+      end
+      puts result(ractor)
+      reader.close
+    RUBY
+
+    expect(output.lines.last.chomp).to eq("SlateDb::InvalidArgumentError")
+  end
+
+  it "refuses to register callbacks outside the main Ractor" do
+    output = run_ractors(<<~RUBY)
+      ractor = Ractor.new(#{url.inspect}) do |url|
+        SlateDb::Database.open("db", url: url) do |db|
+          db.on_overload(1) { |_pending| nil }
+        end
+      rescue Ractor::UnsafeError => e
+        e.message
+      end
+      puts result(ractor)
+    RUBY
+
+    expect(output.lines.last.chomp).to eq("on_overload can only be set from the main Ractor")
+  end
+end
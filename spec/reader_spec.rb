@@ -50,12 +50,76 @@ RSpec.describe SlateDb::Reader do
         end
       end
 
+      it "exposes the reader's internal metrics" do
+        SlateDb::Reader.open(@path, url: @url) do |reader|
+          reader.get("key")
+          expect(reader.stats[:db][:request_count]["op=get"]).to eq(1)
+        end
+      end
+
       it "accepts max_open_file_handles (SlateDB >= 0.13.0)" do
         SlateDb::Reader.open(@path, url: @url, max_open_file_handles: 16) do |reader|
           expect(reader.get("key")).to eq("value")
         end
       end
 
+      it "opens at the newest checkpoint before as_of" do
+        admin = SlateDb::Admin.new(@path, url: @url)
+        admin.create_checkpoint
+        sleep 0.01
+        as_of = Time.now
+        sleep 0.01
+        SlateDb::Database.open(@path, url: @url) do |db|
+          db.put("key", "updated")
+          db.flush
+        end
+        admin.create_checkpoint
+
+        SlateDb::Reader.open(@path, url: @url, as_of:) do |reader|
+          expect(reader.get("key")).to eq("value")
+        end
+        expect { SlateDb::Reader.open(@path, url: @url, as_of: Time.now - 3600) }
+          .to raise_error(SlateDb::InvalidArgumentError)
+        expect { SlateDb::Reader.open(@path, url: @url, as_of:, checkpoint_id: SecureRandom.uuid) }
+          .to raise_error(SlateDb::InvalidArgumentError, /cannot be combined/)
+      end
+
+      it "reports health, including how far behind the writer it is" do
+        SlateDb::Reader.open(@path, url: @url, manifest_poll_interval: 60_000) do |reader|
+          expect(reader.health).to include(healthy: true, manifests_behind: 0, error: nil)
+
+          SlateDb::Database.open(@path, url: @url) do |db|
+            db.put("key", "updated")
+            db.flush
+          end
+          health = reader.health
+
+          expect(reader).to be_healthy
+          expect(health[:manifests_behind]).to be > 0
+          expect(health[:latest_manifest_id]).to eq(health[:manifest_id] + health[:manifests_behind])
+          expect(health[:last_manifest_write]).to be_a(Time)
+        end
+      end
+
+      it "waits for a write's consistency token with min_seq" do
+        SlateDb::Reader.open(@path, url: @url, manifest_poll_interval: 50) do |reader|
+          SlateDb::Database.open(@path, url: @url) do |db|
+            token = db.put("fresh", "value", return_token: true)
+
+            expect(token).to be_a(Integer)
+            expect(reader.get("fresh", min_seq: token)).to eq("value")
+            expect(reader.scan_prefix("fresh", min_seq: token).to_a).to eq([%w[fresh value]])
+          end
+        end
+      end
+
+      it "raises TimeoutError when min_seq is not reached in time" do
+        SlateDb::Reader.open(@path, url: @url) do |reader|
+          expect { reader.get("key", min_seq: 1 << 60, min_seq_timeout_ms: 50) }
+            .to raise_error(SlateDb::TimeoutError, /did not become visible within 50ms/)
+        end
+      end
+
       it "populates the on-disk cache when cache_root is set" do
         SlateDb::Reader.open(@path, url: @url, cache_root: @cache_root,
                                     max_open_file_handles: 8) do |reader|
@@ -0,0 +1,45 @@
+# frozen_string_literal: true
+
+RSpec.describe "shutdown" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "flushes unflushed writes when the process exits" do
+    run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      db.put("key", "value", await_durable: false)
+    RUBY
+
+    SlateDb::Database.open("db", url: url) do |db|
+      expect(db.get("key")).to eq("value")
+    end
+  end
+
+  it "closes open databases on shutdown! and refuses new ones" do
+    output = run_ruby(<<~RUBY)
+      db = SlateDb::Database.open("db", url: #{url.inspect})
+      db.put("key", "value", await_durable: false)
+      puts SlateDb.shutdown!
+      puts SlateDb.shut_down?
+      begin
+        db.get("key")
+      rescue SlateDb::ClosedError
+        puts "closed"
+      end
+      begin
+        SlateDb::Database.open("other")
+      rescue SlateDb::ClosedError => e
+        puts e.message
+      end
+    RUBY
+
+    expect(output.lines.map(&:chomp)).to eq(["1", "true", "closed", "SlateDB has been shut down"])
+    SlateDb::Database.open("db", url: url) do |db|
+      expect(db.get("key")).to eq("value")
+    end
+  end
+end
@@ -165,3 +165,152 @@ RSpec.describe "Database#snapshot" do
     end
   end
 end
+
+RSpec.describe "Snapshot#scan_many" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "returns each range's entries keyed by range" do
+    SlateDb::Database.open(tmpdir) do |db|
+      %w[a b c d e].each { |key| db.put(key, key.upcase) }
+
+      db.snapshot do |snap|
+        results = snap.scan_many(["a".."b", "c"..."e", "e"..])
+
+        expect(results).to eq(
+          ("a".."b") => [%w[a A], %w[b B]],
+          ("c"..."e") => [%w[c C], %w[d D]],
+          ("e"..) => [%w[e E]]
+        )
+      end
+    end
+  end
+
+  it "reads every range from the snapshot's version" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("a", "old")
+      db.snapshot do |snap|
+        db.put("a", "new")
+        db.put("b", "new")
+
+        expect(snap.scan_many(["a".."a", "b".."b"]).values).to eq([[%w[a old]], []])
+      end
+    end
+  end
+
+  it "caps each range at limit" do
+    SlateDb::Database.open(tmpdir) do |db|
+      %w[a b c x y z].each { |key| db.put(key, "1") }
+
+      db.snapshot do |snap|
+        results = snap.scan_many([.."c", "x"..], limit: 2)
+        expect(results.values.map { |entries| entries.map(&:first) }).to eq([%w[a b], %w[x y]])
+      end
+    end
+  end
+
+  it "decodes values with the database's codec" do
+    SlateDb::Database.open(tmpdir, codec: :json) do |db|
+      db.put("user", { "name" => "Ada" })
+
+      db.snapshot do |snap|
+        expect(snap.scan_many(["u"..]).values).to eq([[["user", { "name" => "Ada" }]]])
+      end
+    end
+  end
+
+  it "raises once the snapshot is closed" do
+    SlateDb::Database.open(tmpdir) do |db|
+      snap = db.snapshot
+      snap.close
+
+      expect { snap.scan_many(["a"..]) }.to raise_error(SlateDb::ClosedError)
+    end
+  end
+end
+
+RSpec.describe "SlateDb.diff" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "reports added, removed and changed keys in key order" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("a", "1")
+      db.put("b", "1")
+      db.put("c", "1")
+      before = db.snapshot
+      db.delete("a")
+      db.put("b", "2")
+      db.put("c", "1")
+      db.put("d", "1")
+
+      expect(SlateDb.diff(before, db.snapshot).to_a).to eq(
+        [[:removed, "a", "1", nil], [:changed, "b", "1", "2"], [:added, "d", nil, "1"]]
+      )
+    end
+  end
+
+  it "yields each difference to a block" do
+    SlateDb::Database.open(tmpdir) do |db|
+      before = db.snapshot
+      db.put("key", "value")
+      changes = []
+      SlateDb.diff(before, db.snapshot) { |op, key, old, new| changes << [op, key, old, new] }
+
+      expect(changes).to eq([[:added, "key", nil, "value"]])
+    end
+  end
+
+  it "limits the comparison to a range" do
+    SlateDb::Database.open(tmpdir) do |db|
+      before = db.snapshot
+      %w[a b c d].each { |key| db.put(key, "1") }
+      after = db.snapshot
+
+      expect(SlateDb.diff(before, after, range: "b".."c").map { |_, key| key }).to eq(%w[b c])
+      expect(SlateDb.diff(before, after, range: "b"..."c").map { |_, key| key }).to eq(%w[b])
+      expect(SlateDb.diff(before, after, range: .."b").map { |_, key| key }).to eq(%w[a b])
+      expect(SlateDb.diff(before, after, range: "c"..).map { |_, key| key }).to eq(%w[c d])
+    end
+  end
+
+  it "decodes values with each snapshot's codec" do
+    SlateDb::Database.open(tmpdir, codec: :json) do |db|
+      db.put("user", { "name" => "Ada" })
+      before = db.snapshot
+      db.put("user", { "name" => "Grace" })
+
+      expect(SlateDb.diff(before, db.snapshot).first)
+        .to eq([:changed, "user", { "name" => "Ada" }, { "name" => "Grace" }])
+    end
+  end
+
+  it "is empty for identical snapshots" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("key", "value")
+
+      expect(SlateDb.diff(db.snapshot, db.snapshot).to_a).to be_empty
+    end
+  end
+
+  it "compares snapshots of databases with their own runtimes" do
+    Dir.mktmpdir("slatedb-other") do |other_dir|
+      SlateDb::Database.open(tmpdir, dedicated_runtime: true) do |db|
+        SlateDb::Database.open(other_dir) do |other|
+          db.put("a", "1")
+          db.put("b", "2")
+          other.put("b", "3")
+
+          expect(SlateDb.diff(db.snapshot, other.snapshot).to_a)
+            .to eq([[:removed, "a", "1", nil], [:changed, "b", "2", "3"]])
+        end
+      end
+    end
+  end
+end
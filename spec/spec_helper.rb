@@ -3,6 +3,21 @@
 require "slatedb"
 require "tmpdir"
 require "fileutils"
+require "open3"
+require "rbconfig"
+
+# Runs scripts in a fresh Ruby process, for behaviour that affects the whole
+# process such as shutdown and fork.
+module SubprocessHelper
+  # Run a script with slatedb loaded, failing the example if it exits with
+  # an error. Returns everything it printed.
+  def run_ruby(script)
+    lib = File.expand_path("../lib", __dir__)
+    output, status = Open3.capture2e(RbConfig.ruby, "-I#{lib}", "-rslatedb", "-e", script)
+    expect(status).to be_success, output
+    output
+  end
+end
 
 RSpec.configure do |config|
   # Enable flags like --only-failures and --next-failure
@@ -15,6 +30,8 @@ RSpec.configure do |config|
     c.syntax = :expect
   end
 
+  config.include SubprocessHelper
+
   # Clean up any test databases
   config.after(:suite) do
     # Cleanup handled by individual tests using tmpdir
@@ -0,0 +1,60 @@
+# frozen_string_literal: true
+
+RSpec.describe "SlateDb.check_store" do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "reports a reachable store" do
+    SlateDb::Database.open("db", url: "file://#{tmpdir}") { |db| db.put("key", "value") }
+
+    check = SlateDb.check_store("file://#{tmpdir}", "db")
+    expect(check).to be_ok
+    expect(check.latency).to be >= 0
+    expect(check.problem).to be_nil
+    expect(check.to_h).to include(url: "file://#{tmpdir}", ok: true, error: nil)
+  end
+
+  it "returns network failures instead of raising" do
+    check = SlateDb.check_store(
+      "s3://bucket",
+      store_options: { endpoint: "http://127.0.0.1:1", allow_http: true, region: "us-east-1",
+                       access_key_id: "key", secret_access_key: "secret" },
+      client_options: { max_retries: 0 }
+    )
+
+    expect(check).not_to be_ok
+    expect(check.problem).to eq(:network)
+    expect(check.error).to be_a(SlateDb::UnavailableError)
+  end
+
+  it "raises for settings rejected before any request" do
+    expect do
+      SlateDb.check_store("s3://bucket", store_options: { secret_key: "x" })
+    end.to raise_error(SlateDb::InvalidArgumentError, /unknown store option/)
+  end
+
+  it "requires a positive timeout" do
+    expect do
+      SlateDb.check_store("file://#{tmpdir}", timeout: 0)
+    end.to raise_error(SlateDb::InvalidArgumentError, /timeout must be a positive number/)
+  end
+
+  context "with MinIO" do
+    let(:endpoint) { ENV.fetch("SLATEDB_MINIO_ENDPOINT", nil) }
+
+    before { skip "set SLATEDB_MINIO_ENDPOINT to run against MinIO" unless endpoint }
+
+    it "reports rejected credentials" do
+      check = SlateDb.check_store(
+        "s3://#{ENV.fetch("SLATEDB_MINIO_BUCKET", "slatedb-test")}",
+        store_options: { preset: :minio, endpoint: endpoint, access_key_id: "wrong", secret_access_key: "wrong" }
+      )
+
+      expect(check.problem).to eq(:permission_denied)
+      expect(check.error.details[:status]).to eq(403)
+    end
+  end
+end
@@ -0,0 +1,88 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::Testing do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+  let(:url) { "file://#{tmpdir}" }
+
+  after do
+    SlateDb::Testing.clear_faults
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  # Write a key to an L0 SST, so reading it after reopening goes to the store.
+  def write_flushed(key, value)
+    SlateDb::Database.open("db", url:) do |db|
+      db.put(key, value)
+      db.flush(target: :remote)
+    end
+  end
+
+  describe ".inject_fault" do
+    it "returns a fault describing what it targets" do
+      fault = SlateDb::Testing.inject_fault(:put, error: :throttled, probability: 0.5)
+      expect(fault.op).to eq(:put)
+      expect(fault.error).to eq(:throttled)
+      expect(fault.hits).to eq(0)
+      expect(fault).to be_active
+    end
+
+    it "raises DataError for reads of objects it reports missing" do
+      write_flushed("key", "value")
+
+      SlateDb::Database.open("db", url:) do |db|
+        fault = SlateDb::Testing.inject_fault(:get, error: :not_found, path: ".sst")
+        expect { db.get("key") }.to raise_error(SlateDb::DataError, /injected fault/)
+        expect(fault.hits).to be >= 1
+
+        fault.remove
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "lets SlateDB retry transient errors" do
+      write_flushed("key", "value")
+
+      SlateDb::Database.open("db", url:) do |db|
+        fault = SlateDb::Testing.inject_fault(:get, error: :unavailable, path: ".sst", times: 2)
+        expect(db.get("key")).to eq("value")
+        expect(fault.hits).to eq(2)
+        expect(fault).not_to be_active
+      end
+    end
+
+    it "delays requests without failing them" do
+      write_flushed("key", "value")
+
+      SlateDb::Database.open("db", url:) do |db|
+        SlateDb::Testing.inject_fault(:get, error: nil, delay_ms: 200, path: ".sst", times: 1)
+        started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+        expect(db.get("key")).to eq("value")
+        expect(Process.clock_gettime(Process::CLOCK_MONOTONIC) - started).to be >= 0.2
+      end
+    end
+
+    it "removes the fault when the block returns" do
+      fault = SlateDb::Testing.inject_fault(:list, error: :not_found) do |f|
+        expect(f).to be_active
+        f
+      end
+      expect(fault).not_to be_active
+      expect(fault.remove).to be(false)
+    end
+
+    it "rejects invalid options" do
+      expect { SlateDb::Testing.inject_fault(:scan) }.to raise_error(SlateDb::InvalidArgumentError, /operation/)
+      expect { SlateDb::Testing.inject_fault(:put, error: :boom) }.to raise_error(SlateDb::InvalidArgumentError)
+      expect { SlateDb::Testing.inject_fault(:put, probability: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+      expect { SlateDb::Testing.inject_fault(:put, error: nil) }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
+  describe ".clear_faults" do
+    it "removes every fault and returns how many there were" do
+      faults = [SlateDb::Testing.inject_fault(:put), SlateDb::Testing.inject_fault(:get)]
+      expect(SlateDb::Testing.clear_faults).to eq(2)
+      expect(faults).to all(satisfy { |fault| !fault.active? })
+    end
+  end
+end
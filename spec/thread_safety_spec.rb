@@ -2,6 +2,7 @@
 
 require "spec_helper"
 require "securerandom"
+require "timeout"
 
 RSpec.describe "Thread Safety" do
   describe "concurrent access" do
@@ -112,4 +113,172 @@ RSpec.describe "Thread Safety" do
       end
     end
   end
+
+  describe "errors raised in other threads" do
+    let(:db) { SlateDb::Database.open("/tmp/thread_errors_test_#{SecureRandom.hex(4)}") }
+
+    def error_in_thread
+      Thread.new do
+        yield
+        nil
+      rescue StandardError => e
+        e
+      end.value
+    end
+
+    it "raises SlateDb exception classes outside the thread that loaded the gem" do
+      invalid = error_in_thread { db.get("") }
+      db.close
+      closed = error_in_thread { db.put("key", "value") }
+
+      expect(invalid).to be_a(SlateDb::InvalidArgumentError)
+      expect(closed).to be_a(SlateDb::ClosedError)
+    end
+  end
+
+  describe "interrupting blocking calls" do
+    let(:db) { SlateDb::Database.open("/tmp/interrupt_test_#{SecureRandom.hex(4)}") }
+    let(:events) { Queue.new }
+    let(:watcher) { db.watch("prefix") { |*event| events << event } }
+
+    after do
+      watcher.stop
+      db.close
+    end
+
+    it "raises Timeout::Error from a call blocked on the runtime" do
+      expect { Timeout.timeout(0.2) { watcher.next_event } }.to raise_error(Timeout::Error)
+    end
+
+    it "lets Thread#kill stop a thread blocked on the runtime" do
+      thread = Thread.new { watcher.next_event }
+      sleep 0.1
+      thread.kill
+
+      expect(thread.join(1)).to eq(thread)
+    end
+
+    it "keeps waiting when a blocked thread is woken without an exception" do
+      watcher
+      sleep 0.1
+      watcher.instance_variable_get(:@thread).wakeup
+      db.put("prefix:key", "value")
+
+      expect(Timeout.timeout(5) { events.pop }).to eq(["prefix:key", "value", :put])
+    end
+  end
+
+  describe "waiting on the runtime" do
+    let(:db) { SlateDb::Database.open("/tmp/runtime_wait_test_#{SecureRandom.hex(4)}") }
+
+    after { db.close }
+
+    it "returns results that are ready on the first poll" do
+      db.put("key", "value")
+
+      expect(Array.new(100) { db.get("key") }).to all(eq("value"))
+      expect(db.get("missing")).to be_nil
+    end
+
+    it "releases the GVL while an operation is still pending" do
+      events = Queue.new
+      watcher = db.watch("prefix") { |*event| events << event }
+      sleep 0.1
+
+      expect(watcher.instance_variable_get(:@thread).status).to eq("sleep")
+      db.put("prefix:key", "value")
+      expect(Timeout.timeout(5) { events.pop }).to eq(["prefix:key", "value", :put])
+    ensure
+      watcher&.stop
+    end
+  end
+
+  describe "thread_safe option" do
+    let(:path) { "/tmp/thread_mode_test_#{SecureRandom.hex(4)}" }
+
+    it "shares handles between threads by default" do
+      SlateDb::Database.open(path) do |db|
+        expect(db.thread_safe).to eq(:shared)
+        Thread.new { db.put("key", "value") }.join
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "raises ThreadOwnershipError when another thread uses an exclusive handle" do
+      SlateDb::Database.open(path, thread_safe: :exclusive) do |db|
+        expect(db.thread_safe).to eq(:exclusive)
+        db.put("key", "value")
+
+        error = Thread.new do
+          db.get("key")
+        rescue SlateDb::Error => e
+          e
+        end.value
+
+        expect(error).to be_a(SlateDb::ThreadOwnershipError)
+        expect(error.code).to eq(:thread_ownership)
+        expect(error.message).to include(Thread.current.inspect)
+        expect { Thread.new { db.put_async("key", "other") }.join }.to raise_error(SlateDb::ThreadOwnershipError)
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "lets fibers of the owner thread use an exclusive handle" do
+      SlateDb::Database.open(path, thread_safe: :exclusive) do |db|
+        Fiber.new { db.put("key", "value") }.resume
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "lets another thread close an exclusive handle" do
+      db = SlateDb::Database.open(path, thread_safe: :exclusive)
+      Thread.new { db.close }.join
+      expect { db.get("key") }.to raise_error(SlateDb::ClosedError)
+    end
+
+    it "rejects unknown modes" do
+      expect { SlateDb::Database.open(path, thread_safe: :sometimes) }
+        .to raise_error(SlateDb::InvalidArgumentError, /thread_safe/)
+    end
+  end
+
+  describe "dedicated_runtime option" do
+    let(:path) { "/tmp/dedicated_runtime_test_#{SecureRandom.hex(4)}" }
+
+    it "uses the shared runtime by default" do
+      SlateDb::Database.open(path) { |db| expect(db.dedicated_runtime?).to be(false) }
+    end
+
+    it "runs operations and everything taken from the database on its own runtime" do
+      SlateDb::Database.open(path, dedicated_runtime: true, worker_threads: 2) do |db|
+        expect(db.dedicated_runtime?).to be(true)
+        db.put("a", "1")
+        expect(db.get_async("a").await).to eq("1")
+        db.transaction { |txn| txn.put("b", "2") }
+
+        snapshot = db.snapshot
+        expect(snapshot.scan("a").to_a).to eq([%w[a 1], %w[b 2]])
+        snapshot.close
+
+        threads = 4.times.map { |i| Thread.new { db.put("t#{i}", i.to_s) } }
+        threads.each(&:join)
+        expect(db.scan("t").map(&:first)).to eq(%w[t0 t1 t2 t3])
+      end
+    end
+
+    it "reopens on its own runtime" do
+      SlateDb::Database.open(path, dedicated_runtime: true) do |db|
+        db.put("key", "value")
+        db.reopen
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "requires dedicated_runtime for worker_threads" do
+      expect { SlateDb::Database.open(path, worker_threads: 2) }
+        .to raise_error(SlateDb::InvalidArgumentError, /dedicated_runtime/)
+      expect { SlateDb::Database.open(path, dedicated_runtime: true, worker_threads: 0) }
+        .to raise_error(SlateDb::InvalidArgumentError, /worker_threads/)
+    end
+  end
 end
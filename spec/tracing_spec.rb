@@ -0,0 +1,96 @@
+# frozen_string_literal: true
+
+require "json"
+require "socket"
+
+RSpec.describe "tracing" do
+  let(:traceparent) { "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01" }
+  let(:server) { TCPServer.new("127.0.0.1", 0) }
+  let(:requests) { Queue.new }
+
+  # A minimal OTLP/HTTP collector that records each export request.
+  let!(:collector) do
+    Thread.new do
+      loop do
+        client = server.accept
+        request_line = client.gets
+        headers = {}
+        while (line = client.gets) && line != "\r\n"
+          name, value = line.split(":", 2)
+          headers[name.downcase] = value.strip
+        end
+        body = client.read(headers["content-length"].to_i)
+        client.write("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+        client.close
+        requests << { path: request_line.split[1], headers: headers, body: JSON.parse(body) }
+      end
+    end
+  end
+
+  before do
+    SlateDb.configure_tracing(endpoint: "http://127.0.0.1:#{server.addr[1]}",
+                              service_name: "spec", headers: { "x-api-key" => "secret" })
+  end
+
+  after do
+    SlateDb.configure_tracing(endpoint: nil)
+    collector.kill
+    server.close
+  end
+
+  def exported_spans
+    request = requests.pop(timeout: 5)
+    raise "no spans exported" unless request
+
+    request
+  end
+
+  it "exports a child span for a traced operation" do
+    SlateDb::Database.open("tracing-test") do |db|
+      db.put("key", "value", trace_context: { "traceparent" => traceparent })
+    end
+
+    request = exported_spans
+    expect(request[:path]).to eq("/v1/traces")
+    expect(request[:headers]["x-api-key"]).to eq("secret")
+
+    resource_spans = request[:body]["resourceSpans"].first
+    expect(resource_spans["resource"]["attributes"]).to include(
+      { "key" => "service.name", "value" => { "stringValue" => "spec" } }
+    )
+
+    span = resource_spans["scopeSpans"].first["spans"].first
+    expect(span["name"]).to eq("slatedb.put")
+    expect(span["traceId"]).to eq("0af7651916cd43dd8448eb211c80319c")
+    expect(span["parentSpanId"]).to eq("b7ad6b7169203331")
+    expect(span["spanId"]).to match(/\A\h{16}\z/)
+    expect(span["endTimeUnixNano"].to_i).to be >= span["startTimeUnixNano"].to_i
+  end
+
+  it "accepts symbol keys and traces transaction commits" do
+    SlateDb::Database.open("tracing-test") do |db|
+      txn = db.begin_transaction
+      txn.put("key", "value")
+      txn.commit(trace_context: { traceparent: traceparent })
+    end
+
+    span = exported_spans[:body]["resourceSpans"].first["scopeSpans"].first["spans"].first
+    expect(span["name"]).to eq("slatedb.commit")
+  end
+
+  it "does not trace unsampled or untraced operations" do
+    SlateDb::Database.open("tracing-test") do |db|
+      db.put("key", "value")
+      db.get("key", trace_context: { "traceparent" => traceparent.sub(/-01\z/, "-00") })
+      db.get("key", trace_context: { "traceparent" => "not a traceparent" })
+    end
+
+    expect(requests.pop(timeout: 2)).to be_nil
+  end
+
+  it "rejects endpoints that are not HTTP URLs" do
+    expect do
+      SlateDb.configure_tracing(endpoint: "ftp://collector")
+    end.to raise_error(SlateDb::InvalidArgumentError)
+  end
+end
@@ -51,6 +51,308 @@ RSpec.describe SlateDb::Transaction do
     end
   end
 
+  describe "#get_many" do
+    it "returns values in key order with nil for missing keys" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+
+        txn = db.begin_transaction
+        txn.put("b", "2")
+        expect(txn.get_many(%w[a b missing])).to eq(["1", "2", nil])
+        txn.rollback
+      end
+    end
+
+    it "raises InvalidArgumentError for empty keys" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        expect { txn.get_many(["a", ""]) }.to raise_error(SlateDb::InvalidArgumentError)
+        txn.rollback
+      end
+    end
+  end
+
+  describe "#get_for_update" do
+    it "returns the value like #get" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+
+        txn = db.begin_transaction
+        expect(txn.get_for_update("a")).to eq("1")
+        expect(txn.get_for_update("missing")).to be_nil
+        txn.rollback
+      end
+    end
+
+    it "detects conflicting writes under snapshot isolation" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("counter", "0")
+
+        txn = db.begin_transaction(isolation: :snapshot)
+        value = txn.get_for_update("counter").to_i
+        db.put("counter", "10")
+        txn.put("counter", (value + 1).to_s)
+
+        expect { txn.commit }.to raise_error(SlateDb::TransactionError)
+        expect(db.get("counter")).to eq("10")
+      end
+    end
+
+    it "does not conflict on keys read with #get under snapshot isolation" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("counter", "0")
+
+        txn = db.begin_transaction(isolation: :snapshot)
+        txn.get("counter")
+        db.put("counter", "10")
+        txn.put("other", "1")
+
+        expect { txn.commit }.not_to raise_error
+      end
+    end
+  end
+
+  describe "#delete_many" do
+    it "deletes every key within the transaction" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+        db.put("c", "3")
+
+        txn = db.begin_transaction
+        txn.delete_many(%w[a b])
+        expect(txn.get_many(%w[a b c])).to eq([nil, nil, "3"])
+        txn.commit
+
+        expect(db.get("a")).to be_nil
+        expect(db.get("c")).to eq("3")
+      end
+    end
+
+    it "applies nothing when a key is invalid" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+
+        txn = db.begin_transaction
+        expect { txn.delete_many(["a", ""]) }.to raise_error(SlateDb::InvalidArgumentError)
+        expect(txn.get("a")).to eq("1")
+        txn.rollback
+      end
+    end
+  end
+
+  describe "#write" do
+    it "applies batch operations within the transaction" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("old", "1")
+
+        batch = SlateDb::WriteBatch.new
+        batch.put("a", "1")
+        batch.put("b", "2", ttl: :none)
+        batch.delete("old")
+
+        txn = db.begin_transaction
+        txn.write(batch)
+        expect(txn.get_many(%w[a b old])).to eq(["1", "2", nil])
+        expect(db.get("a")).to be_nil
+        txn.commit
+
+        expect(db.get("a")).to eq("1")
+        expect(db.get("b")).to eq("2")
+        expect(db.get("old")).to be_nil
+      end
+    end
+
+    it "removes keys written by the transaction with delete_range" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a:1", "1")
+
+        batch = SlateDb::WriteBatch.new
+        batch.delete_range("a:", "a;")
+        batch.put("a:3", "3")
+
+        db.transaction do |txn|
+          txn.put("a:2", "2")
+          txn.write(batch)
+        end
+
+        expect(db.scan_prefix("a:").to_a).to eq([%w[a:3 3]])
+      end
+    end
+
+    it "discards the batch on rollback" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new
+        batch.put("a", "1")
+
+        txn = db.begin_transaction
+        txn.write(batch)
+        txn.rollback
+
+        expect(db.get("a")).to be_nil
+      end
+    end
+  end
+
+  describe "savepoints" do
+    it "rolls back writes made after a savepoint" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+
+        txn = db.begin_transaction
+        txn.put("b", "2")
+        sp = txn.savepoint
+        txn.put("a", "changed")
+        txn.delete("b")
+        txn.put("c", "3")
+        txn.rollback_to(sp)
+
+        expect(txn.get_many(%w[a b c])).to eq(["1", "2", nil])
+        txn.commit
+
+        expect(db.get("a")).to eq("1")
+        expect(db.get("b")).to eq("2")
+        expect(db.get("c")).to be_nil
+      end
+    end
+
+    it "supports nested savepoints" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        outer = txn.savepoint
+        txn.put("a", "1")
+        inner = txn.savepoint
+        txn.put("a", "2")
+
+        txn.rollback_to(inner)
+        expect(txn.get("a")).to eq("1")
+        txn.rollback_to(outer)
+        expect(txn.get("a")).to be_nil
+        expect { txn.rollback_to(inner) }.to raise_error(SlateDb::InvalidArgumentError)
+        txn.rollback
+      end
+    end
+
+    it "rolls back the block's writes when it raises" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.transaction do |txn|
+          txn.put("order", "placed")
+          expect do
+            txn.savepoint do
+              txn.put("discount", "10")
+              raise ArgumentError, "no discount"
+            end
+          end.to raise_error(ArgumentError)
+        end
+
+        expect(db.get("order")).to eq("placed")
+        expect(db.get("discount")).to be_nil
+      end
+    end
+
+    it "keeps the block's writes and returns its result" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.transaction do |txn|
+          result = txn.savepoint do
+            txn.put("a", "1")
+            :done
+          end
+          expect(result).to eq(:done)
+        end
+
+        expect(db.get("a")).to eq("1")
+      end
+    end
+
+    it "rolls back batch writes including delete_range" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("k:1", "1")
+        db.put("k:2", "2")
+
+        batch = SlateDb::WriteBatch.new
+        batch.delete_range("k:", "k;")
+
+        txn = db.begin_transaction
+        sp = txn.savepoint
+        txn.write(batch)
+        expect(txn.scan_prefix("k:").to_a).to be_empty
+        txn.rollback_to(sp)
+
+        expect(txn.scan_prefix("k:").to_a).to eq([%w[k:1 1], %w[k:2 2]])
+        txn.rollback
+      end
+    end
+  end
+
+  describe "state accessors" do
+    it "reports the isolation level" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.begin_transaction.isolation_level).to eq(:snapshot)
+        expect(db.begin_transaction(isolation: :serializable).isolation_level).to eq(:serializable)
+      end
+    end
+
+    it "reports a unique id and the read sequence number" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        first = db.begin_transaction
+        second = db.begin_transaction
+
+        expect(first.id).to match(/\A\h{8}-\h{4}-\h{4}-\h{4}-\h{12}\z/)
+        expect(first.id).not_to eq(second.id)
+        expect(first.seqnum).to be >= 1
+      end
+    end
+
+    it "counts buffered writes and their size" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("c", "3")
+        txn = db.begin_transaction
+        expect(txn.op_count).to eq(0)
+        expect(txn.write_bytes).to eq(0)
+
+        txn.put("a", "12")
+        txn.delete("b")
+        txn.delete_many(%w[c d])
+        expect(txn.op_count).to eq(4)
+        expect(txn.write_bytes).to eq(6)
+
+        batch = SlateDb::WriteBatch.new
+        batch.put("e", "5")
+        batch.delete_range("c", "d")
+        txn.write(batch)
+        expect(txn.op_count).to eq(5)
+        expect(txn.write_bytes).to eq(8)
+        txn.rollback
+      end
+    end
+
+    it "restores the counts on rollback_to" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        txn.put("a", "1")
+        savepoint = txn.savepoint
+        txn.put("b", "2")
+        txn.rollback_to(savepoint)
+
+        expect(txn.op_count).to eq(1)
+        expect(txn.write_bytes).to eq(2)
+        txn.rollback
+      end
+    end
+
+    it "describes itself as a hash" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        txn.put("a", "1")
+
+        expect(txn.to_h).to include(id: txn.id, isolation_level: :snapshot, op_count: 1, write_bytes: 2)
+        expect(txn.inspect).to include("op_count=1")
+        txn.rollback
+      end
+    end
+  end
+
   describe "#scan" do
     it "scans keys within transaction" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -65,6 +367,56 @@ RSpec.describe SlateDb::Transaction do
         txn.commit
       end
     end
+
+    it "scans in descending order with a limit" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        db.put("b", "2")
+        txn = db.begin_transaction
+        txn.put("c", "3")
+
+        expect(txn.scan("a", order: :desc, limit: 2).to_a).to eq([%w[c 3], %w[b 2]])
+        txn.rollback
+      end
+    end
+
+    it "yields only keys with keys_only" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("a", "1")
+        txn = db.begin_transaction
+        txn.put("b", "2")
+
+        expect(txn.scan("a", keys_only: true).to_a).to eq(%w[a b])
+        txn.rollback
+      end
+    end
+
+    it "returns exact bytes with binary" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        txn.put("k", "v")
+
+        key, value = txn.scan("k", binary: true).first
+        expect(key.encoding).to eq(Encoding::BINARY)
+        expect(value).to eq("v".b)
+        txn.rollback
+      end
+    end
+  end
+
+  describe "#scan_prefix" do
+    it "supports order, limit and keys_only" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("user:1", "a")
+        txn = db.begin_transaction
+        txn.put("user:2", "b")
+        txn.put("user:3", "c")
+        txn.put("other", "x")
+
+        expect(txn.scan_prefix("user:", order: :desc, limit: 2, keys_only: true).to_a).to eq(%w[user:3 user:2])
+        txn.rollback
+      end
+    end
   end
 
   describe "#commit" do
@@ -96,6 +448,26 @@ RSpec.describe SlateDb::Transaction do
         expect { txn.commit }.to raise_error(SlateDb::ClosedError)
       end
     end
+
+    it "commits within timeout_ms" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        txn.put("key", "value")
+        txn.commit(timeout_ms: 10_000)
+
+        expect(db.get("key")).to eq("value")
+      end
+    end
+
+    it "raises CommitTimeoutError when the commit takes longer than timeout_ms" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        txn.put("key", "value")
+
+        expect { txn.commit(timeout_ms: 0) }.to raise_error(SlateDb::CommitTimeoutError, /within 0ms/)
+        expect(txn.closed?).to be true
+      end
+    end
   end
 
   describe "#rollback" do
@@ -147,6 +519,44 @@ RSpec.describe SlateDb::Transaction do
       end
     end
   end
+
+  describe "read_only" do
+    it "reads at its snapshot and rejects writes" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("key", "value")
+        txn = db.begin_transaction(read_only: true)
+        db.put("key", "updated")
+
+        expect(txn).to be_read_only
+        expect(txn.get("key")).to eq("value")
+        expect(txn.get_for_update("key")).to eq("value")
+        expect { txn.put("key", "other") }.to raise_error(SlateDb::InvalidArgumentError, /read-only/)
+        expect { txn.delete("key") }.to raise_error(SlateDb::InvalidArgumentError, /read-only/)
+        expect { txn.merge("key", "x") }.to raise_error(SlateDb::InvalidArgumentError, /read-only/)
+        expect(txn.commit(return_handle: true)).to be_nil
+        expect(txn).to be_closed
+        expect(db.get("key")).to eq("updated")
+      end
+    end
+
+    it "never conflicts with concurrent writers" do
+      SlateDb::Database.open(tmpdir) do |db|
+        result = db.transaction(read_only: true) do |txn|
+          txn.mark_read(["key"])
+          db.put("key", "changed")
+          txn.get("key")
+        end
+        expect(result).to be_nil
+      end
+    end
+
+    it "cannot be combined with serializable isolation" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.begin_transaction(isolation: :serializable, read_only: true) }
+          .to raise_error(SlateDb::InvalidArgumentError, /serializable/)
+      end
+    end
+  end
 end
 
 RSpec.describe "Database#transaction" do
@@ -203,4 +613,62 @@ RSpec.describe "Database#transaction" do
       end
     end
   end
+
+  describe "retries" do
+    it "re-runs the block after a conflict" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("counter", "0")
+        attempts = 0
+
+        db.transaction(isolation: :serializable, retries: 2) do |txn|
+          attempts += 1
+          value = txn.get("counter").to_i
+          db.put("counter", "10") if attempts == 1
+          txn.put("counter", (value + 1).to_s)
+        end
+
+        expect(attempts).to eq(2)
+        expect(db.get("counter")).to eq("11")
+      end
+    end
+
+    it "raises TransactionError once retries are exhausted" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("counter", "0")
+        attempts = 0
+
+        expect do
+          db.transaction(isolation: :serializable, retries: 1) do |txn|
+            attempts += 1
+            txn.get("counter")
+            db.put("counter", attempts.to_s)
+            txn.put("counter", "conflict")
+          end
+        end.to raise_error(SlateDb::TransactionError)
+
+        expect(attempts).to eq(2)
+      end
+    end
+
+    it "does not retry other errors" do
+      SlateDb::Database.open(tmpdir) do |db|
+        attempts = 0
+
+        expect do
+          db.transaction(retries: 3) do |_txn|
+            attempts += 1
+            raise ArgumentError, "test error"
+          end
+        end.to raise_error(ArgumentError)
+
+        expect(attempts).to eq(1)
+      end
+    end
+
+    it "raises InvalidArgumentError without a block" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.transaction(retries: 1) }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
 end
@@ -0,0 +1,90 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::Watcher do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  def wait_for(timeout: 5)
+    deadline = Time.now + timeout
+    sleep 0.01 until yield || Time.now > deadline
+  end
+
+  it "calls the block for changes under the prefix" do
+    SlateDb::Database.open(tmpdir) do |db|
+      events = Queue.new
+      watcher = db.watch("config:", interval: 0.01) { |*event| events << event }
+
+      db.put("config:a", "1")
+      db.put("other", "x")
+      db.delete("config:a")
+      wait_for { events.size >= 2 }
+      watcher.stop
+
+      expect(events.size).to eq(2)
+      expect(events.pop).to eq(["config:a", "1", :put])
+      expect(events.pop).to eq(["config:a", nil, :delete])
+    end
+  end
+
+  it "ignores changes made before it started" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("config:old", "1")
+      events = Queue.new
+      watcher = db.watch("config:", interval: 0.01) { |key, _value, _op| events << key }
+
+      db.put("config:new", "2")
+      wait_for { events.size >= 1 }
+      watcher.stop
+
+      expect(events.pop).to eq("config:new")
+    end
+  end
+
+  it "replays changes after since_seq" do
+    SlateDb::Database.open(tmpdir) do |db|
+      db.put("config:old", "1")
+      events = Queue.new
+      watcher = db.watch("config:", since_seq: 0, interval: 0.01) { |key, _value, _op| events << key }
+
+      wait_for { events.size >= 1 }
+      watcher.stop
+
+      expect(events.pop).to eq("config:old")
+    end
+  end
+
+  it "stops" do
+    SlateDb::Database.open(tmpdir) do |db|
+      watcher = db.watch("config:") { nil }
+      watcher.stop
+
+      expect(watcher.join(5)).to eq(watcher)
+      expect(watcher).to be_stopped
+    end
+  end
+
+  it "can be stopped from inside the block" do
+    SlateDb::Database.open(tmpdir) do |db|
+      watcher = db.watch("config:", interval: 0.01) { watcher.stop }
+      db.put("config:a", "1")
+
+      expect(watcher.join(5)).to eq(watcher)
+    end
+  end
+
+  it "requires a block" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect { db.watch("config:") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
+  it "rejects encrypted databases" do
+    SlateDb::Database.open(tmpdir, encryption: "k" * 32) do |db|
+      expect { db.watch("config:") { nil } }
+        .to raise_error(SlateDb::InvalidArgumentError, /cannot be combined with encryption/)
+    end
+  end
+end
@@ -1,5 +1,7 @@
 # frozen_string_literal: true
 
+require "objspace"
+
 RSpec.describe SlateDb::WriteBatch do
   let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
 
@@ -49,6 +51,38 @@ RSpec.describe SlateDb::WriteBatch do
       end
     end
 
+    it "accepts :default and :none as ttl values" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new
+                                   .put("a", "1", ttl: :default)
+                                   .put("b", "2", ttl: :none)
+
+        db.write(batch)
+
+        expect(db.get("a")).to eq("1")
+        expect(db.get_key_value("b")[:expire_ts]).to be_nil
+      end
+    end
+
+    it "accepts an absolute expire_at" do
+      SlateDb::Database.open(tmpdir, merge_operator: :string_concat) do |db|
+        deadline = Time.at(Time.now.to_i + 3600)
+        batch = SlateDb::WriteBatch.new
+                                   .put("a", "1", expire_at: deadline)
+                                   .merge("b", "2", expire_at: deadline.to_i * 1000)
+
+        db.write(batch)
+
+        expect(db.get_key_value("a")[:expire_ts]).to eq(deadline.to_i * 1000)
+        expect(db.get_key_value("b")[:expire_ts]).to eq(deadline.to_i * 1000)
+      end
+    end
+
+    it "raises InvalidArgumentError for an unknown ttl symbol" do
+      batch = SlateDb::WriteBatch.new
+      expect { batch.put("key", "value", ttl: :forever) }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+
     it "raises InvalidArgumentError for empty keys" do
       batch = SlateDb::WriteBatch.new
       expect { batch.put("", "value") }.to raise_error(SlateDb::InvalidArgumentError)
@@ -83,6 +117,66 @@ RSpec.describe SlateDb::WriteBatch do
     end
   end
 
+  describe "#delete_range" do
+    it "deletes existing keys in the range" do
+      SlateDb::Database.open(tmpdir) do |db|
+        db.put("user:41:name", "a")
+        db.put("user:42:name", "b")
+        db.put("user:42:email", "c")
+        db.put("user:43:name", "d")
+
+        batch = SlateDb::WriteBatch.new
+                                   .delete_range("user:42:", "user:43:")
+                                   .put("audit", "removed")
+        db.write(batch)
+
+        expect(db.get("user:41:name")).to eq("a")
+        expect(db.get("user:42:name")).to be_nil
+        expect(db.get("user:42:email")).to be_nil
+        expect(db.get("user:43:name")).to eq("d")
+        expect(db.get("audit")).to eq("removed")
+      end
+    end
+
+    it "removes earlier batch writes in the range but keeps later ones" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new
+                                   .put("k:1", "before")
+                                   .delete_range("k:", "k:\xff")
+                                   .put("k:2", "after")
+        db.write(batch)
+
+        expect(db.get("k:1")).to be_nil
+        expect(db.get("k:2")).to eq("after")
+      end
+    end
+
+    it "writes a batch whose range has no keys" do
+      SlateDb::Database.open(tmpdir) do |db|
+        token = db.write(SlateDb::WriteBatch.new.delete_range("a", "b"), return_token: true)
+
+        expect(token).to be_a(Integer)
+        expect(db.get("a")).to be_nil
+      end
+    end
+
+    it "returns self for method chaining" do
+      batch = SlateDb::WriteBatch.new
+      expect(batch.delete_range("a", "b")).to be(batch)
+    end
+
+    it "raises InvalidArgumentError for an empty start key" do
+      batch = SlateDb::WriteBatch.new
+      expect { batch.delete_range("", "b") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+
+    it "raises InvalidArgumentError when start is not before end" do
+      batch = SlateDb::WriteBatch.new
+      expect { batch.delete_range("b", "a") }.to raise_error(SlateDb::InvalidArgumentError)
+      expect { batch.delete_range("a", "a") }.to raise_error(SlateDb::InvalidArgumentError)
+    end
+  end
+
   describe "mixed operations" do
     it "handles puts and deletes in same batch" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -102,6 +196,17 @@ RSpec.describe SlateDb::WriteBatch do
       end
     end
   end
+
+  describe "memory reporting" do
+    it "counts queued keys and values in ObjectSpace.memsize_of" do
+      batch = SlateDb::WriteBatch.new
+      empty = ObjectSpace.memsize_of(batch)
+
+      batch.put("key", "v" * 100_000)
+
+      expect(ObjectSpace.memsize_of(batch)).to be >= empty + 100_000
+    end
+  end
 end
 
 RSpec.describe "Database#write" do
@@ -126,6 +231,54 @@ RSpec.describe "Database#write" do
     end
   end
 
+  describe "#write_chunked" do
+    it "writes the batch in chunks of at most max_ops operations" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new
+        5.times { |i| batch.put("key#{i}", "value#{i}") }
+
+        chunks = []
+        expect(db.write_chunked(batch, max_ops: 2) { |info| chunks << info }).to eq(3)
+
+        expect(chunks.map { |info| info[:ops] }).to eq([2, 2, 1])
+        expect(chunks.map { |info| info[:chunk] }).to eq([1, 2, 3])
+        expect(chunks.last).to include(chunks: 3, percent: 100.0)
+        expect(chunks.map { |info| info[:seq] }).to eq(chunks.map { |info| info[:seq] }.sort)
+        5.times { |i| expect(db.get("key#{i}")).to eq("value#{i}") }
+      end
+    end
+
+    it "splits chunks by max_bytes" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new
+        3.times { |i| batch.put("k#{i}", "x" * 8) }
+
+        expect(db.write_chunked(batch, max_bytes: 20)).to eq(2)
+      end
+    end
+
+    it "removes keys written by earlier chunks with delete_range" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new
+        batch.put("a", "1")
+        batch.put("b", "2")
+        batch.delete_range("a", "b")
+
+        db.write_chunked(batch, max_ops: 1)
+
+        expect(db.get("a")).to be_nil
+        expect(db.get("b")).to eq("2")
+      end
+    end
+
+    it "raises InvalidArgumentError for a max_ops below 1" do
+      SlateDb::Database.open(tmpdir) do |db|
+        batch = SlateDb::WriteBatch.new.put("key", "value")
+        expect { db.write_chunked(batch, max_ops: 0) }.to raise_error(SlateDb::InvalidArgumentError)
+      end
+    end
+  end
+
   describe "#batch" do
     it "creates and writes a batch using block" do
       SlateDb::Database.open(tmpdir) do |db|
@@ -0,0 +1,119 @@
+# frozen_string_literal: true
+
+RSpec.describe SlateDb::WriteResult do
+  let(:tmpdir) { Dir.mktmpdir("slatedb-test") }
+
+  after do
+    FileUtils.rm_rf(tmpdir)
+  end
+
+  it "is not returned unless requested" do
+    SlateDb::Database.open(tmpdir) do |db|
+      expect(db.put("key", "value")).to be_nil
+      expect(db.put("key", "value", await_durable: false)).to be_nil
+    end
+  end
+
+  it "describes a put" do
+    SlateDb::Database.open(tmpdir) do |db|
+      result = db.put("key", "value", return_result: true)
+
+      expect(result).to be_a(described_class)
+      expect(result.seq).to eq(db.get_key_value("key")[:seq])
+      expect(result.durability).to eq(:remote)
+      expect(result).to be_durable
+      expect(result.bytes).to eq(8)
+      expect(result.duration).to be >= 0
+    end
+  end
+
+  it "reports memory durability when not awaiting durability" do
+    SlateDb::Database.open(tmpdir) do |db|
+      result = db.put("key", "value", await_durable: false, return_result: true)
+
+      expect(result.durability).to eq(:memory)
+      expect(result).not_to be_durable
+    end
+  end
+
+  it "describes deletes and batches" do
+    SlateDb::Database.open(tmpdir) do |db|
+      put = db.put("a", "1", return_result: true)
+      delete = db.delete("a", return_result: true)
+      batch = db.batch(return_result: true) do |b|
+        b.put("b", "22")
+        b.delete("c")
+      end
+
+      expect(delete.seq).to be > put.seq
+      expect(delete.bytes).to eq(1)
+      expect(batch.seq).to be > delete.seq
+      expect(batch.bytes).to eq(4)
+    end
+  end
+
+  it "describes a transaction commit" do
+    SlateDb::Database.open(tmpdir) do |db|
+      txn = db.begin_transaction
+      txn.put("a", "1")
+      txn.put("bb", "22")
+      result = txn.commit(await_durable: false, return_result: true)
+
+      expect(result).to be_a(described_class)
+      expect(result.seq).to eq(db.get_key_value("a")[:seq])
+      expect(result.durability).to eq(:memory)
+      expect(result.bytes).to eq(6)
+    end
+  end
+
+  it "converts to a hash" do
+    SlateDb::Database.open(tmpdir) do |db|
+      result = db.put("key", "value", return_result: true)
+
+      expect(result.to_h.keys).to eq(%i[seq durability bytes duration])
+      expect(result.to_h[:seq]).to eq(result.seq)
+    end
+  end
+
+  describe "return_token" do
+    it "returns the write's sequence number" do
+      SlateDb::Database.open(tmpdir) do |db|
+        token = db.put("key", "value", return_token: true)
+        expect(token).to eq(db.get_key_value("key")[:seq])
+        expect(db.batch(return_token: true) { |b| b.put("other", "value") }).to be > token
+      end
+    end
+
+    it "is returned by transaction commits" do
+      SlateDb::Database.open(tmpdir) do |db|
+        txn = db.begin_transaction
+        txn.put("key", "value")
+        token = txn.commit(return_token: true)
+
+        expect(token).to eq(db.get_key_value("key")[:seq])
+        expect(db.get("key", min_seq: token)).to eq("value")
+      end
+    end
+
+    it "is nil for a commit that wrote nothing" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect(db.begin_transaction.commit(return_token: true)).to be_nil
+      end
+    end
+
+    it "is accepted as min_seq by database reads" do
+      SlateDb::Database.open(tmpdir) do |db|
+        token = db.put("key", "value", return_token: true)
+        expect(db.get("key", min_seq: token)).to eq("value")
+        expect(db.scan("key", min_seq: token).to_a).to eq([%w[key value]])
+      end
+    end
+
+    it "cannot be combined with return_result or return_handle" do
+      SlateDb::Database.open(tmpdir) do |db|
+        expect { db.put("key", "value", return_result: true, return_token: true) }
+          .to raise_error(SlateDb::InvalidArgumentError, /return_token cannot be combined/)
+      end
+    end
+  end
+end